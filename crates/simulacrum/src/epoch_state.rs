@@ -25,6 +25,7 @@ use sui_types::{
 
 use crate::SimulatorStore;
 
+#[derive(Clone)]
 pub struct EpochState {
     epoch_start_state: EpochStartSystemState,
     committee: Committee,
@@ -59,6 +60,19 @@ impl EpochState {
         }
     }
 
+    /// Replace the committee for this epoch, e.g. with one whose keys are known locally.
+    pub fn with_committee(mut self, committee: Committee) -> Self {
+        self.committee = committee;
+        self
+    }
+
+    /// Pick up the consensus round counter from where a previous `EpochState` for the same epoch
+    /// left off.
+    pub fn with_next_consensus_round(mut self, round: u64) -> Self {
+        self.next_consensus_round = round;
+        self
+    }
+
     pub fn epoch(&self) -> EpochId {
         self.epoch_start_state.epoch()
     }
@@ -73,6 +87,11 @@ impl EpochState {
         round
     }
 
+    /// The round that the next call to `next_consensus_round` will return, without advancing it.
+    pub fn peek_next_consensus_round(&self) -> u64 {
+        self.next_consensus_round
+    }
+
     pub fn committee(&self) -> &Committee {
         &self.committee
     }
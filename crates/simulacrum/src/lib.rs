@@ -32,6 +32,7 @@ use sui_types::messages_consensus::ConsensusDeterminedVersionAssignments;
 use sui_types::object::{Object, Owner};
 use sui_types::storage::{ObjectStore, ReadStore, RpcStateReader};
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemState;
+use sui_types::sui_system_state::SuiSystemState;
 use sui_types::transaction::EndOfEpochTransactionKind;
 use sui_types::{
     base_types::{EpochId, SuiAddress},
@@ -46,10 +47,14 @@ use sui_types::{
 };
 
 use self::epoch_state::EpochState;
+pub use self::snapshot::Snapshot;
+pub use self::store::forked_store::ForkedStore;
 pub use self::store::in_mem_store::InMemoryStore;
 use self::store::in_mem_store::KeyStore;
 pub use self::store::SimulatorStore;
-use sui_types::messages_checkpoint::{CheckpointContents, CheckpointSequenceNumber};
+use sui_types::messages_checkpoint::{
+    CheckpointContents, CheckpointSequenceNumber, CheckpointSummary, CheckpointVersionSpecificData,
+};
 use sui_types::mock_checkpoint_builder::{MockCheckpointBuilder, ValidatorKeypairProvider};
 use sui_types::{
     gas_coin::GasCoin,
@@ -58,6 +63,7 @@ use sui_types::{
 };
 
 mod epoch_state;
+mod snapshot;
 pub mod store;
 
 /// A `Simulacrum` of Sui.
@@ -143,6 +149,77 @@ where
     }
 }
 
+impl<R> Simulacrum<R, ForkedStore>
+where
+    R: rand::RngCore + rand::CryptoRng,
+{
+    /// Create a new Simulacrum that forks a remote network, from the checkpoint that `store` is
+    /// pinned to.
+    ///
+    /// The remote network's validators are replaced by a local committee (whose keys are generated
+    /// using `rng`) that signs the checkpoints created from then on, and the local accounts are
+    /// funded with gas coins, to pay for transactions.
+    pub fn new_forked(mut rng: R, mut store: ForkedStore) -> Self {
+        let config = ConfigBuilder::new_with_temp_dir()
+            .rng(&mut rng)
+            .deterministic_committee_size(NonZeroUsize::new(1).unwrap())
+            .build();
+
+        let keystore = KeyStore::from_network_config(&config);
+        let epoch_state = new_epoch_state(&keystore, store.get_system_state());
+
+        // The fork starts from an (empty) checkpoint that stands in for the remote checkpoint, but
+        // is signed by the local committee.
+        let fork = store.fork_checkpoint().clone();
+        let contents = CheckpointContents::new_with_digests_and_signatures(vec![], vec![]);
+        let summary = CheckpointSummary {
+            epoch: fork.epoch,
+            sequence_number: fork.sequence_number,
+            network_total_transactions: fork.network_total_transactions,
+            content_digest: *contents.digest(),
+            previous_digest: None,
+            epoch_rolling_gas_cost_summary: fork.epoch_rolling_gas_cost_summary,
+            end_of_epoch_data: None,
+            timestamp_ms: fork.timestamp_ms,
+            version_specific_data: bcs::to_bytes(&CheckpointVersionSpecificData::empty_for_tests())
+                .unwrap(),
+            checkpoint_commitments: Default::default(),
+        };
+
+        let committee = CommitteeWithKeys::new(&keystore, epoch_state.committee());
+        let checkpoint = MockCheckpointBuilder::create_certified_checkpoint(&committee, summary);
+
+        store.insert_committee(epoch_state.committee().clone());
+        store.insert_checkpoint(checkpoint.clone());
+        store.insert_checkpoint_contents(contents);
+
+        let account_coins = config
+            .genesis
+            .objects()
+            .iter()
+            .filter(|object| {
+                object.is_gas_coin()
+                    && matches!(object.owner, Owner::AddressOwner(owner)
+                        if keystore.accounts().any(|(account, _)| *account == owner))
+            })
+            .map(|object| (object.id(), object.clone()))
+            .collect();
+        store.update_objects(account_coins, vec![]);
+
+        Self {
+            rng,
+            keystore,
+            genesis: config.genesis.clone(),
+            store,
+            checkpoint_builder: MockCheckpointBuilder::new(checkpoint),
+            epoch_state,
+            deny_config: TransactionDenyConfig::default(),
+            verifier_signing_config: VerifierSigningConfig::default(),
+            data_ingestion_path: None,
+        }
+    }
+}
+
 impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
     pub fn new_with_network_config_store(config: &NetworkConfig, rng: R, store: S) -> Self {
         let keystore = KeyStore::from_network_config(config);
@@ -284,7 +361,7 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
         self.execute_transaction(tx.into())
             .expect("advancing the epoch cannot fail");
 
-        let new_epoch_state = EpochState::new(self.store.get_system_state());
+        let new_epoch_state = new_epoch_state(&self.keystore, self.store.get_system_state());
         let end_of_epoch_data = EndOfEpochData {
             next_epoch_committee: new_epoch_state.committee().voting_rights.clone(),
             next_epoch_protocol_version,
@@ -450,6 +527,95 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
     }
 }
 
+impl<R, S: store::SimulatorStore + Clone> Simulacrum<R, S> {
+    /// Create a new Simulacrum that picks up from the state captured in `snapshot`.
+    ///
+    /// ```
+    /// use simulacrum::{Simulacrum, Snapshot};
+    /// use rand::rngs::OsRng;
+    ///
+    /// # fn main() {
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # let path = dir.path().join("snapshot");
+    /// let mut simulacrum = Simulacrum::new();
+    /// simulacrum.create_checkpoint();
+    /// simulacrum.snapshot().save(&path).unwrap();
+    ///
+    /// // ...later, possibly in another process...
+    /// let snapshot = Snapshot::load(&path).unwrap();
+    /// let simulacrum = Simulacrum::new_from_snapshot(OsRng, snapshot);
+    /// # }
+    /// ```
+    pub fn new_from_snapshot(rng: R, snapshot: Snapshot<S>) -> Self {
+        let Snapshot {
+            keystore,
+            genesis,
+            store,
+            checkpoint_builder,
+            epoch_state,
+        } = snapshot;
+
+        Self {
+            rng,
+            keystore,
+            genesis,
+            store,
+            checkpoint_builder,
+            epoch_state,
+            deny_config: TransactionDenyConfig::default(),
+            verifier_signing_config: VerifierSigningConfig::default(),
+            data_ingestion_path: None,
+        }
+    }
+
+    /// Capture the current state of the chain, to [`restore`] later, or save to a file.
+    ///
+    /// Transactions that have been executed but not yet included in a checkpoint are captured as
+    /// well, and will be included in the next checkpoint created after restoring.
+    ///
+    /// [`restore`]: Simulacrum::restore
+    pub fn snapshot(&self) -> Snapshot<S> {
+        Snapshot {
+            keystore: self.keystore.clone(),
+            genesis: self.genesis.clone(),
+            store: self.store.clone(),
+            checkpoint_builder: self.checkpoint_builder.clone(),
+            epoch_state: self.epoch_state.clone(),
+        }
+    }
+
+    /// Roll the chain back (or forward) to the state captured in `snapshot`, discarding its
+    /// current state. The same snapshot can be restored any number of times, to run different
+    /// scenarios from a common starting point.
+    ///
+    /// The Simulacrum's source of randomness and configuration are left as they are.
+    pub fn restore(&mut self, snapshot: &Snapshot<S>) {
+        let snapshot = snapshot.clone();
+        self.keystore = snapshot.keystore;
+        self.genesis = snapshot.genesis;
+        self.store = snapshot.store;
+        self.checkpoint_builder = snapshot.checkpoint_builder;
+        self.epoch_state = snapshot.epoch_state;
+    }
+}
+
+/// The state for the epoch described by `system_state`. If the keystore does not hold the keys for
+/// that epoch's committee (e.g. because the chain was forked from a remote network), a committee
+/// of the validators in the keystore is used instead, so that checkpoints can still be signed.
+fn new_epoch_state(keystore: &KeyStore, system_state: SuiSystemState) -> EpochState {
+    let epoch_state = EpochState::new(system_state);
+    if epoch_state
+        .committee()
+        .names()
+        .all(|name| keystore.validator(name).is_some())
+    {
+        epoch_state
+    } else {
+        let committee = keystore.committee(epoch_state.epoch());
+        epoch_state.with_committee(committee)
+    }
+}
+
 pub struct CommitteeWithKeys<'a> {
     keystore: &'a KeyStore,
     committee: &'a Committee,
@@ -663,6 +829,69 @@ mod tests {
     };
 
     use super::*;
+    use crate::store::remote_store::{ForkCheckpoint, RemoteStore};
+    use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
+
+    /// Serves the state of another simulacrum, as if it were a remote network.
+    struct SimulacrumRemote(Simulacrum);
+
+    impl RemoteStore for SimulacrumRemote {
+        fn get_checkpoint(
+            &self,
+            sequence_number: CheckpointSequenceNumber,
+        ) -> Result<ForkCheckpoint> {
+            let checkpoint = self
+                .0
+                .store()
+                .get_checkpoint_by_sequence_number(sequence_number)
+                .context("Checkpoint not found")?;
+
+            Ok(ForkCheckpoint {
+                sequence_number,
+                epoch: checkpoint.epoch,
+                timestamp_ms: checkpoint.timestamp_ms,
+                network_total_transactions: checkpoint.network_total_transactions,
+                epoch_rolling_gas_cost_summary: checkpoint.epoch_rolling_gas_cost_summary.clone(),
+            })
+        }
+
+        fn get_object_at_checkpoint(
+            &self,
+            id: &ObjectID,
+            _checkpoint: CheckpointSequenceNumber,
+        ) -> Result<Option<Object>> {
+            Ok(store::SimulatorStore::get_object(self.0.store(), id))
+        }
+
+        fn get_object_at_version(
+            &self,
+            id: &ObjectID,
+            version: VersionNumber,
+        ) -> Result<Option<Object>> {
+            Ok(self.0.store().get_object_at_version(id, version))
+        }
+    }
+
+    /// A remote that can't be reached.
+    struct OfflineRemote;
+
+    impl RemoteStore for OfflineRemote {
+        fn get_checkpoint(&self, _: CheckpointSequenceNumber) -> Result<ForkCheckpoint> {
+            Err(anyhow!("offline"))
+        }
+
+        fn get_object_at_checkpoint(
+            &self,
+            _: &ObjectID,
+            _: CheckpointSequenceNumber,
+        ) -> Result<Option<Object>> {
+            Err(anyhow!("offline"))
+        }
+
+        fn get_object_at_version(&self, _: &ObjectID, _: VersionNumber) -> Result<Option<Object>> {
+            Err(anyhow!("offline"))
+        }
+    }
 
     #[test]
     fn deterministic_genesis() {
@@ -764,4 +993,128 @@ mod tests {
         assert_eq!(&checkpoint.epoch_rolling_gas_cost_summary, gas_summary);
         assert_eq!(checkpoint.network_total_transactions, 2); // genesis + 1 txn
     }
+
+    #[test]
+    fn fork() {
+        let mut source = Simulacrum::new();
+        source.advance_epoch(/* create_random_state */ false);
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let (tx, _) = source.transfer_txn(recipient);
+        source.execute_transaction(tx).unwrap();
+        let checkpoint = source.create_checkpoint();
+        let coin = source.store().owned_objects(recipient).next().unwrap();
+
+        let cache = tempfile::tempdir().unwrap();
+        let store = ForkedStore::new(
+            SimulacrumRemote(source),
+            checkpoint.sequence_number,
+            Some(cache.path().to_owned()),
+        )
+        .unwrap();
+
+        let mut fork = Simulacrum::new_forked(StdRng::from_seed([2; 32]), store);
+        assert_eq!(fork.epoch_start_state().epoch(), checkpoint.epoch);
+
+        // Objects from the remote network are visible in the fork.
+        let fetched = store::SimulatorStore::get_object(fork.store(), &coin.id()).unwrap();
+        assert_eq!(
+            fetched.compute_object_reference(),
+            coin.compute_object_reference()
+        );
+
+        // Transactions can be executed against the forked state, and checkpoints and epochs
+        // advance from where the remote network left off.
+        let (_, _, gas) = fork.funded_account(MIST_PER_SUI).unwrap();
+        fork.advance_clock(Duration::from_millis(1));
+        let next = fork.create_checkpoint();
+        assert_eq!(next.sequence_number, checkpoint.sequence_number + 1);
+
+        fork.advance_epoch(/* create_random_state */ false);
+        assert_eq!(fork.epoch_start_state().epoch(), checkpoint.epoch + 1);
+        assert!(store::SimulatorStore::get_object(fork.store(), &gas.0).is_some());
+
+        // Everything needed to set up the fork was cached, so it can be set up again offline.
+        let store = ForkedStore::new(
+            OfflineRemote,
+            checkpoint.sequence_number,
+            Some(cache.path().to_owned()),
+        )
+        .unwrap();
+
+        let fork = Simulacrum::new_forked(StdRng::from_seed([2; 32]), store);
+        let fetched = store::SimulatorStore::get_object(fork.store(), &coin.id()).unwrap();
+        assert_eq!(
+            fetched.compute_object_reference(),
+            coin.compute_object_reference()
+        );
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut sim = Simulacrum::new_with_rng(StdRng::from_seed([1; 32]));
+        let (sender, key, gas) = sim.funded_account(MIST_PER_SUI).unwrap();
+        sim.create_checkpoint();
+
+        let snapshot = sim.snapshot();
+        let recipient = SuiAddress::random_for_testing_only();
+        let transfer = |sim: &mut Simulacrum<StdRng>| {
+            let gas = store::SimulatorStore::get_object(sim.store(), &gas.0)
+                .unwrap()
+                .compute_object_reference();
+            let data = TransactionData::new_transfer_sui(
+                recipient,
+                sender,
+                None,
+                gas,
+                1_000_000,
+                sim.reference_gas_price(),
+            );
+            let tx = Transaction::from_data_and_signer(data, vec![&key]);
+            let (effects, err) = sim.execute_transaction(tx).unwrap();
+            assert!(err.is_none());
+            effects
+        };
+
+        // Changes made after the snapshot are discarded when it is restored.
+        transfer(&mut sim);
+        let checkpoint = sim.create_checkpoint();
+        assert!(sim.store().owned_objects(recipient).next().is_some());
+
+        sim.restore(&snapshot);
+        assert!(sim.store().owned_objects(recipient).next().is_none());
+        assert_eq!(
+            sim.store().get_highest_checkpint().unwrap().sequence_number,
+            checkpoint.sequence_number - 1,
+        );
+
+        // Restoring the same snapshot and replaying the same transaction leads to the same state.
+        let effects = transfer(&mut sim);
+        assert_eq!(sim.create_checkpoint().digest(), checkpoint.digest());
+
+        // A snapshot that is saved to a file, and loaded again, picks up where it left off.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot");
+        sim.snapshot().save(&path).unwrap();
+
+        let mut loaded = Simulacrum::new_from_snapshot(
+            StdRng::from_seed([1; 32]),
+            Snapshot::load(&path).unwrap(),
+        );
+        assert_eq!(
+            loaded
+                .store()
+                .get_transaction_effects(effects.transaction_digest()),
+            Some(effects),
+        );
+        assert_eq!(
+            loaded.store().get_highest_checkpint().unwrap().digest(),
+            checkpoint.digest(),
+        );
+
+        loaded.advance_clock(Duration::from_millis(1));
+        loaded.create_checkpoint();
+        loaded.advance_epoch(/* create_random_state */ false);
+        assert_eq!(loaded.epoch_start_state().epoch(), 1);
+    }
 }
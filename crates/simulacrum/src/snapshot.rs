@@ -0,0 +1,92 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sui_config::genesis;
+use sui_types::mock_checkpoint_builder::{MockCheckpointBuilder, MockCheckpointBuilderState};
+
+use crate::epoch_state::EpochState;
+use crate::store::in_mem_store::KeyStore;
+use crate::InMemoryStore;
+
+/// A copy of the state of a [`Simulacrum`] at some point in time, that it can be restored to.
+///
+/// A snapshot captures the chain's state (objects, transactions, checkpoints, the clock and the
+/// epoch) along with the keys of its validators and accounts, but not the Simulacrum's source of
+/// randomness or its configuration (e.g. its deny config, or where it writes checkpoints to).
+///
+/// Snapshots of Simulacra that use an [`InMemoryStore`] can also be saved to, and loaded from, a
+/// file.
+///
+/// [`Simulacrum`]: crate::Simulacrum
+#[derive(Clone)]
+pub struct Snapshot<S = InMemoryStore> {
+    pub(crate) keystore: KeyStore,
+    pub(crate) genesis: genesis::Genesis,
+    pub(crate) store: S,
+    pub(crate) checkpoint_builder: MockCheckpointBuilder,
+    pub(crate) epoch_state: EpochState,
+}
+
+/// The contents of a snapshot file. The epoch state is not stored, because it can be recreated
+/// from the system state in the store, with the exception of the consensus round counter.
+#[derive(Serialize)]
+struct SnapshotFileRef<'a> {
+    keystore: &'a KeyStore,
+    genesis: &'a genesis::Genesis,
+    store: &'a InMemoryStore,
+    checkpoint_builder: MockCheckpointBuilderState,
+    next_consensus_round: u64,
+}
+
+#[derive(Deserialize)]
+struct SnapshotFile {
+    keystore: KeyStore,
+    genesis: genesis::Genesis,
+    store: InMemoryStore,
+    checkpoint_builder: MockCheckpointBuilderState,
+    next_consensus_round: u64,
+}
+
+impl Snapshot<InMemoryStore> {
+    /// Write this snapshot to the file at `path`, replacing it if it already exists.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let file = SnapshotFileRef {
+            keystore: &self.keystore,
+            genesis: &self.genesis,
+            store: &self.store,
+            checkpoint_builder: self.checkpoint_builder.state(),
+            next_consensus_round: self.epoch_state.peek_next_consensus_round(),
+        };
+
+        let bytes = bcs::to_bytes(&file).context("Failed to serialize snapshot")?;
+        fs::write(path, bytes)
+            .with_context(|| format!("Failed to write snapshot to {}", path.display()))
+    }
+
+    /// Read a snapshot previously written to `path` by [`Snapshot::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .with_context(|| format!("Failed to read snapshot from {}", path.display()))?;
+
+        let file: SnapshotFile = bcs::from_bytes(&bytes)
+            .with_context(|| format!("Failed to deserialize snapshot from {}", path.display()))?;
+
+        let epoch_state = crate::new_epoch_state(&file.keystore, file.store.get_system_state())
+            .with_next_consensus_round(file.next_consensus_round);
+
+        Ok(Self {
+            keystore: file.keystore,
+            genesis: file.genesis,
+            store: file.store,
+            checkpoint_builder: MockCheckpointBuilder::from_state(file.checkpoint_builder),
+            epoch_state,
+        })
+    }
+}
@@ -0,0 +1,667 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use sui_types::storage::PackageObject;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    committee::{Committee, EpochId},
+    digests::{ObjectDigest, TransactionDigest},
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    error::{SuiError, SuiResult},
+    messages_checkpoint::{
+        CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
+        VerifiedCheckpoint,
+    },
+    object::{Object, Owner},
+    storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync},
+    transaction::VerifiedTransaction,
+};
+use tracing::{debug, error};
+
+use super::in_mem_store::InMemoryStore;
+use super::remote_store::{ForkCheckpoint, JsonRpcRemoteStore, RemoteStore};
+use super::SimulatorStore;
+
+/// A store that forks a remote network at a pinned checkpoint: Objects (including packages and
+/// the system state) that have not been written locally are fetched from the remote network on
+/// demand, as they were at the end of that checkpoint, and local writes are applied on top.
+///
+/// Everything fetched is cached in memory and, if a cache directory is configured, on disk, so
+/// that subsequent runs against the same checkpoint are deterministic and do not need the network.
+///
+/// Checkpoints, transactions, committees and owned object queries only reflect what has happened
+/// locally.
+///
+/// Failures to fetch from the remote are surfaced as errors by the fallible accessors
+/// (`try_get_object`, `try_get_object_at_version`, and the ones used during execution). The
+/// infallible accessors log them and treat the object as missing. Failed fetches are not cached,
+/// so they are retried the next time the object is read.
+pub struct ForkedStore {
+    remote: Box<dyn RemoteStore>,
+    checkpoint: ForkCheckpoint,
+    cache_dir: Option<PathBuf>,
+
+    /// Objects fetched from the remote, as of the pinned checkpoint. `None` if the object did not
+    /// exist at that point.
+    fetched: RwLock<HashMap<ObjectID, Option<Object>>>,
+    fetched_versions: RwLock<HashMap<(ObjectID, SequenceNumber), Option<Object>>>,
+
+    /// State written locally, on top of the fork.
+    local: InMemoryStore,
+
+    /// Objects that have been deleted or wrapped locally, which should not be fetched from the
+    /// remote anymore.
+    removed: HashSet<ObjectID>,
+}
+
+impl ForkedStore {
+    /// Fork the network served by the fullnode at `rpc_url`, at checkpoint `checkpoint`, caching
+    /// fetched state under `cache_dir`, if provided.
+    ///
+    /// Objects that have been modified since `checkpoint` can't be fetched from a fullnode alone.
+    /// To fork from such a checkpoint, pass a `JsonRpcRemoteStore` configured with a GraphQL
+    /// service to [`Self::new`] instead.
+    pub fn from_rpc(
+        rpc_url: impl Into<String>,
+        checkpoint: CheckpointSequenceNumber,
+        cache_dir: Option<PathBuf>,
+    ) -> Result<Self> {
+        Self::new(JsonRpcRemoteStore::new(rpc_url), checkpoint, cache_dir)
+    }
+
+    pub fn new(
+        remote: impl RemoteStore + 'static,
+        checkpoint: CheckpointSequenceNumber,
+        cache_dir: Option<PathBuf>,
+    ) -> Result<Self> {
+        // Cached state is only valid for the checkpoint it was fetched at.
+        let cache_dir = cache_dir.map(|dir| dir.join(checkpoint.to_string()));
+        if let Some(dir) = &cache_dir {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let checkpoint = match read_cache(cache_dir.as_deref(), "checkpoint") {
+            Some(cached) => cached,
+            None => {
+                let fetched = remote.get_checkpoint(checkpoint)?;
+                write_cache(cache_dir.as_deref(), "checkpoint", &fetched);
+                fetched
+            }
+        };
+
+        Ok(Self {
+            remote: Box::new(remote),
+            checkpoint,
+            cache_dir,
+            fetched: RwLock::new(HashMap::new()),
+            fetched_versions: RwLock::new(HashMap::new()),
+            local: InMemoryStore::default(),
+            removed: HashSet::new(),
+        })
+    }
+
+    /// The checkpoint on the remote network that this store was forked from.
+    pub fn fork_checkpoint(&self) -> &ForkCheckpoint {
+        &self.checkpoint
+    }
+
+    /// Object `id` as of the fork's checkpoint.
+    fn fetch_object(&self, id: &ObjectID) -> Result<Option<Object>> {
+        if let Some(object) = self.fetched.read().unwrap().get(id) {
+            return Ok(object.clone());
+        }
+
+        let key = format!("object_{id}");
+        let object = match read_cache(self.cache_dir.as_deref(), &key) {
+            Some(cached) => cached,
+            None => {
+                debug!("Fetching object {id} from remote");
+                let object = self
+                    .remote
+                    .get_object_at_checkpoint(id, self.checkpoint.sequence_number)
+                    .with_context(|| format!("Failed to fetch object {id} from remote"))?;
+                write_cache(self.cache_dir.as_deref(), &key, &object);
+                object
+            }
+        };
+
+        self.fetched.write().unwrap().insert(*id, object.clone());
+        Ok(object)
+    }
+
+    /// Version `version` of object `id`, as long as it was written no later than the fork's
+    /// checkpoint.
+    fn fetch_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>> {
+        let Some(latest) = self.fetch_object(id)? else {
+            return Ok(None);
+        };
+
+        if latest.version() <= version {
+            return Ok((latest.version() == version).then_some(latest));
+        }
+
+        if let Some(object) = self.fetched_versions.read().unwrap().get(&(*id, version)) {
+            return Ok(object.clone());
+        }
+
+        let key = format!("object_{id}_{}", version.value());
+        let object = match read_cache(self.cache_dir.as_deref(), &key) {
+            Some(cached) => cached,
+            None => {
+                debug!("Fetching object {id} at version {version} from remote");
+                let object = self
+                    .remote
+                    .get_object_at_version(id, version)
+                    .with_context(|| {
+                        format!("Failed to fetch object {id} at version {version} from remote")
+                    })?;
+                write_cache(self.cache_dir.as_deref(), &key, &object);
+                object
+            }
+        };
+
+        self.fetched_versions
+            .write()
+            .unwrap()
+            .insert((*id, version), object.clone());
+        Ok(object)
+    }
+}
+
+impl ForkedStore {
+    /// The latest version of object `id` that is no later than `version`, or `None` if the object
+    /// did not exist yet at that version. Versions written locally are all later than the version
+    /// fetched from the remote, so the remote is only consulted if none of them qualify.
+    fn child_object_at_or_before(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        if let Some(object) = self.local.get_object_at_or_before(id, version) {
+            return Ok(Some(object.clone()));
+        }
+
+        let Some(fetched) = self.fetch_object(id).map_err(storage_error)? else {
+            return Ok(None);
+        };
+
+        if fetched.version() <= version {
+            return Ok(Some(fetched));
+        }
+
+        Err(SuiError::UnsupportedFeatureError {
+            error: format!(
+                "Cannot read object {id} at or before version {version}: it was modified at \
+                 version {} on the remote network, before the fork's checkpoint, and earlier \
+                 versions can only be looked up by their exact version",
+                fetched.version(),
+            ),
+        })
+    }
+}
+
+/// Report an error fetching from the remote as a storage error, for the store's fallible
+/// accessors.
+fn storage_error(e: anyhow::Error) -> SuiError {
+    SuiError::Storage(format!("{e:#}"))
+}
+
+/// The result of an infallible read, which has to treat errors as the object being missing.
+fn or_missing(id: &ObjectID, result: SuiResult<Option<Object>>) -> Option<Object> {
+    result.unwrap_or_else(|e| {
+        error!("Failed to read object {id}: {e}");
+        None
+    })
+}
+
+fn read_cache<T: DeserializeOwned>(cache_dir: Option<&Path>, key: &str) -> Option<T> {
+    let bytes = std::fs::read(cache_dir?.join(format!("{key}.bcs"))).ok()?;
+    bcs::from_bytes(&bytes).ok()
+}
+
+fn write_cache<T: Serialize>(cache_dir: Option<&Path>, key: &str, value: &T) {
+    let Some(dir) = cache_dir else {
+        return;
+    };
+
+    // The cache is an optimization, so failing to write to it is not fatal.
+    let bytes = bcs::to_bytes(value).expect("cached values must serialize");
+    if let Err(e) = std::fs::write(dir.join(format!("{key}.bcs")), bytes) {
+        debug!("Failed to cache {key}: {e}");
+    }
+}
+
+impl BackingPackageStore for ForkedStore {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<PackageObject>> {
+        let Some(package) = SimulatorStore::try_get_object(self, package_id)? else {
+            return Ok(None);
+        };
+
+        if !package.is_package() {
+            return Err(SuiError::BadObjectType {
+                error: format!("Package expected, Move object found: {package_id}"),
+            });
+        }
+
+        Ok(Some(PackageObject::new(package)))
+    }
+}
+
+impl ChildObjectResolver for ForkedStore {
+    fn read_child_object(
+        &self,
+        parent: &ObjectID,
+        child: &ObjectID,
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        let child_object = match SimulatorStore::try_get_object(self, child)? {
+            None => return Ok(None),
+            Some(obj) if obj.version() <= child_version_upper_bound => obj,
+            Some(_) => match self.child_object_at_or_before(child, child_version_upper_bound)? {
+                None => return Ok(None),
+                Some(obj) => obj,
+            },
+        };
+
+        let parent = *parent;
+        if child_object.owner != Owner::ObjectOwner(parent.into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: parent,
+                actual_owner: child_object.owner.clone(),
+            });
+        }
+
+        Ok(Some(child_object))
+    }
+
+    fn get_object_received_at_version(
+        &self,
+        owner: &ObjectID,
+        receiving_object_id: &ObjectID,
+        receive_object_at_version: SequenceNumber,
+        _epoch_id: EpochId,
+    ) -> SuiResult<Option<Object>> {
+        let recv_object = match SimulatorStore::try_get_object(self, receiving_object_id)? {
+            None => return Ok(None),
+            Some(obj) => obj,
+        };
+        if recv_object.owner != Owner::AddressOwner((*owner).into()) {
+            return Ok(None);
+        }
+
+        if recv_object.version() != receive_object_at_version {
+            return Ok(None);
+        }
+        Ok(Some(recv_object))
+    }
+}
+
+impl ObjectStore for ForkedStore {
+    fn get_object(&self, object_id: &ObjectID) -> Option<Object> {
+        SimulatorStore::get_object(self, object_id)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: sui_types::base_types::VersionNumber,
+    ) -> Option<Object> {
+        SimulatorStore::get_object_at_version(self, object_id, version)
+    }
+}
+
+impl ParentSync for ForkedStore {
+    fn get_latest_parent_entry_ref_deprecated(
+        &self,
+        _object_id: ObjectID,
+    ) -> Option<sui_types::base_types::ObjectRef> {
+        panic!("Never called in newer protocol versions")
+    }
+}
+
+impl SimulatorStore for ForkedStore {
+    fn get_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Option<VerifiedCheckpoint> {
+        self.local
+            .get_checkpoint_by_sequence_number(sequence_number)
+            .cloned()
+    }
+
+    fn get_checkpoint_by_digest(&self, digest: &CheckpointDigest) -> Option<VerifiedCheckpoint> {
+        self.local.get_checkpoint_by_digest(digest).cloned()
+    }
+
+    fn get_highest_checkpint(&self) -> Option<VerifiedCheckpoint> {
+        self.local.get_highest_checkpint().cloned()
+    }
+
+    fn get_checkpoint_contents(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> Option<CheckpointContents> {
+        self.local.get_checkpoint_contents(digest).cloned()
+    }
+
+    fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<Committee> {
+        self.local.get_committee_by_epoch(epoch).cloned()
+    }
+
+    fn get_transaction(&self, digest: &TransactionDigest) -> Option<VerifiedTransaction> {
+        self.local.get_transaction(digest).cloned()
+    }
+
+    fn get_transaction_effects(&self, digest: &TransactionDigest) -> Option<TransactionEffects> {
+        self.local.get_transaction_effects(digest).cloned()
+    }
+
+    fn get_transaction_events(&self, digest: &TransactionDigest) -> Option<TransactionEvents> {
+        self.local.get_transaction_events(digest).cloned()
+    }
+
+    fn get_object(&self, id: &ObjectID) -> Option<Object> {
+        or_missing(id, SimulatorStore::try_get_object(self, id))
+    }
+
+    fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        or_missing(id, self.try_get_object_at_version(id, version))
+    }
+
+    fn try_get_object(&self, id: &ObjectID) -> SuiResult<Option<Object>> {
+        if let Some(object) = self.local.get_object(id) {
+            return Ok(Some(object.clone()));
+        }
+
+        if self.removed.contains(id) {
+            return Ok(None);
+        }
+
+        self.fetch_object(id).map_err(storage_error)
+    }
+
+    fn try_get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        if let Some(object) = self.local.get_object_at_version(id, version) {
+            return Ok(Some(object.clone()));
+        }
+
+        self.fetch_object_at_version(id, version)
+            .map_err(storage_error)
+    }
+
+    fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
+        sui_types::sui_system_state::get_sui_system_state(self).expect("system state must exist")
+    }
+
+    fn get_clock(&self) -> sui_types::clock::Clock {
+        SimulatorStore::get_object(self, &sui_types::SUI_CLOCK_OBJECT_ID)
+            .expect("clock should exist")
+            .to_rust()
+            .expect("clock object should deserialize")
+    }
+
+    /// Only objects that have been written locally are returned, because the remote can't be
+    /// queried by owner as of the fork's checkpoint.
+    fn owned_objects(&self, owner: SuiAddress) -> Box<dyn Iterator<Item = Object> + '_> {
+        Box::new(self.local.owned_objects(owner).cloned())
+    }
+
+    fn insert_checkpoint(&mut self, checkpoint: VerifiedCheckpoint) {
+        self.local.insert_checkpoint(checkpoint)
+    }
+
+    fn insert_checkpoint_contents(&mut self, contents: CheckpointContents) {
+        self.local.insert_checkpoint_contents(contents)
+    }
+
+    fn insert_committee(&mut self, committee: Committee) {
+        self.local.insert_committee(committee)
+    }
+
+    fn insert_executed_transaction(
+        &mut self,
+        transaction: VerifiedTransaction,
+        effects: TransactionEffects,
+        events: TransactionEvents,
+        written_objects: BTreeMap<ObjectID, Object>,
+    ) {
+        // Unlike the in-memory store, wrapped objects need to be tracked too, so that they are not
+        // fetched from the remote again.
+        let mut deleted_objects = effects.deleted();
+        deleted_objects.extend(effects.wrapped());
+        let tx_digest = *effects.transaction_digest();
+        self.insert_transaction(transaction);
+        self.insert_transaction_effects(effects);
+        self.insert_events(&tx_digest, events);
+        self.update_objects(written_objects, deleted_objects);
+    }
+
+    fn insert_transaction(&mut self, transaction: VerifiedTransaction) {
+        self.local.insert_transaction(transaction)
+    }
+
+    fn insert_transaction_effects(&mut self, effects: TransactionEffects) {
+        self.local.insert_transaction_effects(effects)
+    }
+
+    fn insert_events(&mut self, tx_digest: &TransactionDigest, events: TransactionEvents) {
+        self.local.insert_events(tx_digest, events)
+    }
+
+    fn update_objects(
+        &mut self,
+        written_objects: BTreeMap<ObjectID, Object>,
+        deleted_objects: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    ) {
+        for (object_id, _, _) in &deleted_objects {
+            self.removed.insert(*object_id);
+        }
+
+        for object_id in written_objects.keys() {
+            self.removed.remove(object_id);
+        }
+
+        self.local.update_objects(written_objects, deleted_objects)
+    }
+
+    fn backing_store(&self) -> &dyn sui_types::storage::BackingStore {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::transaction::InputObjectKind;
+
+    /// A remote that serves a fixed set of object versions, and can be taken offline.
+    struct MockRemote {
+        objects: BTreeMap<(ObjectID, SequenceNumber), Object>,
+        offline: Arc<AtomicBool>,
+    }
+
+    impl MockRemote {
+        fn check_online(&self) -> Result<()> {
+            if self.offline.load(Ordering::Relaxed) {
+                anyhow::bail!("offline");
+            }
+            Ok(())
+        }
+    }
+
+    impl RemoteStore for MockRemote {
+        fn get_checkpoint(
+            &self,
+            sequence_number: CheckpointSequenceNumber,
+        ) -> Result<ForkCheckpoint> {
+            Ok(ForkCheckpoint {
+                sequence_number,
+                epoch: 0,
+                timestamp_ms: 0,
+                network_total_transactions: 0,
+                epoch_rolling_gas_cost_summary: GasCostSummary::default(),
+            })
+        }
+
+        fn get_object_at_checkpoint(
+            &self,
+            id: &ObjectID,
+            _checkpoint: CheckpointSequenceNumber,
+        ) -> Result<Option<Object>> {
+            self.check_online()?;
+            Ok(self
+                .objects
+                .range((*id, SequenceNumber::MIN)..=(*id, SequenceNumber::MAX))
+                .next_back()
+                .map(|(_, object)| object.clone()))
+        }
+
+        fn get_object_at_version(
+            &self,
+            id: &ObjectID,
+            version: SequenceNumber,
+        ) -> Result<Option<Object>> {
+            self.check_online()?;
+            Ok(self.objects.get(&(*id, version)).cloned())
+        }
+    }
+
+    fn object(id: ObjectID, version: u64) -> Object {
+        Object::with_id_owner_version_for_testing(
+            id,
+            SequenceNumber::from_u64(version),
+            Owner::AddressOwner(SuiAddress::ZERO),
+        )
+    }
+
+    fn remote(objects: impl IntoIterator<Item = Object>) -> MockRemote {
+        MockRemote {
+            objects: objects
+                .into_iter()
+                .map(|o| ((o.id(), o.version()), o))
+                .collect(),
+            offline: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    #[test]
+    fn fetch_object_versions() {
+        let id = ObjectID::random();
+        let store = ForkedStore::new(remote([object(id, 1), object(id, 2)]), 10, None).unwrap();
+
+        let latest = store.try_get_object(&id).unwrap().unwrap();
+        assert_eq!(latest.version().value(), 2);
+
+        let v1 = store
+            .try_get_object_at_version(&id, SequenceNumber::from_u64(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(v1.version().value(), 1);
+
+        // Versions after the fork's checkpoint, and objects that didn't exist, are missing.
+        assert!(store
+            .try_get_object_at_version(&id, SequenceNumber::from_u64(3))
+            .unwrap()
+            .is_none());
+        assert!(store.try_get_object(&ObjectID::random()).unwrap().is_none());
+    }
+
+    #[test]
+    fn bounded_child_reads() {
+        let parent = ObjectID::random();
+        let id = ObjectID::random();
+        let child = |version| {
+            Object::with_id_owner_version_for_testing(
+                id,
+                SequenceNumber::from_u64(version),
+                Owner::ObjectOwner(parent.into()),
+            )
+        };
+
+        let mut store = ForkedStore::new(remote([child(2)]), 10, None).unwrap();
+        store.update_objects(BTreeMap::from([(id, child(5))]), vec![]);
+        store.update_objects(BTreeMap::from([(id, child(8))]), vec![]);
+
+        let read = |bound| {
+            store
+                .read_child_object(&parent, &id, SequenceNumber::from_u64(bound))
+                .map(|o| o.map(|o| o.version().value()))
+        };
+
+        // Reads are served from the latest local version within the bound, falling back to the
+        // version fetched from the remote.
+        assert_eq!(read(9).unwrap(), Some(8));
+        assert_eq!(read(7).unwrap(), Some(5));
+        assert_eq!(read(4).unwrap(), Some(2));
+
+        // Versions before the one fetched from the remote can't be found.
+        assert!(matches!(
+            read(1),
+            Err(SuiError::UnsupportedFeatureError { .. })
+        ));
+    }
+
+    #[test]
+    fn fetch_errors_are_propagated() {
+        let id = ObjectID::random();
+        let owned = object(id, 1);
+        let remote = remote([owned.clone()]);
+        let offline = remote.offline.clone();
+        let store = ForkedStore::new(remote, 10, None).unwrap();
+
+        offline.store(true, Ordering::Relaxed);
+        assert!(matches!(
+            store.try_get_object(&id),
+            Err(SuiError::Storage(_))
+        ));
+        assert!(matches!(
+            store.try_get_object_at_version(&id, owned.version()),
+            Err(SuiError::Storage(_))
+        ));
+
+        // Reads during execution fail, rather than treating the object as missing.
+        let kinds = [InputObjectKind::ImmOrOwnedMoveObject(
+            owned.compute_object_reference(),
+        )];
+        assert!(matches!(
+            store.read_objects_for_synchronous_execution(&TransactionDigest::ZERO, &kinds, &[]),
+            Err(SuiError::Storage(_))
+        ));
+        assert!(matches!(
+            store.read_child_object(&ObjectID::random(), &id, owned.version()),
+            Err(SuiError::Storage(_))
+        ));
+        assert!(matches!(
+            store.get_package_object(&id),
+            Err(SuiError::Storage(_))
+        ));
+
+        // Infallible reads treat the object as missing.
+        assert!(SimulatorStore::get_object(&store, &id).is_none());
+
+        // Failures are not cached, so the object can be fetched once the remote is back.
+        offline.store(false, Ordering::Relaxed);
+        let fetched = store.try_get_object(&id).unwrap().unwrap();
+        assert_eq!(
+            fetched.compute_object_reference(),
+            owned.compute_object_reference()
+        );
+    }
+}
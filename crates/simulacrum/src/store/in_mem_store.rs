@@ -4,6 +4,7 @@
 use move_binary_format::CompiledModule;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::{BTreeMap, HashMap};
 use sui_config::genesis;
 use sui_types::storage::{get_module, load_package_object_from_object_store, PackageObject};
@@ -16,16 +17,16 @@ use sui_types::{
     error::SuiError,
     messages_checkpoint::{
         CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
-        VerifiedCheckpoint,
+        TrustedCheckpoint, VerifiedCheckpoint,
     },
     object::{Object, Owner},
     storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync},
-    transaction::VerifiedTransaction,
+    transaction::{TrustedTransaction, VerifiedTransaction},
 };
 
 use super::SimulatorStore;
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct InMemoryStore {
     // Checkpoint data
     checkpoints: BTreeMap<CheckpointSequenceNumber, VerifiedCheckpoint>,
@@ -38,7 +39,7 @@ pub struct InMemoryStore {
     events: HashMap<TransactionDigest, TransactionEvents>,
 
     // Committee data
-    epoch_to_committee: Vec<Committee>,
+    epoch_to_committee: BTreeMap<EpochId, Committee>,
 
     // Object data
     live_objects: HashMap<ObjectID, SequenceNumber>,
@@ -82,7 +83,7 @@ impl InMemoryStore {
     }
 
     pub fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<&Committee> {
-        self.epoch_to_committee.get(epoch as usize)
+        self.epoch_to_committee.get(&epoch)
     }
     pub fn get_transaction(&self, digest: &TransactionDigest) -> Option<&VerifiedTransaction> {
         self.transactions.get(digest)
@@ -110,6 +111,19 @@ impl InMemoryStore {
             .and_then(|versions| versions.get(&version))
     }
 
+    /// The latest version of object `id` that is no later than `version`.
+    pub fn get_object_at_or_before(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Option<&Object> {
+        self.objects
+            .get(id)?
+            .range(..=version)
+            .next_back()
+            .map(|(_, object)| object)
+    }
+
     pub fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
         sui_types::sui_system_state::get_sui_system_state(self).expect("system state must exist")
     }
@@ -156,16 +170,21 @@ impl InMemoryStore {
     }
 
     pub fn insert_committee(&mut self, committee: Committee) {
-        let epoch = committee.epoch as usize;
+        let epoch = committee.epoch;
 
-        if self.epoch_to_committee.get(epoch).is_some() {
+        if self.epoch_to_committee.contains_key(&epoch) {
             return;
         }
 
-        if self.epoch_to_committee.len() == epoch {
-            self.epoch_to_committee.push(committee);
-        } else {
-            panic!("committee was inserted into EpochCommitteeMap out of order");
+        // The first committee can be for any epoch (the store may not start from genesis), but
+        // after that they must be inserted in order.
+        match self.epoch_to_committee.last_key_value() {
+            Some((last, _)) if *last + 1 != epoch => {
+                panic!("committee was inserted into EpochCommitteeMap out of order");
+            }
+            _ => {
+                self.epoch_to_committee.insert(epoch, committee);
+            }
         }
     }
 
@@ -216,6 +235,104 @@ impl InMemoryStore {
     }
 }
 
+/// The contents of an `InMemoryStore`, as they are serialized. Indices are rebuilt from these on
+/// deserialization, and verified checkpoints and transactions are stored as trusted envelopes,
+/// which are treated as verified again when they are read back.
+#[derive(Serialize)]
+struct StoreContentsRef<'a> {
+    checkpoints: Vec<&'a TrustedCheckpoint>,
+    checkpoint_contents: Vec<&'a CheckpointContents>,
+    transactions: Vec<&'a TrustedTransaction>,
+    effects: Vec<&'a TransactionEffects>,
+    events: Vec<(&'a TransactionDigest, &'a TransactionEvents)>,
+    committees: Vec<&'a Committee>,
+    live_objects: Vec<(&'a ObjectID, &'a SequenceNumber)>,
+    objects: Vec<&'a Object>,
+}
+
+#[derive(Deserialize)]
+struct StoreContents {
+    checkpoints: Vec<TrustedCheckpoint>,
+    checkpoint_contents: Vec<CheckpointContents>,
+    transactions: Vec<TrustedTransaction>,
+    effects: Vec<TransactionEffects>,
+    events: Vec<(TransactionDigest, TransactionEvents)>,
+    committees: Vec<Committee>,
+    live_objects: Vec<(ObjectID, SequenceNumber)>,
+    objects: Vec<Object>,
+}
+
+impl Serialize for InMemoryStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StoreContentsRef {
+            checkpoints: self
+                .checkpoints
+                .values()
+                .map(|c| c.serializable_ref())
+                .collect(),
+            checkpoint_contents: self.checkpoint_contents.values().collect(),
+            transactions: self
+                .transactions
+                .values()
+                .map(|t| t.serializable_ref())
+                .collect(),
+            effects: self.effects.values().collect(),
+            events: self.events.iter().collect(),
+            committees: self.epoch_to_committee.values().collect(),
+            live_objects: self.live_objects.iter().collect(),
+            objects: self.objects.values().flat_map(|v| v.values()).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InMemoryStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let contents = StoreContents::deserialize(deserializer)?;
+        let mut store = Self::default();
+
+        for checkpoint in contents.checkpoints {
+            let checkpoint = VerifiedCheckpoint::from(checkpoint);
+            store
+                .checkpoint_digest_to_sequence_number
+                .insert(*checkpoint.digest(), *checkpoint.sequence_number());
+            store
+                .checkpoints
+                .insert(*checkpoint.sequence_number(), checkpoint);
+        }
+
+        for contents in contents.checkpoint_contents {
+            store.insert_checkpoint_contents(contents);
+        }
+
+        for transaction in contents.transactions {
+            store.insert_transaction(VerifiedTransaction::from(transaction));
+        }
+
+        for effects in contents.effects {
+            store.insert_transaction_effects(effects);
+        }
+
+        store.events = contents.events.into_iter().collect();
+        store.epoch_to_committee = contents
+            .committees
+            .into_iter()
+            .map(|committee| (committee.epoch, committee))
+            .collect();
+
+        store.live_objects = contents.live_objects.into_iter().collect();
+        for object in contents.objects {
+            store
+                .objects
+                .entry(object.id())
+                .or_default()
+                .insert(object.version(), object);
+        }
+
+        Ok(store)
+    }
+}
+
 impl BackingPackageStore for InMemoryStore {
     fn get_package_object(
         &self,
@@ -328,6 +445,85 @@ pub struct KeyStore {
     account_keys: BTreeMap<SuiAddress, AccountKeyPair>,
 }
 
+/// Private keys in a `KeyStore`, as they are serialized: Base64 encoded, with public keys and
+/// addresses derived from them again on deserialization.
+#[derive(Serialize, Deserialize)]
+struct KeyStoreContents {
+    validator_keys: Vec<String>,
+    account_keys: Vec<String>,
+}
+
+impl Clone for KeyStore {
+    fn clone(&self) -> Self {
+        use fastcrypto::traits::KeyPair;
+
+        Self {
+            validator_keys: self
+                .validator_keys
+                .iter()
+                .map(|(name, key)| (*name, key.copy()))
+                .collect(),
+            account_keys: self
+                .account_keys
+                .iter()
+                .map(|(address, key)| (*address, key.copy()))
+                .collect(),
+        }
+    }
+}
+
+impl Serialize for KeyStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use fastcrypto::traits::EncodeDecodeBase64;
+
+        KeyStoreContents {
+            validator_keys: self
+                .validator_keys
+                .values()
+                .map(|key| key.encode_base64())
+                .collect(),
+            account_keys: self
+                .account_keys
+                .values()
+                .map(|key| key.encode_base64())
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use fastcrypto::traits::{EncodeDecodeBase64, KeyPair};
+        use serde::de::Error;
+
+        let contents = KeyStoreContents::deserialize(deserializer)?;
+
+        let validator_keys = contents
+            .validator_keys
+            .iter()
+            .map(|encoded| {
+                let key = AuthorityKeyPair::decode_base64(encoded).map_err(D::Error::custom)?;
+                Ok((key.public().into(), key))
+            })
+            .collect::<Result<_, D::Error>>()?;
+
+        let account_keys = contents
+            .account_keys
+            .iter()
+            .map(|encoded| {
+                let key = AccountKeyPair::decode_base64(encoded).map_err(D::Error::custom)?;
+                Ok((key.public().into(), key))
+            })
+            .collect::<Result<_, D::Error>>()?;
+
+        Ok(Self {
+            validator_keys,
+            account_keys,
+        })
+    }
+}
+
 impl KeyStore {
     pub fn from_network_config(
         network_config: &sui_swarm_config::network_config::NetworkConfig,
@@ -363,6 +559,14 @@ impl KeyStore {
     pub fn accounts(&self) -> impl Iterator<Item = (&SuiAddress, &AccountKeyPair)> {
         self.account_keys.iter()
     }
+
+    /// A committee made up of the validators in this keystore, with equal stake, for `epoch`.
+    pub fn committee(&self, epoch: EpochId) -> Committee {
+        Committee::new(
+            epoch,
+            self.validator_keys.keys().map(|name| (*name, 1)).collect(),
+        )
+    }
 }
 
 impl SimulatorStore for InMemoryStore {
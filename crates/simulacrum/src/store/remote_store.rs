@@ -0,0 +1,268 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, Context, Result};
+use fastcrypto::encoding::{Base64, Encoding};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sui_json_rpc_types::{
+    Checkpoint, SuiObjectDataOptions, SuiObjectResponse, SuiPastObjectResponse,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber},
+    committee::EpochId,
+    error::SuiObjectResponseError,
+    gas::GasCostSummary,
+    messages_checkpoint::CheckpointSequenceNumber,
+    object::Object,
+};
+
+/// The parts of a remote network's checkpoint that a fork starts from.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ForkCheckpoint {
+    pub sequence_number: CheckpointSequenceNumber,
+    pub epoch: EpochId,
+    pub timestamp_ms: u64,
+    pub network_total_transactions: u64,
+    pub epoch_rolling_gas_cost_summary: GasCostSummary,
+}
+
+/// A source of state from a remote network, for a `ForkedStore` to fetch from.
+///
+/// Reads are synchronous, because they happen on demand, during transaction execution.
+pub trait RemoteStore: Send + Sync {
+    fn get_checkpoint(&self, sequence_number: CheckpointSequenceNumber) -> Result<ForkCheckpoint>;
+
+    /// The latest version of object `id` as of the end of checkpoint `checkpoint`, or `None` if it
+    /// did not exist (or was not live) at that point.
+    fn get_object_at_checkpoint(
+        &self,
+        id: &ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<Option<Object>>;
+
+    /// Object `id` at version `version`, or `None` if there is no such version of the object.
+    fn get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>>;
+}
+
+/// A `RemoteStore` that reads from a fullnode over JSON-RPC.
+///
+/// Fullnodes only serve the latest version of objects, so objects are looked up as of a past
+/// checkpoint using a GraphQL service, which can serve objects as of any checkpoint it has
+/// retained, if one is configured (see [`Self::with_graphql`]). Without one, only objects that
+/// have not been modified, deleted or wrapped since the checkpoint can be fetched, and fetching
+/// any other object fails.
+pub struct JsonRpcRemoteStore {
+    url: String,
+    graphql_url: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl JsonRpcRemoteStore {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            graphql_url: None,
+            agent: ureq::Agent::new(),
+        }
+    }
+
+    /// Look objects up as of a checkpoint using the GraphQL service at `url`, which must serve the
+    /// same network as the fullnode.
+    pub fn with_graphql(mut self, url: impl Into<String>) -> Self {
+        self.graphql_url = Some(url.into());
+        self
+    }
+
+    fn call<T: DeserializeOwned>(&self, method: &str, params: serde_json::Value) -> Result<T> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response = self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&request.to_string())
+            .with_context(|| format!("Failed to call {method}"))?
+            .into_string()
+            .with_context(|| format!("Failed to read response to {method}"))?;
+
+        let mut response: serde_json::Value = serde_json::from_str(&response)
+            .with_context(|| format!("Failed to parse response to {method}"))?;
+
+        if let Some(error) = response.get("error") {
+            bail!("{method} failed: {error}");
+        }
+
+        serde_json::from_value(response["result"].take())
+            .with_context(|| format!("Unexpected response to {method}"))
+    }
+
+    fn query(
+        &self,
+        url: &str,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let request = json!({
+            "query": query,
+            "variables": variables,
+        });
+
+        let response = self
+            .agent
+            .post(url)
+            .set("Content-Type", "application/json")
+            .send_string(&request.to_string())
+            .context("Failed to query GraphQL")?
+            .into_string()
+            .context("Failed to read GraphQL response")?;
+
+        let mut response: serde_json::Value =
+            serde_json::from_str(&response).context("Failed to parse GraphQL response")?;
+
+        if let Some(errors) = response.get("errors") {
+            bail!("GraphQL query failed: {errors}");
+        }
+
+        Ok(response["data"].take())
+    }
+
+    /// Object `id` as of checkpoint `checkpoint`, read from the GraphQL service at `url`.
+    fn graphql_object_at_checkpoint(
+        &self,
+        url: &str,
+        id: &ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<Option<Object>> {
+        let data = self.query(
+            url,
+            "query ($address: SuiAddress!, $checkpoint: UInt53!) {
+                object(address: $address, atCheckpoint: $checkpoint) { objectBcs }
+            }",
+            json!({ "address": id, "checkpoint": checkpoint }),
+        )?;
+
+        let Some(object_bcs) = data["object"]["objectBcs"].as_str() else {
+            return Ok(None);
+        };
+
+        let bytes = Base64::decode(object_bcs).context("Failed to decode object BCS")?;
+        Ok(Some(
+            bcs::from_bytes(&bytes).context("Failed to deserialize object")?,
+        ))
+    }
+}
+
+impl RemoteStore for JsonRpcRemoteStore {
+    fn get_checkpoint(&self, sequence_number: CheckpointSequenceNumber) -> Result<ForkCheckpoint> {
+        let checkpoint: Checkpoint =
+            self.call("sui_getCheckpoint", json!([sequence_number.to_string()]))?;
+
+        Ok(ForkCheckpoint {
+            sequence_number: checkpoint.sequence_number,
+            epoch: checkpoint.epoch,
+            timestamp_ms: checkpoint.timestamp_ms,
+            network_total_transactions: checkpoint.network_total_transactions,
+            epoch_rolling_gas_cost_summary: checkpoint.epoch_rolling_gas_cost_summary,
+        })
+    }
+
+    fn get_object_at_checkpoint(
+        &self,
+        id: &ObjectID,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<Option<Object>> {
+        if let Some(url) = &self.graphql_url {
+            return self.graphql_object_at_checkpoint(url, id, checkpoint);
+        }
+
+        // Without an archive, the latest version of the object is only usable if it was written
+        // no later than the checkpoint. Objects that the fullnode has no record of never existed,
+        // but objects that have since been deleted or wrapped may have been live at the checkpoint.
+        let response: SuiObjectResponse = self.call(
+            "sui_getObject",
+            json!([id, SuiObjectDataOptions::bcs_lossless()]),
+        )?;
+
+        let data = match response {
+            SuiObjectResponse {
+                data: Some(data), ..
+            } => data,
+
+            SuiObjectResponse {
+                error: Some(SuiObjectResponseError::NotExists { .. }),
+                ..
+            } => return Ok(None),
+
+            SuiObjectResponse {
+                error: Some(SuiObjectResponseError::Deleted { version, .. }),
+                ..
+            } => bail!(
+                "Object {id} was deleted or wrapped at version {version}, and whether it was live \
+                 at checkpoint {checkpoint} can't be resolved without a GraphQL service to look \
+                 it up from"
+            ),
+
+            SuiObjectResponse { error, .. } => bail!(
+                "Object {id} could not be fetched: {}",
+                error.map_or_else(
+                    || "no data or error returned".to_string(),
+                    |e| e.to_string()
+                )
+            ),
+        };
+
+        let object: Object = data.try_into()?;
+        let tx: SuiTransactionBlockResponse = self.call(
+            "sui_getTransactionBlock",
+            json!([
+                object.previous_transaction,
+                SuiTransactionBlockResponseOptions::new()
+            ]),
+        )?;
+
+        let tx_checkpoint = tx.checkpoint.ok_or_else(|| {
+            anyhow!(
+                "Transaction {} is not in a checkpoint",
+                object.previous_transaction
+            )
+        })?;
+
+        if tx_checkpoint > checkpoint {
+            bail!(
+                "Object {id} was modified at checkpoint {tx_checkpoint}, after checkpoint \
+                 {checkpoint}, and its version as of that checkpoint can't be resolved without \
+                 a GraphQL service to look it up from"
+            );
+        }
+
+        Ok(Some(object))
+    }
+
+    fn get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>> {
+        let response: SuiPastObjectResponse = self.call(
+            "sui_tryGetPastObject",
+            json!([id, version, SuiObjectDataOptions::bcs_lossless()]),
+        )?;
+
+        match response {
+            SuiPastObjectResponse::VersionFound(data) => Ok(Some(data.try_into()?)),
+            _ => Ok(None),
+        }
+    }
+}
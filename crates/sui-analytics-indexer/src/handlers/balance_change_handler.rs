@@ -0,0 +1,131 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use sui_types::balance_change::{derive_balance_changes, BalanceChange};
+use sui_types::full_checkpoint_content::{CheckpointData, CheckpointTransaction};
+
+use crate::handlers::{process_transactions, AnalyticsHandler, TransactionProcessor};
+use crate::tables::BalanceChangeEntry;
+use crate::FileType;
+
+const NAME: &str = "balance_change";
+
+#[derive(Clone)]
+pub struct BalanceChangeHandler {}
+
+impl BalanceChangeHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait::async_trait]
+impl AnalyticsHandler<BalanceChangeEntry> for BalanceChangeHandler {
+    async fn process_checkpoint(
+        &self,
+        checkpoint_data: &Arc<CheckpointData>,
+    ) -> Result<Box<dyn Iterator<Item = BalanceChangeEntry> + Send + Sync>> {
+        process_transactions(checkpoint_data.clone(), Arc::new(self.clone())).await
+    }
+
+    fn file_type(&self) -> Result<FileType> {
+        Ok(FileType::BalanceChange)
+    }
+
+    fn name(&self) -> &'static str {
+        NAME
+    }
+}
+
+#[async_trait::async_trait]
+impl TransactionProcessor<BalanceChangeEntry> for BalanceChangeHandler {
+    async fn process_transaction(
+        &self,
+        tx_idx: usize,
+        checkpoint: &CheckpointData,
+    ) -> Result<Box<dyn Iterator<Item = BalanceChangeEntry> + Send + Sync>> {
+        let transaction = &checkpoint.transactions[tx_idx];
+        let epoch = checkpoint.checkpoint_summary.epoch;
+        let checkpoint_seq = checkpoint.checkpoint_summary.sequence_number;
+        let timestamp_ms = checkpoint.checkpoint_summary.timestamp_ms;
+        let transaction_digest = transaction.transaction.digest().base58_encode();
+
+        let entries: Vec<_> = balance_changes(transaction)
+            .into_iter()
+            .map(|change| BalanceChangeEntry {
+                transaction_digest: transaction_digest.clone(),
+                checkpoint: checkpoint_seq,
+                epoch,
+                timestamp_ms,
+                owner: change.address.to_string(),
+                coin_type: change.coin_type.to_canonical_string(/* with_prefix */ true),
+                amount: change.amount.to_string(),
+            })
+            .collect();
+
+        Ok(Box::new(entries.into_iter()))
+    }
+}
+
+/// Changes in the balances of the owners of coins that `transaction` touched, in the same way as
+/// they are reported for transactions by JSON-RPC.
+pub(crate) fn balance_changes(transaction: &CheckpointTransaction) -> Vec<BalanceChange> {
+    derive_balance_changes(
+        &transaction.effects,
+        &transaction.input_objects,
+        &transaction.output_objects,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simulacrum::Simulacrum;
+    use sui_types::base_types::SuiAddress;
+    use sui_types::effects::TransactionEffectsAPI;
+    use sui_types::gas_coin::GAS;
+    use sui_types::storage::ReadStore;
+    use sui_types::transaction::TransactionDataAPI;
+
+    #[tokio::test]
+    pub async fn test_balance_change_handler() -> anyhow::Result<()> {
+        let mut sim = Simulacrum::new();
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let (transaction, amount) = sim.transfer_txn(recipient);
+        let sender = transaction.transaction_data().sender();
+        let (effects, err) = sim.execute_transaction(transaction.clone()).unwrap();
+        assert!(err.is_none());
+        let gas_used = effects.gas_cost_summary().net_gas_usage();
+
+        let checkpoint = sim.create_checkpoint();
+        let checkpoint_data = sim.get_checkpoint_data(
+            checkpoint.clone(),
+            sim.get_checkpoint_contents_by_digest(&checkpoint.content_digest)
+                .unwrap(),
+        )?;
+        let mut entries: Vec<_> = BalanceChangeHandler::new()
+            .process_checkpoint(&Arc::new(checkpoint_data))
+            .await?
+            .map(|entry| (entry.owner, entry.coin_type, entry.amount))
+            .collect();
+        entries.sort();
+
+        let sui = GAS::type_tag().to_canonical_string(true);
+        let mut expected = vec![
+            (
+                sender.to_string(),
+                sui.clone(),
+                (-(amount as i64) - gas_used).to_string(),
+            ),
+            (recipient.to_string(), sui, amount.to_string()),
+        ];
+        expected.sort();
+        assert_eq!(entries, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use chrono::DateTime;
+
+use sui_types::base_types::ObjectID;
+use sui_types::coin::TreasuryCap;
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::full_checkpoint_content::{CheckpointData, CheckpointTransaction};
+use sui_types::object::Object;
+
+use crate::handlers::balance_change_handler::balance_changes;
+use crate::handlers::AnalyticsHandler;
+use crate::tables::CoinFlowEntry;
+use crate::FileType;
+
+const NAME: &str = "coin_flow";
+
+pub struct CoinFlowHandler {}
+
+impl CoinFlowHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait::async_trait]
+impl AnalyticsHandler<CoinFlowEntry> for CoinFlowHandler {
+    async fn process_checkpoint(
+        &self,
+        checkpoint_data: &Arc<CheckpointData>,
+    ) -> Result<Box<dyn Iterator<Item = CoinFlowEntry> + Send + Sync>> {
+        let entries = process_checkpoint_data(checkpoint_data)?;
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn file_type(&self) -> Result<FileType> {
+        Ok(FileType::CoinFlow)
+    }
+
+    fn name(&self) -> &'static str {
+        NAME
+    }
+}
+
+#[derive(Default)]
+struct CoinFlow {
+    transaction_count: u64,
+    balance_change_count: u64,
+    inflow: u128,
+    outflow: u128,
+    supply_change: i128,
+}
+
+fn process_checkpoint_data(checkpoint_data: &CheckpointData) -> Result<Vec<CoinFlowEntry>> {
+    let epoch = checkpoint_data.checkpoint_summary.epoch;
+    let checkpoint_seq = checkpoint_data.checkpoint_summary.sequence_number;
+    let timestamp_ms = checkpoint_data.checkpoint_summary.timestamp_ms;
+    let date = DateTime::from_timestamp_millis(timestamp_ms as i64)
+        .ok_or_else(|| anyhow!("Invalid checkpoint timestamp: {timestamp_ms}"))?
+        .date_naive()
+        .to_string();
+
+    let mut flows: BTreeMap<String, CoinFlow> = BTreeMap::new();
+    for transaction in &checkpoint_data.transactions {
+        let mut changes_by_type: BTreeMap<String, Vec<i128>> = BTreeMap::new();
+        for change in balance_changes(transaction) {
+            changes_by_type
+                .entry(change.coin_type.to_canonical_string(/* with_prefix */ true))
+                .or_default()
+                .push(change.amount);
+        }
+
+        let supply_changes = transaction_supply_changes(transaction)?;
+        let coin_types: BTreeSet<_> = changes_by_type
+            .keys()
+            .chain(supply_changes.keys())
+            .cloned()
+            .collect();
+
+        for coin_type in coin_types {
+            let flow = flows.entry(coin_type.clone()).or_default();
+            flow.transaction_count += 1;
+            flow.supply_change += supply_changes.get(&coin_type).copied().unwrap_or_default();
+
+            let amounts = changes_by_type.remove(&coin_type).unwrap_or_default();
+            flow.balance_change_count += amounts.len() as u64;
+            for amount in amounts {
+                if amount > 0 {
+                    flow.inflow += amount.unsigned_abs();
+                } else {
+                    flow.outflow += amount.unsigned_abs();
+                }
+            }
+        }
+    }
+
+    Ok(flows
+        .into_iter()
+        .map(|(coin_type, flow)| CoinFlowEntry {
+            date: date.clone(),
+            checkpoint: checkpoint_seq,
+            epoch,
+            timestamp_ms,
+            coin_type,
+            transaction_count: flow.transaction_count,
+            balance_change_count: flow.balance_change_count,
+            inflow: flow.inflow.to_string(),
+            outflow: flow.outflow.to_string(),
+            net_flow: (flow.inflow as i128 - flow.outflow as i128).to_string(),
+            supply_change: flow.supply_change.to_string(),
+        })
+        .collect())
+}
+
+fn transaction_supply_changes(
+    transaction: &CheckpointTransaction,
+) -> Result<BTreeMap<String, i128>> {
+    supply_changes(
+        &transaction.effects,
+        &transaction.input_objects,
+        &transaction.output_objects,
+    )
+}
+
+/// Changes in the total supply of coins, by coin type, from minting and burning through the
+/// `TreasuryCap`s that a transaction modified. Treasury caps that are wrapped in other objects
+/// can't be seen, so supply changes made through them are not counted.
+fn supply_changes(
+    effects: &TransactionEffects,
+    input_objects: &[Object],
+    output_objects: &[Object],
+) -> Result<BTreeMap<String, i128>> {
+    let before = treasury_supplies(input_objects)?;
+    let after = treasury_supplies(output_objects)?;
+    let created: BTreeSet<_> = effects
+        .created()
+        .into_iter()
+        .map(|((id, _, _), _)| id)
+        .collect();
+
+    let mut changes: BTreeMap<String, i128> = BTreeMap::new();
+    for (id, (coin_type, supply)) in after {
+        let previous = match before.get(&id) {
+            Some((_, previous)) => *previous,
+            // New treasury caps start with no supply, but the supply of treasury caps that have
+            // been unwrapped may have changed while they were wrapped.
+            None if created.contains(&id) => 0,
+            None => continue,
+        };
+
+        let change = supply as i128 - previous as i128;
+        if change != 0 {
+            *changes.entry(coin_type).or_default() += change;
+        }
+    }
+
+    Ok(changes)
+}
+
+/// The coin type and total supply of each `TreasuryCap` among `objects`, by ID.
+fn treasury_supplies(objects: &[Object]) -> Result<BTreeMap<ObjectID, (String, u64)>> {
+    let mut supplies = BTreeMap::new();
+    for object in objects {
+        let Some(tag) = object.struct_tag() else {
+            continue;
+        };
+
+        let Some(coin_type) = TreasuryCap::is_treasury_with_coin_type(&tag) else {
+            continue;
+        };
+
+        let Some(contents) = object.data.try_as_move().map(|o| o.contents()) else {
+            continue;
+        };
+
+        let cap = TreasuryCap::from_bcs_bytes(contents)?;
+        supplies.insert(
+            object.id(),
+            (
+                coin_type.to_canonical_string(/* with_prefix */ true),
+                cap.total_supply.value,
+            ),
+        );
+    }
+
+    Ok(supplies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::language_storage::StructTag;
+    use simulacrum::Simulacrum;
+    use sui_types::balance::Supply;
+    use sui_types::base_types::SuiAddress;
+    use sui_types::gas_coin::GAS;
+    use sui_types::id::UID;
+    use sui_types::parse_sui_struct_tag;
+    use sui_types::storage::ReadStore;
+
+    #[tokio::test]
+    pub async fn test_coin_flow_handler() -> anyhow::Result<()> {
+        let mut sim = Simulacrum::new();
+
+        let mut amounts = vec![];
+        let mut gas_used = 0;
+        for _ in 0..2 {
+            let (transaction, amount) = sim.transfer_txn(SuiAddress::random_for_testing_only());
+            let (effects, err) = sim.execute_transaction(transaction).unwrap();
+            assert!(err.is_none());
+            amounts.push(amount as i128);
+            gas_used += effects.gas_cost_summary().net_gas_usage() as i128;
+        }
+
+        let checkpoint = sim.create_checkpoint();
+        let checkpoint_data = sim.get_checkpoint_data(
+            checkpoint.clone(),
+            sim.get_checkpoint_contents_by_digest(&checkpoint.content_digest)
+                .unwrap(),
+        )?;
+        let entries: Vec<_> = CoinFlowHandler::new()
+            .process_checkpoint(&Arc::new(checkpoint_data))
+            .await?
+            .collect();
+        assert_eq!(entries.len(), 1);
+
+        let flow = &entries[0];
+        let transferred: i128 = amounts.iter().sum();
+        assert_eq!(flow.coin_type, GAS::type_tag().to_canonical_string(true));
+        assert_eq!(flow.checkpoint, checkpoint.sequence_number);
+        assert_eq!(flow.transaction_count, 2);
+        assert_eq!(flow.balance_change_count, 4);
+        assert_eq!(flow.inflow, transferred.to_string());
+        assert_eq!(flow.outflow, (transferred + gas_used).to_string());
+        assert_eq!(flow.net_flow, (-gas_used).to_string());
+        assert_eq!(flow.supply_change, "0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_supply_changes() -> anyhow::Result<()> {
+        let coin = |name: &str| parse_sui_struct_tag(&format!("0x42::{name}::{name}")).unwrap();
+        let cap = |id: ObjectID, coin_type: &StructTag, value: u64| {
+            Object::treasury_cap_for_testing(
+                coin_type.clone(),
+                TreasuryCap {
+                    id: UID::new(id),
+                    total_supply: Supply { value },
+                },
+            )
+        };
+
+        let (foo, bar) = (coin("foo"), coin("bar"));
+        let (minted, burned, unwrapped) =
+            (ObjectID::random(), ObjectID::random(), ObjectID::random());
+
+        let changes = supply_changes(
+            &TransactionEffects::default(),
+            &[cap(minted, &foo, 100), cap(burned, &bar, 500)],
+            &[
+                cap(minted, &foo, 250),
+                cap(burned, &bar, 200),
+                cap(unwrapped, &foo, 1000),
+            ],
+        )?;
+
+        // The treasury cap that appeared without being created was unwrapped, so its supply
+        // doesn't count as a change.
+        assert_eq!(
+            changes,
+            BTreeMap::from([
+                (foo.to_canonical_string(true), 150),
+                (bar.to_canonical_string(true), -300),
+            ])
+        );
+        Ok(())
+    }
+}
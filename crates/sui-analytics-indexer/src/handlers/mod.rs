@@ -22,7 +22,9 @@ use sui_types::object::{Object, Owner};
 use sui_types::transaction::TransactionData;
 use sui_types::transaction::TransactionDataAPI;
 
+pub mod balance_change_handler;
 pub mod checkpoint_handler;
+pub mod coin_flow_handler;
 pub mod df_handler;
 pub mod event_handler;
 pub mod move_call_handler;
@@ -36,7 +36,9 @@ use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
 use crate::analytics_metrics::AnalyticsMetrics;
 use crate::analytics_processor::AnalyticsProcessor;
+use crate::handlers::balance_change_handler::BalanceChangeHandler;
 use crate::handlers::checkpoint_handler::CheckpointHandler;
+use crate::handlers::coin_flow_handler::CoinFlowHandler;
 use crate::handlers::df_handler::DynamicFieldHandler;
 use crate::handlers::event_handler::EventHandler;
 use crate::handlers::move_call_handler::MoveCallHandler;
@@ -47,7 +49,10 @@ use crate::handlers::transaction_objects_handler::TransactionObjectsHandler;
 use crate::handlers::wrapped_object_handler::WrappedObjectHandler;
 use crate::handlers::AnalyticsHandler;
 use crate::tables::{InputObjectKind, ObjectStatus, OwnerType};
+use crate::writers::arrow_ipc_writer::ArrowIpcWriter;
 use crate::writers::csv_writer::CSVWriter;
+#[cfg(feature = "duckdb")]
+use crate::writers::duckdb_writer::DuckDbWriter;
 use crate::writers::parquet_writer::ParquetWriter;
 use crate::writers::AnalyticsWriter;
 use gcp_bigquery_client::model::query_response::ResultSet;
@@ -73,6 +78,8 @@ const PACKAGE_BCS_DIR_PREFIX: &str = "move_package_bcs";
 const DYNAMIC_FIELD_PREFIX: &str = "dynamic_field";
 
 const WRAPPED_OBJECT_PREFIX: &str = "wrapped_object";
+const BALANCE_CHANGE_PREFIX: &str = "balance_change";
+const COIN_FLOW_PREFIX: &str = "coin_flow";
 
 const TRANSACTION_CONCURRENCY_LIMIT_VAR_NAME: &str = "TRANSACTION_CONCURRENCY_LIMIT";
 const DEFAULT_TRANSACTION_CONCURRENCY_LIMIT: usize = 64;
@@ -191,6 +198,9 @@ pub struct JobConfig {
     pub sf_username: Option<String>,
     pub sf_role: Option<String>,
     pub sf_password_file: Option<String>,
+    /// DuckDB database that tasks with the `DUCKDB` file format load their rows into, instead of
+    /// uploading files to the remote store. Requires the `duckdb` feature.
+    pub local_db_path: Option<PathBuf>,
 
     // This is private to enforce using the TaskContext struct
     #[serde(rename = "tasks")]
@@ -210,6 +220,13 @@ impl JobConfig {
             self.rest_url.clone(),
         )));
 
+        #[cfg(feature = "duckdb")]
+        let local_db = self
+            .local_db_path
+            .as_ref()
+            .map(|path| duckdb::Connection::open(path).map(|conn| Arc::new(Mutex::new(conn))))
+            .transpose()?;
+
         let job_config = Arc::new(self);
         let mut processors = Vec::with_capacity(job_config.task_configs.len());
         let mut task_names = HashSet::new();
@@ -231,6 +248,8 @@ impl JobConfig {
                 checkpoint_dir: Arc::new(temp_dir),
                 metrics: metrics.clone(),
                 lazy_package_cache: lazy_package_cache.clone(),
+                #[cfg(feature = "duckdb")]
+                local_db: local_db.clone(),
             };
 
             processors.push(task_context.create_analytics_processor().await?);
@@ -302,6 +321,8 @@ pub struct TaskContext {
     pub checkpoint_dir: Arc<TempDir>,
     pub metrics: AnalyticsMetrics,
     pub lazy_package_cache: Arc<Mutex<LazyPackageCache>>,
+    #[cfg(feature = "duckdb")]
+    pub local_db: Option<Arc<Mutex<duckdb::Connection>>>,
 }
 
 impl TaskContext {
@@ -389,6 +410,14 @@ impl TaskContext {
                 )))
                 .await
             }
+            FileType::BalanceChange => {
+                self.create_processor_for_handler(Box::new(BalanceChangeHandler::new()))
+                    .await
+            }
+            FileType::CoinFlow => {
+                self.create_processor_for_handler(Box::new(CoinFlowHandler::new()))
+                    .await
+            }
         }
     }
 
@@ -411,13 +440,36 @@ impl TaskContext {
         .await
     }
 
+    #[cfg(feature = "duckdb")]
+    fn local_db(&self) -> Result<duckdb::Connection> {
+        let local_db = self
+            .local_db
+            .as_ref()
+            .ok_or(anyhow!("Missing local db path"))?;
+        Ok(local_db.lock().unwrap().try_clone()?)
+    }
+
+    #[cfg(feature = "duckdb")]
+    fn local_db_next_checkpoint(&self) -> Result<u64> {
+        DuckDbWriter::next_checkpoint(&self.local_db()?, self.task_name())
+    }
+
+    #[cfg(not(feature = "duckdb"))]
+    fn local_db_next_checkpoint(&self) -> Result<u64> {
+        Err(missing_duckdb_feature())
+    }
+
     async fn get_starting_checkpoint_seq_num(&self) -> Result<u64> {
-        let remote_latest = read_store_for_checkpoint(
-            &self.job_config.remote_store_config,
-            self.config.file_type,
-            self.config.remote_store_path_prefix()?.as_ref(),
-        )
-        .await?;
+        let remote_latest = if self.config.file_format == FileFormat::DUCKDB {
+            self.local_db_next_checkpoint()?
+        } else {
+            read_store_for_checkpoint(
+                &self.job_config.remote_store_config,
+                self.config.file_type,
+                self.config.remote_store_path_prefix()?.as_ref(),
+            )
+            .await?
+        };
 
         Ok(self
             .config
@@ -440,6 +492,19 @@ impl TaskContext {
                 self.config.file_type,
                 starting_checkpoint_seq_num,
             )?),
+            FileFormat::ARROW => Box::new(ArrowIpcWriter::new(
+                self.checkpoint_dir_path(),
+                self.config.file_type,
+                starting_checkpoint_seq_num,
+            )?),
+            #[cfg(feature = "duckdb")]
+            FileFormat::DUCKDB => Box::new(DuckDbWriter::new(
+                self.local_db()?,
+                self.task_name(),
+                starting_checkpoint_seq_num,
+            )?),
+            #[cfg(not(feature = "duckdb"))]
+            FileFormat::DUCKDB => return Err(missing_duckdb_feature()),
         })
     }
 
@@ -652,6 +717,10 @@ impl MaxCheckpointReader for NoOpCheckpointReader {
 pub enum FileFormat {
     CSV = 0,
     PARQUET = 1,
+    /// Arrow IPC files, also known as Feather V2 files.
+    ARROW = 2,
+    /// Rows are loaded into a local DuckDB database, rather than written to files.
+    DUCKDB = 3,
 }
 
 impl FileFormat {
@@ -659,6 +728,8 @@ impl FileFormat {
         match self {
             FileFormat::CSV => "csv",
             FileFormat::PARQUET => "parquet",
+            FileFormat::ARROW => "arrow",
+            FileFormat::DUCKDB => "duckdb",
         }
     }
 }
@@ -688,6 +759,8 @@ pub enum FileType {
     MovePackageBCS,
     DynamicField,
     WrappedObject,
+    BalanceChange,
+    CoinFlow,
 }
 
 impl FileType {
@@ -704,6 +777,8 @@ impl FileType {
             FileType::MovePackageBCS => Path::from(PACKAGE_BCS_DIR_PREFIX),
             FileType::DynamicField => Path::from(DYNAMIC_FIELD_PREFIX),
             FileType::WrappedObject => Path::from(WRAPPED_OBJECT_PREFIX),
+            FileType::BalanceChange => Path::from(BALANCE_CHANGE_PREFIX),
+            FileType::CoinFlow => Path::from(COIN_FLOW_PREFIX),
         }
     }
 
@@ -939,6 +1014,13 @@ pub fn join_paths(base: Option<&Path>, child: &Path) -> Path {
     .unwrap_or(child.clone())
 }
 
+#[cfg(not(feature = "duckdb"))]
+fn missing_duckdb_feature() -> anyhow::Error {
+    anyhow!(
+        "The DUCKDB file format requires building sui-analytics-indexer with the `duckdb` feature"
+    )
+}
+
 fn load_password(path: &str) -> anyhow::Result<String> {
     let contents = fs::read_to_string(std::path::Path::new(path))?;
     Ok(contents.trim().to_string())
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS chaindata.BALANCE_CHANGE
+(
+    transaction_digest STRING     NOT NULL,
+    checkpoint         INT64      NOT NULL,
+    epoch              INT64      NOT NULL,
+    timestamp_ms       INT64      NOT NULL,
+    owner              STRING     NOT NULL,
+    coin_type          STRING     NOT NULL,
+    amount             BIGNUMERIC NOT NULL
+) PARTITION BY RANGE_BUCKET(epoch, GENERATE_ARRAY(0, 100000, 10))
+CLUSTER BY owner, coin_type
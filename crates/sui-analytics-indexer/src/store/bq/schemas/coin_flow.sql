@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS chaindata.COIN_FLOW
+(
+    date                 DATE       NOT NULL,
+    checkpoint           INT64      NOT NULL,
+    epoch                INT64      NOT NULL,
+    timestamp_ms         INT64      NOT NULL,
+    coin_type            STRING     NOT NULL,
+    transaction_count    INT64      NOT NULL,
+    balance_change_count INT64      NOT NULL,
+    inflow               BIGNUMERIC NOT NULL,
+    outflow              BIGNUMERIC NOT NULL,
+    net_flow             BIGNUMERIC NOT NULL,
+    supply_change        BIGNUMERIC NOT NULL
+) PARTITION BY date
+CLUSTER BY coin_type
@@ -0,0 +1,16 @@
+// Daily rollup of the per-checkpoint COIN_FLOW table. This view is the only daily rollup: the
+// other outputs of the COIN_FLOW table only have per-checkpoint rows, to be summed by date.
+CREATE VIEW IF NOT EXISTS chaindata.COIN_FLOW_DAILY AS
+SELECT
+    date,
+    coin_type,
+    MIN(checkpoint)           AS first_checkpoint,
+    MAX(checkpoint)           AS last_checkpoint,
+    SUM(transaction_count)    AS transaction_count,
+    SUM(balance_change_count) AS balance_change_count,
+    SUM(inflow)               AS inflow,
+    SUM(outflow)              AS outflow,
+    SUM(net_flow)             AS net_flow,
+    SUM(supply_change)        AS supply_change
+FROM chaindata.COIN_FLOW
+GROUP BY date, coin_type
@@ -285,3 +285,49 @@ pub(crate) struct WrappedObjectEntry {
     pub(crate) json_path: String,
     pub(crate) struct_tag: Option<String>,
 }
+
+// A change in the balance of coins of one type that an owner holds, caused by a transaction.
+// Derived from the coins in the transaction's input and output objects, so gas fees are included
+// in the sender's (or sponsor's) change in SUI balance.
+#[derive(Serialize, Clone, SerializeParquet)]
+pub(crate) struct BalanceChangeEntry {
+    // indexes
+    pub(crate) transaction_digest: String,
+    pub(crate) checkpoint: u64,
+    pub(crate) epoch: u64,
+    pub(crate) timestamp_ms: u64,
+    // balance change info
+    pub(crate) owner: String,
+    pub(crate) coin_type: String,
+    // Signed amount, as a decimal string, because it can exceed the range of a 64-bit integer.
+    pub(crate) amount: String,
+}
+
+// Flow of coins of one type in a checkpoint, rolled up from the balance changes of its
+// transactions. Rows are per checkpoint so that they can be summed by `date` into daily totals,
+// regardless of how the checkpoints are split across files. Only BigQuery has the daily totals
+// built in, as the COIN_FLOW_DAILY view: CSV, parquet, Arrow and DuckDB outputs get the
+// per-checkpoint rows alone.
+#[derive(Serialize, Clone, SerializeParquet)]
+pub(crate) struct CoinFlowEntry {
+    // indexes
+    pub(crate) date: String,
+    pub(crate) checkpoint: u64,
+    pub(crate) epoch: u64,
+    pub(crate) timestamp_ms: u64,
+    pub(crate) coin_type: String,
+    // flow info
+    pub(crate) transaction_count: u64,
+    pub(crate) balance_change_count: u64,
+    // Amounts are decimal strings, because they can exceed the range of a 64-bit integer.
+    // Total increase in balances (i.e. volume).
+    pub(crate) inflow: String,
+    // Total decrease in balances.
+    pub(crate) outflow: String,
+    // Net change in balances: inflow - outflow. This is not the change in the coin's supply, as
+    // besides minting and burning, it includes gas fees and moving coins into and out of
+    // balances held by other objects.
+    pub(crate) net_flow: String,
+    // Change in the coin's total supply, from the treasury caps that transactions modified.
+    pub(crate) supply_change: String,
+}
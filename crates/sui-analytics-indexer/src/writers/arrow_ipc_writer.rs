@@ -0,0 +1,124 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::writers::record_batch_builder::RecordBatchBuilder;
+use crate::{AnalyticsWriter, FileFormat, FileType, ParquetSchema};
+use anyhow::{anyhow, Result};
+use arrow::ipc::writer::FileWriter;
+use serde::Serialize;
+use std::fs::{create_dir_all, remove_file, File};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use sui_storage::object_store::util::path_to_filesystem;
+use sui_types::base_types::EpochId;
+
+// Save table entries to Arrow IPC files (also known as Feather V2 files).
+pub(crate) struct ArrowIpcWriter {
+    root_dir_path: PathBuf,
+    file_type: FileType,
+    epoch: EpochId,
+    checkpoint_range: Range<u64>,
+    builder: RecordBatchBuilder,
+    row_count: usize,
+}
+
+impl ArrowIpcWriter {
+    pub(crate) fn new(
+        root_dir_path: &Path,
+        file_type: FileType,
+        start_checkpoint_seq_num: u64,
+    ) -> Result<Self> {
+        Ok(Self {
+            root_dir_path: root_dir_path.to_path_buf(),
+            file_type,
+            epoch: 0,
+            checkpoint_range: start_checkpoint_seq_num..u64::MAX,
+            builder: RecordBatchBuilder::default(),
+            row_count: 0,
+        })
+    }
+
+    fn file(&self) -> Result<File> {
+        let file_path = path_to_filesystem(
+            self.root_dir_path.clone(),
+            &self
+                .file_type
+                .file_path(FileFormat::ARROW, self.epoch, self.checkpoint_range.clone()),
+        )?;
+        create_dir_all(file_path.parent().ok_or(anyhow!("Bad directory path"))?)?;
+        if file_path.exists() {
+            remove_file(&file_path)?;
+        }
+        Ok(File::create(&file_path)?)
+    }
+}
+
+impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for ArrowIpcWriter {
+    fn file_format(&self) -> Result<FileFormat> {
+        Ok(FileFormat::ARROW)
+    }
+
+    fn write(&mut self, rows: Box<dyn Iterator<Item = S> + Send + Sync>) -> Result<()> {
+        self.row_count += self.builder.append(rows)?;
+        Ok(())
+    }
+
+    fn flush(&mut self, end_checkpoint_seq_num: u64) -> Result<bool> {
+        let Some(batch) = self.builder.finish::<S>()? else {
+            return Ok(false);
+        };
+
+        self.checkpoint_range.end = end_checkpoint_seq_num;
+
+        let mut writer = FileWriter::try_new(self.file()?, &batch.schema())?;
+        writer.write(&batch)?;
+        writer.finish()?;
+        Ok(true)
+    }
+
+    fn reset(&mut self, epoch_num: EpochId, start_checkpoint_seq_num: u64) -> Result<()> {
+        self.epoch = epoch_num;
+        self.checkpoint_range = start_checkpoint_seq_num..u64::MAX;
+        self.builder.clear();
+        self.row_count = 0;
+        Ok(())
+    }
+
+    fn file_size(&self) -> Result<Option<u64>> {
+        // Like the parquet writer, rows are buffered in memory until they are flushed.
+        Ok(None)
+    }
+
+    fn rows(&self) -> Result<usize> {
+        Ok(self.row_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::record_batch_builder::tests::{entries, expected_batch, Entry};
+    use arrow::ipc::reader::FileReader;
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut writer = ArrowIpcWriter::new(dir.path(), FileType::Event, 10)?;
+
+        // Nothing is written if there are no rows.
+        assert!(!AnalyticsWriter::<Entry>::flush(&mut writer, 11)?);
+
+        AnalyticsWriter::<Entry>::write(&mut writer, entries())?;
+        assert_eq!(AnalyticsWriter::<Entry>::rows(&writer)?, 2);
+        assert!(AnalyticsWriter::<Entry>::flush(&mut writer, 12)?);
+
+        let path = path_to_filesystem(
+            dir.path().to_path_buf(),
+            &FileType::Event.file_path(FileFormat::ARROW, 0, 10..12),
+        )?;
+        let batches =
+            FileReader::try_new(File::open(path)?, None)?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(batches, vec![expected_batch()]);
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::Range;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use duckdb::types::Value;
+use duckdb::{appender_params_from_iter, params, Connection};
+use serde::Serialize;
+use tracing::info;
+
+use sui_types::base_types::EpochId;
+
+use crate::writers::AnalyticsWriter;
+use crate::{FileFormat, ParquetSchema, ParquetValue};
+
+/// Table that records the checkpoint ranges that have been loaded into each table in the database.
+const CHECKPOINT_RANGES_TABLE: &str = "_checkpoint_ranges";
+
+// Load table entries into a local DuckDB database, so that they can be queried with SQL. Each
+// task's entries go into a table named after the task, with the same columns as its CSV and
+// parquet files. Rather than being written to a file, the entries for each checkpoint range are
+// loaded into the table in the same transaction that records the range in `_checkpoint_ranges`,
+// which is also where the task resumes from when it restarts.
+pub(crate) struct DuckDbWriter {
+    conn: Mutex<Connection>,
+    table: String,
+    epoch: EpochId,
+    checkpoint_range: Range<u64>,
+    rows: Vec<Vec<ParquetValue>>,
+}
+
+impl DuckDbWriter {
+    pub(crate) fn new(
+        conn: Connection,
+        table: &str,
+        start_checkpoint_seq_num: u64,
+    ) -> Result<Self> {
+        create_checkpoint_ranges_table(&conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            table: table.to_string(),
+            epoch: 0,
+            checkpoint_range: start_checkpoint_seq_num..u64::MAX,
+            rows: vec![],
+        })
+    }
+
+    /// The checkpoint to resume loading `table` from: The end of the last checkpoint range loaded
+    /// into it, or 0 if nothing has been loaded into it yet.
+    pub(crate) fn next_checkpoint(conn: &Connection, table: &str) -> Result<u64> {
+        create_checkpoint_ranges_table(conn)?;
+        let end: Option<u64> = conn.query_row(
+            &format!(
+                "SELECT max(end_checkpoint) FROM {CHECKPOINT_RANGES_TABLE} WHERE table_name = ?"
+            ),
+            [table],
+            |row| row.get(0),
+        )?;
+        Ok(end.unwrap_or(0))
+    }
+
+    fn create_table_sql(&self, columns: &[String], first_row: &[ParquetValue]) -> String {
+        let columns: Vec<_> = columns
+            .iter()
+            .zip(first_row)
+            .map(|(name, value)| {
+                let type_ = match value {
+                    ParquetValue::U64(_) | ParquetValue::OptionU64(_) => "UBIGINT",
+                    ParquetValue::I64(_) => "BIGINT",
+                    ParquetValue::Bool(_) => "BOOLEAN",
+                    ParquetValue::Str(_) | ParquetValue::OptionStr(_) => "VARCHAR",
+                };
+                format!("{} {type_}", quote_identifier(name))
+            })
+            .collect();
+
+        format!(
+            "CREATE TABLE IF NOT EXISTS {} ({})",
+            quote_identifier(&self.table),
+            columns.join(", ")
+        )
+    }
+}
+
+impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for DuckDbWriter {
+    fn file_format(&self) -> Result<FileFormat> {
+        Ok(FileFormat::DUCKDB)
+    }
+
+    fn write(&mut self, rows: Box<dyn Iterator<Item = S> + Send + Sync>) -> Result<()> {
+        let columns = S::schema().len();
+        self.rows
+            .extend(rows.map(|row| (0..columns).map(|i| row.get_column(i)).collect()));
+        Ok(())
+    }
+
+    fn flush(&mut self, end_checkpoint_seq_num: u64) -> Result<bool> {
+        self.checkpoint_range.end = end_checkpoint_seq_num;
+        let rows = std::mem::take(&mut self.rows);
+        let row_count = rows.len();
+
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        if let Some(first_row) = rows.first() {
+            tx.execute_batch(&self.create_table_sql(&S::schema(), first_row))?;
+            let mut appender = tx.appender(&self.table)?;
+            for row in rows {
+                appender.append_row(appender_params_from_iter(row.into_iter().map(to_value)))?;
+            }
+            appender.flush()?;
+        }
+
+        tx.execute(
+            &format!("INSERT INTO {CHECKPOINT_RANGES_TABLE} VALUES (?, ?, ?, ?, ?)"),
+            params![
+                self.table,
+                self.epoch,
+                self.checkpoint_range.start,
+                self.checkpoint_range.end,
+                row_count as u64,
+            ],
+        )?;
+        tx.commit()?;
+
+        info!(
+            "Loaded {row_count} rows for checkpoints {:?} into table {}",
+            self.checkpoint_range, self.table
+        );
+
+        // The rows are already in the database, so there is no file to upload.
+        Ok(false)
+    }
+
+    fn reset(&mut self, epoch_num: EpochId, start_checkpoint_seq_num: u64) -> Result<()> {
+        self.epoch = epoch_num;
+        self.checkpoint_range = start_checkpoint_seq_num..u64::MAX;
+        self.rows.clear();
+        Ok(())
+    }
+
+    fn file_size(&self) -> Result<Option<u64>> {
+        Ok(None)
+    }
+
+    fn rows(&self) -> Result<usize> {
+        Ok(self.rows.len())
+    }
+}
+
+fn create_checkpoint_ranges_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS {CHECKPOINT_RANGES_TABLE} (
+            table_name VARCHAR NOT NULL,
+            epoch UBIGINT NOT NULL,
+            start_checkpoint UBIGINT NOT NULL,
+            end_checkpoint UBIGINT NOT NULL,
+            row_count UBIGINT NOT NULL
+        )"
+    ))
+    .map_err(|e| anyhow!("Failed to create {CHECKPOINT_RANGES_TABLE} table: {e}"))
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn to_value(value: ParquetValue) -> Value {
+    match value {
+        ParquetValue::U64(v) => Value::UBigInt(v),
+        ParquetValue::I64(v) => Value::BigInt(v),
+        ParquetValue::Bool(v) => Value::Boolean(v),
+        ParquetValue::Str(v) => Value::Text(v),
+        ParquetValue::OptionU64(v) => v.map_or(Value::Null, Value::UBigInt),
+        ParquetValue::OptionStr(v) => v.map_or(Value::Null, Value::Text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_analytics_indexer_derive::SerializeParquet;
+
+    #[derive(Serialize, Clone, SerializeParquet)]
+    struct Entry {
+        checkpoint: u64,
+        amount: i64,
+        owner: Option<String>,
+        deleted: bool,
+    }
+
+    fn entries(checkpoint: u64) -> Box<dyn Iterator<Item = Entry> + Send + Sync> {
+        Box::new(
+            [
+                Entry {
+                    checkpoint,
+                    amount: -1,
+                    owner: None,
+                    deleted: false,
+                },
+                Entry {
+                    checkpoint,
+                    amount: 2,
+                    owner: Some("0x1".to_string()),
+                    deleted: true,
+                },
+            ]
+            .into_iter(),
+        )
+    }
+
+    #[test]
+    fn loads_checkpoint_ranges() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        assert_eq!(DuckDbWriter::next_checkpoint(&conn, "entries")?, 0);
+
+        let mut writer = DuckDbWriter::new(conn.try_clone()?, "entries", 0)?;
+        AnalyticsWriter::<Entry>::write(&mut writer, entries(0))?;
+        AnalyticsWriter::<Entry>::write(&mut writer, entries(1))?;
+        assert!(!AnalyticsWriter::<Entry>::flush(&mut writer, 2)?);
+
+        // A range without any rows still counts as loaded.
+        AnalyticsWriter::<Entry>::reset(&mut writer, 1, 2)?;
+        assert!(!AnalyticsWriter::<Entry>::flush(&mut writer, 5)?);
+
+        AnalyticsWriter::<Entry>::reset(&mut writer, 1, 5)?;
+        AnalyticsWriter::<Entry>::write(&mut writer, entries(5))?;
+        assert_eq!(AnalyticsWriter::<Entry>::rows(&writer)?, 2);
+        assert!(!AnalyticsWriter::<Entry>::flush(&mut writer, 6)?);
+
+        assert_eq!(DuckDbWriter::next_checkpoint(&conn, "entries")?, 6);
+        let (rows, amount, owners): (i64, i64, i64) = conn.query_row(
+            "SELECT count(*), sum(amount)::BIGINT, count(owner) FROM entries
+                WHERE NOT deleted OR checkpoint = 5",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        assert_eq!((rows, amount, owners), (4, -1, 1));
+        Ok(())
+    }
+}
@@ -6,8 +6,12 @@ use anyhow::Result;
 use serde::Serialize;
 use sui_types::base_types::EpochId;
 
+pub mod arrow_ipc_writer;
 pub mod csv_writer;
+#[cfg(feature = "duckdb")]
+pub mod duckdb_writer;
 pub mod parquet_writer;
+mod record_batch_builder;
 
 pub trait AnalyticsWriter<S: Serialize + ParquetSchema>: Send + Sync + 'static {
     /// File format i.e. csv, parquet, etc
@@ -1,17 +1,13 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{AnalyticsWriter, FileFormat, FileType, ParquetSchema, ParquetValue};
+use crate::writers::record_batch_builder::RecordBatchBuilder;
+use crate::{AnalyticsWriter, FileFormat, FileType, ParquetSchema};
 use anyhow::{anyhow, Result};
-use arrow_array::{
-    builder::{ArrayBuilder, BooleanBuilder, GenericStringBuilder, Int64Builder, UInt64Builder},
-    ArrayRef, RecordBatch,
-};
 use serde::Serialize;
 use std::fs::{create_dir_all, remove_file, File};
 use std::ops::Range;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
 use sui_storage::object_store::util::path_to_filesystem;
 use sui_types::base_types::EpochId;
 
@@ -19,42 +15,13 @@ use parquet::arrow::ArrowWriter;
 use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
 
-type StrBuilder = GenericStringBuilder<i32>;
-
-enum ColumnBuilder {
-    U64(UInt64Builder),
-    I64(Int64Builder),
-    Bool(BooleanBuilder),
-    Str(StrBuilder),
-}
-
-impl ColumnBuilder {
-    fn as_any_builder(&mut self) -> &mut dyn ArrayBuilder {
-        match self {
-            Self::U64(b) => b,
-            Self::I64(b) => b,
-            Self::Bool(b) => b,
-            Self::Str(b) => b,
-        }
-    }
-
-    fn finish(self) -> ArrayRef {
-        match self {
-            Self::U64(mut b) => Arc::new(b.finish()),
-            Self::I64(mut b) => Arc::new(b.finish()),
-            Self::Bool(mut b) => Arc::new(b.finish()),
-            Self::Str(mut b) => Arc::new(b.finish()),
-        }
-    }
-}
-
 // Save table entries to parquet files.
 pub(crate) struct ParquetWriter {
     root_dir_path: PathBuf,
     file_type: FileType,
     epoch: EpochId,
     checkpoint_range: Range<u64>,
-    builders: Vec<ColumnBuilder>,
+    builder: RecordBatchBuilder,
     row_count: usize,
 }
 
@@ -69,7 +36,7 @@ impl ParquetWriter {
             file_type,
             epoch: 0,
             checkpoint_range: start_checkpoint_seq_num..u64::MAX,
-            builders: vec![],
+            builder: RecordBatchBuilder::default(),
             row_count: 0,
         })
     }
@@ -97,82 +64,18 @@ impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for ParquetWriter {
     }
 
     fn write(&mut self, rows: Box<dyn Iterator<Item = S> + Send + Sync>) -> Result<()> {
-        // Make the iterator peekable
-        let mut row_iter = rows.peekable();
-
-        // Check if iterator is empty
-        if row_iter.peek().is_none() {
-            return Ok(());
-        }
-
-        // Lazily sample the first row to infer the schema and decide which concrete builder to instantiate
-        if self.builders.is_empty() {
-            if let Some(first_row) = row_iter.peek() {
-                for col_idx in 0..S::schema().len() {
-                    let value = first_row.get_column(col_idx);
-                    self.builders.push(match value {
-                        ParquetValue::U64(_) | ParquetValue::OptionU64(_) => {
-                            ColumnBuilder::U64(UInt64Builder::new())
-                        }
-                        ParquetValue::I64(_) => ColumnBuilder::I64(Int64Builder::new()),
-                        ParquetValue::Bool(_) => ColumnBuilder::Bool(BooleanBuilder::new()),
-                        ParquetValue::Str(_) | ParquetValue::OptionStr(_) => {
-                            ColumnBuilder::Str(StrBuilder::new())
-                        }
-                    });
-                }
-            }
-        }
-
-        let mut count = 0;
-        for row in row_iter {
-            count += 1;
-            for (col_idx, value) in (0..S::schema().len()).map(|i| (i, row.get_column(i))) {
-                match (&mut self.builders[col_idx], value) {
-                    (ColumnBuilder::U64(b), ParquetValue::U64(v)) => b.append_value(v),
-                    (ColumnBuilder::I64(b), ParquetValue::I64(v)) => b.append_value(v),
-                    (ColumnBuilder::Bool(b), ParquetValue::Bool(v)) => b.append_value(v),
-                    (ColumnBuilder::Str(b), ParquetValue::Str(v)) => b.append_value(&v),
-
-                    (ColumnBuilder::U64(b), ParquetValue::OptionU64(opt)) => match opt {
-                        Some(v) => b.append_value(v),
-                        None => b.append_null(),
-                    },
-                    (ColumnBuilder::Str(b), ParquetValue::OptionStr(opt)) => match opt {
-                        Some(v) => b.append_value(&v),
-                        None => b.append_null(),
-                    },
-
-                    _ => return Err(anyhow!("type mismatch on column {}", col_idx)),
-                }
-            }
-        }
-
-        self.row_count += count;
+        self.row_count += self.builder.append(rows)?;
         Ok(())
     }
 
     fn flush(&mut self, end_checkpoint_seq_num: u64) -> Result<bool> {
         // Nothing to flush if builders aren't initialized or are empty
-        if self.builders.is_empty()
-            || self
-                .builders
-                .iter_mut()
-                .all(|b| b.as_any_builder().is_empty())
-        {
+        let Some(batch) = self.builder.finish::<S>()? else {
             return Ok(false);
-        }
+        };
 
         self.checkpoint_range.end = end_checkpoint_seq_num;
 
-        // Turn builders into Arrow arrays.
-        let arrays: Vec<ArrayRef> = std::mem::take(&mut self.builders)
-            .into_iter()
-            .map(|b| b.finish())
-            .collect();
-
-        let batch = RecordBatch::try_from_iter(S::schema().iter().zip(arrays))?;
-
         let propertiess = WriterProperties::builder()
             .set_compression(Compression::SNAPPY)
             .build();
@@ -185,7 +88,7 @@ impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for ParquetWriter {
     fn reset(&mut self, epoch_num: EpochId, start_checkpoint_seq_num: u64) -> Result<()> {
         self.epoch = epoch_num;
         self.checkpoint_range = start_checkpoint_seq_num..u64::MAX;
-        self.builders.clear();
+        self.builder.clear();
         self.row_count = 0;
         Ok(())
     }
@@ -201,3 +104,40 @@ impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for ParquetWriter {
         Ok(self.row_count)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::record_batch_builder::tests::{entries, expected_batch, Entry};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut writer = ParquetWriter::new(dir.path(), FileType::Event, 10)?;
+
+        // Nothing is written if there are no rows.
+        assert!(!AnalyticsWriter::<Entry>::flush(&mut writer, 11)?);
+
+        AnalyticsWriter::<Entry>::write(&mut writer, entries())?;
+        assert_eq!(AnalyticsWriter::<Entry>::rows(&writer)?, 2);
+        assert!(AnalyticsWriter::<Entry>::flush(&mut writer, 12)?);
+
+        let path = path_to_filesystem(
+            dir.path().to_path_buf(),
+            &FileType::Event.file_path(FileFormat::PARQUET, 0, 10..12),
+        )?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?;
+
+        // Every column is compressed with snappy.
+        let row_group = reader.metadata().row_group(0);
+        assert_eq!(row_group.num_rows(), 2);
+        for column in row_group.columns() {
+            assert_eq!(column.compression(), Compression::SNAPPY);
+        }
+
+        let batches = reader.build()?.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(batches, vec![expected_batch()]);
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{ParquetSchema, ParquetValue};
+use anyhow::{anyhow, Result};
+use arrow_array::{
+    builder::{ArrayBuilder, BooleanBuilder, GenericStringBuilder, Int64Builder, UInt64Builder},
+    ArrayRef, RecordBatch,
+};
+use std::sync::Arc;
+
+type StrBuilder = GenericStringBuilder<i32>;
+
+enum ColumnBuilder {
+    U64(UInt64Builder),
+    I64(Int64Builder),
+    Bool(BooleanBuilder),
+    Str(StrBuilder),
+}
+
+impl ColumnBuilder {
+    fn as_any_builder(&mut self) -> &mut dyn ArrayBuilder {
+        match self {
+            Self::U64(b) => b,
+            Self::I64(b) => b,
+            Self::Bool(b) => b,
+            Self::Str(b) => b,
+        }
+    }
+
+    fn finish(self) -> ArrayRef {
+        match self {
+            Self::U64(mut b) => Arc::new(b.finish()),
+            Self::I64(mut b) => Arc::new(b.finish()),
+            Self::Bool(mut b) => Arc::new(b.finish()),
+            Self::Str(mut b) => Arc::new(b.finish()),
+        }
+    }
+}
+
+// Accumulates table entries column by column, for writers that output Arrow record batches.
+#[derive(Default)]
+pub(crate) struct RecordBatchBuilder {
+    builders: Vec<ColumnBuilder>,
+}
+
+impl RecordBatchBuilder {
+    /// Append rows to the batch, returning the number of rows appended.
+    pub(crate) fn append<S: ParquetSchema>(
+        &mut self,
+        rows: impl Iterator<Item = S>,
+    ) -> Result<usize> {
+        // Make the iterator peekable
+        let mut row_iter = rows.peekable();
+
+        // Check if iterator is empty
+        if row_iter.peek().is_none() {
+            return Ok(0);
+        }
+
+        // Lazily sample the first row to infer the schema and decide which concrete builder to instantiate
+        if self.builders.is_empty() {
+            if let Some(first_row) = row_iter.peek() {
+                for col_idx in 0..S::schema().len() {
+                    let value = first_row.get_column(col_idx);
+                    self.builders.push(match value {
+                        ParquetValue::U64(_) | ParquetValue::OptionU64(_) => {
+                            ColumnBuilder::U64(UInt64Builder::new())
+                        }
+                        ParquetValue::I64(_) => ColumnBuilder::I64(Int64Builder::new()),
+                        ParquetValue::Bool(_) => ColumnBuilder::Bool(BooleanBuilder::new()),
+                        ParquetValue::Str(_) | ParquetValue::OptionStr(_) => {
+                            ColumnBuilder::Str(StrBuilder::new())
+                        }
+                    });
+                }
+            }
+        }
+
+        let mut count = 0;
+        for row in row_iter {
+            count += 1;
+            for (col_idx, value) in (0..S::schema().len()).map(|i| (i, row.get_column(i))) {
+                match (&mut self.builders[col_idx], value) {
+                    (ColumnBuilder::U64(b), ParquetValue::U64(v)) => b.append_value(v),
+                    (ColumnBuilder::I64(b), ParquetValue::I64(v)) => b.append_value(v),
+                    (ColumnBuilder::Bool(b), ParquetValue::Bool(v)) => b.append_value(v),
+                    (ColumnBuilder::Str(b), ParquetValue::Str(v)) => b.append_value(&v),
+
+                    (ColumnBuilder::U64(b), ParquetValue::OptionU64(opt)) => match opt {
+                        Some(v) => b.append_value(v),
+                        None => b.append_null(),
+                    },
+                    (ColumnBuilder::Str(b), ParquetValue::OptionStr(opt)) => match opt {
+                        Some(v) => b.append_value(&v),
+                        None => b.append_null(),
+                    },
+
+                    _ => return Err(anyhow!("type mismatch on column {}", col_idx)),
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    pub(crate) fn is_empty(&mut self) -> bool {
+        self.builders.is_empty()
+            || self
+                .builders
+                .iter_mut()
+                .all(|b| b.as_any_builder().is_empty())
+    }
+
+    /// Turn the rows appended so far into a record batch, or return `None` if there are none.
+    pub(crate) fn finish<S: ParquetSchema>(&mut self) -> Result<Option<RecordBatch>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+
+        // Turn builders into Arrow arrays.
+        let arrays: Vec<ArrayRef> = std::mem::take(&mut self.builders)
+            .into_iter()
+            .map(|b| b.finish())
+            .collect();
+
+        Ok(Some(RecordBatch::try_from_iter(
+            S::schema().iter().zip(arrays),
+        )?))
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.builders.clear();
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use arrow_array::{BooleanArray, Int64Array, StringArray, UInt64Array};
+    use serde::Serialize;
+    use sui_analytics_indexer_derive::SerializeParquet;
+
+    #[derive(Serialize, Clone, SerializeParquet)]
+    pub(crate) struct Entry {
+        checkpoint: u64,
+        amount: i64,
+        owner: Option<String>,
+        previous_version: Option<u64>,
+        deleted: bool,
+    }
+
+    pub(crate) fn entries() -> Box<dyn Iterator<Item = Entry> + Send + Sync> {
+        Box::new(
+            [
+                Entry {
+                    checkpoint: 1,
+                    amount: -1,
+                    owner: None,
+                    previous_version: Some(3),
+                    deleted: false,
+                },
+                Entry {
+                    checkpoint: 2,
+                    amount: 2,
+                    owner: Some("0x1".to_string()),
+                    previous_version: None,
+                    deleted: true,
+                },
+            ]
+            .into_iter(),
+        )
+    }
+
+    /// The record batch that `entries` should turn into.
+    pub(crate) fn expected_batch() -> RecordBatch {
+        RecordBatch::try_from_iter([
+            (
+                "checkpoint",
+                Arc::new(UInt64Array::from(vec![1, 2])) as ArrayRef,
+            ),
+            ("amount", Arc::new(Int64Array::from(vec![-1, 2]))),
+            (
+                "owner",
+                Arc::new(StringArray::from(vec![None, Some("0x1")])),
+            ),
+            (
+                "previous_version",
+                Arc::new(UInt64Array::from(vec![Some(3), None])),
+            ),
+            ("deleted", Arc::new(BooleanArray::from(vec![false, true]))),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn builds_record_batch() -> Result<()> {
+        let mut builder = RecordBatchBuilder::default();
+        assert!(builder.finish::<Entry>()?.is_none());
+
+        assert_eq!(builder.append(entries())?, 2);
+        assert_eq!(builder.finish::<Entry>()?, Some(expected_batch()));
+
+        // Finishing a batch starts a new one.
+        assert!(builder.is_empty());
+        assert!(builder.finish::<Entry>()?.is_none());
+        Ok(())
+    }
+}
@@ -116,6 +116,7 @@ impl FaucetClient for LocalFaucetClient {
         FaucetResponse {
             status: RequestStatus::Success,
             coins_sent: Some(coins),
+            request_id: None,
         }
     }
 }
@@ -17,7 +17,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::collections::{BTreeMap, BTreeSet};
 use std::net::SocketAddr;
-use std::num::{NonZeroU32, NonZeroUsize};
+use std::num::{NonZeroU32, NonZeroU64, NonZeroUsize};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
@@ -1162,6 +1162,11 @@ pub struct StateSnapshotConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_store_config: Option<ObjectStoreConfig>,
     pub concurrency: usize,
+    /// If set, snapshots are written as deltas on top of the previous epoch's snapshot, and a full
+    /// snapshot is only written for every epoch that is a multiple of this interval (or when the
+    /// previous epoch's snapshot is missing). Every snapshot is a full snapshot if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_snapshot_interval: Option<NonZeroU64>,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
+use anyhow::{anyhow, bail};
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use sui_types::base_types::{ObjectID, SuiAddress};
@@ -37,6 +40,20 @@ pub struct TransactionDenyConfig {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     address_deny_list: Vec<SuiAddress>,
 
+    /// A list of functions (`package::module::function`) that are not allowed to be called
+    /// directly from transactions. Only the exact package ID is matched, so calls to the same
+    /// function in other versions of the package are still allowed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    function_deny_list: Vec<QualifiedName>,
+
+    /// A list of Move struct types (`address::module::Name`) that are not allowed to be used as
+    /// the types of input objects (or receiving objects), or as type arguments in calls. A type
+    /// is also denied if it appears as a type parameter (e.g. denying `0x2::sui::SUI` also denies
+    /// `0x2::coin::Coin<0x2::sui::SUI>`). The address is that of the package that first defined
+    /// the type.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    type_deny_list: Vec<QualifiedName>,
+
     /// Whether publishing new packages is disabled.
     #[serde(default)]
     package_publish_disabled: bool,
@@ -64,6 +81,12 @@ pub struct TransactionDenyConfig {
     #[serde(skip)]
     address_deny_set: OnceCell<HashSet<SuiAddress>>,
 
+    #[serde(skip)]
+    function_deny_set: OnceCell<HashSet<QualifiedName>>,
+
+    #[serde(skip)]
+    type_deny_set: OnceCell<HashSet<QualifiedName>>,
+
     /// Whether receiving objects transferred to other objects is allowed
     #[serde(default)]
     receiving_objects_disabled: bool,
@@ -106,6 +129,94 @@ impl TransactionDenyConfig {
             .get_or_init(|| self.address_deny_list.iter().cloned().collect())
     }
 
+    pub fn get_function_deny_set(&self) -> &HashSet<QualifiedName> {
+        self.function_deny_set
+            .get_or_init(|| self.function_deny_list.iter().cloned().collect())
+    }
+
+    pub fn get_type_deny_set(&self) -> &HashSet<QualifiedName> {
+        self.type_deny_set
+            .get_or_init(|| self.type_deny_list.iter().cloned().collect())
+    }
+
+    /// All the rules that deny specific objects, packages, addresses, functions or types.
+    pub fn rules(&self) -> Vec<DenyRule> {
+        let objects = self.object_deny_list.iter().copied().map(DenyRule::Object);
+        let packages = self
+            .package_deny_list
+            .iter()
+            .copied()
+            .map(DenyRule::Package);
+        let addresses = self
+            .address_deny_list
+            .iter()
+            .copied()
+            .map(DenyRule::Address);
+        let functions = self
+            .function_deny_list
+            .iter()
+            .cloned()
+            .map(DenyRule::Function);
+        let types = self.type_deny_list.iter().cloned().map(DenyRule::Type);
+
+        objects
+            .chain(packages)
+            .chain(addresses)
+            .chain(functions)
+            .chain(types)
+            .collect()
+    }
+
+    /// Add `rule` to the config. Returns `false` if the rule was already present.
+    pub fn add_rule(&mut self, rule: DenyRule) -> bool {
+        fn add<T: PartialEq>(list: &mut Vec<T>, item: T) -> bool {
+            if list.contains(&item) {
+                return false;
+            }
+            list.push(item);
+            true
+        }
+
+        let added = match rule {
+            DenyRule::Object(id) => add(&mut self.object_deny_list, id),
+            DenyRule::Package(id) => add(&mut self.package_deny_list, id),
+            DenyRule::Address(address) => add(&mut self.address_deny_list, address),
+            DenyRule::Function(name) => add(&mut self.function_deny_list, name),
+            DenyRule::Type(name) => add(&mut self.type_deny_list, name),
+        };
+
+        self.reset_deny_sets();
+        added
+    }
+
+    /// Remove `rule` from the config. Returns `false` if the rule was not present.
+    pub fn remove_rule(&mut self, rule: &DenyRule) -> bool {
+        fn remove<T: PartialEq>(list: &mut Vec<T>, item: &T) -> bool {
+            let len = list.len();
+            list.retain(|i| i != item);
+            list.len() != len
+        }
+
+        let removed = match rule {
+            DenyRule::Object(id) => remove(&mut self.object_deny_list, id),
+            DenyRule::Package(id) => remove(&mut self.package_deny_list, id),
+            DenyRule::Address(address) => remove(&mut self.address_deny_list, address),
+            DenyRule::Function(name) => remove(&mut self.function_deny_list, name),
+            DenyRule::Type(name) => remove(&mut self.type_deny_list, name),
+        };
+
+        self.reset_deny_sets();
+        removed
+    }
+
+    fn reset_deny_sets(&mut self) {
+        self.object_deny_set = OnceCell::new();
+        self.package_deny_set = OnceCell::new();
+        self.address_deny_set = OnceCell::new();
+        self.function_deny_set = OnceCell::new();
+        self.type_deny_set = OnceCell::new();
+    }
+
     pub fn package_publish_disabled(&self) -> bool {
         self.package_publish_disabled
     }
@@ -193,6 +304,16 @@ impl TransactionDenyConfigBuilder {
         self
     }
 
+    pub fn add_denied_function(mut self, function: QualifiedName) -> Self {
+        self.config.function_deny_list.push(function);
+        self
+    }
+
+    pub fn add_denied_type(mut self, type_: QualifiedName) -> Self {
+        self.config.type_deny_list.push(type_);
+        self
+    }
+
     pub fn disable_zklogin_sig(mut self) -> Self {
         self.config.zklogin_sig_disabled = true;
         self
@@ -211,3 +332,180 @@ impl TransactionDenyConfigBuilder {
         Ok(self)
     }
 }
+
+/// The fully qualified name of a Move function or struct type, `address::module::name`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct QualifiedName {
+    pub address: ObjectID,
+    pub module: String,
+    pub name: String,
+}
+
+impl QualifiedName {
+    pub fn new(address: ObjectID, module: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            address,
+            module: module.into(),
+            name: name.into(),
+        }
+    }
+}
+
+impl FromStr for QualifiedName {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split("::");
+        let (Some(address), Some(module), Some(name), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            bail!("Expected `address::module::name`, got {s:?}");
+        };
+
+        if module.is_empty() || name.is_empty() {
+            bail!("Expected `address::module::name`, got {s:?}");
+        }
+
+        Ok(Self {
+            address: ObjectID::from_str(address)
+                .map_err(|e| anyhow!("Invalid address in {s:?}: {e}"))?,
+            module: module.to_owned(),
+            name: name.to_owned(),
+        })
+    }
+}
+
+impl TryFrom<String> for QualifiedName {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<QualifiedName> for String {
+    fn from(name: QualifiedName) -> Self {
+        name.to_string()
+    }
+}
+
+impl fmt::Display for QualifiedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}::{}", self.address, self.module, self.name)
+    }
+}
+
+/// A single rule in a [`TransactionDenyConfig`], denying a specific object, package, address,
+/// function or type. Rules are written as `kind:value`, e.g. `object:0x123`, or
+/// `function:0x2::coin::mint`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum DenyRule {
+    Object(ObjectID),
+    Package(ObjectID),
+    Address(SuiAddress),
+    Function(QualifiedName),
+    Type(QualifiedName),
+}
+
+impl FromStr for DenyRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((kind, value)) = s.split_once(':') else {
+            bail!("Expected `kind:value`, got {s:?}");
+        };
+
+        Ok(match kind {
+            "object" => DenyRule::Object(
+                ObjectID::from_str(value).map_err(|e| anyhow!("Invalid object ID: {e}"))?,
+            ),
+            "package" => DenyRule::Package(
+                ObjectID::from_str(value).map_err(|e| anyhow!("Invalid package ID: {e}"))?,
+            ),
+            "address" => DenyRule::Address(
+                SuiAddress::from_str(value).map_err(|e| anyhow!("Invalid address: {e}"))?,
+            ),
+            "function" => DenyRule::Function(value.parse()?),
+            "type" => DenyRule::Type(value.parse()?),
+            _ => bail!(
+                "Unknown kind of deny rule {kind:?}, expected one of object, package, address, \
+                 function or type"
+            ),
+        })
+    }
+}
+
+impl TryFrom<String> for DenyRule {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<DenyRule> for String {
+    fn from(rule: DenyRule) -> Self {
+        rule.to_string()
+    }
+}
+
+impl fmt::Display for DenyRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DenyRule::Object(id) => write!(f, "object:{id}"),
+            DenyRule::Package(id) => write!(f, "package:{id}"),
+            DenyRule::Address(address) => write!(f, "address:{address}"),
+            DenyRule::Function(name) => write!(f, "function:{name}"),
+            DenyRule::Type(name) => write!(f, "type:{name}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deny_rule_round_trip() {
+        for rule in [
+            "object:0x0000000000000000000000000000000000000000000000000000000000000123",
+            "address:0x0000000000000000000000000000000000000000000000000000000000000456",
+            "function:0x0000000000000000000000000000000000000000000000000000000000000002::coin::mint",
+            "type:0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI",
+        ] {
+            let parsed: DenyRule = rule.parse().unwrap();
+            assert_eq!(parsed.to_string(), rule);
+        }
+
+        assert!("function:0x2::coin".parse::<DenyRule>().is_err());
+        assert!("function:0x2::coin::mint::extra"
+            .parse::<DenyRule>()
+            .is_err());
+        assert!("module:0x2::coin".parse::<DenyRule>().is_err());
+    }
+
+    #[test]
+    fn add_and_remove_rules() {
+        let mut config = TransactionDenyConfigBuilder::new()
+            .add_denied_object(ObjectID::ZERO)
+            .build();
+
+        // Populate the lookup sets, to check that they are kept up to date.
+        assert!(config.get_object_deny_set().contains(&ObjectID::ZERO));
+
+        let function: DenyRule = "function:0x2::coin::mint".parse().unwrap();
+        assert!(config.add_rule(function.clone()));
+        assert!(!config.add_rule(function.clone()));
+        assert_eq!(
+            config.rules(),
+            vec![DenyRule::Object(ObjectID::ZERO), function]
+        );
+        assert_eq!(config.get_function_deny_set().len(), 1);
+
+        assert!(config.remove_rule(&DenyRule::Object(ObjectID::ZERO)));
+        assert!(!config.remove_rule(&DenyRule::Object(ObjectID::ZERO)));
+        assert!(config.get_object_deny_set().is_empty());
+    }
+}
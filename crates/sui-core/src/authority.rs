@@ -57,6 +57,7 @@ use std::{
     vec,
 };
 use sui_config::node::{AuthorityOverloadConfig, StateDebugDumpConfig};
+use sui_config::transaction_deny_config::DenyRule;
 use sui_config::NodeConfig;
 use sui_protocol_config::PerObjectCongestionControlMode;
 use sui_types::crypto::RandomnessRound;
@@ -181,6 +182,7 @@ pub use crate::checkpoints::checkpoint_executor::utils::{
 };
 
 use crate::authority::authority_store_tables::AuthorityPrunerTables;
+use crate::authority::transaction_deny_rules::TransactionDenyRules;
 use crate::authority_client::NetworkAuthorityClient;
 use crate::validator_tx_finalizer::ValidatorTxFinalizer;
 #[cfg(msim)]
@@ -239,6 +241,7 @@ pub mod shared_object_congestion_tracker;
 pub mod shared_object_version_manager;
 pub mod test_authority_builder;
 pub mod transaction_deferral;
+pub mod transaction_deny_rules;
 mod weighted_moving_average;
 
 pub(crate) mod authority_store;
@@ -382,6 +385,10 @@ const GAS_LATENCY_RATIO_BUCKETS: &[f64] = &[
     3000.0, 4000.0, 5000.0, 6000.0, 7000.0, 8000.0, 9000.0, 10000.0, 50000.0, 100000.0, 1000000.0,
 ];
 
+/// The file (in the node's DB directory) that changes made to the transaction deny config at
+/// runtime are persisted to.
+const TRANSACTION_DENY_RULES_FILE: &str = "transaction_deny_rules.json";
+
 pub const DEV_INSPECT_GAS_COIN_VALUE: u64 = 1_000_000_000_000_000;
 
 impl AuthorityMetrics {
@@ -935,6 +942,9 @@ pub struct AuthorityState {
 
     pub config: NodeConfig,
 
+    /// The transaction deny config from `config`, with any changes made to it at runtime.
+    transaction_deny_rules: TransactionDenyRules,
+
     /// Current overload status in this authority. Updated periodically.
     pub overload_info: AuthorityOverloadInfo,
 
@@ -1002,7 +1012,7 @@ impl AuthorityState {
             transaction.tx_signatures(),
             &input_object_kinds,
             &receiving_objects_refs,
-            &self.config.transaction_deny_config,
+            &self.transaction_deny_rules.config(),
             self.get_backing_package_store().as_ref(),
         );
 
@@ -1038,6 +1048,12 @@ impl AuthorityState {
             epoch_store.epoch(),
         )?;
 
+        sui_transaction_checks::deny::check_input_object_types(
+            &self.transaction_deny_rules.config(),
+            &input_objects,
+            &receiving_objects,
+        )?;
+
         let (_gas_status, checked_input_objects) = sui_transaction_checks::check_transaction_input(
             epoch_store.protocol_config(),
             epoch_store.reference_gas_price(),
@@ -1681,6 +1697,22 @@ impl AuthorityState {
         )
     }
 
+    pub fn transaction_deny_rules(&self) -> Vec<DenyRule> {
+        self.transaction_deny_rules.rules()
+    }
+
+    /// Start denying transactions that match `rule`, until it is removed, including after the node
+    /// restarts. Returns `false` if the rule was already in effect.
+    pub fn add_transaction_deny_rule(&self, rule: DenyRule) -> SuiResult<bool> {
+        self.transaction_deny_rules.add(rule)
+    }
+
+    /// Stop denying transactions that match `rule`, including after the node restarts, even if
+    /// the rule is in the node's config. Returns `false` if the rule was not in effect.
+    pub fn remove_transaction_deny_rule(&self, rule: DenyRule) -> SuiResult<bool> {
+        self.transaction_deny_rules.remove(rule)
+    }
+
     pub async fn reconfigure_traffic_control(
         &self,
         params: TrafficControlReconfigParams,
@@ -2014,7 +2046,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.config.transaction_deny_config,
+            &self.transaction_deny_rules.config(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -2026,6 +2058,12 @@ impl AuthorityState {
             epoch_store.epoch(),
         )?;
 
+        sui_transaction_checks::deny::check_input_object_types(
+            &self.transaction_deny_rules.config(),
+            &input_objects,
+            &receiving_objects,
+        )?;
+
         // make a gas object if one was not provided
         let mut gas_data = transaction.gas_data().clone();
         let ((gas_status, checked_input_objects), mock_gas) = if transaction.gas().is_empty() {
@@ -2205,7 +2243,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.config.transaction_deny_config,
+            &self.transaction_deny_rules.config(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -2217,6 +2255,12 @@ impl AuthorityState {
             epoch_store.epoch(),
         )?;
 
+        sui_transaction_checks::deny::check_input_object_types(
+            &self.transaction_deny_rules.config(),
+            &input_objects,
+            &receiving_objects,
+        )?;
+
         // mock a gas object if one was not provided
         let mock_gas_id = if transaction.gas().is_empty() {
             let mock_gas_object = Object::new_move(
@@ -2373,7 +2417,7 @@ impl AuthorityState {
             &[],
             &input_object_kinds,
             &receiving_object_refs,
-            &self.config.transaction_deny_config,
+            &self.transaction_deny_rules.config(),
             self.get_backing_package_store().as_ref(),
         )?;
 
@@ -2385,6 +2429,12 @@ impl AuthorityState {
             epoch_store.epoch(),
         )?;
 
+        sui_transaction_checks::deny::check_input_object_types(
+            &self.transaction_deny_rules.config(),
+            &input_objects,
+            &receiving_objects,
+        )?;
+
         let (gas_status, checked_input_objects) = if skip_checks {
             // If we are skipping checks, then we call the check_dev_inspect_input function which will perform
             // only lightweight checks on the transaction input. And if the gas field is empty, that means we will
@@ -3160,6 +3210,11 @@ impl AuthorityState {
         } else {
             None
         };
+        let transaction_deny_rules = TransactionDenyRules::new(
+            config.transaction_deny_config.clone(),
+            config.db_path().join(TRANSACTION_DENY_RULES_FILE),
+        )
+        .expect("Failed to load transaction deny rules");
         let state = Arc::new(AuthorityState {
             name,
             secret,
@@ -3179,6 +3234,7 @@ impl AuthorityState {
             _authority_per_epoch_pruner,
             db_checkpoint_config: db_checkpoint_config.clone(),
             config,
+            transaction_deny_rules,
             overload_info: AuthorityOverloadInfo::default(),
             validator_tx_finalizer,
             chain_identifier,
@@ -647,9 +647,38 @@ impl AuthorityStore {
         expected_sha3_digest: &[u8; 32],
     ) -> SuiResult<()> {
         let mut hasher = Sha3_256::default();
+        let batch = Self::live_objects_batch(
+            perpetual_db,
+            live_objects.inspect(|object| hasher.update(object.object_reference().2.inner())),
+        )?;
+        let sha3_digest = hasher.finalize().digest;
+        if *expected_sha3_digest != sha3_digest {
+            error!(
+                "Sha does not match! expected: {:?}, actual: {:?}",
+                expected_sha3_digest, sha3_digest
+            );
+            return Err(SuiError::from("Sha does not match"));
+        }
+        batch.write()?;
+        Ok(())
+    }
+
+    /// Insert live objects restored from a state snapshot, that the caller has already checked
+    /// against it.
+    pub fn insert_verified_live_objects(
+        perpetual_db: &AuthorityPerpetualTables,
+        live_objects: impl Iterator<Item = LiveObject>,
+    ) -> SuiResult<()> {
+        Self::live_objects_batch(perpetual_db, live_objects)?.write()?;
+        Ok(())
+    }
+
+    fn live_objects_batch(
+        perpetual_db: &AuthorityPerpetualTables,
+        live_objects: impl Iterator<Item = LiveObject>,
+    ) -> SuiResult<DBBatch> {
         let mut batch = perpetual_db.objects.batch();
         for object in live_objects {
-            hasher.update(object.object_reference().2.inner());
             match object {
                 LiveObject::Normal(object) => {
                     let store_object_wrapper = get_store_object(object.clone());
@@ -680,14 +709,22 @@ impl AuthorityStore {
                 }
             }
         }
-        let sha3_digest = hasher.finalize().digest;
-        if *expected_sha3_digest != sha3_digest {
-            error!(
-                "Sha does not match! expected: {:?}, actual: {:?}",
-                expected_sha3_digest, sha3_digest
-            );
-            return Err(SuiError::from("Sha does not match"));
-        }
+        Ok(batch)
+    }
+
+    /// Remove object versions restored by `bulk_insert_live_objects`, e.g. because they are no
+    /// longer live as of a later state snapshot.
+    pub fn bulk_remove_live_objects(
+        perpetual_db: &AuthorityPerpetualTables,
+        object_refs: impl Iterator<Item = ObjectRef>,
+    ) -> SuiResult<()> {
+        let object_refs: Vec<_> = object_refs.collect();
+        let mut batch = perpetual_db.objects.batch();
+        batch.delete_batch(
+            &perpetual_db.objects,
+            object_refs.iter().map(ObjectKey::from),
+        )?;
+        batch.delete_batch(&perpetual_db.live_owned_object_markers, object_refs.iter())?;
         batch.write()?;
         Ok(())
     }
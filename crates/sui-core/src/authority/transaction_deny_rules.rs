@@ -0,0 +1,192 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use arc_swap::{ArcSwap, Guard};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sui_config::transaction_deny_config::{DenyRule, TransactionDenyConfig};
+use sui_types::error::{SuiError, SuiResult};
+use tracing::info;
+
+/// The transaction deny config in effect on a node: The deny config from the node's config, with
+/// rules that were added or removed at runtime (through the admin interface) applied on top.
+///
+/// Runtime changes are persisted to a file, and re-applied to the node's deny config when it
+/// restarts, so they stay in effect until they are reverted, even if the node's config changes.
+pub struct TransactionDenyRules {
+    path: PathBuf,
+    base: TransactionDenyConfig,
+    overrides: Mutex<DenyRuleOverrides>,
+    config: ArcSwap<TransactionDenyConfig>,
+}
+
+/// Changes made to the node's deny config at runtime.
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct DenyRuleOverrides {
+    added: Vec<DenyRule>,
+    removed: Vec<DenyRule>,
+}
+
+impl TransactionDenyRules {
+    /// Apply the runtime changes persisted at `path` (if any) to the node's deny config, `base`.
+    pub fn new(base: TransactionDenyConfig, path: PathBuf) -> SuiResult<Self> {
+        let overrides = if path.exists() {
+            let bytes = fs::read(&path).map_err(|e| SuiError::FileIOError(e.to_string()))?;
+            serde_json::from_slice(&bytes).map_err(|e| {
+                SuiError::FileIOError(format!(
+                    "Failed to read transaction deny rules from {}: {e}",
+                    path.display()
+                ))
+            })?
+        } else {
+            DenyRuleOverrides::default()
+        };
+
+        let config = overrides.apply(&base);
+        Ok(Self {
+            path,
+            base,
+            overrides: Mutex::new(overrides),
+            config: ArcSwap::new(Arc::new(config)),
+        })
+    }
+
+    pub fn config(&self) -> Guard<Arc<TransactionDenyConfig>> {
+        self.config.load()
+    }
+
+    pub fn rules(&self) -> Vec<DenyRule> {
+        self.config.load().rules()
+    }
+
+    /// Start denying transactions that match `rule`. Returns `false` if the rule was already in
+    /// effect.
+    pub fn add(&self, rule: DenyRule) -> SuiResult<bool> {
+        let added = self.update(|overrides, config| {
+            if config.rules().contains(&rule) {
+                return false;
+            }
+
+            overrides.removed.retain(|r| r != &rule);
+            overrides.added.push(rule.clone());
+            true
+        })?;
+
+        if added {
+            info!("Added transaction deny rule {rule}");
+        }
+        Ok(added)
+    }
+
+    /// Stop denying transactions that match `rule`. Returns `false` if the rule was not in effect.
+    pub fn remove(&self, rule: DenyRule) -> SuiResult<bool> {
+        let removed = self.update(|overrides, config| {
+            if !config.rules().contains(&rule) {
+                return false;
+            }
+
+            // Rules that come from the node's config need to be explicitly removed from it.
+            overrides.added.retain(|r| r != &rule);
+            if self.base.rules().contains(&rule) {
+                overrides.removed.push(rule.clone());
+            }
+            true
+        })?;
+
+        if removed {
+            info!("Removed transaction deny rule {rule}");
+        }
+        Ok(removed)
+    }
+
+    /// Change the overrides with `f`, given the config currently in effect. If `f` reports that it
+    /// changed them, persist them, and start using the resulting config.
+    fn update(
+        &self,
+        f: impl FnOnce(&mut DenyRuleOverrides, &TransactionDenyConfig) -> bool,
+    ) -> SuiResult<bool> {
+        let mut overrides = self.overrides.lock();
+        let mut updated = overrides.clone();
+
+        if !f(&mut updated, &self.config.load()) {
+            return Ok(false);
+        }
+
+        let bytes = serde_json::to_vec_pretty(&updated)
+            .map_err(|e| SuiError::FileIOError(e.to_string()))?;
+
+        // Write to a temporary file first, so that a crash can't leave the rules partially
+        // written.
+        let tmp_path = self.path.with_extension("tmp");
+        let parent = self.path.parent().unwrap_or(&self.path);
+        fs::create_dir_all(parent)
+            .and_then(|_| fs::write(&tmp_path, bytes))
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|e| {
+                SuiError::FileIOError(format!(
+                    "Failed to write transaction deny rules to {}: {e}",
+                    self.path.display()
+                ))
+            })?;
+
+        self.config.store(Arc::new(updated.apply(&self.base)));
+        *overrides = updated;
+        Ok(true)
+    }
+}
+
+impl DenyRuleOverrides {
+    fn apply(&self, base: &TransactionDenyConfig) -> TransactionDenyConfig {
+        let mut config = base.clone();
+        for rule in &self.removed {
+            config.remove_rule(rule);
+        }
+        for rule in &self.added {
+            config.add_rule(rule.clone());
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_config::transaction_deny_config::TransactionDenyConfigBuilder;
+    use sui_types::base_types::{ObjectID, SuiAddress};
+
+    #[test]
+    fn runtime_changes_are_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rules.json");
+
+        let address = SuiAddress::random_for_testing_only();
+        let base = TransactionDenyConfigBuilder::new()
+            .add_denied_address(address)
+            .build();
+
+        let object = DenyRule::Object(ObjectID::random());
+        let function: DenyRule = "function:0x2::coin::mint".parse().unwrap();
+
+        let rules = TransactionDenyRules::new(base.clone(), path.clone()).unwrap();
+        assert!(rules.add(object.clone()).unwrap());
+        assert!(rules.add(function.clone()).unwrap());
+        assert!(!rules.add(function.clone()).unwrap());
+        assert!(rules.remove(DenyRule::Address(address)).unwrap());
+        assert!(!rules.remove(DenyRule::Address(address)).unwrap());
+        assert!(rules.remove(object.clone()).unwrap());
+        assert_eq!(rules.rules(), vec![function.clone()]);
+        assert!(rules.config().get_address_deny_set().is_empty());
+
+        // The changes are re-applied to the base config when the rules are loaded again.
+        let rules = TransactionDenyRules::new(base.clone(), path).unwrap();
+        assert_eq!(rules.rules(), vec![function]);
+
+        // Adding back a rule from the base config.
+        assert!(rules.add(DenyRule::Address(address)).unwrap());
+        assert!(rules.config().get_address_deny_set().contains(&address));
+    }
+}
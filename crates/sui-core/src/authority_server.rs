@@ -1649,9 +1649,22 @@ impl ValidatorService {
         }
     }
 
-    async fn handle_traffic_req(&self, client: Option<IpAddr>) -> Result<(), tonic::Status> {
+    fn get_client_api_key<T>(&self, request: &tonic::Request<T>) -> Option<String> {
+        let header = self.traffic_controller.as_ref()?.api_key_header()?;
+        request
+            .metadata()
+            .get(header)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    }
+
+    async fn handle_traffic_req(
+        &self,
+        client: Option<IpAddr>,
+        api_key: &Option<String>,
+    ) -> Result<(), tonic::Status> {
         if let Some(traffic_controller) = &self.traffic_controller {
-            if !traffic_controller.check(&client, &None).await {
+            if !traffic_controller.check(&client, &None, api_key).await {
                 // Entity in blocklist
                 Err(tonic::Status::from_error(SuiError::TooManyRequests.into()))
             } else {
@@ -1665,6 +1678,8 @@ impl ValidatorService {
     fn handle_traffic_resp<T>(
         &self,
         client: Option<IpAddr>,
+        api_key: Option<String>,
+        method: &str,
         wrapped_response: WrappedServiceResponse<T>,
     ) -> Result<tonic::Response<T>, tonic::Status> {
         let (error, spam_weight, unwrapped_response) = match wrapped_response {
@@ -1686,6 +1701,8 @@ impl ValidatorService {
                     (error_weight, error_type)
                 }),
                 spam_weight,
+                api_key,
+                method: Some(method.to_string()),
                 timestamp: SystemTime::now(),
             })
         }
@@ -1724,21 +1741,34 @@ fn normalize(err: SuiError) -> Weight {
 /// Implements generic pre- and post-processing. Since this is on the critical
 /// path, any heavy lifting should be done in a separate non-blocking task
 /// unless it is necessary to override the return value.
+///
+/// The method name reported to traffic control defaults to the name of the
+/// handler without its `_impl` suffix, and can be overridden where that does
+/// not match the name of the service method.
 #[macro_export]
 macro_rules! handle_with_decoration {
     ($self:ident, $func_name:ident, $request:ident) => {{
+        $crate::handle_with_decoration!(
+            $self,
+            $func_name,
+            $request,
+            stringify!($func_name).trim_end_matches("_impl")
+        )
+    }};
+    ($self:ident, $func_name:ident, $request:ident, $method:expr) => {{
         if $self.client_id_source.is_none() {
             return $self.$func_name($request).await.map(|(result, _)| result);
         }
 
         let client = $self.get_client_ip_addr(&$request, $self.client_id_source.as_ref().unwrap());
+        let api_key = $self.get_client_api_key(&$request);
 
-        // check if either IP is blocked, in which case return early
-        $self.handle_traffic_req(client.clone()).await?;
+        // check if either IP or the API key is blocked, in which case return early
+        $self.handle_traffic_req(client.clone(), &api_key).await?;
 
         // handle traffic tallying
         let wrapped_response = $self.$func_name($request).await;
-        $self.handle_traffic_resp(client, wrapped_response)
+        $self.handle_traffic_resp(client, api_key, $method, wrapped_response)
     }};
 }
 
@@ -1755,7 +1785,12 @@ impl Validator for ValidatorService {
         spawn_monitored_task!(async move {
             // NB: traffic tally wrapping handled within the task rather than on task exit
             // to prevent an attacker from subverting traffic control by severing the connection
-            handle_with_decoration!(validator_service, handle_submit_transaction_impl, request)
+            handle_with_decoration!(
+                validator_service,
+                handle_submit_transaction_impl,
+                request,
+                "submit_transaction"
+            )
         })
         .await
         .unwrap()
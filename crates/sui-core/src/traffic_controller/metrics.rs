@@ -11,6 +11,7 @@ pub struct TrafficControllerMetrics {
     pub tallies: IntCounter,
     pub connection_ip_blocklist_len: IntGauge,
     pub proxy_ip_blocklist_len: IntGauge,
+    pub api_key_blocklist_len: IntGauge,
     pub requests_blocked_at_protocol: IntCounter,
     pub blocks_delegated_to_firewall: IntCounter,
     pub firewall_delegation_request_fail: IntCounter,
@@ -54,6 +55,12 @@ impl TrafficControllerMetrics {
                 registry
             )
             .unwrap(),
+            api_key_blocklist_len: register_int_gauge_with_registry!(
+                "api_key_blocklist_len",
+                "Number of client API keys in the protocol layer blocklist",
+                registry
+            )
+            .unwrap(),
             requests_blocked_at_protocol: register_int_counter_with_registry!(
                 "requests_blocked_at_protocol",
                 "Number of requests blocked by this node at the protocol level",
@@ -25,6 +25,7 @@ use mysten_metrics::spawn_monitored_task;
 use parking_lot::Mutex as ParkingLotMutex;
 use rand::Rng;
 use std::fmt::Debug;
+use std::hash::Hash;
 use std::time::{Duration, Instant, SystemTime};
 use sui_types::traffic_control::{
     PolicyConfig, PolicyType, RemoteFirewallConfig, TrafficControlReconfigParams, Weight,
@@ -36,12 +37,13 @@ use tracing::{debug, error, info, trace, warn};
 pub const METRICS_INTERVAL_SECS: u64 = 2;
 pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 300;
 
-type Blocklist = Arc<DashMap<IpAddr, SystemTime>>;
+type Blocklist<K = IpAddr> = Arc<DashMap<K, SystemTime>>;
 
 #[derive(Clone)]
 pub struct Blocklists {
     clients: Blocklist,
     proxied_clients: Blocklist,
+    api_keys: Blocklist<String>,
 }
 
 #[derive(Clone)]
@@ -63,6 +65,7 @@ pub struct TrafficController {
     error_policy: Option<Arc<Mutex<TrafficControlPolicy>>>,
     policy_config: Arc<RwLock<PolicyConfig>>,
     fw_config: Option<RemoteFirewallConfig>,
+    api_key_header: Option<String>,
 }
 
 impl Debug for TrafficController {
@@ -92,6 +95,7 @@ impl TrafficController {
         fw_config: Option<RemoteFirewallConfig>,
     ) -> Self {
         metrics.dry_run_enabled.set(policy_config.dry_run as i64);
+        let api_key_header = policy_config.api_key_header.clone();
         match policy_config.allow_list.clone() {
             Some(allow_list) => {
                 let allowlist = allow_list
@@ -108,6 +112,7 @@ impl TrafficController {
                     metrics,
                     policy_config: Arc::new(RwLock::new(policy_config)),
                     fw_config,
+                    api_key_header,
                     spam_policy: None,
                     error_policy: None,
                 }
@@ -124,10 +129,12 @@ impl TrafficController {
                     acl: Acl::Blocklists(Blocklists {
                         clients: Arc::new(DashMap::new()),
                         proxied_clients: Arc::new(DashMap::new()),
+                        api_keys: Arc::new(DashMap::new()),
                     }),
                     metrics,
                     policy_config: Arc::new(RwLock::new(policy_config)),
                     fw_config,
+                    api_key_header,
                     spam_policy: Some(spam_policy),
                     error_policy: Some(error_policy),
                 };
@@ -288,6 +295,11 @@ impl TrafficController {
         }
     }
 
+    /// Name of the request header from which clients' API keys should be read, if any.
+    pub fn api_key_header(&self) -> Option<&str> {
+        self.api_key_header.as_deref()
+    }
+
     fn open_tally_channel(&self, tx: mpsc::Sender<TrafficTally>) {
         self.tally_channel.lock().replace(tx);
     }
@@ -340,7 +352,12 @@ impl TrafficController {
     }
 
     /// Handle check with dry-run mode considered
-    pub async fn check(&self, client: &Option<IpAddr>, proxied_client: &Option<IpAddr>) -> bool {
+    pub async fn check(
+        &self,
+        client: &Option<IpAddr>,
+        proxied_client: &Option<IpAddr>,
+        api_key: &Option<String>,
+    ) -> bool {
         let policy_config = { self.policy_config.read().await.clone() };
         let check_with_dry_run_maybe = |allowed| -> bool {
             match (allowed, policy_config.dry_run) {
@@ -368,7 +385,7 @@ impl TrafficController {
             }
             Acl::Blocklists(blocklists) => {
                 let allowed = self
-                    .check_blocklists(blocklists, client, proxied_client)
+                    .check_blocklists(blocklists, client, proxied_client, api_key)
                     .await;
                 check_with_dry_run_maybe(allowed)
             }
@@ -381,6 +398,7 @@ impl TrafficController {
         blocklists: &Blocklists,
         client: &Option<IpAddr>,
         proxied_client: &Option<IpAddr>,
+        api_key: &Option<String>,
     ) -> bool {
         let client_check = self.check_and_clear_blocklist(
             client,
@@ -392,15 +410,20 @@ impl TrafficController {
             blocklists.proxied_clients.clone(),
             &self.metrics.proxy_ip_blocklist_len,
         );
-        let (client_check, proxied_client_check) =
-            futures::future::join(client_check, proxied_client_check).await;
-        client_check && proxied_client_check
+        let api_key_check = self.check_and_clear_blocklist(
+            api_key,
+            blocklists.api_keys.clone(),
+            &self.metrics.api_key_blocklist_len,
+        );
+        let (client_check, proxied_client_check, api_key_check) =
+            futures::future::join3(client_check, proxied_client_check, api_key_check).await;
+        client_check && proxied_client_check && api_key_check
     }
 
-    async fn check_and_clear_blocklist(
+    async fn check_and_clear_blocklist<K: Hash + Eq>(
         &self,
-        client: &Option<IpAddr>,
-        blocklist: Blocklist,
+        client: &Option<K>,
+        blocklist: Blocklist<K>,
         blocklist_len_gauge: &IntGauge,
     ) -> bool {
         let client = match client {
@@ -438,12 +461,18 @@ async fn run_clear_blocklists_loop(blocklists: Blocklists, metrics: Arc<TrafficC
         blocklists
             .proxied_clients
             .retain(|_, expiration| now < *expiration);
+        blocklists
+            .api_keys
+            .retain(|_, expiration| now < *expiration);
         metrics
             .connection_ip_blocklist_len
             .set(blocklists.clients.len() as i64);
         metrics
             .proxy_ip_blocklist_len
             .set(blocklists.proxied_clients.len() as i64);
+        metrics
+            .api_key_blocklist_len
+            .set(blocklists.api_keys.len() as i64);
     }
 }
 
@@ -600,13 +629,20 @@ async fn handle_error_tally(
         .tally_error_types
         .with_label_values(&[error_type.as_str()])
         .inc();
-    let resp = policy.lock().await.handle_tally(tally);
+    let mut resp = policy.lock().await.handle_tally(tally);
     metrics.error_tally_handled.inc();
     if let Some(fw_config) = fw_config {
         if fw_config.delegate_error_blocking && !mem_drainfile_present {
             let client = nodefw_client
                 .as_ref()
                 .expect("Expected NodeFWClient for blocklist delegation");
+            // The firewall only knows about IP addresses, so API keys are always blocked here
+            block_api_key(
+                resp.block_api_key.take(),
+                policy_config,
+                &blocklists,
+                &metrics,
+            );
             return delegate_policy_response(
                 resp,
                 policy_config,
@@ -634,13 +670,20 @@ async fn handle_spam_tally(
     if !(tally.spam_weight.is_sampled() && policy_config.spam_sample_rate.is_sampled()) {
         return Ok(());
     }
-    let resp = policy.lock().await.handle_tally(tally.clone());
+    let mut resp = policy.lock().await.handle_tally(tally.clone());
     metrics.tally_handled.inc();
     if let Some(fw_config) = fw_config {
         if fw_config.delegate_spam_blocking && !mem_drainfile_present {
             let client = nodefw_client
                 .as_ref()
                 .expect("Expected NodeFWClient for blocklist delegation");
+            // The firewall only knows about IP addresses, so API keys are always blocked here
+            block_api_key(
+                resp.block_api_key.take(),
+                policy_config,
+                &blocklists,
+                &metrics,
+            );
             return delegate_policy_response(
                 resp,
                 policy_config,
@@ -664,12 +707,14 @@ async fn handle_policy_response(
     let PolicyResponse {
         block_client,
         block_proxied_client,
+        block_api_key: api_key,
     } = response;
     let PolicyConfig {
         connection_blocklist_ttl_sec,
         proxy_blocklist_ttl_sec,
         ..
     } = policy_config;
+    block_api_key(api_key, policy_config, &blocklists, &metrics);
     if let Some(client) = block_client {
         if blocklists
             .clients
@@ -700,6 +745,30 @@ async fn handle_policy_response(
     }
 }
 
+fn block_api_key(
+    api_key: Option<String>,
+    policy_config: &PolicyConfig,
+    blocklists: &Blocklists,
+    metrics: &TrafficControllerMetrics,
+) {
+    let Some(api_key) = api_key else {
+        return;
+    };
+    if blocklists
+        .api_keys
+        .insert(
+            api_key,
+            SystemTime::now() + Duration::from_secs(policy_config.connection_blocklist_ttl_sec),
+        )
+        .is_none()
+    {
+        // Only increment the metric if the key was not already blocked.
+        // Note that we do not log the key itself, as it is a credential
+        debug!("Adding API key to blocklist");
+        metrics.api_key_blocklist_len.inc();
+    }
+}
+
 async fn delegate_policy_response(
     response: PolicyResponse,
     policy_config: &PolicyConfig,
@@ -710,6 +779,7 @@ async fn delegate_policy_response(
     let PolicyResponse {
         block_client,
         block_proxied_client,
+        ..
     } = response;
     let PolicyConfig {
         connection_blocklist_ttl_sec,
@@ -906,7 +976,7 @@ impl TrafficSim {
 
         while start.elapsed() < duration {
             let client = Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, task_num)));
-            let allowed = controller.check(&client, &None).await;
+            let allowed = controller.check(&client, &None, &None).await;
             if allowed {
                 if currently_blocked {
                     total_time_blocked += time_blocked_start.elapsed();
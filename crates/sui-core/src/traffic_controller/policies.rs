@@ -13,10 +13,14 @@ use std::fmt::Debug;
 use std::hash::Hash;
 use std::time::Duration;
 use std::time::{Instant, SystemTime};
-use sui_types::traffic_control::{FreqThresholdConfig, PolicyConfig, PolicyType, Weight};
+use sui_types::traffic_control::{
+    FreqThresholdConfig, PolicyConfig, PolicyType, TokenBucketConfig, Weight,
+};
 use tracing::{info, trace};
 
 const HIGHEST_RATES_CAPACITY: usize = 20;
+/// How often the token bucket policy forgets about clients whose buckets have refilled
+const BUCKET_PRUNE_INTERVAL: Duration = Duration::from_secs(10);
 
 /// The type of request client.
 #[derive(Hash, Eq, PartialEq, Debug)]
@@ -228,6 +232,10 @@ pub struct TrafficTally {
     pub through_fullnode: Option<IpAddr>,
     pub error_info: Option<(Weight, String)>,
     pub spam_weight: Weight,
+    /// API key presented by the client, if the node is configured to read one
+    pub api_key: Option<String>,
+    /// Name of the method that was called, for policies that weigh methods differently
+    pub method: Option<String>,
     pub timestamp: SystemTime,
 }
 
@@ -243,15 +251,28 @@ impl TrafficTally {
             through_fullnode,
             error_info,
             spam_weight,
+            api_key: None,
+            method: None,
             timestamp: SystemTime::now(),
         }
     }
+
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
+
+    pub fn with_method(mut self, method: impl Into<String>) -> Self {
+        self.method = Some(method.into());
+        self
+    }
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct PolicyResponse {
     pub block_client: Option<IpAddr>,
     pub block_proxied_client: Option<IpAddr>,
+    pub block_api_key: Option<String>,
 }
 
 pub trait Policy {
@@ -265,6 +286,7 @@ pub trait Policy {
 // not object safe, so we can't use a trait object instead
 pub enum TrafficControlPolicy {
     FreqThreshold(FreqThresholdPolicy),
+    TokenBucket(TokenBucketPolicy),
     NoOp(NoOpPolicy),
     // Test policies below this point
     TestNConnIP(TestNConnIPPolicy),
@@ -276,6 +298,7 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::FreqThreshold(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::TokenBucket(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestNConnIP(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.handle_tally(tally),
         }
@@ -285,6 +308,7 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.policy_config(),
             TrafficControlPolicy::FreqThreshold(policy) => policy.policy_config(),
+            TrafficControlPolicy::TokenBucket(policy) => policy.policy_config(),
             TrafficControlPolicy::TestNConnIP(policy) => policy.policy_config(),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.policy_config(),
         }
@@ -304,6 +328,9 @@ impl TrafficControlPolicy {
             PolicyType::FreqThreshold(freq_threshold_config) => Self::FreqThreshold(
                 FreqThresholdPolicy::new(policy_config, freq_threshold_config),
             ),
+            PolicyType::TokenBucket(token_bucket_config) => {
+                Self::TokenBucket(TokenBucketPolicy::new(policy_config, token_bucket_config))
+            }
             PolicyType::TestNConnIP(n) => {
                 Self::TestNConnIP(TestNConnIPPolicy::new(policy_config, n).await)
             }
@@ -408,6 +435,161 @@ impl FreqThresholdPolicy {
         PolicyResponse {
             block_client,
             block_proxied_client,
+            block_api_key: None,
+        }
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct BucketLimit {
+    capacity: f64,
+    refill_rate: f64,
+}
+
+#[derive(Clone, Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn full(limit: BucketLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, limit: BucketLimit, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.refill_rate).min(limit.capacity);
+        self.last_refill = now;
+    }
+
+    /// Takes `cost` tokens from the bucket, returning false (and taking
+    /// nothing) if there are not enough tokens left.
+    fn try_take(&mut self, cost: f64, limit: BucketLimit, now: Instant) -> bool {
+        self.refill(limit, now);
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_full(&self, limit: BucketLimit, now: Instant) -> bool {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens + elapsed * limit.refill_rate >= limit.capacity
+    }
+}
+
+/// A set of token buckets sharing the same limit, keyed by client.
+struct TokenBuckets<K> {
+    limit: BucketLimit,
+    buckets: HashMap<K, TokenBucket>,
+}
+
+impl<K: Hash + Eq + Clone> TokenBuckets<K> {
+    fn new(capacity: u64, refill_rate: f64) -> Self {
+        Self {
+            limit: BucketLimit {
+                capacity: capacity as f64,
+                refill_rate,
+            },
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Charges `cost` tokens to `key`, returning the key if it should be blocked.
+    fn charge(&mut self, key: K, cost: f64, now: Instant) -> Option<K> {
+        let limit = self.limit;
+        let allowed = self
+            .buckets
+            .entry(key.clone())
+            .or_insert_with(|| TokenBucket::full(limit, now))
+            .try_take(cost, limit, now);
+        (!allowed).then_some(key)
+    }
+
+    /// Drops buckets that have refilled completely, which behave the same
+    /// as buckets that do not exist, to bound memory usage.
+    fn prune(&mut self, now: Instant) {
+        let limit = self.limit;
+        self.buckets.retain(|_, bucket| !bucket.is_full(limit, now));
+    }
+}
+
+pub struct TokenBucketPolicy {
+    pub config: PolicyConfig,
+    token_bucket_config: TokenBucketConfig,
+    clients: TokenBuckets<IpAddr>,
+    proxied_clients: TokenBuckets<IpAddr>,
+    api_keys: TokenBuckets<String>,
+    last_prune: Instant,
+}
+
+impl TokenBucketPolicy {
+    pub fn new(config: PolicyConfig, token_bucket_config: TokenBucketConfig) -> Self {
+        let TokenBucketConfig {
+            client_capacity,
+            client_refill_rate,
+            proxied_client_capacity,
+            proxied_client_refill_rate,
+            api_key_capacity,
+            api_key_refill_rate,
+            ..
+        } = token_bucket_config;
+        Self {
+            config,
+            clients: TokenBuckets::new(client_capacity, client_refill_rate),
+            proxied_clients: TokenBuckets::new(proxied_client_capacity, proxied_client_refill_rate),
+            api_keys: TokenBuckets::new(
+                api_key_capacity.unwrap_or(client_capacity),
+                api_key_refill_rate.unwrap_or(client_refill_rate),
+            ),
+            token_bucket_config,
+            last_prune: Instant::now(),
+        }
+    }
+
+    pub fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let now = Instant::now();
+        if now.saturating_duration_since(self.last_prune) >= BUCKET_PRUNE_INTERVAL {
+            self.clients.prune(now);
+            self.proxied_clients.prune(now);
+            self.api_keys.prune(now);
+            self.last_prune = now;
+        }
+
+        let cost = self
+            .token_bucket_config
+            .method_cost(tally.method.as_deref()) as f64;
+        trace!(
+            "TokenBucketPolicy handling tally -- method: {:?}, cost: {:?}, client: {:?}",
+            tally.method,
+            cost,
+            tally.direct,
+        );
+
+        PolicyResponse {
+            block_client: tally
+                .direct
+                .and_then(|client| self.clients.charge(client, cost, now)),
+            block_proxied_client: tally
+                .through_fullnode
+                .and_then(|client| self.proxied_clients.charge(client, cost, now)),
+            block_api_key: tally
+                .api_key
+                .and_then(|key| self.api_keys.charge(key, cost, now)),
         }
     }
 
@@ -477,6 +659,7 @@ impl TestNConnIPPolicy {
                 None
             },
             block_proxied_client: None,
+            block_api_key: None,
         }
     }
 
@@ -543,6 +726,8 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))),
             error_info: None,
             spam_weight: Weight::one(),
+            api_key: None,
+            method: None,
             timestamp: SystemTime::now(),
         };
         let bob = TrafficTally {
@@ -550,6 +735,8 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(4, 3, 2, 1))),
             error_info: None,
             spam_weight: Weight::one(),
+            api_key: None,
+            method: None,
             timestamp: SystemTime::now(),
         };
         let charlie = TrafficTally {
@@ -557,6 +744,8 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8))),
             error_info: None,
             spam_weight: Weight::one(),
+            api_key: None,
+            method: None,
             timestamp: SystemTime::now(),
         };
 
@@ -674,6 +863,93 @@ mod tests {
         assert_eq!(proxied_rate, 1);
     }
 
+    #[sim_test]
+    async fn test_token_bucket_policy() {
+        // Direct clients can burst 10 tokens and get 5 back per second,
+        // and executing a transaction costs 5 tokens.
+        let mut policy = TokenBucketPolicy::new(
+            PolicyConfig::default(),
+            TokenBucketConfig {
+                client_capacity: 10,
+                client_refill_rate: 5.0,
+                proxied_client_capacity: 2,
+                proxied_client_refill_rate: 1.0,
+                api_key_capacity: Some(3),
+                method_costs: [("sui_executeTransactionBlock".to_string(), 5)]
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            },
+        );
+        let alice = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5))),
+            None,
+            None,
+            Weight::one(),
+        );
+        let bob = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(4, 3, 2, 1))),
+            None,
+            None,
+            Weight::one(),
+        );
+
+        // alice can make a burst of 10 cheap requests, but not 11
+        for i in 0..10 {
+            let response = policy.handle_tally(alice.clone().with_method("sui_getObject"));
+            assert_eq!(response.block_client, None, "Blocked at i = {}", i);
+        }
+        let response = policy.handle_tally(alice.clone().with_method("sui_getObject"));
+        assert_eq!(response.block_client, alice.direct);
+
+        // bob is only allowed two expensive requests in the same burst
+        for _ in 0..2 {
+            let response =
+                policy.handle_tally(bob.clone().with_method("sui_executeTransactionBlock"));
+            assert_eq!(response.block_client, None);
+        }
+        let response = policy.handle_tally(bob.clone().with_method("sui_executeTransactionBlock"));
+        assert_eq!(response.block_client, bob.direct);
+
+        // after a second, each has 5 tokens again
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        let response = policy.handle_tally(bob.clone().with_method("sui_executeTransactionBlock"));
+        assert_eq!(response.block_client, None);
+        let response = policy.handle_tally(bob.clone().with_method("sui_getObject"));
+        assert_eq!(response.block_client, bob.direct);
+
+        // proxied clients and API keys have their own limits, tracked
+        // separately from the connection IP
+        let carol = TrafficTally::new(
+            None,
+            Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))),
+            None,
+            Weight::one(),
+        )
+        .with_api_key(Some("carol-key".to_string()));
+        for _ in 0..2 {
+            let response = policy.handle_tally(carol.clone());
+            assert_eq!(response.block_client, None);
+            assert_eq!(response.block_proxied_client, None);
+            assert_eq!(response.block_api_key, None);
+        }
+        let response = policy.handle_tally(carol.clone());
+        assert_eq!(response.block_proxied_client, carol.through_fullnode);
+        assert_eq!(response.block_api_key, None);
+
+        // the same key from another address is still limited by key
+        let carol_elsewhere = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8))),
+            None,
+            None,
+            Weight::one(),
+        )
+        .with_api_key(Some("carol-key".to_string()));
+        let response = policy.handle_tally(carol_elsewhere.clone());
+        assert_eq!(response.block_client, None);
+        assert_eq!(response.block_api_key, Some("carol-key".to_string()));
+    }
+
     #[sim_test]
     async fn test_traffic_sketch_mem_estimate() {
         // Test for getting a rough estimate of memory usage for the traffic sketch
@@ -13,7 +13,9 @@ use move_core_types::ident_str;
 use std::path::PathBuf;
 use std::sync::Arc;
 use sui_config::certificate_deny_config::CertificateDenyConfigBuilder;
-use sui_config::transaction_deny_config::{TransactionDenyConfig, TransactionDenyConfigBuilder};
+use sui_config::transaction_deny_config::{
+    DenyRule, QualifiedName, TransactionDenyConfig, TransactionDenyConfigBuilder,
+};
 use sui_swarm_config::genesis_config::{AccountConfig, DEFAULT_GAS_AMOUNT};
 use sui_swarm_config::network_config::NetworkConfig;
 use sui_test_transaction_builder::TestTransactionBuilder;
@@ -30,6 +32,7 @@ use sui_types::utils::get_zklogin_user_address;
 use sui_types::utils::{
     make_zklogin_tx, to_sender_signed_transaction, to_sender_signed_transaction_with_multi_signers,
 };
+use sui_types::SUI_SYSTEM_PACKAGE_ID;
 
 const ACCOUNT_NUM: usize = 5;
 const GAS_OBJECT_COUNT: usize = 15;
@@ -441,6 +444,90 @@ async fn test_package_denied() {
     assert!(result.is_ok());
 }
 
+async fn handle_staking_transaction(
+    state: &Arc<AuthorityState>,
+    account: &Account,
+) -> SuiResult<HandleTransactionResponse> {
+    let gas_price = state.reference_gas_price_for_testing().unwrap();
+    let tx = TestTransactionBuilder::new(account.0, account.2[0], gas_price)
+        .call_staking(account.2[1], SuiAddress::default())
+        .build_and_sign(&account.1);
+    let epoch_store = state.epoch_store_for_testing();
+    let tx = epoch_store.verify_transaction(tx).unwrap();
+    state.handle_transaction(&epoch_store, tx).await
+}
+
+#[tokio::test]
+async fn test_function_denied() {
+    let (network_config, state) = setup_test(
+        TransactionDenyConfigBuilder::new()
+            .add_denied_function(QualifiedName::new(
+                SUI_SYSTEM_PACKAGE_ID,
+                "sui_system",
+                "request_add_stake",
+            ))
+            .build(),
+    )
+    .await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+    assert_denied(&handle_staking_transaction(&state, &accounts[0]).await);
+
+    // Transactions that don't call the function are still allowed.
+    assert!(transfer_with_account(&accounts[1], &accounts[1], &state)
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_input_type_denied() {
+    let (network_config, state) = setup_test(
+        TransactionDenyConfigBuilder::new()
+            .add_denied_type(QualifiedName::new(
+                SUI_SYSTEM_PACKAGE_ID,
+                "sui_system",
+                "SuiSystemState",
+            ))
+            .build(),
+    )
+    .await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+
+    // Staking takes the system state object as an input.
+    assert_denied(&handle_staking_transaction(&state, &accounts[0]).await);
+    assert!(transfer_with_account(&accounts[1], &accounts[1], &state)
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_runtime_deny_rules() {
+    let (network_config, state) = setup_test(TransactionDenyConfigBuilder::new().build()).await;
+    let accounts = get_accounts_and_coins(&network_config, &state);
+
+    // Rules added at runtime take effect immediately.
+    let address = DenyRule::Address(accounts[0].0);
+    assert!(state.add_transaction_deny_rule(address.clone()).unwrap());
+    assert!(!state.add_transaction_deny_rule(address.clone()).unwrap());
+    assert_denied(&transfer_with_account(&accounts[0], &accounts[0], &state).await);
+
+    let function: DenyRule =
+        format!("function:{SUI_SYSTEM_PACKAGE_ID}::sui_system::request_add_stake")
+            .parse()
+            .unwrap();
+    assert!(state.add_transaction_deny_rule(function.clone()).unwrap());
+    assert_denied(&handle_staking_transaction(&state, &accounts[1]).await);
+    assert_eq!(
+        state.transaction_deny_rules(),
+        vec![address.clone(), function]
+    );
+
+    // As does removing them.
+    assert!(state.remove_transaction_deny_rule(address).unwrap());
+    assert!(transfer_with_account(&accounts[0], &accounts[0], &state)
+        .await
+        .is_ok());
+}
+
 #[tokio::test]
 async fn test_certificate_deny() {
     let (network_config, state) = setup_test(TransactionDenyConfig::default()).await;
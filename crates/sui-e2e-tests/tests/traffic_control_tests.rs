@@ -28,7 +28,8 @@ use sui_types::{
     quorum_driver_types::ExecuteTransactionRequestType,
     signature::GenericSignature,
     traffic_control::{
-        FreqThresholdConfig, PolicyConfig, PolicyType, RemoteFirewallConfig, Weight,
+        FreqThresholdConfig, PolicyConfig, PolicyType, RemoteFirewallConfig, TokenBucketConfig,
+        Weight,
     },
 };
 use test_cluster::{TestCluster, TestClusterBuilder};
@@ -399,6 +400,55 @@ async fn test_fullnode_traffic_control_spam_blocked() -> Result<(), anyhow::Erro
     panic!("Expected spam policy to trigger within {txn_count} requests");
 }
 
+#[tokio::test]
+async fn test_fullnode_traffic_control_token_bucket_method_costs() -> Result<(), anyhow::Error> {
+    telemetry_subscribers::init_for_testing();
+    let policy_config = PolicyConfig {
+        connection_blocklist_ttl_sec: 3,
+        // Clients can burst 100 tokens, which is plenty for cheap requests,
+        // but total transaction block counts are expensive.
+        spam_policy_type: PolicyType::TokenBucket(TokenBucketConfig {
+            client_capacity: 100,
+            client_refill_rate: 10.0,
+            method_costs: [("sui_getTotalTransactionBlocks".to_string(), 50)]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        }),
+        spam_sample_rate: Weight::one(),
+        dry_run: false,
+        ..Default::default()
+    };
+    let test_cluster = TestClusterBuilder::new()
+        .with_fullnode_policy_config(Some(policy_config))
+        .build()
+        .await;
+    let jsonrpc_client = &test_cluster.fullnode_handle.rpc_client;
+
+    // cheap requests that fit within the bucket are not blocked
+    for _ in 0..5 {
+        let _: serde_json::Value = jsonrpc_client
+            .request("sui_getChainIdentifier", rpc_params![])
+            .await
+            .expect("Cheap requests should not be blocked");
+    }
+
+    // but the bucket only covers a couple of expensive requests
+    for _ in 0..10 {
+        let response: Result<serde_json::Value, _> = jsonrpc_client
+            .request("sui_getTotalTransactionBlocks", rpc_params![])
+            .await;
+        if let Err(err) = response {
+            assert!(
+                err.to_string().contains("Too many requests"),
+                "Error not due to spam policy"
+            );
+            return Ok(());
+        }
+    }
+    panic!("Expected token bucket policy to block expensive requests");
+}
+
 #[tokio::test]
 async fn test_fullnode_traffic_control_error_blocked() -> Result<(), anyhow::Error> {
     telemetry_subscribers::init_for_testing();
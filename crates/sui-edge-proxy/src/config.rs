@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -16,8 +16,25 @@ use url::Url;
 pub struct ProxyConfig {
     pub listen_address: SocketAddr,
     pub metrics_address: SocketAddr,
-    pub execution_peer: PeerConfig,
-    pub read_peer: PeerConfig,
+    /// Upstreams that transactions are sent to for execution. Accepts a single peer, for
+    /// compatibility with older configs.
+    #[serde(alias = "execution-peer", deserialize_with = "one_or_many")]
+    pub execution_peers: Vec<PeerConfig>,
+    /// Upstreams that all other requests are sent to. Accepts a single peer, for compatibility
+    /// with older configs.
+    #[serde(alias = "read-peer", deserialize_with = "one_or_many")]
+    pub read_peers: Vec<PeerConfig>,
+    /// How requests are spread across the upstreams for each role.
+    #[serde(default)]
+    pub selection: SelectionStrategy,
+    /// Health checking of upstreams, used to stop sending requests to upstreams that are down or
+    /// have fallen behind.
+    #[serde(default)]
+    pub health_check: HealthCheckConfig,
+    /// Number of times a failed read request is retried on another upstream. Execution requests
+    /// are never retried.
+    #[serde(default = "default_read_retries")]
+    pub read_retries: usize,
     /// Maximum number of idle connections to keep in the connection pool.
     /// When set, this limits the number of connections that remain open but unused,
     /// helping to conserve system resources.
@@ -42,10 +59,88 @@ fn default_idle_timeout() -> Duration {
     Duration::from_secs(60)
 }
 
+fn default_read_retries() -> usize {
+    1
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct PeerConfig {
     pub address: Url,
+    /// Relative share of requests sent to this peer, under weighted round-robin selection.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SelectionStrategy {
+    /// Cycle through healthy upstreams, in proportion to their weights.
+    #[default]
+    WeightedRoundRobin,
+    /// Send each request to the healthy upstream with the lowest recent latency.
+    LeastLatency,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HealthCheckConfig {
+    /// How often each upstream is polled for its latest checkpoint.
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_health_check_interval")]
+    pub interval_seconds: Duration,
+    /// How long to wait for an upstream to respond to a health check, before treating it as down.
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_health_check_timeout")]
+    pub timeout_seconds: Duration,
+    /// Upstreams whose latest checkpoint is more than this many checkpoints behind the most
+    /// up-to-date upstream for the same role are ejected until they catch up.
+    #[serde(default = "default_max_checkpoint_lag")]
+    pub max_checkpoint_lag: u64,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            interval_seconds: default_health_check_interval(),
+            timeout_seconds: default_health_check_timeout(),
+            max_checkpoint_lag: default_max_checkpoint_lag(),
+        }
+    }
+}
+
+fn default_health_check_interval() -> Duration {
+    Duration::from_secs(5)
+}
+
+fn default_health_check_timeout() -> Duration {
+    Duration::from_secs(2)
+}
+
+fn default_max_checkpoint_lag() -> u64 {
+    30
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<PeerConfig>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(PeerConfig),
+        Many(Vec<PeerConfig>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(peer) => vec![peer],
+        OneOrMany::Many(peers) => peers,
+    })
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -77,12 +172,30 @@ pub async fn load<P: AsRef<std::path::Path>>(path: P) -> Result<(ProxyConfig, Cl
         .build()
         .expect("Failed to build HTTP/2 client");
 
-    validate_peer_url(&client, &config.read_peer).await?;
-    validate_peer_url(&client, &config.execution_peer).await?;
+    validate_peers(&client, "read", &config.read_peers).await?;
+    validate_peers(&client, "execution", &config.execution_peers).await?;
 
     Ok((config, client))
 }
 
+/// Wait until at least one of `peers` is reachable. The rest are left to the health checker, so
+/// that one peer being down doesn't stop the proxy from starting.
+async fn validate_peers(client: &Client, role: &str, peers: &[PeerConfig]) -> Result<()> {
+    if peers.is_empty() {
+        bail!("No {role} peers configured");
+    }
+
+    if let Some(peer) = peers.iter().find(|p| p.weight == 0) {
+        bail!("{role} peer {} has a weight of 0", peer.address);
+    }
+
+    let validations = peers
+        .iter()
+        .map(|peer| Box::pin(validate_peer_url(client, peer)));
+    let (result, _, _) = futures::future::select_all(validations).await;
+    result
+}
+
 /// Validate that the given PeerConfig URL has a valid host
 async fn validate_peer_url(client: &Client, peer: &PeerConfig) -> Result<()> {
     let health_url = peer
@@ -1,8 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::config::{LoggingConfig, PeerConfig};
+use crate::config::LoggingConfig;
 use crate::metrics::AppMetrics;
+use crate::upstream::UpstreamPool;
 use axum::{
     body::Body,
     extract::{Request, State},
@@ -12,6 +13,7 @@ use axum::{
 };
 use bytes::Bytes;
 use rand::Rng;
+use std::sync::Arc;
 use std::time::Instant;
 use tracing::{debug, warn};
 
@@ -33,8 +35,9 @@ impl PeerRole {
 #[derive(Clone)]
 pub struct AppState {
     client: reqwest::Client,
-    read_peer: PeerConfig,
-    execution_peer: PeerConfig,
+    read_pool: Arc<UpstreamPool>,
+    execution_pool: Arc<UpstreamPool>,
+    read_retries: usize,
     metrics: AppMetrics,
     logging_config: LoggingConfig,
 }
@@ -42,15 +45,17 @@ pub struct AppState {
 impl AppState {
     pub fn new(
         client: reqwest::Client,
-        read_peer: PeerConfig,
-        execution_peer: PeerConfig,
+        read_pool: Arc<UpstreamPool>,
+        execution_pool: Arc<UpstreamPool>,
+        read_retries: usize,
         metrics: AppMetrics,
         logging_config: LoggingConfig,
     ) -> Self {
         Self {
             client,
-            read_peer,
-            execution_peer,
+            read_pool,
+            execution_pool,
+            read_retries,
             metrics,
             logging_config,
         }
@@ -152,59 +157,97 @@ async fn proxy_request(
         .with_label_values(&[peer_type_str])
         .observe(body_bytes.len() as f64);
 
-    let peer_config = match peer_type {
-        PeerRole::Read => &state.read_peer,
-        PeerRole::Execution => &state.execution_peer,
+    let (pool, attempts) = match peer_type {
+        // Reads are idempotent, so they can be retried on another upstream if they fail.
+        PeerRole::Read => (&state.read_pool, state.read_retries + 1),
+        PeerRole::Execution => (&state.execution_pool, 1),
     };
 
-    let mut target_url = peer_config.address.clone();
-    target_url.set_path(parts.uri.path());
-    if let Some(query) = parts.uri.query() {
-        target_url.set_query(Some(query));
-    }
-
     // remove host header to avoid interfering with reqwest auto-host header
     let mut headers = parts.headers.clone();
     headers.remove("host");
-    let request_builder = state
-        .client
-        .request(parts.method.clone(), target_url)
-        .headers(headers)
-        .body(body_bytes.clone());
-    debug!("Request builder: {:?}", request_builder);
-
-    let upstream_start = Instant::now();
-    let response = match request_builder.send().await {
-        Ok(response) => {
-            let status = response.status().as_u16().to_string();
-            metrics
-                .upstream_response_latency
-                .with_label_values(&[peer_type_str, &status])
-                .observe(upstream_start.elapsed().as_secs_f64());
-            metrics
-                .requests_total
-                .with_label_values(&[peer_type_str, &status])
-                .inc();
-            debug!("Response: {:?}", response);
-            response
+
+    let mut tried = vec![];
+    let response = loop {
+        let Some((index, upstream)) = pool.select(&tried) else {
+            return Err((
+                StatusCode::BAD_GATEWAY,
+                format!("No {peer_type_str} upstream available"),
+            ));
+        };
+        tried.push(index);
+        let can_retry = tried.len() < attempts && tried.len() < pool.len();
+
+        let mut target_url = upstream.address().clone();
+        target_url.set_path(parts.uri.path());
+        if let Some(query) = parts.uri.query() {
+            target_url.set_query(Some(query));
         }
-        Err(e) => {
-            warn!("Failed to send request: {}", e);
-            metrics
-                .upstream_response_latency
-                .with_label_values(&[peer_type_str, "error"])
-                .observe(upstream_start.elapsed().as_secs_f64());
-            metrics
-                .requests_total
-                .with_label_values(&[peer_type_str, "error"])
-                .inc();
-            if e.is_timeout() {
+
+        let request_builder = state
+            .client
+            .request(parts.method.clone(), target_url)
+            .headers(headers.clone())
+            .body(body_bytes.clone());
+        debug!("Request builder: {:?}", request_builder);
+
+        let upstream_start = Instant::now();
+        match request_builder.send().await {
+            Ok(response) => {
+                let status = response.status().as_u16().to_string();
+                metrics
+                    .upstream_response_latency
+                    .with_label_values(&[peer_type_str, &status])
+                    .observe(upstream_start.elapsed().as_secs_f64());
                 metrics
-                    .timeouts_total
-                    .with_label_values(&[peer_type_str])
+                    .requests_total
+                    .with_label_values(&[peer_type_str, &status])
                     .inc();
+                debug!("Response: {:?}", response);
+
+                if response.status().is_server_error() && can_retry {
+                    warn!(
+                        "Upstream {} responded with {}, retrying",
+                        upstream.address(),
+                        response.status()
+                    );
+                    metrics
+                        .retries_total
+                        .with_label_values(&[peer_type_str])
+                        .inc();
+                    continue;
+                }
+
+                upstream.record_latency(upstream_start.elapsed());
+                break response;
+            }
+            Err(e) => {
+                warn!("Failed to send request to {}: {}", upstream.address(), e);
+                metrics
+                    .upstream_response_latency
+                    .with_label_values(&[peer_type_str, "error"])
+                    .observe(upstream_start.elapsed().as_secs_f64());
+                metrics
+                    .requests_total
+                    .with_label_values(&[peer_type_str, "error"])
+                    .inc();
+                if e.is_timeout() {
+                    metrics
+                        .timeouts_total
+                        .with_label_values(&[peer_type_str])
+                        .inc();
+                }
+
+                if can_retry {
+                    metrics
+                        .retries_total
+                        .with_label_values(&[peer_type_str])
+                        .inc();
+                    continue;
+                }
+
+                return Err((StatusCode::BAD_GATEWAY, format!("Request failed: {}", e)));
             }
-            return Err((StatusCode::BAD_GATEWAY, format!("Request failed: {}", e)));
         }
     };
 
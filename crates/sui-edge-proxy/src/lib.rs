@@ -4,3 +4,4 @@
 pub mod config;
 pub mod handlers;
 pub mod metrics;
+pub mod upstream;
@@ -5,9 +5,11 @@ use axum::{routing::any, Router};
 use clap::Parser;
 use mysten_metrics::start_prometheus_server;
 use reqwest::Client;
+use std::sync::Arc;
 use sui_edge_proxy::config::{load, ProxyConfig};
 use sui_edge_proxy::handlers::{proxy_handler, AppState};
 use sui_edge_proxy::metrics::AppMetrics;
+use sui_edge_proxy::upstream::UpstreamPool;
 use tracing::info;
 
 #[derive(Parser, Debug)]
@@ -42,10 +44,35 @@ async fn main() {
 
     let app_metrics = AppMetrics::new(&prometheus_registry);
 
+    let read_pool = Arc::new(UpstreamPool::new(
+        "read",
+        config.read_peers.clone(),
+        config.selection,
+    ));
+    let execution_pool = Arc::new(UpstreamPool::new(
+        "execution",
+        config.execution_peers.clone(),
+        config.selection,
+    ));
+
+    for pool in [&read_pool, &execution_pool] {
+        info!(
+            "Checking health of {} {} upstream(s)",
+            pool.len(),
+            pool.role()
+        );
+        tokio::spawn(pool.clone().run_health_checks(
+            client.clone(),
+            config.health_check.clone(),
+            app_metrics.clone(),
+        ));
+    }
+
     let app_state = AppState::new(
         client,
-        config.read_peer.clone(),
-        config.execution_peer.clone(),
+        read_pool,
+        execution_pool,
+        config.read_retries,
         app_metrics,
         config.logging,
     );
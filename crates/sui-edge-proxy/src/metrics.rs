@@ -3,12 +3,15 @@
 
 use prometheus::{
     register_gauge_vec_with_registry, register_histogram_vec_with_registry,
-    register_int_counter_vec_with_registry, GaugeVec, HistogramVec, IntCounterVec, Registry,
+    register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry, GaugeVec,
+    HistogramVec, IntCounterVec, IntGaugeVec, Registry,
 };
 
 #[derive(Clone)]
 pub struct AppMetrics {
     pub backend_up: GaugeVec,
+    pub upstream_up: GaugeVec,
+    pub upstream_checkpoint_lag: IntGaugeVec,
     pub requests_total: IntCounterVec,
     pub request_latency: HistogramVec,
     pub upstream_response_latency: HistogramVec,
@@ -16,6 +19,7 @@ pub struct AppMetrics {
     pub request_size_bytes: HistogramVec,
     pub timeouts_total: IntCounterVec,
     pub error_counts: IntCounterVec,
+    pub retries_total: IntCounterVec,
 }
 
 impl AppMetrics {
@@ -28,6 +32,20 @@ impl AppMetrics {
                 registry
             )
             .unwrap(),
+            upstream_up: register_gauge_vec_with_registry!(
+                "edge_proxy_upstream_up",
+                "Indicates if an individual upstream is healthy (1) or ejected (0)",
+                &["peer_type", "peer"],
+                registry
+            )
+            .unwrap(),
+            upstream_checkpoint_lag: register_int_gauge_vec_with_registry!(
+                "edge_proxy_upstream_checkpoint_lag",
+                "Number of checkpoints the upstream is behind the most up-to-date upstream for the same role",
+                &["peer_type", "peer"],
+                registry
+            )
+            .unwrap(),
             requests_total: register_int_counter_vec_with_registry!(
                 "edge_proxy_requests_total",
                 "Total number of requests processed by the edge proxy",
@@ -77,6 +95,13 @@ impl AppMetrics {
                 registry
             )
             .unwrap(),
+            retries_total: register_int_counter_vec_with_registry!(
+                "edge_proxy_retries_total",
+                "Total number of requests retried on another upstream",
+                &["peer_type"],
+                registry
+            )
+            .unwrap(),
         }
     }
 }
@@ -0,0 +1,456 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::{HealthCheckConfig, PeerConfig, SelectionStrategy};
+use crate::metrics::AppMetrics;
+use anyhow::{anyhow, Context, Result};
+use reqwest::Client;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use url::Url;
+
+/// Weight given to the newest sample when updating an upstream's average latency.
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+/// A single upstream fullnode, along with what the proxy has learned about its health.
+pub struct Upstream {
+    config: PeerConfig,
+    /// Whether the upstream responded to its last health check, and was not lagging.
+    healthy: AtomicBool,
+    /// Exponentially weighted moving average of response latency, in microseconds.
+    latency_micros: AtomicU64,
+}
+
+/// The upstreams serving one role (reads or execution), and the state needed to pick between
+/// them.
+pub struct UpstreamPool {
+    role: &'static str,
+    upstreams: Vec<Upstream>,
+    strategy: SelectionStrategy,
+    /// Counts requests, to drive weighted round-robin selection.
+    next: AtomicUsize,
+}
+
+impl Upstream {
+    fn new(config: PeerConfig) -> Self {
+        Self {
+            config,
+            // Upstreams are assumed healthy until a health check says otherwise.
+            healthy: AtomicBool::new(true),
+            latency_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn address(&self) -> &Url {
+        &self.config.address
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// Fold a new latency sample into the upstream's average latency.
+    pub fn record_latency(&self, latency: Duration) {
+        let sample = latency.as_micros() as f64;
+        let _ = self
+            .latency_micros
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |prev| {
+                Some(if prev == 0 {
+                    sample as u64
+                } else {
+                    (prev as f64 * (1.0 - LATENCY_SMOOTHING) + sample * LATENCY_SMOOTHING) as u64
+                })
+            });
+    }
+
+    fn latency_micros(&self) -> u64 {
+        self.latency_micros.load(Ordering::Relaxed)
+    }
+}
+
+impl UpstreamPool {
+    pub fn new(role: &'static str, peers: Vec<PeerConfig>, strategy: SelectionStrategy) -> Self {
+        Self {
+            role,
+            upstreams: peers.into_iter().map(Upstream::new).collect(),
+            strategy,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn role(&self) -> &'static str {
+        self.role
+    }
+
+    pub fn len(&self) -> usize {
+        self.upstreams.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.upstreams.is_empty()
+    }
+
+    /// Pick an upstream to send a request to, skipping the upstreams at indices in `tried` (which
+    /// have already failed this request). Unhealthy upstreams are only picked if there are no
+    /// healthy ones left, on the basis that a stale response is better than none.
+    pub fn select(&self, tried: &[usize]) -> Option<(usize, &Upstream)> {
+        let untried = || {
+            self.upstreams
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !tried.contains(i))
+        };
+
+        let healthy: Vec<_> = untried().filter(|(_, u)| u.is_healthy()).collect();
+        let candidates = if healthy.is_empty() {
+            untried().collect()
+        } else {
+            healthy
+        };
+
+        match self.strategy {
+            SelectionStrategy::WeightedRoundRobin => {
+                let total: u64 = candidates.iter().map(|(_, u)| u.config.weight as u64).sum();
+                if total == 0 {
+                    return None;
+                }
+
+                let mut ticket = self.next.fetch_add(1, Ordering::Relaxed) as u64 % total;
+                for (i, upstream) in candidates {
+                    let weight = upstream.config.weight as u64;
+                    if ticket < weight {
+                        return Some((i, upstream));
+                    }
+                    ticket -= weight;
+                }
+
+                None
+            }
+
+            SelectionStrategy::LeastLatency => candidates
+                .into_iter()
+                .min_by_key(|(_, u)| u.latency_micros()),
+        }
+    }
+
+    /// Poll every upstream for its latest checkpoint, and mark upstreams that don't respond, or
+    /// that are more than `max_checkpoint_lag` checkpoints behind the most up-to-date upstream, as
+    /// unhealthy.
+    pub async fn check_health(
+        &self,
+        client: &Client,
+        config: &HealthCheckConfig,
+        metrics: &AppMetrics,
+    ) {
+        let checks = self.upstreams.iter().map(|upstream| async move {
+            let start = Instant::now();
+            let result =
+                latest_checkpoint(client, upstream.address(), config.timeout_seconds).await;
+            if result.is_ok() {
+                upstream.record_latency(start.elapsed());
+            }
+            result
+        });
+
+        let results = futures::future::join_all(checks).await;
+        let max_checkpoint = results.iter().flatten().copied().max();
+
+        for (upstream, result) in self.upstreams.iter().zip(results) {
+            let address = upstream.address().as_str();
+            let healthy = match result {
+                Ok(checkpoint) => {
+                    let lag = max_checkpoint.unwrap_or(checkpoint) - checkpoint;
+                    metrics
+                        .upstream_checkpoint_lag
+                        .with_label_values(&[self.role, address])
+                        .set(lag as i64);
+
+                    if lag > config.max_checkpoint_lag {
+                        debug!(
+                            "{} upstream {address} is {lag} checkpoints behind",
+                            self.role
+                        );
+                        false
+                    } else {
+                        true
+                    }
+                }
+
+                Err(e) => {
+                    debug!(
+                        "Health check failed for {} upstream {address}: {e:#}",
+                        self.role
+                    );
+                    false
+                }
+            };
+
+            let was_healthy = upstream.healthy.swap(healthy, Ordering::Relaxed);
+            match (was_healthy, healthy) {
+                (true, false) => warn!("Ejecting {} upstream {address}", self.role),
+                (false, true) => info!("Restoring {} upstream {address}", self.role),
+                _ => {}
+            }
+
+            metrics
+                .upstream_up
+                .with_label_values(&[self.role, address])
+                .set(if healthy { 1.0 } else { 0.0 });
+        }
+
+        // The backend for a role is up as long as one of its upstreams is healthy.
+        let any_healthy = self.upstreams.iter().any(Upstream::is_healthy);
+        metrics
+            .backend_up
+            .with_label_values(&[self.role])
+            .set(if any_healthy { 1.0 } else { 0.0 });
+    }
+
+    /// Check the health of the pool's upstreams every `config.interval_seconds`, forever.
+    pub async fn run_health_checks(
+        self: Arc<Self>,
+        client: Client,
+        config: HealthCheckConfig,
+        metrics: AppMetrics,
+    ) {
+        let mut interval = tokio::time::interval(config.interval_seconds);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.check_health(&client, &config, &metrics).await;
+        }
+    }
+}
+
+/// Ask the upstream at `address` for its latest checkpoint sequence number over JSON-RPC.
+async fn latest_checkpoint(client: &Client, address: &Url, timeout: Duration) -> Result<u64> {
+    let response: serde_json::Value = client
+        .post(address.clone())
+        .timeout(timeout)
+        .json(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sui_getLatestCheckpointSequenceNumber",
+            "params": [],
+        }))
+        .send()
+        .await
+        .context("Failed to send request")?
+        .error_for_status()?
+        .json()
+        .await
+        .context("Failed to parse response")?;
+
+    // Sequence numbers are returned as strings, to avoid losing precision in JavaScript.
+    let result = response
+        .get("result")
+        .ok_or_else(|| anyhow!("No result in response: {response}"))?;
+
+    match result {
+        serde_json::Value::String(s) => s.parse().context("Invalid checkpoint sequence number"),
+        serde_json::Value::Number(n) => n
+            .as_u64()
+            .ok_or_else(|| anyhow!("Invalid checkpoint sequence number: {n}")),
+        _ => Err(anyhow!("Invalid checkpoint sequence number: {result}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, routing::post, Json, Router};
+    use prometheus::Registry;
+    use tokio::net::TcpListener;
+
+    fn peer(port: u16, weight: u32) -> PeerConfig {
+        PeerConfig {
+            address: Url::parse(&format!("http://127.0.0.1:{port}/")).unwrap(),
+            weight,
+        }
+    }
+
+    fn pool(weights: &[u32], strategy: SelectionStrategy) -> UpstreamPool {
+        let peers = weights
+            .iter()
+            .enumerate()
+            .map(|(i, w)| peer(9000 + i as u16, *w))
+            .collect();
+        UpstreamPool::new("read", peers, strategy)
+    }
+
+    /// Select an upstream `n` times, and count how often each one was picked.
+    fn selections(pool: &UpstreamPool, tried: &[usize], n: usize) -> Vec<usize> {
+        let mut counts = vec![0; pool.len()];
+        for _ in 0..n {
+            let (i, _) = pool.select(tried).unwrap();
+            counts[i] += 1;
+        }
+        counts
+    }
+
+    /// Start a fake fullnode that reports `checkpoint` as its latest checkpoint, returning its
+    /// port.
+    async fn fake_fullnode(checkpoint: Arc<AtomicU64>) -> u16 {
+        async fn latest(State(checkpoint): State<Arc<AtomicU64>>) -> Json<serde_json::Value> {
+            Json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": checkpoint.load(Ordering::Relaxed).to_string(),
+            }))
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let app = Router::new()
+            .route("/", post(latest))
+            .with_state(checkpoint);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        port
+    }
+
+    /// A port that nothing is listening on.
+    async fn unused_port() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().port()
+    }
+
+    #[test]
+    fn weighted_round_robin() {
+        let pool = pool(&[1, 3], SelectionStrategy::WeightedRoundRobin);
+        assert_eq!(selections(&pool, &[], 400), vec![100, 300]);
+    }
+
+    #[test]
+    fn least_latency() {
+        let pool = pool(&[1, 1, 1], SelectionStrategy::LeastLatency);
+        pool.upstreams[0].record_latency(Duration::from_millis(10));
+        pool.upstreams[1].record_latency(Duration::from_millis(5));
+        pool.upstreams[2].record_latency(Duration::from_millis(20));
+        assert_eq!(selections(&pool, &[], 10), vec![0, 10, 0]);
+
+        // Latency is smoothed, so a single slow response doesn't immediately demote an upstream.
+        pool.upstreams[1].record_latency(Duration::from_millis(20));
+        assert!((7_999..=8_000).contains(&pool.upstreams[1].latency_micros()));
+        assert_eq!(selections(&pool, &[], 10), vec![0, 10, 0]);
+
+        pool.upstreams[1].record_latency(Duration::from_millis(50));
+        assert_eq!(selections(&pool, &[], 10), vec![10, 0, 0]);
+    }
+
+    #[test]
+    fn unhealthy_fallback() {
+        let pool = pool(&[1, 1, 1], SelectionStrategy::WeightedRoundRobin);
+        pool.upstreams[1].healthy.store(false, Ordering::Relaxed);
+        assert_eq!(selections(&pool, &[], 10), vec![5, 0, 5]);
+
+        // Unhealthy upstreams are still used when there is nothing else left.
+        pool.upstreams[0].healthy.store(false, Ordering::Relaxed);
+        pool.upstreams[2].healthy.store(false, Ordering::Relaxed);
+        assert_eq!(selections(&pool, &[], 9), vec![3, 3, 3]);
+        assert_eq!(selections(&pool, &[0, 2], 3), vec![0, 3, 0]);
+    }
+
+    #[test]
+    fn tried_exclusion() {
+        for strategy in [
+            SelectionStrategy::WeightedRoundRobin,
+            SelectionStrategy::LeastLatency,
+        ] {
+            let pool = pool(&[1, 1, 1], strategy);
+            assert_eq!(selections(&pool, &[0], 6)[0], 0);
+            assert_eq!(selections(&pool, &[0, 2], 3), vec![0, 3, 0]);
+            assert!(pool.select(&[0, 1, 2]).is_none());
+        }
+
+        // A healthy upstream that has already been tried is not preferred over an unhealthy one
+        // that has not.
+        let pool = pool(&[1, 1], SelectionStrategy::WeightedRoundRobin);
+        pool.upstreams[1].healthy.store(false, Ordering::Relaxed);
+        assert_eq!(selections(&pool, &[0], 2), vec![0, 2]);
+    }
+
+    #[tokio::test]
+    async fn lag_ejection() {
+        let fresh = Arc::new(AtomicU64::new(100));
+        let recent = Arc::new(AtomicU64::new(80));
+        let stale = Arc::new(AtomicU64::new(50));
+
+        let peers = vec![
+            peer(fake_fullnode(fresh.clone()).await, 1),
+            peer(fake_fullnode(recent.clone()).await, 1),
+            peer(fake_fullnode(stale.clone()).await, 1),
+            peer(unused_port().await, 1),
+        ];
+
+        let pool = UpstreamPool::new("read", peers, SelectionStrategy::WeightedRoundRobin);
+        let client = Client::new();
+        let config = HealthCheckConfig {
+            interval_seconds: Duration::from_secs(1),
+            timeout_seconds: Duration::from_secs(1),
+            max_checkpoint_lag: 30,
+        };
+        let metrics = AppMetrics::new(&Registry::new());
+
+        let upstream_up = |i: usize| {
+            metrics
+                .upstream_up
+                .with_label_values(&["read", pool.upstreams[i].address().as_str()])
+                .get()
+        };
+
+        let lag = |i: usize| {
+            metrics
+                .upstream_checkpoint_lag
+                .with_label_values(&["read", pool.upstreams[i].address().as_str()])
+                .get()
+        };
+
+        pool.check_health(&client, &config, &metrics).await;
+        let healthy: Vec<_> = pool.upstreams.iter().map(Upstream::is_healthy).collect();
+        assert_eq!(healthy, vec![true, true, false, false]);
+        assert_eq!(
+            (0..4).map(upstream_up).collect::<Vec<_>>(),
+            vec![1.0, 1.0, 0.0, 0.0]
+        );
+        assert_eq!((0..3).map(lag).collect::<Vec<_>>(), vec![0, 20, 50]);
+        assert_eq!(metrics.backend_up.with_label_values(&["read"]).get(), 1.0);
+        assert_eq!(selections(&pool, &[], 4), vec![2, 2, 0, 0]);
+
+        // The stale upstream catches up, and is restored.
+        stale.store(100, Ordering::Relaxed);
+        pool.check_health(&client, &config, &metrics).await;
+        let healthy: Vec<_> = pool.upstreams.iter().map(Upstream::is_healthy).collect();
+        assert_eq!(healthy, vec![true, true, true, false]);
+        assert_eq!(lag(2), 0);
+
+        // Lag is measured against the most up-to-date upstream.
+        fresh.store(200, Ordering::Relaxed);
+        pool.check_health(&client, &config, &metrics).await;
+        let healthy: Vec<_> = pool.upstreams.iter().map(Upstream::is_healthy).collect();
+        assert_eq!(healthy, vec![true, false, false, false]);
+        assert_eq!(metrics.backend_up.with_label_values(&["read"]).get(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn all_down() {
+        let peers = vec![peer(unused_port().await, 1), peer(unused_port().await, 1)];
+        let pool = UpstreamPool::new("execution", peers, SelectionStrategy::LeastLatency);
+        let config = HealthCheckConfig {
+            timeout_seconds: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let metrics = AppMetrics::new(&Registry::new());
+
+        pool.check_health(&Client::new(), &config, &metrics).await;
+        assert!(pool.upstreams.iter().all(|u| !u.is_healthy()));
+        assert_eq!(
+            metrics.backend_up.with_label_values(&["execution"]).get(),
+            0.0
+        );
+
+        // Requests are still sent somewhere, in case the health checks are wrong.
+        assert!(pool.select(&[]).is_some());
+    }
+}
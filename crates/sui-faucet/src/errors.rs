@@ -4,7 +4,7 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Serialize, Deserialize, Error, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Error, Debug, Clone, PartialEq, Eq)]
 pub enum FaucetError {
     #[error("Wallet Error: `{0}`")]
     Wallet(String),
@@ -12,6 +12,12 @@ pub enum FaucetError {
     #[error("Coin Transfer Failed `{0}`")]
     Transfer(String),
 
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
+    #[error("Request not found: {0}")]
+    RequestNotFound(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -3,9 +3,12 @@
 
 use clap::Parser;
 use std::net::Ipv4Addr;
+use std::path::PathBuf;
 
 pub const DEFAULT_AMOUNT: u64 = 200_000_000_000;
 pub const DEFAULT_NUM_COINS: usize = 5;
+pub const DEFAULT_QUOTA_WINDOW_SECS: u64 = 24 * 60 * 60;
+pub const DEFAULT_BATCH_INTERVAL_MS: u64 = 500;
 
 #[derive(Parser, Clone)]
 #[clap(
@@ -29,6 +32,44 @@ pub struct FaucetConfig {
 
     #[clap(long, default_value_t = 60)]
     pub wallet_client_timeout_secs: u64,
+
+    /// Maximum number of requests that can be made for the same recipient address within
+    /// `quota-window-secs`. Unlimited if unset.
+    #[clap(long)]
+    pub max_requests_per_address: Option<u64>,
+
+    /// Maximum number of requests that can be made from the same client IP within
+    /// `quota-window-secs`. Unlimited if unset.
+    #[clap(long)]
+    pub max_requests_per_ip: Option<u64>,
+
+    /// Header that the proxy in front of the faucet puts the client's IP address in (e.g.
+    /// `x-forwarded-for`). The last address in the header is used, as that is the one added by
+    /// the proxy. Requests that arrive without the header are not subject to per-IP quotas, so
+    /// the faucet should only be reachable through that proxy. If unset, the address of the peer
+    /// that connected to the faucet is used.
+    #[clap(long)]
+    pub client_ip_header: Option<String>,
+
+    /// Length of the sliding window that request quotas apply over.
+    #[clap(long, default_value_t = DEFAULT_QUOTA_WINDOW_SECS)]
+    pub quota_window_secs: u64,
+
+    /// Directory to persist the ledger of requests in. If unset, the ledger is only kept in
+    /// memory, and quotas are reset when the faucet restarts. Either way, requests are only kept
+    /// for `quota-window-secs`.
+    #[clap(long)]
+    pub ledger_path: Option<PathBuf>,
+
+    /// Pay out to up to this many recipients in a single transaction, by collecting requests that
+    /// arrive within `batch-interval-ms` of each other. Requests are paid out one at a time if
+    /// unset.
+    #[clap(long)]
+    pub batch_size: Option<usize>,
+
+    /// How long to wait for a batch to fill up before paying it out.
+    #[clap(long, default_value_t = DEFAULT_BATCH_INTERVAL_MS)]
+    pub batch_interval_ms: u64,
 }
 
 impl Default for FaucetConfig {
@@ -39,6 +80,13 @@ impl Default for FaucetConfig {
             amount: DEFAULT_AMOUNT,
             num_coins: DEFAULT_NUM_COINS,
             wallet_client_timeout_secs: 60,
+            max_requests_per_address: None,
+            max_requests_per_ip: None,
+            client_ip_header: None,
+            quota_window_secs: DEFAULT_QUOTA_WINDOW_SECS,
+            ledger_path: None,
+            batch_size: None,
+            batch_interval_ms: DEFAULT_BATCH_INTERVAL_MS,
         }
     }
 }
@@ -0,0 +1,310 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sui_sdk::types::base_types::SuiAddress;
+use typed_store::rocks::{DBMap, MetricConf};
+use typed_store::{DBMapUtils, Map};
+
+use crate::{CoinInfo, FaucetError, RequestStatus};
+
+/// Everything the faucet knows about a request it has accepted, as recorded in the ledger.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RequestRecord {
+    pub request_id: String,
+    pub recipient: SuiAddress,
+    pub client_ip: Option<IpAddr>,
+    pub timestamp_ms: u64,
+    pub status: RequestStatus,
+    pub coins_sent: Option<Vec<CoinInfo>>,
+}
+
+#[derive(DBMapUtils)]
+struct LedgerTables {
+    /// Requests, keyed by request ID.
+    requests: DBMap<String, RequestRecord>,
+    /// Index of request IDs by the time they were accepted, to find recent requests.
+    requests_by_time: DBMap<(u64, String), ()>,
+}
+
+/// A record of the requests the faucet has accepted, and what it paid out in response. Records
+/// are forgotten once they fall out of the retention window, to bound the ledger's size.
+pub struct Ledger {
+    /// How long records are kept for, after they were accepted.
+    retention_ms: u64,
+    store: Store,
+}
+
+enum Store {
+    /// Records are lost when the faucet restarts.
+    InMemory(Mutex<InMemoryLedger>),
+    /// Records are persisted to a RocksDB database.
+    Persistent(LedgerTables),
+}
+
+struct InMemoryLedger {
+    requests: HashMap<String, RequestRecord>,
+    requests_by_time: BTreeMap<(u64, String), ()>,
+}
+
+impl Ledger {
+    /// A ledger that only keeps records in memory, for `retention` after they were accepted
+    /// (usually the quota window, after which records no longer count against quotas).
+    pub fn in_memory(retention: Duration) -> Self {
+        Ledger {
+            retention_ms: retention.as_millis() as u64,
+            store: Store::InMemory(Mutex::new(InMemoryLedger {
+                requests: HashMap::new(),
+                requests_by_time: BTreeMap::new(),
+            })),
+        }
+    }
+
+    /// A ledger that persists records in a database at `path`, for `retention` after they were
+    /// accepted.
+    pub fn open(path: &Path, retention: Duration) -> Self {
+        Ledger {
+            retention_ms: retention.as_millis() as u64,
+            store: Store::Persistent(LedgerTables::open_tables_read_write(
+                path.to_path_buf(),
+                MetricConf::new("faucet_ledger"),
+                None,
+                None,
+            )),
+        }
+    }
+
+    /// Add a new request to the ledger, or update an existing one. Requests that have fallen out
+    /// of the retention window, as of this request, are forgotten.
+    pub fn record(&self, record: &RequestRecord) -> Result<(), FaucetError> {
+        let time_key = (record.timestamp_ms, record.request_id.clone());
+        let cutoff_ms = record.timestamp_ms.saturating_sub(self.retention_ms);
+        match &self.store {
+            Store::InMemory(ledger) => {
+                let mut ledger = ledger.lock().unwrap();
+                ledger.requests_by_time.insert(time_key, ());
+                ledger
+                    .requests
+                    .insert(record.request_id.clone(), record.clone());
+
+                ledger.prune(cutoff_ms);
+                Ok(())
+            }
+
+            Store::Persistent(tables) => {
+                let expired = tables.expired(cutoff_ms)?;
+                let mut batch = tables.requests.batch();
+                batch
+                    .delete_batch(&tables.requests_by_time, &expired)
+                    .map_err(|e| FaucetError::internal(format!("Couldn't prune ledger: {e:?}")))?;
+                batch
+                    .delete_batch(&tables.requests, expired.iter().map(|(_, id)| id))
+                    .map_err(|e| FaucetError::internal(format!("Couldn't prune ledger: {e:?}")))?;
+                batch
+                    .insert_batch(&tables.requests_by_time, [(time_key, ())])
+                    .map_err(|e| {
+                        FaucetError::internal(format!("Couldn't insert into ledger: {e:?}"))
+                    })?;
+                batch
+                    .insert_batch(&tables.requests, [(&record.request_id, record)])
+                    .map_err(|e| {
+                        FaucetError::internal(format!("Couldn't insert into ledger: {e:?}"))
+                    })?;
+                batch
+                    .write()
+                    .map_err(|e| FaucetError::internal(format!("Couldn't write to ledger: {e:?}")))
+            }
+        }
+    }
+
+    /// Look up a request by its ID.
+    pub fn get(&self, request_id: &str) -> Result<Option<RequestRecord>, FaucetError> {
+        match &self.store {
+            Store::InMemory(ledger) => Ok(ledger.lock().unwrap().requests.get(request_id).cloned()),
+            Store::Persistent(tables) => tables
+                .requests
+                .get(&request_id.to_string())
+                .map_err(|e| FaucetError::internal(format!("Couldn't read from ledger: {e:?}"))),
+        }
+    }
+
+    /// All requests accepted at or after `timestamp_ms`, in the order they were accepted.
+    pub fn requests_since(&self, timestamp_ms: u64) -> Result<Vec<RequestRecord>, FaucetError> {
+        let start = (timestamp_ms, String::new());
+        match &self.store {
+            Store::InMemory(ledger) => {
+                let ledger = ledger.lock().unwrap();
+                Ok(ledger
+                    .requests_by_time
+                    .range(start..)
+                    .filter_map(|((_, id), _)| ledger.requests.get(id).cloned())
+                    .collect())
+            }
+
+            Store::Persistent(tables) => {
+                let ids: Vec<_> = tables
+                    .requests_by_time
+                    .safe_iter_with_bounds(Some(start), None)
+                    .map(|entry| entry.map(|((_, id), _)| id))
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| {
+                        FaucetError::internal(format!("Couldn't read from ledger: {e:?}"))
+                    })?;
+
+                Ok(tables
+                    .requests
+                    .multi_get(&ids)
+                    .map_err(|e| {
+                        FaucetError::internal(format!("Couldn't read from ledger: {e:?}"))
+                    })?
+                    .into_iter()
+                    .flatten()
+                    .collect())
+            }
+        }
+    }
+}
+
+impl LedgerTables {
+    /// The time index keys of requests accepted before `cutoff_ms`.
+    fn expired(&self, cutoff_ms: u64) -> Result<Vec<(u64, String)>, FaucetError> {
+        self.requests_by_time
+            .safe_iter_with_bounds(None, Some((cutoff_ms, String::new())))
+            .map(|entry| entry.map(|(key, _)| key))
+            .collect::<Result<_, _>>()
+            .map_err(|e| FaucetError::internal(format!("Couldn't read from ledger: {e:?}")))
+    }
+}
+
+impl InMemoryLedger {
+    /// Forget requests accepted before `cutoff_ms`.
+    fn prune(&mut self, cutoff_ms: u64) {
+        let recent = self.requests_by_time.split_off(&(cutoff_ms, String::new()));
+
+        for (_, id) in std::mem::replace(&mut self.requests_by_time, recent).into_keys() {
+            self.requests.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(request_id: &str, timestamp_ms: u64, status: RequestStatus) -> RequestRecord {
+        RequestRecord {
+            request_id: request_id.to_string(),
+            recipient: SuiAddress::ZERO,
+            client_ip: Some("127.0.0.1".parse().unwrap()),
+            timestamp_ms,
+            status,
+            coins_sent: None,
+        }
+    }
+
+    fn check_ledger(ledger: &Ledger) {
+        ledger
+            .record(&record("a", 10, RequestStatus::Pending))
+            .unwrap();
+        ledger
+            .record(&record("b", 20, RequestStatus::Pending))
+            .unwrap();
+        ledger
+            .record(&record("c", 30, RequestStatus::Pending))
+            .unwrap();
+        ledger
+            .record(&record("b", 20, RequestStatus::Success))
+            .unwrap();
+
+        let b = ledger.get("b").unwrap().unwrap();
+        assert!(matches!(b.status, RequestStatus::Success));
+        assert!(ledger.get("d").unwrap().is_none());
+
+        let recent: Vec<_> = ledger
+            .requests_since(20)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.request_id)
+            .collect();
+        assert_eq!(recent, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn in_memory_ledger() {
+        check_ledger(&Ledger::in_memory(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn in_memory_ledger_pruning() {
+        let ledger = Ledger::in_memory(Duration::from_millis(100));
+        ledger
+            .record(&record("a", 10, RequestStatus::Pending))
+            .unwrap();
+        ledger
+            .record(&record("b", 50, RequestStatus::Pending))
+            .unwrap();
+
+        // Recording a request forgets the requests that fall outside the retention window.
+        ledger
+            .record(&record("c", 120, RequestStatus::Pending))
+            .unwrap();
+        assert!(ledger.get("a").unwrap().is_none());
+        assert!(ledger.get("b").unwrap().is_some());
+
+        let recent: Vec<_> = ledger
+            .requests_since(0)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.request_id)
+            .collect();
+        assert_eq!(recent, vec!["b", "c"]);
+
+        let Store::InMemory(inner) = &ledger.store else {
+            panic!("Expected an in-memory ledger");
+        };
+        assert_eq!(inner.lock().unwrap().requests.len(), 2);
+    }
+
+    #[test]
+    fn persistent_ledger() {
+        let dir = tempfile::tempdir().unwrap();
+        check_ledger(&Ledger::open(dir.path(), Duration::from_secs(60)));
+
+        // Records survive re-opening the ledger.
+        let ledger = Ledger::open(dir.path(), Duration::from_secs(60));
+        assert!(ledger.get("a").unwrap().is_some());
+    }
+
+    #[test]
+    fn persistent_ledger_pruning() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = Ledger::open(dir.path(), Duration::from_millis(100));
+        ledger
+            .record(&record("a", 10, RequestStatus::Pending))
+            .unwrap();
+        ledger
+            .record(&record("b", 50, RequestStatus::Pending))
+            .unwrap();
+
+        // Recording a request deletes the requests that fall outside the retention window.
+        ledger
+            .record(&record("c", 120, RequestStatus::Pending))
+            .unwrap();
+        assert!(ledger.get("a").unwrap().is_none());
+        assert!(ledger.get("b").unwrap().is_some());
+
+        let recent: Vec<_> = ledger
+            .requests_since(0)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.request_id)
+            .collect();
+        assert_eq!(recent, vec!["b", "c"]);
+    }
+}
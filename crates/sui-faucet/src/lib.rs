@@ -4,13 +4,19 @@
 mod app_state;
 mod errors;
 mod faucet_config;
+mod ledger;
 mod local_faucet;
+mod quota;
 mod server;
 mod types;
 
 pub use app_state::AppState;
 pub use errors::FaucetError;
 pub use faucet_config::FaucetConfig;
+pub use ledger::RequestRecord;
 pub use local_faucet::LocalFaucet;
 pub use server::{create_wallet_context, start_faucet};
-pub use types::{CoinInfo, FaucetRequest, FaucetResponse, FixedAmountRequest, RequestStatus};
+pub use types::{
+    CoinInfo, FaucetRequest, FaucetResponse, FixedAmountRequest, RequestStatus,
+    RequestStatusResponse,
+};
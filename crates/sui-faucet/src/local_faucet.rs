@@ -1,16 +1,23 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
 use std::fmt;
-use std::sync::Arc;
+use std::net::IpAddr;
+use std::sync::{Arc, Weak};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::bail;
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, oneshot, Mutex};
 use tokio::time::Duration;
-use tracing::info;
+use tracing::{error, info};
+use uuid::Uuid;
 
+use crate::ledger::{Ledger, RequestRecord};
+use crate::quota::Quotas;
 use crate::FaucetConfig;
 use crate::FaucetError;
+use crate::{FaucetResponse, RequestStatus};
 use sui_sdk::{
     rpc_types::{SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions},
     types::quorum_driver_types::ExecuteTransactionRequestType,
@@ -24,6 +31,7 @@ use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBui
 use sui_sdk::types::{
     base_types::{ObjectID, SuiAddress},
     gas_coin::GasCoin,
+    object::Owner,
     transaction::{Transaction, TransactionData},
 };
 use sui_sdk::wallet_context::WalletContext;
@@ -37,6 +45,15 @@ pub struct LocalFaucet {
     coin_id: Arc<Mutex<ObjectID>>,
     coin_amount: u64,
     num_coins: usize,
+    quotas: Quotas,
+    ledger: Ledger,
+    /// Set in batch mode, to send requests to be paid out as part of the next batch.
+    batches: Option<mpsc::Sender<BatchedRequest>>,
+}
+
+struct BatchedRequest {
+    recipient: SuiAddress,
+    coins: oneshot::Sender<Result<Vec<CoinInfo>, FaucetError>>,
 }
 
 /// We do not just derive(Debug) because WalletContext and the WriteAheadLog do not implement Debug / are also hard
@@ -58,13 +75,123 @@ impl LocalFaucet {
         let (coins, active_address) = find_gas_coins_and_address(&mut wallet, &config).await?;
         info!("Starting faucet with address: {:?}", active_address);
 
-        Ok(Arc::new(LocalFaucet {
+        // Requests are only kept in memory for as long as they count against quotas.
+        let window = Duration::from_secs(config.quota_window_secs);
+        let ledger = match &config.ledger_path {
+            Some(path) => Ledger::open(path, window),
+            None => Ledger::in_memory(window),
+        };
+
+        // Requests accepted before the faucet was restarted still count against quotas, unless
+        // they failed.
+        let quotas = Quotas::new(
+            window,
+            config.max_requests_per_address,
+            config.max_requests_per_ip,
+        );
+        for record in ledger.requests_since(now_ms().saturating_sub(window.as_millis() as u64))? {
+            if matches!(record.status, RequestStatus::Failure(_)) {
+                continue;
+            }
+
+            quotas.restore(record.recipient, record.client_ip, record.timestamp_ms);
+        }
+
+        let (batches, batch_rx) = match config.batch_size {
+            Some(batch_size) => {
+                let (tx, rx) = mpsc::channel(batch_size.max(1));
+                (Some(tx), Some(rx))
+            }
+            None => (None, None),
+        };
+
+        let faucet = Arc::new(LocalFaucet {
             wallet,
             active_address,
             coin_id: Arc::new(Mutex::new(*coins[0].id())),
             coin_amount: config.amount,
             num_coins: config.num_coins,
-        }))
+            quotas,
+            ledger,
+            batches,
+        });
+
+        if let (Some(batch_size), Some(batch_rx)) = (config.batch_size, batch_rx) {
+            tokio::spawn(run_batches(
+                Arc::downgrade(&faucet),
+                batch_rx,
+                batch_size.max(1),
+                Duration::from_millis(config.batch_interval_ms),
+            ));
+        }
+
+        Ok(faucet)
+    }
+
+    /// Handle a request from `client_ip` for gas to be sent to `recipient`: Check it against
+    /// quotas, record it in the ledger, and pay it out (as part of a batch, in batch mode). Only
+    /// requests that are paid out count against quotas.
+    pub async fn request_gas(
+        &self,
+        recipient: SuiAddress,
+        client_ip: Option<IpAddr>,
+    ) -> FaucetResponse {
+        let timestamp_ms = now_ms();
+        if let Err(e) = self.quotas.acquire(recipient, client_ip, timestamp_ms) {
+            return e.into();
+        }
+
+        let mut record = RequestRecord {
+            request_id: Uuid::new_v4().to_string(),
+            recipient,
+            client_ip,
+            timestamp_ms,
+            status: RequestStatus::Pending,
+            coins_sent: None,
+        };
+
+        if let Err(e) = self.ledger.record(&record) {
+            self.quotas.release(recipient, client_ip, timestamp_ms);
+            return e.into();
+        }
+
+        let coins = match &self.batches {
+            Some(batches) => self.batched_request_execute_tx(batches, recipient).await,
+            None => self.local_request_execute_tx(recipient).await,
+        };
+
+        match coins {
+            Ok(coins) => {
+                record.status = RequestStatus::Success;
+                record.coins_sent = Some(coins);
+            }
+            Err(e) => {
+                self.quotas.release(recipient, client_ip, timestamp_ms);
+                record.status = RequestStatus::Failure(e);
+            }
+        }
+
+        // The coins have already been sent (or not), so the response should reflect that even if
+        // the outcome couldn't be recorded.
+        if let Err(e) = self.ledger.record(&record) {
+            error!(
+                "Failed to record outcome of request {}: {e}",
+                record.request_id
+            );
+        }
+
+        FaucetResponse {
+            status: record.status,
+            coins_sent: record.coins_sent,
+            request_id: Some(record.request_id),
+        }
+    }
+
+    /// Look up a request in the ledger.
+    pub fn request_status(&self, request_id: &str) -> Result<RequestRecord, FaucetError> {
+        self.ledger
+            .get(request_id)?
+            .ok_or_else(|| FaucetError::RequestNotFound(request_id.to_string()))
     }
 
     /// Make transaction and execute it.
@@ -72,6 +199,34 @@ impl LocalFaucet {
         &self,
         recipient: SuiAddress,
     ) -> Result<Vec<CoinInfo>, FaucetError> {
+        let mut coins = self.batch_execute_tx(&[recipient]).await?;
+        Ok(coins.pop().unwrap_or_default())
+    }
+
+    async fn batched_request_execute_tx(
+        &self,
+        batches: &mpsc::Sender<BatchedRequest>,
+        recipient: SuiAddress,
+    ) -> Result<Vec<CoinInfo>, FaucetError> {
+        let (tx, rx) = oneshot::channel();
+        batches
+            .send(BatchedRequest {
+                recipient,
+                coins: tx,
+            })
+            .await
+            .map_err(|_| FaucetError::internal("Batch processing has stopped"))?;
+
+        rx.await
+            .map_err(|_| FaucetError::internal("Request was dropped from its batch"))?
+    }
+
+    /// Make a single transaction paying out to all `recipients`, and execute it. Returns the coins
+    /// sent to each recipient, in the same order as `recipients`.
+    async fn batch_execute_tx(
+        &self,
+        recipients: &[SuiAddress],
+    ) -> Result<Vec<Vec<CoinInfo>>, FaucetError> {
         let gas_price = self
             .wallet
             .get_reference_gas_price()
@@ -79,9 +234,12 @@ impl LocalFaucet {
             .map_err(|e| FaucetError::internal(format!("Failed to get gas price: {}", e)))?;
 
         let mut ptb = ProgrammableTransactionBuilder::new();
-        let recipients = vec![recipient; self.num_coins];
-        let amounts = vec![self.coin_amount; self.num_coins];
-        ptb.pay_sui(recipients, amounts)
+        let amounts = vec![self.coin_amount; self.num_coins * recipients.len()];
+        let recipients_per_coin = recipients
+            .iter()
+            .flat_map(|r| std::iter::repeat(*r).take(self.num_coins))
+            .collect();
+        ptb.pay_sui(recipients_per_coin, amounts)
             .map_err(FaucetError::internal)?;
 
         let ptb = ptb.finish();
@@ -96,7 +254,7 @@ impl LocalFaucet {
             self.active_address,
             vec![coin_id_ref],
             ptb,
-            GAS_BUDGET,
+            GAS_BUDGET * recipients.len() as u64,
             gas_price,
         );
 
@@ -111,17 +269,27 @@ impl LocalFaucet {
             ));
         };
 
-        let coins: Vec<CoinInfo> = effects
-            .created()
+        let mut coins_by_owner: HashMap<SuiAddress, Vec<CoinInfo>> = HashMap::new();
+        for o in effects.created() {
+            if let Owner::AddressOwner(owner) = o.owner {
+                coins_by_owner.entry(owner).or_default().push(CoinInfo {
+                    amount: self.coin_amount,
+                    id: o.object_id(),
+                    transfer_tx_digest: *effects.transaction_digest(),
+                });
+            }
+        }
+
+        // The same recipient may appear more than once in a batch, in which case each of its
+        // requests gets its share of the coins sent to it.
+        Ok(recipients
             .iter()
-            .map(|o| CoinInfo {
-                amount: self.coin_amount,
-                id: o.object_id(),
-                transfer_tx_digest: *effects.transaction_digest(),
+            .map(|recipient| {
+                let coins = coins_by_owner.entry(*recipient).or_default();
+                let rest = coins.split_off(self.num_coins.min(coins.len()));
+                std::mem::replace(coins, rest)
             })
-            .collect();
-
-        Ok(coins)
+            .collect())
     }
 
     async fn execute_txn(
@@ -184,6 +352,53 @@ impl LocalFaucet {
     }
 }
 
+/// Collect requests into batches of up to `batch_size` recipients, waiting up to `interval` for a
+/// batch to fill up, and pay out each batch in a single transaction. Stops once the faucet has
+/// been dropped.
+async fn run_batches(
+    faucet: Weak<LocalFaucet>,
+    mut requests: mpsc::Receiver<BatchedRequest>,
+    batch_size: usize,
+    interval: Duration,
+) {
+    while let Some(first) = requests.recv().await {
+        let mut batch = vec![first];
+        let deadline = tokio::time::Instant::now() + interval;
+        while batch.len() < batch_size {
+            match tokio::time::timeout_at(deadline, requests.recv()).await {
+                Ok(Some(request)) => batch.push(request),
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        let Some(faucet) = faucet.upgrade() else {
+            return;
+        };
+
+        info!("Paying out batch of {} requests", batch.len());
+        let recipients: Vec<_> = batch.iter().map(|r| r.recipient).collect();
+        match faucet.batch_execute_tx(&recipients).await {
+            Ok(coins) => {
+                for (request, coins) in batch.into_iter().zip(coins) {
+                    let _ = request.coins.send(Ok(coins));
+                }
+            }
+            Err(e) => {
+                for request in batch {
+                    let _ = request.coins.send(Err(e.clone()));
+                }
+            }
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
 /// Finds gas coins with sufficient balance and returns the address to use as the active address
 /// for the faucet. If the initial active address in the wallet does not have enough gas coins,
 /// it will iterate through the addresses to find one with sufficient gas coins.
@@ -0,0 +1,227 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use sui_sdk::types::base_types::SuiAddress;
+
+use crate::FaucetError;
+
+/// Limits on the number of requests a recipient address, or a client IP, can make within a
+/// sliding window.
+pub struct Quotas {
+    window_ms: u64,
+    addresses: Mutex<Window<SuiAddress>>,
+    ips: Mutex<Window<IpAddr>>,
+}
+
+/// Timestamps of the requests made by each key within the last `window_ms`.
+struct Window<K> {
+    limit: Option<u64>,
+    requests: HashMap<K, VecDeque<u64>>,
+    last_sweep_ms: u64,
+}
+
+impl Quotas {
+    pub fn new(
+        window: Duration,
+        max_requests_per_address: Option<u64>,
+        max_requests_per_ip: Option<u64>,
+    ) -> Self {
+        Self {
+            window_ms: window.as_millis() as u64,
+            addresses: Mutex::new(Window::new(max_requests_per_address)),
+            ips: Mutex::new(Window::new(max_requests_per_ip)),
+        }
+    }
+
+    /// Count a request from `client_ip` to `recipient`, made at `now_ms`, against their quotas.
+    /// Fails without counting the request if either has exhausted their quota.
+    pub fn acquire(
+        &self,
+        recipient: SuiAddress,
+        client_ip: Option<IpAddr>,
+        now_ms: u64,
+    ) -> Result<(), FaucetError> {
+        let since_ms = now_ms.saturating_sub(self.window_ms);
+        let window_secs = self.window_ms / 1000;
+
+        // Lock both windows up front, so that concurrent requests can't both pass the check
+        // before either is counted.
+        let mut addresses = self.addresses.lock().unwrap();
+        let mut ips = self.ips.lock().unwrap();
+
+        if !addresses.has_capacity(&recipient, since_ms) {
+            return Err(FaucetError::TooManyRequests(format!(
+                "Recipient {recipient} has reached its limit of requests in the last \
+                 {window_secs} seconds"
+            )));
+        }
+
+        if let Some(ip) = client_ip {
+            if !ips.has_capacity(&ip, since_ms) {
+                return Err(FaucetError::TooManyRequests(format!(
+                    "Client {ip} has reached its limit of requests in the last {window_secs} \
+                     seconds"
+                )));
+            }
+        }
+
+        addresses.record(recipient, now_ms, since_ms);
+        if let Some(ip) = client_ip {
+            ips.record(ip, now_ms, since_ms);
+        }
+
+        Ok(())
+    }
+
+    /// Stop counting a request from `client_ip` to `recipient`, made at `timestamp_ms`, against
+    /// their quotas (e.g. because it could not be paid out).
+    pub fn release(&self, recipient: SuiAddress, client_ip: Option<IpAddr>, timestamp_ms: u64) {
+        self.addresses
+            .lock()
+            .unwrap()
+            .forget(&recipient, timestamp_ms);
+
+        if let Some(ip) = client_ip {
+            self.ips.lock().unwrap().forget(&ip, timestamp_ms);
+        }
+    }
+
+    /// Count a request that was made before the faucet started (e.g. read back from the ledger)
+    /// against quotas, unconditionally.
+    pub fn restore(&self, recipient: SuiAddress, client_ip: Option<IpAddr>, timestamp_ms: u64) {
+        self.addresses
+            .lock()
+            .unwrap()
+            .record(recipient, timestamp_ms, 0);
+
+        if let Some(ip) = client_ip {
+            self.ips.lock().unwrap().record(ip, timestamp_ms, 0);
+        }
+    }
+}
+
+impl<K: Hash + Eq> Window<K> {
+    fn new(limit: Option<u64>) -> Self {
+        Self {
+            limit,
+            requests: HashMap::new(),
+            last_sweep_ms: 0,
+        }
+    }
+
+    fn has_capacity(&mut self, key: &K, since_ms: u64) -> bool {
+        let Some(limit) = self.limit else {
+            return true;
+        };
+
+        let Some(requests) = self.requests.get_mut(key) else {
+            return limit > 0;
+        };
+
+        while requests.front().is_some_and(|t| *t < since_ms) {
+            requests.pop_front();
+        }
+
+        (requests.len() as u64) < limit
+    }
+
+    fn record(&mut self, key: K, now_ms: u64, since_ms: u64) {
+        if self.limit.is_none() {
+            return;
+        }
+
+        self.requests.entry(key).or_default().push_back(now_ms);
+
+        // Periodically forget about keys that have not made a request within the window, so
+        // that memory usage is bounded by the number of keys active within the window.
+        if since_ms > self.last_sweep_ms {
+            self.requests
+                .retain(|_, requests| requests.back().is_some_and(|t| *t >= since_ms));
+            self.last_sweep_ms = now_ms;
+        }
+    }
+
+    fn forget(&mut self, key: &K, timestamp_ms: u64) {
+        let Some(requests) = self.requests.get_mut(key) else {
+            return;
+        };
+
+        if let Some(i) = requests.iter().rposition(|t| *t == timestamp_ms) {
+            requests.remove(i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_and_ip_quotas() {
+        let quotas = Quotas::new(Duration::from_secs(10), Some(2), Some(3));
+        let alice = SuiAddress::random_for_testing_only();
+        let bob = SuiAddress::random_for_testing_only();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        // Alice can make two requests in the window, but not three.
+        quotas.acquire(alice, Some(ip), 0).unwrap();
+        quotas.acquire(alice, Some(ip), 1_000).unwrap();
+        let err = quotas.acquire(alice, Some(ip), 2_000).unwrap_err();
+        assert!(matches!(err, FaucetError::TooManyRequests(_)), "{err}");
+
+        // The rejected request does not count against the IP, so it can make one more request.
+        quotas.acquire(bob, Some(ip), 3_000).unwrap();
+        let err = quotas.acquire(bob, Some(ip), 4_000).unwrap_err();
+        assert!(matches!(err, FaucetError::TooManyRequests(_)), "{err}");
+
+        // But bob can still be paid from a different IP.
+        quotas
+            .acquire(bob, Some("10.0.0.2".parse().unwrap()), 5_000)
+            .unwrap();
+
+        // Once the first requests leave the window, alice can make requests again.
+        quotas.acquire(alice, None, 10_500).unwrap();
+        let err = quotas.acquire(alice, None, 10_600).unwrap_err();
+        assert!(matches!(err, FaucetError::TooManyRequests(_)), "{err}");
+    }
+
+    #[test]
+    fn released_requests() {
+        let quotas = Quotas::new(Duration::from_secs(10), Some(1), Some(1));
+        let alice = SuiAddress::random_for_testing_only();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        quotas.acquire(alice, Some(ip), 1_000).unwrap();
+        assert!(quotas.acquire(alice, Some(ip), 2_000).is_err());
+
+        // Releasing a request gives its quota back to both the recipient and the IP.
+        quotas.release(alice, Some(ip), 1_000);
+        quotas.acquire(alice, Some(ip), 3_000).unwrap();
+        assert!(quotas.acquire(alice, Some(ip), 4_000).is_err());
+    }
+
+    #[test]
+    fn restored_requests() {
+        let quotas = Quotas::new(Duration::from_secs(10), Some(1), None);
+        let alice = SuiAddress::random_for_testing_only();
+
+        quotas.restore(alice, None, 5_000);
+        assert!(quotas.acquire(alice, None, 6_000).is_err());
+        assert!(quotas.acquire(alice, None, 16_000).is_ok());
+    }
+
+    #[test]
+    fn no_limits() {
+        let quotas = Quotas::new(Duration::from_secs(10), None, None);
+        let alice = SuiAddress::random_for_testing_only();
+        for t in 0..100 {
+            quotas.acquire(alice, None, t).unwrap();
+        }
+    }
+}
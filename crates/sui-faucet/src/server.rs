@@ -5,8 +5,9 @@ use crate::types::*;
 use crate::{AppState, FaucetConfig, FaucetError, FaucetRequest};
 use axum::{
     error_handling::HandleErrorLayer,
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{ConnectInfo, Path},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::{get, post},
     BoxError, Extension, Json, Router,
 };
@@ -31,46 +32,52 @@ async fn health() -> &'static str {
 
 async fn request_local_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
-    // ) -> &'static str {
 ) -> impl IntoResponse {
     let FaucetRequest::FixedAmountRequest(request) = payload;
     info!("Local request for address: {}", request.recipient);
-    let request = state
-        .faucet
-        .local_request_execute_tx(request.recipient)
-        .await;
+    let client_ip = client_ip(state.config.client_ip_header.as_deref(), &headers, peer);
+    let response = state.faucet.request_gas(request.recipient, client_ip).await;
 
-    if let Err(e) = request {
-        return (
+    let status = match &response.status {
+        RequestStatus::Success | RequestStatus::Pending => StatusCode::OK,
+        RequestStatus::Failure(FaucetError::TooManyRequests(_)) => StatusCode::TOO_MANY_REQUESTS,
+        RequestStatus::Failure(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    (status, Json(response))
+}
+
+async fn request_status(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(request_id): Path<String>,
+) -> Response {
+    match state.faucet.request_status(&request_id) {
+        Ok(record) => (StatusCode::OK, Json(RequestStatusResponse::from(record))).into_response(),
+        Err(e @ FaucetError::RequestNotFound(_)) => {
+            (StatusCode::NOT_FOUND, Json(FaucetResponse::from(e))).into_response()
+        }
+        Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(FaucetResponse {
-                status: RequestStatus::Failure(e),
-                coins_sent: None,
-            }),
-        );
+            Json(FaucetResponse::from(e)),
+        )
+            .into_response(),
     }
+}
 
-    let Ok(coins) = request else {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(FaucetResponse {
-                status: RequestStatus::Failure(FaucetError::internal(format!(
-                    "Failed to execute transaction: {}",
-                    request.unwrap_err()
-                ))),
-                coins_sent: None,
-            }),
-        );
+/// The IP address of the client that made a request. If the faucet is behind a proxy, this is
+/// the address reported by the proxy in `header` (proxies append the address they received the
+/// request from, so the last address is the only one that the client cannot forge). Otherwise,
+/// it is the address of the `peer` that connected to the faucet.
+fn client_ip(header: Option<&str>, headers: &HeaderMap, peer: SocketAddr) -> Option<IpAddr> {
+    let Some(header) = header else {
+        return Some(peer.ip());
     };
 
-    (
-        StatusCode::OK,
-        Json(FaucetResponse {
-            status: RequestStatus::Success,
-            coins_sent: Some(coins),
-        }),
-    )
+    let value = headers.get(header)?.to_str().ok()?;
+    value.rsplit(',').next()?.trim().parse().ok()
 }
 
 pub fn create_wallet_context(
@@ -114,6 +121,7 @@ pub async fn start_faucet(app_state: Arc<AppState>) -> Result<(), anyhow::Error>
         .route("/v2/gas", post(request_local_gas))
         .route("/v1/gas", post(request_local_gas))
         .route("/gas", post(request_local_gas))
+        .route("/v2/status/{request_id}", get(request_status))
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(handle_error))
@@ -190,6 +198,30 @@ mod tests {
         // Verify the transaction was successful
         assert!(faucet_response.coins_sent.is_some());
 
+        // The request can be looked up by its ID
+        let request_id = faucet_response.request_id.unwrap();
+        let response = client
+            .get(format!("http://127.0.0.1:{port}/v2/status/{request_id}"))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let record = response.json::<serde_json::Value>().await.unwrap();
+        assert!(record.get("client_ip").is_none());
+        let record: RequestStatusResponse = serde_json::from_value(record).unwrap();
+        assert_eq!(record.recipient, recipient);
+        assert!(matches!(record.status, RequestStatus::Success));
+
+        // Unknown requests are not found
+        let response = client
+            .get(format!("http://127.0.0.1:{port}/v2/status/unknown"))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
         // Test invalid request
         let response = client
             .post(format!("http://127.0.0.1:{port}/v2/gas",))
@@ -203,4 +235,31 @@ mod tests {
         assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
         handle.abort();
     }
+
+    #[test]
+    fn test_client_ip() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            "1.2.3.4, 10.0.0.1, 192.168.0.1".parse().unwrap(),
+        );
+        headers.insert("x-real-ip", "not an ip".parse().unwrap());
+        let peer: SocketAddr = "10.0.0.2:1234".parse().unwrap();
+
+        // The address added by the closest proxy is used.
+        assert_eq!(
+            client_ip(Some("x-forwarded-for"), &headers, peer),
+            Some("192.168.0.1".parse().unwrap()),
+        );
+
+        // Without a header configured, the peer's address is used.
+        assert_eq!(
+            client_ip(None, &headers, peer),
+            Some("10.0.0.2".parse().unwrap())
+        );
+
+        // A missing header, or an unparseable value.
+        assert_eq!(client_ip(Some("forwarded"), &headers, peer), None);
+        assert_eq!(client_ip(Some("x-real-ip"), &headers, peer), None);
+    }
 }
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::FaucetError;
+use crate::{FaucetError, RequestRecord};
 use serde::{Deserialize, Serialize};
 use sui_sdk::types::{
     base_types::{ObjectID, SuiAddress},
@@ -26,16 +26,32 @@ impl FaucetRequest {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RequestStatus {
     Success,
     Failure(FaucetError),
+    /// The request has been accepted, but its coins have not been sent yet.
+    Pending,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct FaucetResponse {
     pub status: RequestStatus,
     pub coins_sent: Option<Vec<CoinInfo>>,
+    /// Identifies the request in the faucet's ledger, for use with `/v2/status/<request_id>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// Response to `/v2/status/<request_id>`: the request's record in the ledger, without details
+/// about the client that made it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RequestStatusResponse {
+    pub request_id: String,
+    pub recipient: SuiAddress,
+    pub timestamp_ms: u64,
+    pub status: RequestStatus,
+    pub coins_sent: Option<Vec<CoinInfo>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -46,11 +62,24 @@ pub struct CoinInfo {
     pub transfer_tx_digest: TransactionDigest,
 }
 
+impl From<RequestRecord> for RequestStatusResponse {
+    fn from(record: RequestRecord) -> Self {
+        RequestStatusResponse {
+            request_id: record.request_id,
+            recipient: record.recipient,
+            timestamp_ms: record.timestamp_ms,
+            status: record.status,
+            coins_sent: record.coins_sent,
+        }
+    }
+}
+
 impl From<FaucetError> for FaucetResponse {
     fn from(value: FaucetError) -> Self {
         FaucetResponse {
             status: RequestStatus::Failure(value),
             coins_sent: None,
+            request_id: None,
         }
     }
 }
@@ -60,6 +89,7 @@ impl From<reqwest::Error> for FaucetResponse {
         FaucetResponse {
             status: RequestStatus::Failure(FaucetError::internal(value)),
             coins_sent: None,
+            request_id: None,
         }
     }
 }
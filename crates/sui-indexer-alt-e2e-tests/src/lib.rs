@@ -24,7 +24,8 @@ use sui_indexer_alt_jsonrpc::{
     RpcArgs as JsonRpcArgs,
 };
 use sui_indexer_alt_reader::{
-    bigtable_reader::BigtableArgs, system_package_task::SystemPackageTaskArgs,
+    bigtable_reader::BigtableArgs, fullnode_client::FullnodeArgs,
+    system_package_task::SystemPackageTaskArgs,
 };
 use sui_pg_db::{
     temp::{get_available_port, TempDb},
@@ -339,6 +340,7 @@ impl OffchainCluster {
             None,
             DbArgs::default(),
             BigtableArgs::default(),
+            FullnodeArgs::default(),
             graphql_args,
             SystemPackageTaskArgs::default(),
             "0.0.0",
@@ -0,0 +1,103 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//# init --protocol-version 70 --accounts A --addresses P=0x0 C=0x42 --simulator
+
+// cp | SUI            | FAKE
+// ---+----------------+-----------------
+//  1 |                | 10, 200, 3000
+//  2 | 400, 50000     | 10, 200, 3000
+
+//# publish --sender A
+module P::fake {
+  use sui::coin;
+
+  public struct FAKE has drop {}
+
+  fun init(otw: FAKE, ctx: &mut TxContext) {
+    let (mut treasury, metadata) = coin::create_currency(
+      otw,
+      2,
+      b"FAKE",
+      b"",
+      b"",
+      option::none(),
+      ctx,
+    );
+
+    let c1 = coin::mint(&mut treasury, 10, ctx);
+    let c2 = coin::mint(&mut treasury, 200, ctx);
+    let c3 = coin::mint(&mut treasury, 3000, ctx);
+
+    transfer::public_freeze_object(metadata);
+    transfer::public_transfer(treasury, ctx.sender());
+    transfer::public_transfer(c1, @C);
+    transfer::public_transfer(c2, @C);
+    transfer::public_transfer(c3, @C);
+  }
+}
+
+//# create-checkpoint
+
+//# programmable --sender A --inputs 400 50000 @C
+//> 0: SplitCoins(Gas, [Input(0), Input(1)]);
+//> 1: TransferObjects([NestedResult(0,0), NestedResult(0,1)], Input(2))
+
+//# create-checkpoint
+
+//# run-graphql
+{ # Coins default to SUI, and are returned largest first
+  address(address: "@{C}") {
+    coins {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { coinBalance }
+    }
+  }
+}
+
+//# run-graphql
+{ # Coins of another type
+  address(address: "@{C}") {
+    coins(type: "@{P}::fake::FAKE") {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { coinBalance }
+    }
+  }
+}
+
+//# run-graphql
+{ # Coins as of an earlier checkpoint
+  checkpoint(sequenceNumber: 1) {
+    query {
+      address(address: "@{C}") {
+        sui: coins { nodes { coinBalance } }
+        fake: coins(type: "@{P}::fake::FAKE") { nodes { coinBalance } }
+      }
+    }
+  }
+}
+
+//# run-graphql
+{ # Paginating coins from the front and the back
+  address(address: "@{C}") {
+    first: coins(first: 2, type: "@{P}::fake::FAKE") {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { coinBalance }
+    }
+
+    last: coins(last: 1, type: "@{P}::fake::FAKE") {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { coinBalance }
+    }
+  }
+}
+
+//# run-graphql
+{ # An address without any coins
+  address(address: "0x43") {
+    coins {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { coinBalance }
+    }
+  }
+}
@@ -0,0 +1,133 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//# init --protocol-version 70 --accounts A B --addresses P=0x0 --simulator
+
+//# publish
+module P::M {
+  public struct E1 has copy, drop { value: u64 }
+  public struct E2<phantom T> has copy, drop { value: u64 }
+
+  public fun emit_1(value: u64) {
+    sui::event::emit(E1 { value })
+  }
+
+  public fun emit_2_u64(value: u64) {
+    sui::event::emit(E2<u64> { value })
+  }
+
+  public fun emit_2_bool(value: u64) {
+    sui::event::emit(E2<bool> { value })
+  }
+}
+
+module P::N {
+  public fun emit(value: u64) {
+    P::M::emit_1(value)
+  }
+}
+
+//# create-checkpoint
+
+//# programmable --sender A --inputs 1 2
+//> 0: P::M::emit_1(Input(0));
+//> 1: P::M::emit_2_u64(Input(1))
+
+//# programmable --sender B --inputs 3 4
+//> 0: P::N::emit(Input(0));
+//> 1: P::M::emit_2_bool(Input(1))
+
+//# create-checkpoint
+
+//# programmable --sender A --inputs 5 6 7
+//> 0: P::M::emit_1(Input(0));
+//> 1: P::M::emit_1(Input(1));
+//> 2: P::M::emit_1(Input(2))
+
+//# create-checkpoint
+
+//# run-graphql
+{ # Events whose types are defined in a package
+  events(filter: { type: "@{P}" }) {
+    pageInfo { hasPreviousPage hasNextPage }
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Events of a non-generic type, including ones emitted through another module
+  events(filter: { type: "@{P}::M::E1" }) {
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # A generic type without type parameters matches all its instantiations
+  events(filter: { type: "@{P}::M::E2" }) {
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # A generic type with type parameters only matches that instantiation
+  events(filter: { type: "@{P}::M::E2<u64>" }) {
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Events emitted by a module, regardless of where their type is defined
+  events(filter: { module: "@{P}::N" }) {
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Events emitted by a package, from transactions sent by B
+  events(filter: { module: "@{P}", sender: "@{B}" }) {
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Events of a type, from transactions sent by A
+  events(filter: { type: "@{P}::M::E1", sender: "@{A}" }) {
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Events from transactions in a checkpoint
+  events(filter: { atCheckpoint: 2 }) {
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Checkpoint bounds combine
+  events(filter: { afterCheckpoint: 0, beforeCheckpoint: 3, type: "@{P}::M::E1" }) {
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Events after a checkpoint
+  events(filter: { afterCheckpoint: 2 }) {
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Empty checkpoint range
+  events(filter: { afterCheckpoint: 2, beforeCheckpoint: 3 }) {
+    pageInfo { hasPreviousPage hasNextPage }
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Filtering by both module and type is not supported
+  events(filter: { module: "@{P}::M", type: "@{P}::M::E1" }) {
+    edges { cursor node { sequenceNumber } }
+  }
+}
@@ -0,0 +1,125 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//# init --protocol-version 70 --accounts A B --addresses P=0x0 --simulator
+
+//# publish
+module P::M {
+  public struct E1 has copy, drop { value: u64 }
+  public struct E2<phantom T> has copy, drop { value: u64 }
+
+  public fun emit_1(value: u64) {
+    sui::event::emit(E1 { value })
+  }
+
+  public fun emit_2_u64(value: u64) {
+    sui::event::emit(E2<u64> { value })
+  }
+
+  public fun emit_2_bool(value: u64) {
+    sui::event::emit(E2<bool> { value })
+  }
+}
+
+module P::N {
+  public fun emit(value: u64) {
+    P::M::emit_1(value)
+  }
+}
+
+//# create-checkpoint
+
+//# programmable --sender A --inputs 1 2
+//> 0: P::M::emit_1(Input(0));
+//> 1: P::M::emit_2_u64(Input(1))
+
+//# programmable --sender B --inputs 3 4
+//> 0: P::N::emit(Input(0));
+//> 1: P::M::emit_2_bool(Input(1))
+
+//# create-checkpoint
+
+//# programmable --sender A --inputs 5 6 7
+//> 0: P::M::emit_1(Input(0));
+//> 1: P::M::emit_1(Input(1));
+//> 2: P::M::emit_1(Input(2))
+
+//# create-checkpoint
+
+//# run-graphql
+{ # Limit from the front
+  events(first: 2, filter: { type: "@{P}::M::E1" }) {
+    pageInfo { hasPreviousPage hasNextPage startCursor endCursor }
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Offset at the front
+  events(first: 2, after: "AwAAAAAAAAAAAAAAAAAAAA==", filter: { type: "@{P}::M::E1" }) {
+    pageInfo { hasPreviousPage hasNextPage startCursor endCursor }
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Limit from the back
+  events(last: 2, filter: { type: "@{P}::M::E1" }) {
+    pageInfo { hasPreviousPage hasNextPage startCursor endCursor }
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Offset at the back
+  events(last: 2, before: "BAAAAAAAAAACAAAAAAAAAA==", filter: { type: "@{P}::M::E1" }) {
+    pageInfo { hasPreviousPage hasNextPage startCursor endCursor }
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Offset at the front and back
+  events(after: "AgAAAAAAAAAAAAAAAAAAAA==", before: "BAAAAAAAAAABAAAAAAAAAA==", filter: { type: "@{P}::M::E1" }) {
+    pageInfo { hasPreviousPage hasNextPage startCursor endCursor }
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # Cursors can point into the middle of a transaction's events
+  events(first: 1, after: "AgAAAAAAAAAAAAAAAAAAAA==", filter: { atCheckpoint: 2 }) {
+    pageInfo { hasPreviousPage hasNextPage startCursor endCursor }
+    edges { cursor node { sequenceNumber } }
+  }
+}
+
+//# run-graphql
+{ # A transaction's events, limit from the front
+  transaction(digest: "@{digest_6}") {
+    events(first: 2) {
+      pageInfo { hasPreviousPage hasNextPage startCursor endCursor }
+      edges { cursor node { sequenceNumber } }
+    }
+  }
+}
+
+//# run-graphql --cursors 0
+{ # A transaction's events, offset at the front
+  transaction(digest: "@{digest_6}") {
+    events(after: "@{cursor_0}") {
+      pageInfo { hasPreviousPage hasNextPage startCursor endCursor }
+      edges { cursor node { sequenceNumber } }
+    }
+  }
+}
+
+//# run-graphql --cursors 2
+{ # A transaction's events, offset at the back
+  transaction(digest: "@{digest_6}") {
+    events(last: 1, before: "@{cursor_0}") {
+      pageInfo { hasPreviousPage hasNextPage startCursor endCursor }
+      edges { cursor node { sequenceNumber } }
+    }
+  }
+}
@@ -0,0 +1,140 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//# init --protocol-version 70 --accounts A --addresses P=0x0 --simulator
+
+// cp | parent version | fields
+// ---+----------------+-----------------------
+//  1 | 3              |
+//  2 | 4              | 1: 10, 2: 20, 3: 30
+//  3 | 5              | 1: 11,        3: 30, 4: 40
+
+//# publish
+module P::M {
+  use sui::dynamic_field as df;
+
+  public struct Parent has key, store {
+    id: UID,
+  }
+
+  public fun new(ctx: &mut TxContext): Parent {
+    Parent { id: object::new(ctx) }
+  }
+
+  public fun add(p: &mut Parent, name: u64, value: u64) {
+    df::add(&mut p.id, name, value)
+  }
+
+  public fun set(p: &mut Parent, name: u64, value: u64) {
+    *df::borrow_mut(&mut p.id, name) = value
+  }
+
+  public fun remove(p: &mut Parent, name: u64) {
+    let _: u64 = df::remove(&mut p.id, name);
+  }
+}
+
+//# programmable --sender A --inputs @A
+//> 0: P::M::new();
+//> 1: TransferObjects([Result(0)], Input(0))
+
+//# create-checkpoint
+
+//# programmable --sender A --inputs object(2,0) 1 10 2 20 3 30
+//> 0: P::M::add(Input(0), Input(1), Input(2));
+//> 1: P::M::add(Input(0), Input(3), Input(4));
+//> 2: P::M::add(Input(0), Input(5), Input(6))
+
+//# create-checkpoint
+
+//# programmable --sender A --inputs object(2,0) 1 11 2 4 40
+//> 0: P::M::set(Input(0), Input(1), Input(2));
+//> 1: P::M::remove(Input(0), Input(3));
+//> 2: P::M::add(Input(0), Input(4), Input(5))
+
+//# create-checkpoint
+
+//# run-graphql
+{ # Before any fields were added
+  object(address: "@{obj_2_0}", atCheckpoint: 1) {
+    version
+    dynamicFields {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { version }
+    }
+  }
+}
+
+//# run-graphql
+{ # A past version of the parent lists the fields it had at that version, even though one of them
+  # has since been removed, and another added.
+  object(address: "@{obj_2_0}", atCheckpoint: 2) {
+    version
+    dynamicFields {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { version }
+    }
+  }
+}
+
+//# run-graphql
+{ # Paginating through a past version's fields only returns fields from that version.
+  object(address: "@{obj_2_0}", atCheckpoint: 2) {
+    first: dynamicFields(first: 2) {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { version }
+    }
+
+    last: dynamicFields(last: 2) {
+      pageInfo { hasPreviousPage hasNextPage }
+      nodes { version }
+    }
+  }
+}
+
+//# run-graphql
+{ # The latest version of the parent lists the latest fields.
+  object(address: "@{obj_2_0}") {
+    version
+    dynamicFields {
+      pageInfo { hasPreviousPage hasNextPage }
+    }
+
+    first: dynamicFields(first: 2) {
+      pageInfo { hasPreviousPage hasNextPage }
+    }
+
+    one: dynamicField(name: { type: "u64", bcs: "AQAAAAAAAAA=" }) {
+      version
+      value { ... on MoveValue { json } }
+    }
+
+    two: dynamicField(name: { type: "u64", bcs: "AgAAAAAAAAA=" }) {
+      version
+    }
+
+    four: dynamicField(name: { type: "u64", bcs: "BAAAAAAAAAA=" }) {
+      version
+      value { ... on MoveValue { json } }
+    }
+  }
+}
+
+//# run-graphql
+{ # Fields are fetched as of the parent's version, and match the fields it lists.
+  object(address: "@{obj_2_0}", atCheckpoint: 2) {
+    one: dynamicField(name: { type: "u64", bcs: "AQAAAAAAAAA=" }) {
+      version
+      value { ... on MoveValue { json } }
+    }
+
+    two: dynamicField(name: { type: "u64", bcs: "AgAAAAAAAAA=" }) {
+      version
+      value { ... on MoveValue { json } }
+    }
+
+    four: dynamicField(name: { type: "u64", bcs: "BAAAAAAAAAA=" }) {
+      version
+    }
+  }
+}
@@ -0,0 +1,436 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use anyhow::Context;
+use fastcrypto::encoding::{Base64, Encoding};
+use prometheus::Registry;
+use reqwest::Client;
+use serde_json::{json, Value};
+use sui_indexer_alt_graphql::{config::RpcConfig, start_rpc, RpcArgs};
+use sui_indexer_alt_reader::{
+    bigtable_reader::BigtableArgs,
+    fullnode_client::{FullnodeArgs, FullnodeClient},
+    system_package_task::SystemPackageTaskArgs,
+};
+use sui_macros::sim_test;
+use sui_pg_db::{temp::get_available_port, DbArgs};
+use sui_swarm_config::genesis_config::AccountConfig;
+use sui_types::{
+    base_types::SuiAddress,
+    effects::{TransactionEffects, TransactionEffectsAPI},
+    message_envelope::Message,
+    transaction::{TransactionData, TransactionDataAPI},
+};
+use test_cluster::{TestCluster, TestClusterBuilder};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+struct FnDelegationTestCluster {
+    onchain_cluster: TestCluster,
+    rpc_url: Url,
+    rpc_handle: JoinHandle<()>,
+    client: Client,
+    cancel: CancellationToken,
+}
+
+impl FnDelegationTestCluster {
+    /// Creates a new test cluster with a GraphQL service that forwards transaction execution and
+    /// simulation to the cluster's fullnode, if `with_fullnode` is true.
+    async fn new(with_fullnode: bool) -> anyhow::Result<Self> {
+        let onchain_cluster = TestClusterBuilder::new()
+            .with_num_validators(1)
+            .with_epoch_duration_ms(2000)
+            .with_accounts(vec![
+                AccountConfig {
+                    address: None,
+                    gas_amounts: vec![1_000_000_000_000; 2],
+                };
+                4
+            ])
+            .build()
+            .await;
+
+        let fullnode_args = FullnodeArgs {
+            fullnode_rpc_url: with_fullnode.then(|| onchain_cluster.rpc_url().to_owned()),
+        };
+
+        let cancel = CancellationToken::new();
+
+        let rpc_listen_address =
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), get_available_port());
+        let rpc_url = Url::parse(&format!("http://{}/graphql", rpc_listen_address))
+            .expect("Failed to parse RPC URL");
+
+        // We don't expose metrics in these tests, but we create a registry to collect them anyway.
+        let registry = Registry::new();
+
+        let rpc_args = RpcArgs {
+            rpc_listen_address,
+            no_ide: true,
+        };
+
+        let rpc_handle = start_rpc(
+            None,
+            None,
+            DbArgs::default(),
+            BigtableArgs::default(),
+            fullnode_args,
+            rpc_args,
+            SystemPackageTaskArgs::default(),
+            "0.0.0",
+            RpcConfig::default(),
+            vec![],
+            &registry,
+            cancel.child_token(),
+        )
+        .await
+        .expect("Failed to start GraphQL server");
+
+        Ok(Self {
+            onchain_cluster,
+            rpc_url,
+            rpc_handle,
+            client: Client::new(),
+            cancel,
+        })
+    }
+
+    /// Builds a simple transaction and returns it along with its Base64-encoded bytes and
+    /// signatures.
+    async fn transfer_transaction(&self) -> anyhow::Result<(TransactionData, String, Vec<String>)> {
+        let recipient = self.recipient();
+        let tx = self
+            .onchain_cluster
+            .test_transaction_builder()
+            .await
+            .transfer_sui(Some(1_000), recipient)
+            .build();
+        let signed_tx = self.onchain_cluster.wallet.sign_transaction(&tx);
+        let (tx_bytes, sigs) = signed_tx.to_tx_bytes_and_signatures();
+        let tx_bytes = tx_bytes.encoded();
+        let sigs: Vec<_> = sigs.iter().map(|sig| sig.encoded()).collect();
+
+        Ok((tx, tx_bytes, sigs))
+    }
+
+    /// The address that `transfer_transaction` sends SUI to.
+    fn recipient(&self) -> SuiAddress {
+        self.onchain_cluster.wallet.get_addresses()[1]
+    }
+
+    async fn execute_graphql(&self, query: &str, variables: Value) -> anyhow::Result<Value> {
+        let response = self
+            .client
+            .post(self.rpc_url.clone())
+            .json(&json!({
+                "query": query,
+                "variables": variables,
+            }))
+            .send()
+            .await
+            .context("Request to GraphQL server failed")?;
+
+        let body: Value = response
+            .json()
+            .await
+            .context("Failed to parse GraphQL response")?;
+
+        Ok(body)
+    }
+
+    async fn stopped(self) {
+        self.cancel.cancel();
+        let _ = self.rpc_handle.await;
+    }
+}
+
+const EXECUTE: &str = r#"
+    mutation ($txBytes: Base64!, $signatures: [Base64!]!) {
+        executeTransaction(txBytes: $txBytes, signatures: $signatures) {
+            digest
+            effectsDigest
+            effectsBcs
+            balanceChanges {
+                nodes {
+                    owner { address }
+                    amount
+                }
+            }
+        }
+    }
+"#;
+
+const SIMULATE: &str = r#"
+    query ($txBytes: Base64!, $checksEnabled: Boolean) {
+        simulateTransaction(txBytes: $txBytes, checksEnabled: $checksEnabled) {
+            digest
+            effectsDigest
+            effectsBcs
+            balanceChanges {
+                nodes {
+                    owner { address }
+                    amount
+                }
+            }
+        }
+    }
+"#;
+
+/// Check that `effects` (a `TransactionEffects` GraphQL object) describes the successful execution
+/// of `tx`, which transferred 1000 MIST to `recipient`.
+fn assert_successful_effects(tx: &TransactionData, recipient: SuiAddress, effects: &Value) {
+    assert_eq!(effects["digest"], tx.digest().to_string());
+
+    let bcs = Base64::decode(effects["effectsBcs"].as_str().unwrap()).unwrap();
+    let native: TransactionEffects = bcs::from_bytes(&bcs).unwrap();
+    assert_eq!(native.transaction_digest(), &tx.digest());
+    assert!(native.status().is_ok());
+    assert_eq!(effects["effectsDigest"], native.digest().to_string());
+
+    // The sender paid for the transfer and gas, and the recipient received the transfer.
+    let changes = effects["balanceChanges"]["nodes"].as_array().unwrap();
+    assert_eq!(changes.len(), 2);
+    assert!(changes
+        .iter()
+        .any(|c| c["owner"]["address"] == recipient.to_string() && c["amount"] == "1000"));
+    assert!(changes.iter().any(|c| {
+        c["owner"]["address"] == tx.sender().to_string()
+            && c["amount"].as_str().unwrap().starts_with('-')
+    }));
+}
+
+#[sim_test]
+async fn test_execution() {
+    telemetry_subscribers::init_for_testing();
+    let test_cluster = FnDelegationTestCluster::new(true)
+        .await
+        .expect("Failed to create test cluster");
+
+    let (tx, tx_bytes, sigs) = test_cluster.transfer_transaction().await.unwrap();
+    let recipient = test_cluster.recipient();
+
+    let response = test_cluster
+        .execute_graphql(EXECUTE, json!({ "txBytes": tx_bytes, "signatures": sigs }))
+        .await
+        .unwrap();
+
+    tracing::info!("execution response is {:?}", response);
+    assert!(response["errors"].is_null());
+    assert_successful_effects(&tx, recipient, &response["data"]["executeTransaction"]);
+
+    // The transaction was committed, so the fullnode knows about it.
+    let executed = test_cluster
+        .onchain_cluster
+        .sui_client()
+        .read_api()
+        .get_transaction_with_options(tx.digest(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(executed.digest, tx.digest());
+
+    test_cluster.stopped().await;
+}
+
+#[sim_test]
+async fn test_execution_with_empty_sigs() {
+    telemetry_subscribers::init_for_testing();
+    let test_cluster = FnDelegationTestCluster::new(true)
+        .await
+        .expect("Failed to create test cluster");
+
+    let (_, tx_bytes, _) = test_cluster.transfer_transaction().await.unwrap();
+
+    let response = test_cluster
+        .execute_graphql(EXECUTE, json!({ "txBytes": tx_bytes, "signatures": [] }))
+        .await
+        .unwrap();
+
+    tracing::info!("execution response is {:?}", response);
+    assert!(response["data"].is_null());
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "BAD_USER_INPUT"
+    );
+    assert!(response["errors"][0]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Transaction rejected by fullnode"));
+
+    test_cluster.stopped().await;
+}
+
+#[sim_test]
+async fn test_execution_with_invalid_sig() {
+    telemetry_subscribers::init_for_testing();
+    let test_cluster = FnDelegationTestCluster::new(true)
+        .await
+        .expect("Failed to create test cluster");
+
+    let (_, tx_bytes, _) = test_cluster.transfer_transaction().await.unwrap();
+
+    let response = test_cluster
+        .execute_graphql(
+            EXECUTE,
+            json!({ "txBytes": tx_bytes, "signatures": [Base64::encode([0u8; 4])] }),
+        )
+        .await
+        .unwrap();
+
+    assert!(response["data"].is_null());
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "BAD_USER_INPUT"
+    );
+    assert!(response["errors"][0]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Invalid signature at position 0"));
+
+    test_cluster.stopped().await;
+}
+
+#[sim_test]
+async fn test_simulation() {
+    telemetry_subscribers::init_for_testing();
+    let test_cluster = FnDelegationTestCluster::new(true)
+        .await
+        .expect("Failed to create test cluster");
+
+    let (tx, tx_bytes, _) = test_cluster.transfer_transaction().await.unwrap();
+    let recipient = test_cluster.recipient();
+
+    for checks_enabled in [None, Some(true), Some(false)] {
+        let response = test_cluster
+            .execute_graphql(
+                SIMULATE,
+                json!({ "txBytes": tx_bytes, "checksEnabled": checks_enabled }),
+            )
+            .await
+            .unwrap();
+
+        tracing::info!("simulation response is {:?}", response);
+        assert!(response["errors"].is_null());
+        assert_successful_effects(&tx, recipient, &response["data"]["simulateTransaction"]);
+    }
+
+    // Simulating a transaction does not commit it.
+    let executed = test_cluster
+        .onchain_cluster
+        .sui_client()
+        .read_api()
+        .get_transaction_with_options(tx.digest(), Default::default())
+        .await;
+    assert!(executed.is_err());
+
+    test_cluster.stopped().await;
+}
+
+#[sim_test]
+async fn test_simulation_with_invalid_tx() {
+    let test_cluster = FnDelegationTestCluster::new(true)
+        .await
+        .expect("Failed to create test cluster");
+
+    let response = test_cluster
+        .execute_graphql(
+            SIMULATE,
+            json!({ "txBytes": Base64::encode(b"invalid_tx_bytes") }),
+        )
+        .await
+        .unwrap();
+
+    assert!(response["data"].is_null());
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        "BAD_USER_INPUT"
+    );
+    assert!(response["errors"][0]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Invalid transaction bytes"));
+
+    test_cluster.stopped().await;
+}
+
+#[sim_test]
+async fn test_without_fullnode() {
+    let test_cluster = FnDelegationTestCluster::new(false)
+        .await
+        .expect("Failed to create test cluster");
+
+    let (_, tx_bytes, sigs) = test_cluster.transfer_transaction().await.unwrap();
+
+    let execution = test_cluster
+        .execute_graphql(EXECUTE, json!({ "txBytes": tx_bytes, "signatures": sigs }))
+        .await
+        .unwrap();
+
+    let simulation = test_cluster
+        .execute_graphql(SIMULATE, json!({ "txBytes": tx_bytes }))
+        .await
+        .unwrap();
+
+    for response in [execution, simulation] {
+        assert!(response["data"].is_null());
+        assert_eq!(
+            response["errors"][0]["extensions"]["code"],
+            "INTERNAL_SERVER_ERROR"
+        );
+        assert!(response["errors"][0]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Fullnode RPC URL not configured"));
+    }
+
+    test_cluster.stopped().await;
+}
+
+#[sim_test]
+async fn test_fullnode_client() {
+    let test_cluster = FnDelegationTestCluster::new(false)
+        .await
+        .expect("Failed to create test cluster");
+
+    let client = FullnodeClient::new(test_cluster.onchain_cluster.rpc_url().to_owned()).unwrap();
+    let (tx, _, _) = test_cluster.transfer_transaction().await.unwrap();
+    let signed_tx = test_cluster.onchain_cluster.wallet.sign_transaction(&tx);
+    let signatures = signed_tx.tx_signatures().to_vec();
+
+    // Simulation returns the effects, without needing signatures.
+    let simulated = client
+        .simulate_transaction(tx.clone(), /* checks_enabled */ true)
+        .await
+        .unwrap();
+    assert_eq!(simulated.transaction, tx);
+    assert!(simulated.signatures.is_empty());
+    assert_eq!(simulated.effects.transaction_digest(), &tx.digest());
+    assert!(simulated.effects.status().is_ok());
+    assert_eq!(simulated.balance_changes.len(), 2);
+
+    // Execution returns the same effects, along with the signatures that were sent.
+    let executed = client
+        .execute_transaction(tx.clone(), signatures.clone())
+        .await
+        .unwrap();
+    assert_eq!(executed.transaction, tx);
+    assert_eq!(executed.signatures, signatures);
+    assert_eq!(executed.effects.transaction_digest(), &tx.digest());
+    assert!(executed.effects.status().is_ok());
+    assert_eq!(executed.balance_changes, simulated.balance_changes);
+
+    // Re-using the same gas coin version for the same transaction with different signatures is
+    // rejected.
+    let err = client
+        .execute_transaction(tx.clone(), vec![])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        sui_indexer_alt_reader::error::Error::FullnodeRequest(_)
+    ));
+
+    test_cluster.stopped().await;
+}
@@ -0,0 +1,778 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use bincode::Options as _;
+use prometheus::Registry;
+use scoped_futures::ScopedBoxFuture;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sui_indexer_alt_framework_store_traits::{
+    self as store, CommitterWatermark, PrunerWatermark, ReaderWatermark,
+};
+use tokio_util::sync::CancellationToken;
+use typed_store::rocksdb::{
+    BoundColumnFamily, Direction, IteratorMode, Options, WriteBatch, DB, DEFAULT_COLUMN_FAMILY_NAME,
+};
+
+use crate::{
+    ingestion::{ClientArgs, IngestionConfig},
+    Indexer, IndexerArgs,
+};
+
+/// Column family holding the committer watermark for each pipeline.
+const COMMITTER_WATERMARKS: &str = "committer_watermarks";
+
+/// Column family holding the reader watermark (`reader_lo` and the time it was last updated) for
+/// each pipeline.
+const READER_WATERMARKS: &str = "reader_watermarks";
+
+/// Column family holding the pruner watermark (`pruner_hi`) for each pipeline.
+const PRUNER_WATERMARKS: &str = "pruner_watermarks";
+
+/// A `Store` backed by a RocksDB instance on local disk, so that an indexer can run as a single
+/// binary, without a separate database.
+///
+/// Each of the committer, reader and pruner watermarks is kept in its own column family, because
+/// each is updated by a different task in the framework, and this way none of those tasks need to
+/// coordinate with each other to avoid clobbering the others' updates. Pipelines store their data
+/// in their own column families (tables), which must be declared when the store is opened.
+#[derive(Clone)]
+pub struct EmbeddedStore(Arc<DB>);
+
+/// A connection to an [EmbeddedStore]. Outside of a transaction, writes are applied as soon as
+/// they are made. Inside a transaction, writes are staged and applied atomically when the
+/// transaction succeeds, and reads do not observe writes staged by the same transaction.
+pub struct Connection<'c> {
+    store: &'c EmbeddedStore,
+    staged: Option<Mutex<WriteBatch>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredCommitterWatermark {
+    epoch_hi_inclusive: u64,
+    checkpoint_hi_inclusive: u64,
+    tx_hi: u64,
+    timestamp_ms_hi_inclusive: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredReaderWatermark {
+    reader_lo: u64,
+    pruner_timestamp_ms: u64,
+}
+
+impl Indexer<EmbeddedStore> {
+    /// Create a new instance of the indexer framework, writing to an embedded store at `path`
+    /// (created if it does not exist yet). `tables` lists the tables that the indexer's pipelines
+    /// write to, in addition to the tables the framework uses to track watermarks. `indexer_args`,
+    /// `client_args`, and `ingestion_config` are as in [Indexer::new].
+    ///
+    /// After initialization, at least one pipeline must be added using [Self::concurrent_pipeline]
+    /// or [Self::sequential_pipeline], before the indexer is started using [Self::run].
+    pub async fn new_embedded(
+        path: impl AsRef<Path>,
+        tables: &[&str],
+        indexer_args: IndexerArgs,
+        client_args: ClientArgs,
+        ingestion_config: IngestionConfig,
+        registry: &Registry,
+        cancel: CancellationToken,
+    ) -> Result<Self> {
+        let store = EmbeddedStore::open(path, tables).context("Failed to open embedded store")?;
+
+        Indexer::new(
+            store,
+            indexer_args,
+            client_args,
+            ingestion_config,
+            registry,
+            cancel,
+        )
+        .await
+    }
+}
+
+impl EmbeddedStore {
+    /// Open the store at `path`, creating it if it does not exist, along with the column families
+    /// for each of `tables`.
+    pub fn open(path: impl AsRef<Path>, tables: &[&str]) -> Result<Self> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+
+        let mut cfs = vec![
+            DEFAULT_COLUMN_FAMILY_NAME,
+            COMMITTER_WATERMARKS,
+            READER_WATERMARKS,
+            PRUNER_WATERMARKS,
+        ];
+
+        for table in tables {
+            if cfs.contains(table) {
+                return Err(anyhow!("Table name {table:?} is reserved or repeated"));
+            }
+
+            cfs.push(table);
+        }
+
+        let db = DB::open_cf(&options, path, cfs)?;
+        Ok(Self(Arc::new(db)))
+    }
+
+    /// Read the value at `key` in `table`.
+    pub fn get<K: Serialize, V: DeserializeOwned>(
+        &self,
+        table: &str,
+        key: &K,
+    ) -> Result<Option<V>> {
+        let cf = self.cf(table)?;
+        let Some(bytes) = self.0.get_pinned_cf(&cf, encode_key(key)?)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(bcs::from_bytes(&bytes).with_context(|| {
+            format!("Failed to deserialize value from {table:?}")
+        })?))
+    }
+
+    /// Read up to `limit` entries from `table`, in key order, starting from the entry at `from`
+    /// (inclusive), or from the first entry if `from` is `None`.
+    pub fn scan<K: Serialize + DeserializeOwned, V: DeserializeOwned>(
+        &self,
+        table: &str,
+        from: Option<&K>,
+        limit: usize,
+    ) -> Result<Vec<(K, V)>> {
+        let cf = self.cf(table)?;
+        let start = from.map(encode_key).transpose()?;
+        let mode = match &start {
+            Some(start) => IteratorMode::From(start, Direction::Forward),
+            None => IteratorMode::Start,
+        };
+
+        self.0
+            .iterator_cf(&cf, mode)
+            .take(limit)
+            .map(|entry| {
+                let (k, v) = entry?;
+                Ok((decode_key(&k)?, bcs::from_bytes(&v)?))
+            })
+            .collect()
+    }
+
+    fn cf(&self, table: &str) -> Result<Arc<BoundColumnFamily<'_>>> {
+        self.0
+            .cf_handle(table)
+            .ok_or_else(|| anyhow!("Table {table:?} not found in embedded store"))
+    }
+}
+
+impl Connection<'_> {
+    /// The store this connection writes to, for reads.
+    pub fn store(&self) -> &EmbeddedStore {
+        self.store
+    }
+
+    /// Write `value` at `key` in `table`, replacing any existing value.
+    pub fn put<K: Serialize, V: Serialize>(
+        &mut self,
+        table: &str,
+        key: &K,
+        value: &V,
+    ) -> Result<()> {
+        let key = encode_key(key)?;
+        let value = bcs::to_bytes(value)?;
+        self.write(table, |batch, cf| batch.put_cf(cf, key, value))
+    }
+
+    /// Remove the value at `key` in `table`, if there is one.
+    pub fn delete<K: Serialize>(&mut self, table: &str, key: &K) -> Result<()> {
+        let key = encode_key(key)?;
+        self.write(table, |batch, cf| batch.delete_cf(cf, key))
+    }
+
+    /// Remove all values in `table` whose keys are between `from` (inclusive) and `to_exclusive`,
+    /// e.g. to prune a range of checkpoints.
+    pub fn delete_range<K: Serialize>(
+        &mut self,
+        table: &str,
+        from: &K,
+        to_exclusive: &K,
+    ) -> Result<()> {
+        let from = encode_key(from)?;
+        let to = encode_key(to_exclusive)?;
+        self.write(table, |batch, cf| batch.delete_range_cf(cf, from, to))
+    }
+
+    /// Apply a write to `table`, either by staging it (in a transaction), or by applying it
+    /// immediately.
+    fn write(
+        &mut self,
+        table: &str,
+        f: impl FnOnce(&mut WriteBatch, &Arc<BoundColumnFamily<'_>>),
+    ) -> Result<()> {
+        let cf = self.store.cf(table)?;
+
+        if let Some(staged) = &mut self.staged {
+            let batch = staged
+                .get_mut()
+                .map_err(|_| anyhow!("Staged writes poisoned"))?;
+            f(batch, &cf);
+        } else {
+            let mut batch = WriteBatch::default();
+            f(&mut batch, &cf);
+            self.store.0.write(batch)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl store::Connection for Connection<'_> {
+    async fn committer_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> Result<Option<CommitterWatermark>> {
+        let watermark: Option<StoredCommitterWatermark> =
+            self.store.get(COMMITTER_WATERMARKS, &pipeline)?;
+
+        Ok(watermark.map(|w| CommitterWatermark {
+            epoch_hi_inclusive: w.epoch_hi_inclusive,
+            checkpoint_hi_inclusive: w.checkpoint_hi_inclusive,
+            tx_hi: w.tx_hi,
+            timestamp_ms_hi_inclusive: w.timestamp_ms_hi_inclusive,
+        }))
+    }
+
+    async fn reader_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> Result<Option<ReaderWatermark>> {
+        let Some(committer): Option<StoredCommitterWatermark> =
+            self.store.get(COMMITTER_WATERMARKS, &pipeline)?
+        else {
+            return Ok(None);
+        };
+
+        let reader: Option<StoredReaderWatermark> = self.store.get(READER_WATERMARKS, &pipeline)?;
+
+        Ok(Some(ReaderWatermark {
+            checkpoint_hi_inclusive: committer.checkpoint_hi_inclusive,
+            reader_lo: reader.map_or(0, |r| r.reader_lo),
+        }))
+    }
+
+    async fn pruner_watermark(
+        &mut self,
+        pipeline: &'static str,
+        delay: Duration,
+    ) -> Result<Option<PrunerWatermark>> {
+        if self
+            .store
+            .get::<_, StoredCommitterWatermark>(COMMITTER_WATERMARKS, &pipeline)?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let reader: Option<StoredReaderWatermark> = self.store.get(READER_WATERMARKS, &pipeline)?;
+        let pruner_hi: Option<u64> = self.store.get(PRUNER_WATERMARKS, &pipeline)?;
+
+        let (reader_lo, pruner_timestamp_ms) =
+            reader.map_or((0, 0), |r| (r.reader_lo, r.pruner_timestamp_ms));
+
+        //     |---------- + delay ---------------------|
+        //                             |--- wait_for ---|
+        //     |-----------------------|----------------|
+        //     ^                       ^
+        //     pruner_timestamp        now
+        let wait_for_ms = pruner_timestamp_ms as i64 + delay.as_millis() as i64 - now_ms()? as i64;
+
+        Ok(Some(PrunerWatermark {
+            wait_for_ms,
+            reader_lo,
+            pruner_hi: pruner_hi.unwrap_or(0),
+        }))
+    }
+
+    async fn set_committer_watermark(
+        &mut self,
+        pipeline: &'static str,
+        watermark: CommitterWatermark,
+    ) -> Result<bool> {
+        let existing: Option<StoredCommitterWatermark> =
+            self.store.get(COMMITTER_WATERMARKS, &pipeline)?;
+
+        if existing.is_some_and(|e| e.checkpoint_hi_inclusive >= watermark.checkpoint_hi_inclusive)
+        {
+            return Ok(false);
+        }
+
+        self.put(
+            COMMITTER_WATERMARKS,
+            &pipeline,
+            &StoredCommitterWatermark {
+                epoch_hi_inclusive: watermark.epoch_hi_inclusive,
+                checkpoint_hi_inclusive: watermark.checkpoint_hi_inclusive,
+                tx_hi: watermark.tx_hi,
+                timestamp_ms_hi_inclusive: watermark.timestamp_ms_hi_inclusive,
+            },
+        )?;
+
+        Ok(true)
+    }
+
+    async fn set_reader_watermark(
+        &mut self,
+        pipeline: &'static str,
+        reader_lo: u64,
+    ) -> Result<bool> {
+        if self
+            .store
+            .get::<_, StoredCommitterWatermark>(COMMITTER_WATERMARKS, &pipeline)?
+            .is_none()
+        {
+            return Ok(false);
+        }
+
+        let existing: Option<StoredReaderWatermark> =
+            self.store.get(READER_WATERMARKS, &pipeline)?;
+
+        if existing.is_some_and(|e| e.reader_lo >= reader_lo) {
+            return Ok(false);
+        }
+
+        self.put(
+            READER_WATERMARKS,
+            &pipeline,
+            &StoredReaderWatermark {
+                reader_lo,
+                pruner_timestamp_ms: now_ms()?,
+            },
+        )?;
+
+        Ok(true)
+    }
+
+    async fn set_pruner_watermark(
+        &mut self,
+        pipeline: &'static str,
+        pruner_hi: u64,
+    ) -> Result<bool> {
+        if self
+            .store
+            .get::<_, StoredCommitterWatermark>(COMMITTER_WATERMARKS, &pipeline)?
+            .is_none()
+        {
+            return Ok(false);
+        }
+
+        self.put(PRUNER_WATERMARKS, &pipeline, &pruner_hi)?;
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl store::Store for EmbeddedStore {
+    type Connection<'c> = Connection<'c>;
+
+    async fn connect<'c>(&'c self) -> Result<Self::Connection<'c>> {
+        Ok(Connection {
+            store: self,
+            staged: None,
+        })
+    }
+}
+
+#[async_trait]
+impl store::TransactionalStore for EmbeddedStore {
+    async fn transaction<'a, R, F>(&self, f: F) -> Result<R>
+    where
+        R: Send + 'a,
+        F: Send + 'a,
+        F: for<'r> FnOnce(
+            &'r mut Self::Connection<'_>,
+        ) -> ScopedBoxFuture<'a, 'r, anyhow::Result<R>>,
+    {
+        let mut conn = Connection {
+            store: self,
+            staged: Some(Mutex::new(WriteBatch::default())),
+        };
+
+        let result = f(&mut conn).await?;
+
+        let batch = conn
+            .staged
+            .take()
+            .expect("Transaction connection has staged writes")
+            .into_inner()
+            .map_err(|_| anyhow!("Staged writes poisoned"))?;
+
+        self.0.write(batch)?;
+        Ok(result)
+    }
+}
+
+/// Keys are encoded as fixed-width big-endian integers, so that the byte order of encoded keys
+/// matches the natural order of (unsigned) integer keys, and tuples of them.
+fn key_options() -> impl bincode::Options {
+    bincode::DefaultOptions::new()
+        .with_big_endian()
+        .with_fixint_encoding()
+}
+
+fn encode_key<K: Serialize + ?Sized>(key: &K) -> Result<Vec<u8>> {
+    key_options()
+        .serialize(key)
+        .context("Failed to serialize key")
+}
+
+fn decode_key<K: DeserializeOwned>(bytes: &[u8]) -> Result<K> {
+    key_options()
+        .deserialize(bytes)
+        .context("Failed to deserialize key")
+}
+
+fn now_ms() -> Result<u64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("System time before UNIX epoch")?
+        .as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use scoped_futures::ScopedFutureExt;
+    use sui_indexer_alt_framework_store_traits::{
+        Connection as _, Store as _, TransactionalStore as _,
+    };
+    use sui_synthetic_ingestion::synthetic_ingestion;
+    use tempfile::tempdir;
+    use tokio::time::{sleep, timeout};
+
+    use crate::{
+        pipeline::{
+            concurrent::{self, ConcurrentConfig, PrunerConfig},
+            sequential::{self, SequentialConfig},
+            Processor,
+        },
+        types::full_checkpoint_content::CheckpointData,
+        FieldCount,
+    };
+
+    use super::*;
+
+    const TABLE: &str = "values";
+
+    /// Table for storing transaction counts per checkpoint.
+    const TX_COUNTS: &str = "tx_counts";
+
+    /// Table for storing the running total of transactions.
+    const TOTALS: &str = "totals";
+
+    #[derive(FieldCount)]
+    struct TxCount {
+        cp_sequence_number: u64,
+        count: u64,
+    }
+
+    /// Test concurrent pipeline for populating [TX_COUNTS], with pruning.
+    struct TxCounts;
+
+    /// Test sequential pipeline for populating [TOTALS].
+    struct TotalTxs;
+
+    impl Processor for TxCounts {
+        const NAME: &'static str = "tx_counts";
+        type Value = TxCount;
+
+        fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
+            Ok(vec![TxCount {
+                cp_sequence_number: checkpoint.checkpoint_summary.sequence_number,
+                count: checkpoint.transactions.len() as u64,
+            }])
+        }
+    }
+
+    #[async_trait]
+    impl concurrent::Handler for TxCounts {
+        type Store = EmbeddedStore;
+
+        async fn commit<'a>(values: &[Self::Value], conn: &mut Connection<'a>) -> Result<usize> {
+            for value in values {
+                conn.put(TX_COUNTS, &value.cp_sequence_number, &value.count)?;
+            }
+
+            Ok(values.len())
+        }
+
+        async fn prune<'a>(
+            &self,
+            from: u64,
+            to_exclusive: u64,
+            conn: &mut Connection<'a>,
+        ) -> Result<usize> {
+            conn.delete_range(TX_COUNTS, &from, &to_exclusive)?;
+            Ok((to_exclusive - from) as usize)
+        }
+    }
+
+    impl Processor for TotalTxs {
+        const NAME: &'static str = "total_txs";
+        type Value = u64;
+
+        fn process(&self, checkpoint: &Arc<CheckpointData>) -> Result<Vec<Self::Value>> {
+            Ok(vec![checkpoint.transactions.len() as u64])
+        }
+    }
+
+    #[async_trait]
+    impl sequential::Handler for TotalTxs {
+        type Store = EmbeddedStore;
+        type Batch = u64;
+
+        fn batch(batch: &mut Self::Batch, values: Vec<Self::Value>) {
+            *batch += values.into_iter().sum::<u64>();
+        }
+
+        async fn commit<'a>(batch: &Self::Batch, conn: &mut Connection<'a>) -> Result<usize> {
+            let total: u64 = conn.store().get(TOTALS, &"total")?.unwrap_or(0);
+            conn.put(TOTALS, &"total", &(total + batch))?;
+            Ok(1)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_committer_watermark_only_raised() {
+        let dir = tempdir().unwrap();
+        let store = EmbeddedStore::open(dir.path(), &[]).unwrap();
+        let mut conn = store.connect().await.unwrap();
+
+        assert!(conn.committer_watermark("p").await.unwrap().is_none());
+
+        let w = CommitterWatermark::new_for_testing(10);
+        assert!(conn.set_committer_watermark("p", w).await.unwrap());
+        assert!(!conn.set_committer_watermark("p", w).await.unwrap());
+
+        let w = CommitterWatermark::new_for_testing(5);
+        assert!(!conn.set_committer_watermark("p", w).await.unwrap());
+
+        let w = conn.committer_watermark("p").await.unwrap().unwrap();
+        assert_eq!(w.checkpoint_hi_inclusive, 10);
+    }
+
+    #[tokio::test]
+    async fn test_reader_and_pruner_watermarks() {
+        let dir = tempdir().unwrap();
+        let store = EmbeddedStore::open(dir.path(), &[]).unwrap();
+        let mut conn = store.connect().await.unwrap();
+
+        // Reader and pruner watermarks can't be set before the committer watermark exists.
+        assert!(!conn.set_reader_watermark("p", 5).await.unwrap());
+        assert!(!conn.set_pruner_watermark("p", 5).await.unwrap());
+
+        let w = CommitterWatermark::new_for_testing(10);
+        assert!(conn.set_committer_watermark("p", w).await.unwrap());
+
+        assert!(conn.set_reader_watermark("p", 5).await.unwrap());
+        assert!(!conn.set_reader_watermark("p", 4).await.unwrap());
+        assert!(conn.set_pruner_watermark("p", 3).await.unwrap());
+
+        let r = conn.reader_watermark("p").await.unwrap().unwrap();
+        assert_eq!(r.checkpoint_hi_inclusive, 10);
+        assert_eq!(r.reader_lo, 5);
+
+        let delay = Duration::from_secs(60);
+        let p = conn.pruner_watermark("p", delay).await.unwrap().unwrap();
+        assert_eq!(p.reader_lo, 5);
+        assert_eq!(p.pruner_hi, 3);
+        assert!(p.wait_for_ms > 0 && p.wait_for_ms <= delay.as_millis() as i64);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_atomicity() {
+        let dir = tempdir().unwrap();
+        let store = EmbeddedStore::open(dir.path(), &[TABLE]).unwrap();
+
+        // A failed transaction leaves no trace.
+        let result = store
+            .transaction(|conn| {
+                async move {
+                    conn.put(TABLE, &1u64, &"one".to_owned())?;
+                    let w = CommitterWatermark::new_for_testing(1);
+                    conn.set_committer_watermark("p", w).await?;
+                    Err::<(), _>(anyhow!("Boom"))
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert!(store.get::<_, String>(TABLE, &1u64).unwrap().is_none());
+
+        let mut conn = store.connect().await.unwrap();
+        assert!(conn.committer_watermark("p").await.unwrap().is_none());
+
+        // A successful transaction applies all its writes.
+        store
+            .transaction(|conn| {
+                async move {
+                    conn.put(TABLE, &1u64, &"one".to_owned())?;
+                    let w = CommitterWatermark::new_for_testing(1);
+                    conn.set_committer_watermark("p", w).await?;
+                    Ok(())
+                }
+                .scope_boxed()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.get::<_, String>(TABLE, &1u64).unwrap().as_deref(),
+            Some("one")
+        );
+
+        let w = conn.committer_watermark("p").await.unwrap().unwrap();
+        assert_eq!(w.checkpoint_hi_inclusive, 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_range() {
+        let dir = tempdir().unwrap();
+        let store = EmbeddedStore::open(dir.path(), &[TABLE]).unwrap();
+        let mut conn = store.connect().await.unwrap();
+
+        for cp in 0u64..10 {
+            conn.put(TABLE, &cp, &cp).unwrap();
+        }
+
+        conn.delete_range(TABLE, &2u64, &8u64).unwrap();
+
+        let remaining: Vec<(u64, u64)> = store.scan(TABLE, None, 100).unwrap();
+        let keys: Vec<_> = remaining.into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec![0, 1, 8, 9]);
+    }
+
+    /// Run an indexer with a concurrent pipeline that is pruned, and a sequential pipeline, over
+    /// synthetic checkpoints, writing to an embedded store.
+    #[tokio::test]
+    async fn test_indexer_end_to_end() {
+        let checkpoint_dir = tempdir().unwrap();
+        synthetic_ingestion::generate_ingestion(synthetic_ingestion::Config {
+            ingestion_dir: checkpoint_dir.path().to_owned(),
+            starting_checkpoint: 0,
+            num_checkpoints: 10,
+            checkpoint_size: 2,
+        })
+        .await;
+
+        let store_dir = tempdir().unwrap();
+        let cancel = CancellationToken::new();
+        let mut indexer = Indexer::new_embedded(
+            store_dir.path(),
+            &[TX_COUNTS, TOTALS],
+            IndexerArgs {
+                first_checkpoint: Some(0),
+                ..Default::default()
+            },
+            ClientArgs {
+                local_ingestion_path: Some(checkpoint_dir.path().to_owned()),
+                ..Default::default()
+            },
+            IngestionConfig::default(),
+            &Registry::new(),
+            cancel.clone(),
+        )
+        .await
+        .unwrap();
+
+        indexer
+            .concurrent_pipeline(
+                TxCounts,
+                ConcurrentConfig {
+                    committer: Default::default(),
+                    pruner: Some(PrunerConfig {
+                        interval_ms: 10,
+                        delay_ms: 0,
+                        retention: 3,
+                        max_chunk_size: 2,
+                        prune_concurrency: 1,
+                    }),
+                },
+            )
+            .await
+            .unwrap();
+
+        indexer
+            .sequential_pipeline(TotalTxs, SequentialConfig::default())
+            .await
+            .unwrap();
+
+        let store = indexer.store().clone();
+        let handle = indexer.run().await.unwrap();
+
+        // The pruner only runs while the pipeline is running, so the indexer is left to poll for
+        // checkpoints past the last synthetic one until both pipelines have caught up, and the
+        // pruner has removed everything outside the retention window.
+        let mut conn = store.connect().await.unwrap();
+        timeout(Duration::from_secs(60), async {
+            loop {
+                let pruner = conn
+                    .pruner_watermark(TxCounts::NAME, Duration::ZERO)
+                    .await
+                    .unwrap();
+
+                let totals = conn.committer_watermark(TotalTxs::NAME).await.unwrap();
+
+                if pruner.is_some_and(|w| w.pruner_hi == 7)
+                    && totals.is_some_and(|w| w.checkpoint_hi_inclusive == 9)
+                {
+                    break;
+                }
+
+                sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for the indexer to catch up");
+
+        cancel.cancel();
+        handle.await.unwrap();
+
+        // Only the checkpoints in the retention window are left.
+        let counts: Vec<(u64, u64)> = store.scan(TX_COUNTS, None, 100).unwrap();
+        assert_eq!(counts, vec![(7, 2), (8, 2), (9, 2)]);
+
+        let w = conn
+            .committer_watermark(TxCounts::NAME)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(w.checkpoint_hi_inclusive, 9);
+
+        let r = conn
+            .reader_watermark(TxCounts::NAME)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(r.reader_lo, 7);
+
+        // The sequential pipeline saw every checkpoint, in order.
+        let total: Option<u64> = store.get(TOTALS, &"total").unwrap();
+        assert_eq!(total, Some(20));
+    }
+
+    #[test]
+    fn test_reserved_table_name() {
+        let dir = tempdir().unwrap();
+        assert!(EmbeddedStore::open(dir.path(), &[COMMITTER_WATERMARKS]).is_err());
+    }
+}
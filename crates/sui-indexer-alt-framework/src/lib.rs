@@ -28,6 +28,8 @@ pub use sui_types as types;
 
 #[cfg(feature = "cluster")]
 pub mod cluster;
+#[cfg(feature = "embedded")]
+pub mod embedded;
 pub mod ingestion;
 pub mod metrics;
 pub mod pipeline;
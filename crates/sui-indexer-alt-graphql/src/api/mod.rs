@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod mutation;
 pub(crate) mod query;
 pub(crate) mod scalars;
 pub(crate) mod types;
@@ -0,0 +1,31 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{Context, Object};
+
+use crate::{error::RpcError, scope::Scope};
+
+use super::{
+    scalars::base64::Base64,
+    types::transaction_effects::{self, TransactionEffects},
+};
+
+pub struct Mutation;
+
+#[Object]
+impl Mutation {
+    /// Execute a transaction, committing its effects on chain.
+    ///
+    /// - `txBytes` is a `TransactionData` struct that has been BCS-encoded and then Base64-encoded.
+    /// - `signatures` are a list of `flag || signature || pubkey` bytes, Base64-encoded.
+    ///
+    /// Waits until the transaction has reached finality on chain to return its transaction effects. The transaction is forwarded to a fullnode for execution, so it may not be visible to other queries until it has been indexed.
+    async fn execute_transaction(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+    ) -> Result<TransactionEffects, RpcError<transaction_effects::Error>> {
+        TransactionEffects::execute(ctx, Scope::new(ctx)?, tx_bytes, signatures).await
+    }
+}
@@ -12,17 +12,18 @@ use crate::{
 };
 
 use super::{
-    scalars::{digest::Digest, sui_address::SuiAddress, uint53::UInt53},
+    scalars::{base64::Base64, digest::Digest, sui_address::SuiAddress, uint53::UInt53},
     types::{
         address::Address,
         checkpoint::Checkpoint,
         epoch::Epoch,
+        event::{self, Event, EventFilter},
         move_package::{self, CheckpointFilter, MovePackage, PackageKey},
         object::{self, Object, ObjectKey, VersionFilter},
         protocol_configs::ProtocolConfigs,
         service_config::ServiceConfig,
         transaction::Transaction,
-        transaction_effects::TransactionEffects,
+        transaction_effects::{self, TransactionEffects},
     },
 };
 
@@ -75,6 +76,27 @@ impl Query {
         Epoch::fetch(ctx, scope, epoch_id).await
     }
 
+    /// Paginate events that are emitted in the network, optionally filtered by event filters.
+    ///
+    /// Events can be filtered by the checkpoints their transactions were finalized in (`filter.afterCheckpoint`, `filter.atCheckpoint`, `filter.beforeCheckpoint`), their transactions' sender (`filter.sender`), and either the module that emitted them (`filter.module`), or their type (`filter.type`).
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<event::CEvent>,
+        last: Option<u64>,
+        before: Option<event::CEvent>,
+        filter: Option<EventFilter>,
+    ) -> Result<Option<Connection<String, Event>>, RpcError<event::Error>> {
+        let pagination: &PaginationConfig = ctx.data()?;
+        let limits = pagination.limits("Query", "events");
+        let page = Page::from_params(limits, first, after, last, before)?;
+
+        Ok(Some(
+            Event::paginate(ctx, self.scope(ctx)?, page, filter.unwrap_or_default()).await?,
+        ))
+    }
+
     /// Fetch checkpoints by their sequence numbers.
     ///
     /// Returns a list of checkpoints that is guaranteed to be the same length as `keys`. If a checkpoint in `keys` could not be found in the store, its corresponding entry in the result will be `null`. This could be because the checkpoint does not exist yet, or because it was pruned.
@@ -338,6 +360,27 @@ impl Query {
         ServiceConfig
     }
 
+    /// Simulate running a transaction to inspect its effects without committing to them on-chain.
+    ///
+    /// - `txBytes` is a `TransactionData` struct that has been BCS-encoded and then Base64-encoded.
+    /// - `checksEnabled` controls whether the fullnode runs the checks it would normally perform before executing a transaction (such as verifying that gas and input objects are valid). Defaults to `true`.
+    ///
+    /// The transaction is forwarded to a fullnode for simulation, and no signatures are required.
+    async fn simulate_transaction(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: Base64,
+        checks_enabled: Option<bool>,
+    ) -> Result<TransactionEffects, RpcError<transaction_effects::Error>> {
+        TransactionEffects::simulate(
+            ctx,
+            self.scope(ctx)?,
+            tx_bytes,
+            checks_enabled.unwrap_or(true),
+        )
+        .await
+    }
+
     /// Fetch a transaction by its digest.
     ///
     /// Returns `null` if the transaction does not exist in the store, either because it never existed or because it was pruned.
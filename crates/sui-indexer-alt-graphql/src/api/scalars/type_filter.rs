@@ -0,0 +1,192 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+
+use move_core_types::{identifier::Identifier, language_storage::StructTag};
+use sui_types::parse_sui_struct_tag;
+
+use super::{impl_string_input, sui_address::SuiAddress};
+
+/// A filter on modules, either all the modules in a package (`package`), or a specific module
+/// (`package::module`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ModuleFilter {
+    /// Filter the module by the package it's from.
+    Package(SuiAddress),
+
+    /// Exact match on the module.
+    Module(SuiAddress, String),
+}
+
+/// A filter on types, either by the package or module they are defined in, or by their name.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum TypeFilter {
+    /// Filter the type by the package or module it's from.
+    Module(ModuleFilter),
+
+    /// If the struct tag has type parameters, treat it as an exact filter on that instantiation,
+    /// otherwise treat it as a filter on all generic instantiations of the type (as well as an
+    /// exact match on a type with no type parameters). E.g.
+    ///
+    ///  0x2::coin::Coin
+    ///
+    /// would match both 0x2::coin::Coin and 0x2::coin::Coin<0x2::sui::SUI>.
+    Type(StructTag),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Invalid module filter {0:?}, expected 'package' or 'package::module'")]
+    BadModuleFilter(String),
+
+    #[error(
+        "Invalid type filter {0:?}, expected 'package', 'package::module', \
+         'package::module::Name', or 'package::module::Name<...>'"
+    )]
+    BadTypeFilter(String),
+}
+
+impl_string_input!(ModuleFilter);
+impl_string_input!(TypeFilter);
+
+impl ModuleFilter {
+    /// The package this filter applies to.
+    pub(crate) fn package(&self) -> SuiAddress {
+        match self {
+            ModuleFilter::Package(p) | ModuleFilter::Module(p, _) => *p,
+        }
+    }
+
+    /// The module this filter applies to, if it is restricted to a specific module.
+    pub(crate) fn module(&self) -> Option<&str> {
+        match self {
+            ModuleFilter::Package(_) => None,
+            ModuleFilter::Module(_, m) => Some(m.as_str()),
+        }
+    }
+}
+
+impl FromStr for ModuleFilter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let bad = || Error::BadModuleFilter(s.to_owned());
+
+        let mut parts = s.split("::");
+        let package = parts.next().ok_or_else(bad)?;
+        let package = SuiAddress::from_str(package).map_err(|_| bad())?;
+
+        let Some(module) = parts.next() else {
+            return Ok(ModuleFilter::Package(package));
+        };
+
+        if parts.next().is_some() || !Identifier::is_valid(module) {
+            return Err(bad());
+        }
+
+        Ok(ModuleFilter::Module(package, module.to_owned()))
+    }
+}
+
+impl FromStr for TypeFilter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        // Only a fully-qualified type name has three components (or type parameters), anything
+        // shorter is a filter on the package or module.
+        let prefix = s.split('<').next().unwrap_or(s);
+        if !s.contains('<') && prefix.split("::").count() < 3 {
+            return ModuleFilter::from_str(s)
+                .map(TypeFilter::Module)
+                .map_err(|_| Error::BadTypeFilter(s.to_owned()));
+        }
+
+        parse_sui_struct_tag(s)
+            .map(TypeFilter::Type)
+            .map_err(|_| Error::BadTypeFilter(s.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_filter() {
+        assert_eq!(
+            ModuleFilter::from_str("0x2").unwrap(),
+            ModuleFilter::Package(SuiAddress::from_str("0x2").unwrap()),
+        );
+
+        assert_eq!(
+            ModuleFilter::from_str("0x2::coin").unwrap(),
+            ModuleFilter::Module(SuiAddress::from_str("0x2").unwrap(), "coin".to_owned()),
+        );
+    }
+
+    #[test]
+    fn test_bad_module_filter() {
+        for input in [
+            "",
+            "2",
+            "0x2::",
+            "0x2::1coin",
+            "0x2::coin::Coin",
+            "0x2::coin::",
+        ] {
+            assert!(
+                matches!(
+                    ModuleFilter::from_str(input),
+                    Err(Error::BadModuleFilter(_))
+                ),
+                "{input:?} should not parse",
+            );
+        }
+    }
+
+    #[test]
+    fn test_type_filter() {
+        assert_eq!(
+            TypeFilter::from_str("0x2").unwrap(),
+            TypeFilter::Module(ModuleFilter::Package(SuiAddress::from_str("0x2").unwrap())),
+        );
+
+        assert_eq!(
+            TypeFilter::from_str("0x2::coin").unwrap(),
+            TypeFilter::Module(ModuleFilter::Module(
+                SuiAddress::from_str("0x2").unwrap(),
+                "coin".to_owned(),
+            )),
+        );
+
+        let TypeFilter::Type(tag) = TypeFilter::from_str("0x2::coin::Coin").unwrap() else {
+            panic!("Expected a type");
+        };
+        assert_eq!(tag.name.as_str(), "Coin");
+        assert!(tag.type_params.is_empty());
+
+        let TypeFilter::Type(tag) = TypeFilter::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap()
+        else {
+            panic!("Expected a type");
+        };
+        assert_eq!(tag.name.as_str(), "Coin");
+        assert_eq!(tag.type_params.len(), 1);
+    }
+
+    #[test]
+    fn test_bad_type_filter() {
+        for input in [
+            "",
+            "0x2::",
+            "0x2::coin::",
+            "0x2::coin::Coin<",
+            "0x2::coin::Coin::X",
+        ] {
+            assert!(
+                matches!(TypeFilter::from_str(input), Err(Error::BadTypeFilter(_))),
+                "{input:?} should not parse",
+            );
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+
+use move_core_types::language_storage::TypeTag;
+use sui_types::parse_sui_type_tag;
+
+use super::impl_string_input;
+
+/// A fully-qualified Move type, accepted as input, e.g. `0x2::sui::SUI`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct TypeInput(TypeTag);
+
+#[derive(thiserror::Error, Debug)]
+#[error("Invalid type {0:?}, expected a fully-qualified type name")]
+pub(crate) struct Error(String);
+
+impl_string_input!(TypeInput);
+
+impl From<TypeInput> for TypeTag {
+    fn from(value: TypeInput) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for TypeInput {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        parse_sui_type_tag(s)
+            .map(TypeInput)
+            .map_err(|_| Error(s.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_input() {
+        let TypeTag::Struct(tag) = TypeTag::from(TypeInput::from_str("0x2::sui::SUI").unwrap())
+        else {
+            panic!("Expected a struct type");
+        };
+
+        assert_eq!(tag.module.as_str(), "sui");
+        assert_eq!(tag.name.as_str(), "SUI");
+
+        assert_eq!(
+            TypeTag::from(TypeInput::from_str("vector<u8>").unwrap()),
+            TypeTag::Vector(Box::new(TypeTag::U8)),
+        );
+    }
+
+    #[test]
+    fn test_bad_type_input() {
+        for input in ["", "0x2", "0x2::sui", "0x2::sui::SUI<", "not a type"] {
+            assert!(
+                TypeInput::from_str(input).is_err(),
+                "{input:?} should not parse"
+            );
+        }
+    }
+}
@@ -1,12 +1,25 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use async_graphql::{Interface, Object};
-use sui_types::base_types::SuiAddress as NativeSuiAddress;
+use async_graphql::{connection::Connection, Context, Interface, Object};
+use sui_types::{
+    base_types::SuiAddress as NativeSuiAddress, dynamic_field::DynamicFieldType, gas_coin::GAS,
+};
 
-use crate::{api::scalars::sui_address::SuiAddress, scope::Scope};
+use crate::{
+    api::scalars::{sui_address::SuiAddress, type_input::TypeInput},
+    error::RpcError,
+    pagination::{Page, PaginationConfig},
+    scope::Scope,
+};
 
-use super::{move_package::MovePackage, object::Object};
+use super::{
+    balance::{Balance, CBalance},
+    coin::{CCoin, Coin},
+    dynamic_field::{CDynamicField, DynamicField, DynamicFieldName},
+    move_package::MovePackage,
+    object::{self, Object},
+};
 
 /// Interface implemented by GraphQL types representing entities that are identified by an address.
 ///
@@ -15,6 +28,8 @@ use super::{move_package::MovePackage, object::Object};
 #[graphql(name = "IAddressable", field(name = "address", ty = "SuiAddress"))]
 pub(crate) enum IAddressable {
     Address(Address),
+    Coin(Coin),
+    DynamicField(DynamicField),
     MovePackage(MovePackage),
     Object(Object),
 }
@@ -33,6 +48,114 @@ impl Address {
     pub(crate) async fn address(&self) -> SuiAddress {
         AddressableImpl::from(self).address()
     }
+
+    /// Total balance of all coins with marker type owned by this address. If type is not supplied, it defaults to `0x2::sui::SUI`.
+    ///
+    /// Returns `null` if the address does not own any coins of this type.
+    async fn balance(
+        &self,
+        ctx: &Context<'_>,
+        coin_type: Option<TypeInput>,
+    ) -> Result<Option<Balance>, RpcError> {
+        let coin_type = coin_type.map_or_else(GAS::type_tag, Into::into);
+        Balance::fetch(ctx, self.scope.clone(), self.address, coin_type).await
+    }
+
+    /// The balances of all coin types owned by this address.
+    async fn balances(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CBalance>,
+        last: Option<u64>,
+        before: Option<CBalance>,
+    ) -> Result<Option<Connection<String, Balance>>, RpcError> {
+        let pagination: &PaginationConfig = ctx.data()?;
+        let limits = pagination.limits("Address", "balances");
+        let page = Page::from_params(limits, first, after, last, before)?;
+
+        Ok(Some(
+            Balance::paginate(ctx, self.scope.clone(), page, self.address).await?,
+        ))
+    }
+
+    /// The coin objects for this address. If type is not supplied, it defaults to `0x2::sui::SUI`.
+    ///
+    /// Coins are returned in descending order of balance (approximately), so the largest coins are returned first.
+    async fn coins(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CCoin>,
+        last: Option<u64>,
+        before: Option<CCoin>,
+        #[graphql(name = "type")] type_: Option<TypeInput>,
+    ) -> Result<Option<Connection<String, Coin>>, RpcError> {
+        let pagination: &PaginationConfig = ctx.data()?;
+        let limits = pagination.limits("Address", "coins");
+        let page = Page::from_params(limits, first, after, last, before)?;
+
+        let coin_type = type_.map_or_else(GAS::type_tag, Into::into);
+        Ok(Some(
+            Coin::paginate(ctx, self.scope.clone(), page, self.address, coin_type).await?,
+        ))
+    }
+
+    /// Access a dynamic field on an object using its type and BCS-encoded name.
+    ///
+    /// Returns `null` if a dynamic field with that name could not be found attached to the object with this address.
+    async fn dynamic_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<object::Error>> {
+        DynamicField::by_name(
+            ctx,
+            self.scope.clone(),
+            self.address,
+            None,
+            DynamicFieldType::DynamicField,
+            name,
+        )
+        .await
+    }
+
+    /// Dynamic fields and dynamic object fields owned by the object with this address, ordered by their IDs.
+    async fn dynamic_fields(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CDynamicField>,
+        last: Option<u64>,
+        before: Option<CDynamicField>,
+    ) -> Result<Option<Connection<String, DynamicField>>, RpcError<object::Error>> {
+        let pagination: &PaginationConfig = ctx.data()?;
+        let limits = pagination.limits("Address", "dynamicFields");
+        let page = Page::from_params(limits, first, after, last, before)?;
+
+        Ok(Some(
+            DynamicField::paginate(ctx, self.scope.clone(), page, self.address, None).await?,
+        ))
+    }
+
+    /// Access a dynamic object field on an object using its type and BCS-encoded name.
+    ///
+    /// Returns `null` if a dynamic object field with that name could not be found attached to the object with this address.
+    async fn dynamic_object_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<object::Error>> {
+        DynamicField::by_name(
+            ctx,
+            self.scope.clone(),
+            self.address,
+            None,
+            DynamicFieldType::DynamicObject,
+            name,
+        )
+        .await
+    }
 }
 
 impl Address {
@@ -0,0 +1,230 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context as _;
+use async_graphql::{
+    connection::{Connection, CursorType, Edge},
+    Context, Object,
+};
+use diesel::{sql_types::Bytea, QueryableByName};
+use futures::future::try_join_all;
+use move_core_types::language_storage::TypeTag;
+use sui_indexer_alt_reader::pg_reader::PgReader;
+use sui_indexer_alt_schema::transactions::BalanceChange as StoredBalanceChange;
+use sui_sql_macro::query;
+use sui_types::{base_types::SuiAddress as NativeSuiAddress, object::Owner};
+
+use crate::{
+    api::scalars::{big_int::BigInt, cursor::BcsCursor, uint53::UInt53},
+    error::RpcError,
+    pagination::Page,
+    scope::Scope,
+};
+
+use super::{
+    address::Address,
+    coin::{live_coins, Coin, StoredCoin},
+};
+
+/// The total balance of a particular coin type owned by an address.
+#[derive(Clone)]
+pub(crate) struct Balance {
+    pub(crate) coin_type: TypeTag,
+    pub(crate) coin_object_count: u64,
+    pub(crate) total_balance: u128,
+}
+
+#[derive(Clone)]
+pub(crate) struct BalanceChange {
+    pub(crate) scope: Scope,
+    pub(crate) native: StoredBalanceChange,
+}
+
+/// Cursor for iterating over balances. Points at a particular coin type, by its BCS representation.
+pub(crate) type CBalance = BcsCursor<Vec<u8>>;
+
+/// A coin type owned by an address.
+#[derive(QueryableByName)]
+struct StoredCoinType {
+    #[diesel(sql_type = Bytea)]
+    coin_type: Vec<u8>,
+}
+
+/// The total balance of a particular coin type owned by an address, summed across all the coins of that type it owns.
+#[Object]
+impl Balance {
+    /// Coin type for the balance, such as `0x2::sui::SUI`.
+    async fn coin_type(&self) -> String {
+        self.coin_type.to_canonical_string(/* with_prefix */ true)
+    }
+
+    /// How many coins of this type constitute the balance.
+    async fn coin_object_count(&self) -> UInt53 {
+        self.coin_object_count.into()
+    }
+
+    /// Total balance across all coins of this coin type.
+    async fn total_balance(&self) -> BigInt {
+        BigInt::from(self.total_balance)
+    }
+}
+
+/// Effect on the balance of a particular coin type for a particular owner, as a result of executing a transaction.
+#[Object]
+impl BalanceChange {
+    /// The account or object whose balance has changed. This is `null` if the owner is shared or immutable.
+    async fn owner(&self) -> Option<Address> {
+        let StoredBalanceChange::V1 { owner, .. } = &self.native;
+        let address = match owner {
+            Owner::AddressOwner(a) | Owner::ObjectOwner(a) => *a,
+            Owner::ConsensusAddressOwner { owner, .. } => *owner,
+            Owner::Shared { .. } | Owner::Immutable => return None,
+        };
+
+        Some(Address::with_address(self.scope.clone(), address))
+    }
+
+    /// The inner type of the coin whose balance has changed (e.g. `0x2::sui::SUI`).
+    async fn coin_type(&self) -> String {
+        let StoredBalanceChange::V1 { coin_type, .. } = &self.native;
+        coin_type.clone()
+    }
+
+    /// The signed balance change.
+    async fn amount(&self) -> BigInt {
+        let StoredBalanceChange::V1 { amount, .. } = &self.native;
+        BigInt::from(*amount)
+    }
+}
+
+impl Balance {
+    /// Fetch the total balance of coins of type `coin_type` owned by `owner`, as of the checkpoint
+    /// being viewed. Returns `None` if the owner does not own any coins of this type.
+    pub(crate) async fn fetch(
+        ctx: &Context<'_>,
+        scope: Scope,
+        owner: NativeSuiAddress,
+        coin_type: TypeTag,
+    ) -> Result<Option<Self>, RpcError> {
+        let pg_reader: &PgReader = ctx.data()?;
+
+        let bytes = bcs::to_bytes(&coin_type).context("Failed to serialize coin type")?;
+        let query = live_coins(&scope, owner, query!(" AND c.coin_type = {Bytea}", bytes));
+
+        let mut c = pg_reader
+            .connect()
+            .await
+            .context("Failed to connect to database")?;
+
+        let coins: Vec<StoredCoin> = c
+            .results(query)
+            .await
+            .context("Failed to read from database")?;
+
+        if coins.is_empty() {
+            return Ok(None);
+        }
+
+        // The buckets only give an approximation of each coin's balance, so the coins themselves
+        // need to be loaded to calculate the total.
+        let coins = try_join_all(
+            coins
+                .iter()
+                .map(|c| Coin::checkpoint_bounded(ctx, scope.clone(), &c.object_id)),
+        )
+        .await?;
+
+        let mut balance = Balance {
+            coin_type,
+            coin_object_count: 0,
+            total_balance: 0,
+        };
+
+        for coin in coins.into_iter().flatten() {
+            balance.coin_object_count += 1;
+            balance.total_balance += coin.value() as u128;
+        }
+
+        Ok(Some(balance))
+    }
+
+    /// Paginate through the balances of all coin types owned by `owner`, as of the checkpoint
+    /// being viewed. Balances are ordered by their coin type.
+    pub(crate) async fn paginate(
+        ctx: &Context<'_>,
+        scope: Scope,
+        page: Page<CBalance>,
+        owner: NativeSuiAddress,
+    ) -> Result<Connection<String, Balance>, RpcError> {
+        let mut conn = Connection::new(false, false);
+
+        let pg_reader: &PgReader = ctx.data()?;
+
+        let mut conditions = query!("");
+        if let Some(after) = page.after() {
+            conditions += query!(" AND {Bytea} <= c.coin_type", (**after).clone());
+        }
+
+        if let Some(before) = page.before() {
+            conditions += query!(" AND c.coin_type <= {Bytea}", (**before).clone());
+        }
+
+        let query = query!(
+            r#"
+            SELECT DISTINCT
+                coin_type
+            FROM (
+                {}
+            ) c
+            ORDER BY {}
+            LIMIT {BigInt}
+            "#,
+            live_coins(&scope, owner, conditions),
+            if page.is_from_front() {
+                query!("coin_type")
+            } else {
+                query!("coin_type DESC")
+            },
+            page.limit() as i64 + 2,
+        );
+
+        let mut c = pg_reader
+            .connect()
+            .await
+            .context("Failed to connect to database")?;
+
+        let mut results: Vec<StoredCoinType> = c
+            .results(query)
+            .await
+            .context("Failed to read from database")?;
+
+        if !page.is_from_front() {
+            results.reverse();
+        }
+
+        let (prev, next, results) =
+            page.paginate_results(results, |t| BcsCursor::new(t.coin_type.clone()));
+
+        conn.has_previous_page = prev;
+        conn.has_next_page = next;
+
+        let (cursors, stored): (Vec<_>, Vec<_>) = results.unzip();
+        let balances = try_join_all(stored.into_iter().map(|s| {
+            let scope = scope.clone();
+            async move {
+                let coin_type: TypeTag =
+                    bcs::from_bytes(&s.coin_type).context("Failed to deserialize coin type")?;
+                Balance::fetch(ctx, scope, owner, coin_type).await
+            }
+        }))
+        .await?;
+
+        for (cursor, balance) in cursors.into_iter().zip(balances) {
+            if let Some(balance) = balance {
+                conn.edges.push(Edge::new(cursor.encode_cursor(), balance));
+            }
+        }
+
+        Ok(conn)
+    }
+}
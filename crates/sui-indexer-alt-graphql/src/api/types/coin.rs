@@ -0,0 +1,341 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context as _;
+use async_graphql::{
+    connection::{Connection, CursorType, Edge},
+    Context, Object,
+};
+use diesel::{
+    sql_types::{BigInt as SqlBigInt, Bytea, SmallInt},
+    QueryableByName,
+};
+use futures::future::try_join_all;
+use move_core_types::language_storage::TypeTag;
+use serde::{Deserialize, Serialize};
+use sui_indexer_alt_reader::pg_reader::PgReader;
+use sui_indexer_alt_schema::objects::StoredCoinOwnerKind;
+use sui_pg_db::query::Query;
+use sui_sql_macro::query;
+use sui_types::{base_types::SuiAddress as NativeSuiAddress, coin::Coin as NativeCoin};
+
+use crate::{
+    api::scalars::{
+        base64::Base64, big_int::BigInt, cursor::BcsCursor, sui_address::SuiAddress, uint53::UInt53,
+    },
+    error::{user_input_as_internal, RpcError},
+    pagination::Page,
+    scope::Scope,
+};
+
+use super::{
+    address::AddressableImpl,
+    object::{self, CVersion, Object, ObjectImpl, VersionFilter},
+    transaction::Transaction,
+};
+
+pub(crate) struct Coin {
+    /// Representation of this Coin as a generic Object.
+    super_: Object,
+
+    /// Coin specific data, extracted from the native representation of the generic object.
+    native: NativeCoin,
+}
+
+/// Cursor for iterating over coins. Coins are ordered by their balance bucket (largest first), then by the checkpoint they entered that bucket in, and then by their ID.
+pub(crate) type CCoin = BcsCursor<CoinCursor>;
+
+/// Inner struct for the cursor produced while iterating over coins.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub(crate) struct CoinCursor {
+    pub coin_balance_bucket: u8,
+    pub cp_sequence_number: u64,
+    pub object_id: Vec<u8>,
+}
+
+/// A coin owned by an address, from the `coin_balance_buckets` table.
+#[derive(QueryableByName)]
+pub(crate) struct StoredCoin {
+    #[diesel(sql_type = Bytea)]
+    pub(crate) object_id: Vec<u8>,
+
+    #[diesel(sql_type = SqlBigInt)]
+    pub(crate) cp_sequence_number: i64,
+
+    #[diesel(sql_type = Bytea)]
+    pub(crate) coin_type: Vec<u8>,
+
+    #[diesel(sql_type = SmallInt)]
+    pub(crate) coin_balance_bucket: i16,
+}
+
+/// Some objects are coins, which hold a balance of some coin type (identified by the type parameter of the coin's `0x2::coin::Coin<T>` type).
+#[Object]
+impl Coin {
+    /// The Coin's ID.
+    pub(crate) async fn address(&self) -> SuiAddress {
+        AddressableImpl::from(&self.super_.super_).address()
+    }
+
+    /// The version of this coin that this content comes from.
+    pub(crate) async fn version(&self) -> UInt53 {
+        ObjectImpl::from(&self.super_).version()
+    }
+
+    /// 32-byte hash that identifies the coin's contents, encoded in Base58.
+    pub(crate) async fn digest(&self) -> String {
+        ObjectImpl::from(&self.super_).digest()
+    }
+
+    /// Balance of this coin.
+    async fn coin_balance(&self) -> Option<BigInt> {
+        Some(BigInt::from(self.native.value()))
+    }
+
+    /// Fetch the coin as an object with the same ID, at a different version, root version bound, or checkpoint.
+    ///
+    /// If no additional bound is provided, the latest version of this object is fetched at the latest checkpoint.
+    pub(crate) async fn object_at(
+        &self,
+        ctx: &Context<'_>,
+        version: Option<UInt53>,
+        root_version: Option<UInt53>,
+        checkpoint: Option<UInt53>,
+    ) -> Result<Option<Object>, RpcError<object::Error>> {
+        ObjectImpl::from(&self.super_)
+            .object_at(ctx, version, root_version, checkpoint)
+            .await
+    }
+
+    /// The Base64-encoded BCS serialization of this coin, as an `Object`.
+    pub(crate) async fn object_bcs(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<Base64>, RpcError<object::Error>> {
+        ObjectImpl::from(&self.super_).object_bcs(ctx).await
+    }
+
+    /// Paginate all versions of this coin treated as an object, after this one.
+    pub(crate) async fn object_versions_after(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CVersion>,
+        last: Option<u64>,
+        before: Option<CVersion>,
+        filter: Option<VersionFilter>,
+    ) -> Result<Connection<String, Object>, RpcError<object::Error>> {
+        ObjectImpl::from(&self.super_)
+            .object_versions_after(ctx, first, after, last, before, filter)
+            .await
+    }
+
+    /// Paginate all versions of this coin treated as an object, before this one.
+    pub(crate) async fn object_versions_before(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CVersion>,
+        last: Option<u64>,
+        before: Option<CVersion>,
+        filter: Option<VersionFilter>,
+    ) -> Result<Connection<String, Object>, RpcError<object::Error>> {
+        ObjectImpl::from(&self.super_)
+            .object_versions_before(ctx, first, after, last, before, filter)
+            .await
+    }
+
+    /// The transaction that created this version of the object.
+    pub(crate) async fn previous_transaction(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<Transaction>, RpcError<object::Error>> {
+        ObjectImpl::from(&self.super_)
+            .previous_transaction(ctx)
+            .await
+    }
+}
+
+impl Coin {
+    /// Try to downcast an `Object` to a `Coin`. This function returns `None` if `object`'s
+    /// contents cannot be fetched, or it is not a coin.
+    pub(crate) async fn from_object(
+        object: &Object,
+        ctx: &Context<'_>,
+    ) -> Result<Option<Self>, RpcError<object::Error>> {
+        let super_ = object.inflated(ctx).await?;
+
+        let Some(super_contents) = &super_.contents else {
+            return Ok(None);
+        };
+
+        let Some(native) = super_contents.as_coin_maybe() else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self { super_, native }))
+    }
+
+    /// Load the coin with ID `object_id` as of the checkpoint being viewed.
+    pub(crate) async fn checkpoint_bounded(
+        ctx: &Context<'_>,
+        scope: Scope,
+        object_id: &[u8],
+    ) -> Result<Option<Self>, RpcError> {
+        let address =
+            NativeSuiAddress::from_bytes(object_id).context("Failed to deserialize SuiAddress")?;
+
+        let checkpoint: UInt53 = scope.checkpoint_viewed_at().into();
+        let Some(object) = Object::checkpoint_bounded(ctx, scope, address.into(), checkpoint)
+            .await
+            .map_err(user_input_as_internal)?
+        else {
+            return Ok(None);
+        };
+
+        Coin::from_object(&object, ctx)
+            .await
+            .map_err(user_input_as_internal)
+    }
+
+    /// The coin's balance.
+    pub(crate) fn value(&self) -> u64 {
+        self.native.value()
+    }
+
+    /// Paginate through the coins of type `coin_type` owned by `owner`, as of the checkpoint being
+    /// viewed. Coins are returned in descending order of their balance bucket, so that the largest
+    /// coins are returned first.
+    pub(crate) async fn paginate(
+        ctx: &Context<'_>,
+        scope: Scope,
+        page: Page<CCoin>,
+        owner: NativeSuiAddress,
+        coin_type: TypeTag,
+    ) -> Result<Connection<String, Coin>, RpcError> {
+        let mut conn = Connection::new(false, false);
+
+        let pg_reader: &PgReader = ctx.data()?;
+
+        let coin_type = bcs::to_bytes(&coin_type).context("Failed to serialize coin type")?;
+        let mut conditions = query!(" AND c.coin_type = {Bytea}", coin_type);
+
+        if let Some(after) = page.after() {
+            conditions += query!(
+                " AND (c.coin_balance_bucket, c.cp_sequence_number, c.object_id) <= ({SmallInt}, {BigInt}, {Bytea})",
+                after.coin_balance_bucket as i16,
+                after.cp_sequence_number as i64,
+                after.object_id.clone(),
+            );
+        }
+
+        if let Some(before) = page.before() {
+            conditions += query!(
+                " AND (c.coin_balance_bucket, c.cp_sequence_number, c.object_id) >= ({SmallInt}, {BigInt}, {Bytea})",
+                before.coin_balance_bucket as i16,
+                before.cp_sequence_number as i64,
+                before.object_id.clone(),
+            );
+        }
+
+        let query = query!(
+            "{} ORDER BY {} LIMIT {BigInt}",
+            live_coins(&scope, owner, conditions),
+            if page.is_from_front() {
+                query!("c.coin_balance_bucket DESC, c.cp_sequence_number DESC, c.object_id DESC")
+            } else {
+                query!("c.coin_balance_bucket, c.cp_sequence_number, c.object_id")
+            },
+            page.limit() as i64 + 2,
+        );
+
+        let mut c = pg_reader
+            .connect()
+            .await
+            .context("Failed to connect to database")?;
+
+        let mut results: Vec<StoredCoin> = c
+            .results(query)
+            .await
+            .context("Failed to read from database")?;
+
+        if !page.is_from_front() {
+            results.reverse();
+        }
+
+        let (prev, next, results) = page.paginate_results(results, |c| {
+            BcsCursor::new(CoinCursor {
+                coin_balance_bucket: c.coin_balance_bucket as u8,
+                cp_sequence_number: c.cp_sequence_number as u64,
+                object_id: c.object_id.clone(),
+            })
+        });
+
+        conn.has_previous_page = prev;
+        conn.has_next_page = next;
+
+        let (cursors, stored): (Vec<_>, Vec<_>) = results.unzip();
+        let coins = try_join_all(
+            stored
+                .iter()
+                .map(|s| Coin::checkpoint_bounded(ctx, scope.clone(), &s.object_id)),
+        )
+        .await?;
+
+        for (cursor, coin) in cursors.into_iter().zip(coins) {
+            if let Some(coin) = coin {
+                conn.edges.push(Edge::new(cursor.encode_cursor(), coin));
+            }
+        }
+
+        Ok(conn)
+    }
+}
+
+/// Query for the coins owned by `owner` as of the checkpoint being viewed, further filtered by
+/// `conditions`, which can refer to the coins' rows as `c`. Coins that `owner` owns through
+/// fast-path or consensus ownership are both included.
+///
+/// A coin's owner, type and balance bucket are tracked in `coin_balance_buckets`, which gains a
+/// row every time one of them changes, so a coin belongs to the owner if its latest row at or
+/// before the checkpoint being viewed does.
+pub(crate) fn live_coins<'q>(
+    scope: &Scope,
+    owner: NativeSuiAddress,
+    conditions: Query<'q>,
+) -> Query<'q> {
+    let checkpoint = scope.checkpoint_viewed_at() as i64;
+
+    query!(
+        r#"
+        SELECT
+            c.object_id,
+            c.cp_sequence_number,
+            c.coin_type,
+            c.coin_balance_bucket
+        FROM
+            coin_balance_buckets c
+        WHERE
+            c.owner_kind IN ({SmallInt}, {SmallInt})
+        AND c.owner_id = {Bytea}
+        AND c.cp_sequence_number <= {BigInt}
+        AND NOT EXISTS (
+            SELECT
+                1
+            FROM
+                coin_balance_buckets n
+            WHERE
+                n.object_id = c.object_id
+            AND c.cp_sequence_number < n.cp_sequence_number
+            AND n.cp_sequence_number <= {BigInt}
+        )
+        {}
+        "#,
+        StoredCoinOwnerKind::Fastpath,
+        StoredCoinOwnerKind::Consensus,
+        owner.to_vec(),
+        checkpoint,
+        checkpoint,
+        conditions,
+    )
+}
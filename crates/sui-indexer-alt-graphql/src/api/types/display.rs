@@ -0,0 +1,175 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::Context as _;
+use async_graphql::{dataloader::DataLoader, Context, SimpleObject};
+use move_core_types::language_storage::{StructTag, TypeTag};
+use sui_display::v1::Format;
+use sui_indexer_alt_reader::{
+    displays::DisplayKey, package_resolver::PackageResolver, pg_reader::PgReader,
+};
+use sui_types::{
+    collection_types::VecMap,
+    display::{DisplayObject, DisplayVersionUpdatedEvent},
+};
+
+use crate::{
+    config::Limits,
+    error::{bad_user_input, RpcError},
+};
+
+use super::object::{self, Object};
+
+/// A single field of an object's Display, rendered by substituting data from the object into the template string that the Display defines for that field.
+#[derive(SimpleObject)]
+pub(crate) struct DisplayEntry {
+    /// The name of the Display field.
+    pub(crate) key: String,
+
+    /// The Display field's template string, with placeholders substituted by values from the object. `null` if the field could not be rendered.
+    pub(crate) value: Option<String>,
+
+    /// Why the field could not be rendered, if it could not be rendered.
+    pub(crate) error: Option<String>,
+}
+
+impl DisplayEntry {
+    /// Render the `Display` registered for `object`'s type, against the object's contents.
+    ///
+    /// The `Display` for each type is looked up in `sum_displays`, which only tracks its latest
+    /// released version. Its template is read from the `Display` object itself as of the
+    /// checkpoint being viewed, so that objects viewed at past checkpoints are rendered with the
+    /// template that was in effect then. If the `Display` object had not been released at that
+    /// version (its `version` differs from the latest release), the template may include edits
+    /// that were not released until later.
+    ///
+    /// Returns `None` if the object's contents could not be found, if it is not a Move object, or
+    /// if its type did not have a `Display` registered for it as of the checkpoint being viewed.
+    /// Individual fields that are malformed or fail to render are reported as entries with an
+    /// `error`.
+    pub(crate) async fn render(
+        ctx: &Context<'_>,
+        object: &Object,
+    ) -> Result<Option<Vec<Self>>, RpcError<object::Error>> {
+        let Some(contents) = object.contents(ctx).await? else {
+            return Ok(None);
+        };
+
+        let Some(move_object) = contents.data.try_as_move() else {
+            return Ok(None);
+        };
+
+        let limits: &Limits = ctx.data()?;
+        let pg_loader: &Arc<DataLoader<PgReader>> = ctx.data()?;
+        let resolver: &PackageResolver = ctx.data()?;
+
+        let struct_tag: StructTag = move_object.type_().clone().into();
+        let Some(stored) = pg_loader
+            .load_one(DisplayKey(struct_tag.clone()))
+            .await
+            .context("Failed to fetch Display format")?
+        else {
+            return Ok(None);
+        };
+
+        let latest: DisplayVersionUpdatedEvent =
+            bcs::from_bytes(&stored.display).context("Failed to deserialize Display format")?;
+
+        let scope = object.super_.scope.clone();
+        let checkpoint = scope.checkpoint_viewed_at();
+        let Some(display) =
+            Object::checkpoint_bounded(ctx, scope, latest.id.bytes.into(), checkpoint.into())
+                .await?
+        else {
+            return Ok(None);
+        };
+
+        let Some(display) = display.contents(ctx).await? else {
+            return Ok(None);
+        };
+
+        let Some(display) = display.data.try_as_move() else {
+            return Ok(None);
+        };
+
+        let display: DisplayObject =
+            bcs::from_bytes(display.contents()).context("Failed to deserialize Display object")?;
+
+        // The Display object had not been released yet as of this checkpoint.
+        if display.version == 0 {
+            return Ok(None);
+        }
+
+        // The Display object has not been updated since its latest release, so the released
+        // fields can be used as-is.
+        let fields = if display.version == latest.version {
+            latest.fields
+        } else {
+            display.fields
+        };
+
+        let type_ = TypeTag::Struct(Box::new(struct_tag));
+        let layout = resolver.type_layout(type_.clone()).await.with_context(|| {
+            format!(
+                "Failed to resolve layout for type {}",
+                type_.to_canonical_display(/* with_prefix */ true)
+            )
+        })?;
+
+        // Each field is parsed and rendered separately, so that a malformed template only affects
+        // its own field, but they all share the same output budget.
+        let mut budget = limits.max_display_output_size;
+        let mut entries = BTreeMap::new();
+        for entry in fields.contents {
+            let key = entry.key.clone();
+            let field = VecMap {
+                contents: vec![entry],
+            };
+
+            let format = match Format::parse(limits.max_display_field_depth, &field) {
+                Ok(format) => format,
+                Err(e) => {
+                    entries.insert(key, Err(e));
+                    continue;
+                }
+            };
+
+            let rendered = format
+                .display(budget, move_object.contents(), &layout)
+                .map_err(|_| {
+                    bad_user_input(object::Error::DisplayTooLarge(
+                        limits.max_display_output_size,
+                    ))
+                })?;
+
+            for (key, value) in rendered {
+                if let Ok(value) = &value {
+                    budget -= key.len() + value.len();
+                }
+
+                entries.insert(key, value);
+            }
+        }
+
+        Ok(Some(
+            entries
+                .into_iter()
+                .map(|(key, value)| match value {
+                    Ok(value) => DisplayEntry {
+                        key,
+                        value: Some(value),
+                        error: None,
+                    },
+
+                    Err(e) => DisplayEntry {
+                        key,
+                        value: None,
+                        error: Some(format!("{e:#}")),
+                    },
+                })
+                .collect(),
+        ))
+    }
+}
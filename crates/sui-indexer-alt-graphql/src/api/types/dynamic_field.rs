@@ -0,0 +1,448 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use async_graphql::{
+    connection::{Connection, CursorType, Edge},
+    dataloader::DataLoader,
+    Context, InputObject, Object, Union,
+};
+use diesel::{sql_types::Bytea, QueryableByName};
+use futures::future::try_join_all;
+use move_core_types::{annotated_value as A, language_storage::TypeTag};
+use sui_indexer_alt_reader::{
+    object_versions::VersionedObjectVersionKey, package_resolver::PackageResolver,
+    pg_reader::PgReader,
+};
+use sui_indexer_alt_schema::objects::StoredOwnerKind;
+use sui_sql_macro::query;
+use sui_types::{
+    base_types::SuiAddress as NativeSuiAddress,
+    dynamic_field::{
+        derive_dynamic_field_id, visitor::FieldVisitor, DynamicFieldInfo, DynamicFieldType,
+        DYNAMIC_FIELD_FIELD_STRUCT_NAME, DYNAMIC_FIELD_MODULE_NAME,
+    },
+    SUI_FRAMEWORK_ADDRESS,
+};
+
+use crate::{
+    api::scalars::{
+        base64::Base64, cursor::BcsCursor, sui_address::SuiAddress, type_input::TypeInput,
+        uint53::UInt53,
+    },
+    error::RpcError,
+    pagination::Page,
+    scope::Scope,
+};
+
+use super::{
+    address::AddressableImpl,
+    move_value::MoveValue,
+    object::{self, CVersion, Object, ObjectImpl, VersionFilter},
+    transaction::Transaction,
+};
+
+pub(crate) struct DynamicField {
+    /// Representation of this dynamic field as a generic Object.
+    super_: Object,
+
+    /// The root version that this dynamic field was queried at. This can be a later version than
+    /// the version of the dynamic field's object (`super_`).
+    root_version: Option<u64>,
+}
+
+/// The value of a dynamic field, which is either a Move value (for a dynamic field), or an object (for a dynamic object field).
+#[derive(Union)]
+pub(crate) enum DynamicFieldValue {
+    MoveValue(MoveValue),
+    Object(Object),
+}
+
+/// The name of a dynamic field, used to look it up on its parent.
+#[derive(InputObject, Debug, Clone)]
+pub(crate) struct DynamicFieldName {
+    /// The type of the dynamic field's name, like 'u64' or '0x2::kiosk::Listing'.
+    #[graphql(name = "type")]
+    pub(crate) type_: TypeInput,
+
+    /// The Base64-encoded BCS serialization of the dynamic field's name.
+    pub(crate) bcs: Base64,
+}
+
+/// Cursor for iterating over dynamic fields. Points at a particular dynamic field, by its ID.
+pub(crate) type CDynamicField = BcsCursor<Vec<u8>>;
+
+/// The ID of a dynamic field owned by a parent.
+#[derive(QueryableByName)]
+struct StoredDynamicField {
+    #[diesel(sql_type = Bytea)]
+    object_id: Vec<u8>,
+}
+
+/// Dynamic fields are heterogenous fields that can be added or removed from an object at runtime. Their names are arbitrary Move values that have `copy`, `drop`, and `store`.
+///
+/// There are two sub-types of dynamic fields:
+///
+/// - Dynamic fields can store any value that has `store`. Objects stored in this kind of field will be considered wrapped (not accessible via its ID by external tools like explorers, wallets, etc. accessing storage).
+/// - Dynamic object fields can only store objects (values that have the `key` ability, and an `id: UID` as its first field) that have `store`, but they will still be directly accessible off-chain via their ID after being attached as a field.
+#[Object]
+impl DynamicField {
+    /// The DynamicField's ID.
+    pub(crate) async fn address(&self) -> SuiAddress {
+        AddressableImpl::from(&self.super_.super_).address()
+    }
+
+    /// The version of this dynamic field that this content comes from.
+    pub(crate) async fn version(&self) -> UInt53 {
+        ObjectImpl::from(&self.super_).version()
+    }
+
+    /// 32-byte hash that identifies the dynamic field's contents, encoded in Base58.
+    pub(crate) async fn digest(&self) -> String {
+        ObjectImpl::from(&self.super_).digest()
+    }
+
+    /// The dynamic field's name, as a Move value. This is used to uniquely identify the field among the other fields of its parent.
+    async fn name(&self, ctx: &Context<'_>) -> Result<Option<MoveValue>, RpcError<object::Error>> {
+        let Some((bytes, layout)) = self.contents(ctx).await? else {
+            return Ok(None);
+        };
+
+        let field = FieldVisitor::deserialize(&bytes, &layout)
+            .context("Failed to deserialize dynamic field")?;
+
+        Ok(Some(MoveValue::new(
+            field.name_layout.into(),
+            field.name_bytes.to_owned(),
+        )))
+    }
+
+    /// Fetch the dynamic field as an object with the same ID, at a different version, root version bound, or checkpoint.
+    ///
+    /// If no additional bound is provided, the latest version of this object is fetched at the latest checkpoint.
+    pub(crate) async fn object_at(
+        &self,
+        ctx: &Context<'_>,
+        version: Option<UInt53>,
+        root_version: Option<UInt53>,
+        checkpoint: Option<UInt53>,
+    ) -> Result<Option<Object>, RpcError<object::Error>> {
+        ObjectImpl::from(&self.super_)
+            .object_at(ctx, version, root_version, checkpoint)
+            .await
+    }
+
+    /// The Base64-encoded BCS serialization of this dynamic field, as an `Object`.
+    pub(crate) async fn object_bcs(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<Base64>, RpcError<object::Error>> {
+        ObjectImpl::from(&self.super_).object_bcs(ctx).await
+    }
+
+    /// Paginate all versions of this dynamic field treated as an object, after this one.
+    pub(crate) async fn object_versions_after(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CVersion>,
+        last: Option<u64>,
+        before: Option<CVersion>,
+        filter: Option<VersionFilter>,
+    ) -> Result<Connection<String, Object>, RpcError<object::Error>> {
+        ObjectImpl::from(&self.super_)
+            .object_versions_after(ctx, first, after, last, before, filter)
+            .await
+    }
+
+    /// Paginate all versions of this dynamic field treated as an object, before this one.
+    pub(crate) async fn object_versions_before(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CVersion>,
+        last: Option<u64>,
+        before: Option<CVersion>,
+        filter: Option<VersionFilter>,
+    ) -> Result<Connection<String, Object>, RpcError<object::Error>> {
+        ObjectImpl::from(&self.super_)
+            .object_versions_before(ctx, first, after, last, before, filter)
+            .await
+    }
+
+    /// The transaction that created this version of the object.
+    pub(crate) async fn previous_transaction(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<Transaction>, RpcError<object::Error>> {
+        ObjectImpl::from(&self.super_)
+            .previous_transaction(ctx)
+            .await
+    }
+
+    /// The dynamic field's value. For dynamic object fields, this is the object stored in the field (fetched as of the same root version or checkpoint as its parent), otherwise it is a Move value.
+    async fn value(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<DynamicFieldValue>, RpcError<object::Error>> {
+        let Some((bytes, layout)) = self.contents(ctx).await? else {
+            return Ok(None);
+        };
+
+        let field = FieldVisitor::deserialize(&bytes, &layout)
+            .context("Failed to deserialize dynamic field")?;
+
+        if field.kind == DynamicFieldType::DynamicField {
+            return Ok(Some(DynamicFieldValue::MoveValue(MoveValue::new(
+                field.value_layout.into(),
+                field.value_bytes.to_owned(),
+            ))));
+        }
+
+        let address: NativeSuiAddress =
+            bcs::from_bytes(field.value_bytes).context("Failed to deserialize object ID")?;
+
+        let scope = self.super_.super_.scope.clone();
+        let object = if let Some(root_version) = self.root_version {
+            Object::version_bounded(ctx, scope, address.into(), root_version.into()).await?
+        } else {
+            let cp: UInt53 = scope.checkpoint_viewed_at().into();
+            Object::checkpoint_bounded(ctx, scope, address.into(), cp).await?
+        };
+
+        Ok(object.map(DynamicFieldValue::Object))
+    }
+}
+
+impl DynamicField {
+    /// Look up the dynamic field (or dynamic object field, depending on `kind`) on `parent` with
+    /// name `name`.
+    ///
+    /// If `root_version` is provided, the latest version of the field at or before that version
+    /// is fetched, otherwise the latest version of the field as of the checkpoint being viewed is
+    /// fetched.
+    pub(crate) async fn by_name(
+        ctx: &Context<'_>,
+        scope: Scope,
+        parent: NativeSuiAddress,
+        root_version: Option<u64>,
+        kind: DynamicFieldType,
+        name: DynamicFieldName,
+    ) -> Result<Option<Self>, RpcError<object::Error>> {
+        let type_: TypeTag = name.type_.into();
+        let type_ = match kind {
+            DynamicFieldType::DynamicField => type_,
+            DynamicFieldType::DynamicObject => {
+                DynamicFieldInfo::dynamic_object_field_wrapper(type_).into()
+            }
+        };
+
+        let field_id = derive_dynamic_field_id(parent, &type_, &name.bcs.0)
+            .context("Failed to derive dynamic field ID")?;
+
+        Self::fetch(ctx, scope, field_id.into(), root_version).await
+    }
+
+    /// Paginate through the dynamic fields owned by `parent`. Fields are ordered by their IDs.
+    ///
+    /// If `root_version` is provided, it is a version of `parent`, and the fields are listed as of
+    /// the checkpoint that wrote that version, with the latest version of each field at or before
+    /// `root_version` fetched. Otherwise fields are listed, and their latest versions fetched, as
+    /// of the checkpoint being viewed.
+    pub(crate) async fn paginate(
+        ctx: &Context<'_>,
+        scope: Scope,
+        page: Page<CDynamicField>,
+        parent: NativeSuiAddress,
+        root_version: Option<u64>,
+    ) -> Result<Connection<String, DynamicField>, RpcError<object::Error>> {
+        let mut conn = Connection::new(false, false);
+
+        let pg_reader: &PgReader = ctx.data()?;
+
+        // Adding or removing a dynamic field modifies its parent, so the fields that a version of
+        // the parent has are the ones it owned as of the checkpoint that wrote that version. This
+        // is only accurate to the granularity of a checkpoint: if a later transaction in the same
+        // checkpoint modified the parent again, the listing reflects that transaction instead.
+        let checkpoint = if let Some(root_version) = root_version {
+            let pg_loader: &Arc<DataLoader<PgReader>> = ctx.data()?;
+            let Some(stored) = pg_loader
+                .load_one(VersionedObjectVersionKey(parent.into(), root_version))
+                .await
+                .context("Failed to fetch parent object version")?
+            else {
+                return Ok(conn);
+            };
+
+            stored.cp_sequence_number
+        } else {
+            scope.checkpoint_viewed_at() as i64
+        };
+
+        let mut query = query!(
+            r#"
+            SELECT
+                o.object_id
+            FROM
+                obj_info o
+            WHERE
+                o.owner_kind = {SmallInt}
+            AND o.owner_id = {Bytea}
+            AND o.package = {Bytea}
+            AND o.module = {Text}
+            AND o.name = {Text}
+            AND o.cp_sequence_number <= {BigInt}
+            AND NOT EXISTS (
+                SELECT
+                    1
+                FROM
+                    obj_info n
+                WHERE
+                    n.object_id = o.object_id
+                AND o.cp_sequence_number < n.cp_sequence_number
+                AND n.cp_sequence_number <= {BigInt}
+            )
+            "#,
+            StoredOwnerKind::Object,
+            parent.to_vec(),
+            SUI_FRAMEWORK_ADDRESS.to_vec(),
+            DYNAMIC_FIELD_MODULE_NAME.to_string(),
+            DYNAMIC_FIELD_FIELD_STRUCT_NAME.to_string(),
+            checkpoint,
+            checkpoint,
+        );
+
+        if let Some(after) = page.after() {
+            query += query!(" AND {Bytea} <= o.object_id", (**after).clone());
+        }
+
+        if let Some(before) = page.before() {
+            query += query!(" AND o.object_id <= {Bytea}", (**before).clone());
+        }
+
+        query += query!(
+            " ORDER BY {} LIMIT {BigInt}",
+            if page.is_from_front() {
+                query!("o.object_id")
+            } else {
+                query!("o.object_id DESC")
+            },
+            page.limit() as i64 + 2,
+        );
+
+        let mut c = pg_reader
+            .connect()
+            .await
+            .context("Failed to connect to database")?;
+
+        let mut results: Vec<StoredDynamicField> = c
+            .results(query)
+            .await
+            .context("Failed to read from database")?;
+
+        if !page.is_from_front() {
+            results.reverse();
+        }
+
+        let (prev, next, results) =
+            page.paginate_results(results, |f| BcsCursor::new(f.object_id.clone()));
+
+        conn.has_previous_page = prev;
+        conn.has_next_page = next;
+
+        let (cursors, stored): (Vec<_>, Vec<_>) = results.unzip();
+        let fields = try_join_all(stored.into_iter().map(|s| {
+            let scope = scope.clone();
+            async move {
+                let address = NativeSuiAddress::from_bytes(&s.object_id)
+                    .context("Failed to deserialize SuiAddress")?;
+                Self::fetch(ctx, scope, address.into(), root_version).await
+            }
+        }))
+        .await?;
+
+        for (cursor, field) in cursors.into_iter().zip(fields) {
+            if let Some(field) = field {
+                conn.edges.push(Edge::new(cursor.encode_cursor(), field));
+            }
+        }
+
+        Ok(conn)
+    }
+
+    /// Try to downcast an `Object` to a `DynamicField`. This function returns `None` if
+    /// `object`'s contents cannot be fetched, or it is not a dynamic field.
+    pub(crate) async fn from_object(
+        object: &Object,
+        ctx: &Context<'_>,
+        root_version: Option<u64>,
+    ) -> Result<Option<Self>, RpcError<object::Error>> {
+        let super_ = object.inflated(ctx).await?;
+
+        let Some(super_contents) = &super_.contents else {
+            return Ok(None);
+        };
+
+        let Some(move_object) = super_contents.data.try_as_move() else {
+            return Ok(None);
+        };
+
+        if !move_object.type_().is_dynamic_field() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            super_,
+            root_version,
+        }))
+    }
+
+    /// Fetch the dynamic field object with ID `address`, bounded by `root_version` if it is
+    /// provided, or by the checkpoint being viewed otherwise.
+    async fn fetch(
+        ctx: &Context<'_>,
+        scope: Scope,
+        address: SuiAddress,
+        root_version: Option<u64>,
+    ) -> Result<Option<Self>, RpcError<object::Error>> {
+        let object = if let Some(root_version) = root_version {
+            Object::version_bounded(ctx, scope, address, root_version.into()).await?
+        } else {
+            let cp: UInt53 = scope.checkpoint_viewed_at().into();
+            Object::checkpoint_bounded(ctx, scope, address, cp).await?
+        };
+
+        let Some(object) = object else {
+            return Ok(None);
+        };
+
+        Self::from_object(&object, ctx, root_version).await
+    }
+
+    /// The dynamic field's contents, as BCS bytes, paired with its layout.
+    async fn contents(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<(Vec<u8>, A::MoveTypeLayout)>, RpcError<object::Error>> {
+        let Some(contents) = &self.super_.contents else {
+            return Ok(None);
+        };
+
+        let Some(move_object) = contents.data.try_as_move() else {
+            return Ok(None);
+        };
+
+        let resolver: &PackageResolver = ctx.data()?;
+        let type_: TypeTag = move_object.type_().clone().into();
+        let layout = resolver.type_layout(type_.clone()).await.with_context(|| {
+            format!(
+                "Failed to resolve layout for type {}",
+                type_.to_canonical_display(/* with_prefix */ true)
+            )
+        })?;
+
+        Ok(Some((move_object.contents().to_owned(), layout)))
+    }
+}
@@ -0,0 +1,456 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::Range;
+
+use anyhow::Context as _;
+use async_graphql::{
+    connection::{Connection, CursorType, Edge},
+    Context, InputObject, Object,
+};
+use diesel::{
+    sql_types::{BigInt, Bytea},
+    QueryableByName,
+};
+use futures::future::{try_join, try_join_all};
+use serde::{Deserialize, Serialize};
+use sui_indexer_alt_reader::{kv_loader::KvLoader, pg_reader::PgReader};
+use sui_sql_macro::query;
+use sui_types::{
+    base_types::SuiAddress as NativeSuiAddress, digests::TransactionDigest,
+    event::Event as NativeEvent,
+};
+
+use crate::{
+    api::scalars::{
+        base64::Base64,
+        cursor::BcsCursor,
+        date_time::DateTime,
+        sui_address::SuiAddress,
+        type_filter::{ModuleFilter, TypeFilter},
+        uint53::UInt53,
+    },
+    error::{bad_user_input, RpcError},
+    pagination::Page,
+    scope::Scope,
+};
+
+use super::{address::Address, transaction::Transaction};
+
+#[derive(Clone)]
+pub(crate) struct Event {
+    pub(crate) scope: Scope,
+    pub(crate) native: NativeEvent,
+
+    /// Digest of the transaction that emitted this event.
+    pub(crate) transaction_digest: TransactionDigest,
+
+    /// Position of this event in its transaction's list of events.
+    pub(crate) sequence_number: u64,
+
+    /// Timestamp of the checkpoint that included the transaction that emitted this event, or
+    /// `None` if the transaction has not been included in a checkpoint yet.
+    pub(crate) timestamp_ms: Option<u64>,
+}
+
+/// Filter for paginating events.
+///
+/// At most one of `module` and `type` can be specified. Checkpoint bounds combine, so that an event must satisfy all the bounds provided to be included.
+#[derive(InputObject, Default, Debug)]
+pub(crate) struct EventFilter {
+    /// Filter to events from transactions that were finalized strictly after this checkpoint.
+    pub(crate) after_checkpoint: Option<UInt53>,
+
+    /// Filter to events from transactions that were finalized in this checkpoint.
+    pub(crate) at_checkpoint: Option<UInt53>,
+
+    /// Filter to events from transactions that were finalized strictly before this checkpoint.
+    pub(crate) before_checkpoint: Option<UInt53>,
+
+    /// Filter to events emitted by this module, given as either `package` (all the modules in a package), or `package::module`. An event is emitted by a module if a function in that module is called by a programmable transaction command that emits the event.
+    pub(crate) module: Option<ModuleFilter>,
+
+    /// Filter to events from transactions sent by this address.
+    pub(crate) sender: Option<SuiAddress>,
+
+    /// Filter to events by their type. The type can be given as a `package`, a `package::module`, or a fully-qualified type name. A generic type name without type parameters (e.g. `0x2::coin::CoinEvent`) matches all its instantiations, while a type name with type parameters (e.g. `0x2::coin::CoinEvent<0x2::sui::SUI>`) matches only that instantiation.
+    #[graphql(name = "type")]
+    pub(crate) type_: Option<TypeFilter>,
+}
+
+/// Cursor for iterating over events. Points at a particular event, by the sequence number of the transaction that emitted it, and its position in that transaction's events.
+pub(crate) type CEvent = BcsCursor<EventCursor>;
+
+/// Inner struct for the cursor produced while iterating over events.
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub(crate) struct EventCursor {
+    pub tx_sequence_number: u64,
+    pub ev_sequence_number: u64,
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub(crate) enum Error {
+    #[error("Filtering events by both emitting module and event type is not supported")]
+    ModuleAndType,
+}
+
+/// A transaction that emitted events matching a filter, alongside its digest.
+#[derive(QueryableByName)]
+struct TxDigestRow {
+    #[diesel(sql_type = BigInt)]
+    tx_sequence_number: i64,
+
+    #[diesel(sql_type = Bytea)]
+    tx_digest: Vec<u8>,
+}
+
+/// An event emitted by a transaction. Events are emitted by Move code, and are not stored on-chain, but are part of the transaction's effects.
+#[Object]
+impl Event {
+    /// The Base64-encoded BCS serialization of this event, as an `Event`.
+    async fn event_bcs(&self) -> Result<Option<Base64>, RpcError> {
+        let bytes = bcs::to_bytes(&self.native).context("Failed to serialize event")?;
+        Ok(Some(Base64(bytes)))
+    }
+
+    /// The fully-qualified type of this event, e.g. `0x2::coin::CoinEvent<0x2::sui::SUI>`.
+    async fn event_type(&self) -> String {
+        self.native
+            .type_
+            .to_canonical_string(/* with_prefix */ true)
+    }
+
+    /// The address of the account that sent the transaction that emitted this event.
+    async fn sender(&self) -> Option<Address> {
+        let sender = self.native.sender;
+        (sender != NativeSuiAddress::ZERO)
+            .then(|| Address::with_address(self.scope.clone(), sender))
+    }
+
+    /// The position of this event in the list of events emitted by its transaction.
+    async fn sequence_number(&self) -> UInt53 {
+        self.sequence_number.into()
+    }
+
+    /// The timestamp of the checkpoint that included the transaction that emitted this event.
+    async fn timestamp(&self) -> Result<Option<DateTime>, RpcError> {
+        let Some(timestamp_ms) = self.timestamp_ms else {
+            return Ok(None);
+        };
+
+        Ok(Some(DateTime::from_ms(timestamp_ms as i64)?))
+    }
+
+    /// The transaction that emitted this event.
+    async fn transaction(&self) -> Option<Transaction> {
+        Some(Transaction::with_id(
+            self.scope.clone(),
+            self.transaction_digest,
+        ))
+    }
+
+    /// The module containing the function that was called by the programmable transaction command that emitted this event, as `package::module`.
+    async fn transaction_module(&self) -> String {
+        format!(
+            "{}::{}",
+            SuiAddress::from(self.native.package_id),
+            self.native.transaction_module,
+        )
+    }
+}
+
+impl Event {
+    /// Paginate through events emitted by transactions, subject to the `filter`.
+    ///
+    /// Events are indexed per-transaction, so this first finds the page of transactions that emitted a matching event, and then loads their contents to find the individual events.
+    pub(crate) async fn paginate(
+        ctx: &Context<'_>,
+        scope: Scope,
+        page: Page<CEvent>,
+        filter: EventFilter,
+    ) -> Result<Connection<String, Event>, RpcError<Error>> {
+        let mut conn = Connection::new(false, false);
+
+        if filter.module.is_some() && filter.type_.is_some() {
+            return Err(bad_user_input(Error::ModuleAndType));
+        }
+
+        let Some(Range {
+            start: tx_lo,
+            end: tx_hi,
+        }) = filter.tx_bounds(ctx, &scope).await?
+        else {
+            return Ok(conn);
+        };
+
+        let pg_reader: &PgReader = ctx.data()?;
+        let kv_loader: &KvLoader = ctx.data()?;
+
+        let mut conditions = query!("");
+        if let Some(after) = page.after() {
+            conditions += query!(
+                " AND {BigInt} <= tx_sequence_number",
+                after.tx_sequence_number as i64,
+            );
+        }
+
+        if let Some(before) = page.before() {
+            conditions += query!(
+                " AND tx_sequence_number <= {BigInt}",
+                before.tx_sequence_number as i64,
+            );
+        }
+
+        if let Some(sender) = filter.sender {
+            conditions += query!(
+                " AND sender = {Bytea}",
+                NativeSuiAddress::from(sender).to_vec(),
+            );
+        }
+
+        // Every event has an entry in `ev_emit_mod`, so it is also used when there is no filter
+        // on type.
+        let table = if let Some(type_) = &filter.type_ {
+            let (package, module, name, type_params): (NativeSuiAddress, _, _, _) = match type_ {
+                TypeFilter::Module(m) => (m.package().into(), m.module(), None, None),
+                TypeFilter::Type(tag) => (
+                    tag.address.into(),
+                    Some(tag.module.as_str()),
+                    Some(tag.name.as_str()),
+                    Some(&tag.type_params).filter(|ps| !ps.is_empty()),
+                ),
+            };
+
+            conditions += query!(" AND package = {Bytea}", package.to_vec());
+
+            if let Some(module) = module {
+                conditions += query!(" AND module = {Text}", module.to_owned());
+            }
+
+            if let Some(name) = name {
+                conditions += query!(" AND name = {Text}", name.to_owned());
+            }
+
+            if let Some(type_params) = type_params {
+                let instantiation =
+                    bcs::to_bytes(type_params).context("Failed to serialize type parameters")?;
+                conditions += query!(" AND instantiation = {Bytea}", instantiation);
+            }
+
+            query!("ev_struct_inst")
+        } else {
+            if let Some(module) = &filter.module {
+                conditions += query!(
+                    " AND package = {Bytea}",
+                    NativeSuiAddress::from(module.package()).to_vec(),
+                );
+
+                if let Some(module) = module.module() {
+                    conditions += query!(" AND module = {Text}", module.to_owned());
+                }
+            }
+
+            query!("ev_emit_mod")
+        };
+
+        let query = query!(
+            r#"
+            SELECT
+                e.tx_sequence_number,
+                d.tx_digest
+            FROM (
+                SELECT DISTINCT
+                    tx_sequence_number
+                FROM
+                    {}
+                WHERE
+                    {BigInt} <= tx_sequence_number
+                AND tx_sequence_number < {BigInt}
+                {}
+                ORDER BY {}
+                LIMIT {BigInt}
+            ) e
+            INNER JOIN
+                tx_digests d
+            ON (e.tx_sequence_number = d.tx_sequence_number)
+            "#,
+            table,
+            tx_lo as i64,
+            tx_hi as i64,
+            conditions,
+            if page.is_from_front() {
+                query!("tx_sequence_number")
+            } else {
+                query!("tx_sequence_number DESC")
+            },
+            page.limit() as i64 + 2,
+        );
+
+        let mut c = pg_reader
+            .connect()
+            .await
+            .context("Failed to connect to database")?;
+
+        let mut rows: Vec<TxDigestRow> = c
+            .results(query)
+            .await
+            .context("Failed to read from database")?;
+
+        // The join does not preserve the order of the inner query, so restore it, going forwards.
+        rows.sort_by_key(|r| r.tx_sequence_number);
+
+        let transactions = try_join_all(rows.iter().map(|r| async {
+            let digest = TransactionDigest::try_from(r.tx_digest.clone())
+                .context("Failed to deserialize transaction digest")?;
+
+            let contents = kv_loader
+                .load_one_transaction(digest)
+                .await
+                .context("Failed to fetch transaction contents")?;
+
+            Ok::<_, anyhow::Error>((digest, contents))
+        }))
+        .await?;
+
+        // Expand transactions into the events that match the filter, including the events that
+        // the page's cursors point to, so that the page's bounds can be checked.
+        let mut results = vec![];
+        for (row, (digest, contents)) in rows.iter().zip(transactions) {
+            let Some(contents) = contents else {
+                continue;
+            };
+
+            let tx_sequence_number = row.tx_sequence_number as u64;
+            for (ev_sequence_number, native) in contents.events()?.into_iter().enumerate() {
+                let cursor = EventCursor {
+                    tx_sequence_number,
+                    ev_sequence_number: ev_sequence_number as u64,
+                };
+
+                if page.after().is_some_and(|a| cursor < **a)
+                    || page.before().is_some_and(|b| **b < cursor)
+                    || !filter.matches(&native)
+                {
+                    continue;
+                }
+
+                let event = Event {
+                    scope: scope.clone(),
+                    native,
+                    transaction_digest: digest,
+                    sequence_number: cursor.ev_sequence_number,
+                    timestamp_ms: contents.timestamp_ms(),
+                };
+
+                results.push((cursor, event));
+            }
+        }
+
+        let (prev, next, results) = page.paginate_results(results, |(c, _)| BcsCursor::new(*c));
+
+        conn.has_previous_page = prev;
+        conn.has_next_page = next;
+
+        for (cursor, (_, event)) in results {
+            conn.edges.push(Edge::new(cursor.encode_cursor(), event));
+        }
+
+        Ok(conn)
+    }
+}
+
+impl EventFilter {
+    /// Whether `event` satisfies the non-checkpoint parts of this filter. The database only
+    /// identifies the transactions that emitted matching events, so this is used to find the
+    /// matching events within those transactions.
+    fn matches(&self, event: &NativeEvent) -> bool {
+        if self
+            .sender
+            .is_some_and(|s| NativeSuiAddress::from(s) != event.sender)
+        {
+            return false;
+        }
+
+        if let Some(module) = &self.module {
+            if NativeSuiAddress::from(module.package()) != NativeSuiAddress::from(event.package_id)
+                || module
+                    .module()
+                    .is_some_and(|m| m != event.transaction_module.as_str())
+            {
+                return false;
+            }
+        }
+
+        let type_ = &event.type_;
+        match &self.type_ {
+            None => true,
+
+            Some(TypeFilter::Module(module)) => {
+                NativeSuiAddress::from(module.package()) == NativeSuiAddress::from(type_.address)
+                    && module.module().is_none_or(|m| m == type_.module.as_str())
+            }
+
+            Some(TypeFilter::Type(tag)) => {
+                tag.address == type_.address
+                    && tag.module == type_.module
+                    && tag.name == type_.name
+                    && (tag.type_params.is_empty() || tag.type_params == type_.type_params)
+            }
+        }
+    }
+
+    /// Translate this filter's checkpoint bounds (and the checkpoint being viewed) into a range of
+    /// transaction sequence numbers. Returns `None` if the range is empty.
+    async fn tx_bounds(
+        &self,
+        ctx: &Context<'_>,
+        scope: &Scope,
+    ) -> Result<Option<Range<u64>>, RpcError<Error>> {
+        let cp_lo = [
+            self.after_checkpoint
+                .map(|c| u64::from(c).saturating_add(1)),
+            self.at_checkpoint.map(u64::from),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(0);
+
+        if self.before_checkpoint.is_some_and(|c| u64::from(c) == 0) {
+            return Ok(None);
+        }
+
+        let cp_hi = [
+            Some(scope.checkpoint_viewed_at()),
+            self.at_checkpoint.map(u64::from),
+            self.before_checkpoint.map(|c| u64::from(c) - 1),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(0);
+
+        if cp_hi < cp_lo {
+            return Ok(None);
+        }
+
+        let kv_loader: &KvLoader = ctx.data()?;
+        let (lo, hi) = try_join(
+            kv_loader.load_one_checkpoint(cp_lo),
+            kv_loader.load_one_checkpoint(cp_hi),
+        )
+        .await
+        .context("Failed to fetch checkpoint bounds")?;
+
+        // If the lower bound has been pruned, so have the events before it.
+        let tx_lo = lo.map_or(0, |(summary, contents, _)| {
+            summary.network_total_transactions - contents.size() as u64
+        });
+
+        let Some((summary, _, _)) = hi else {
+            return Ok(None);
+        };
+
+        Ok(Some(tx_lo..summary.network_total_transactions))
+    }
+}
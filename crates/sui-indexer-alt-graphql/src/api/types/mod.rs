@@ -2,10 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub(crate) mod address;
+pub(crate) mod balance;
 pub(crate) mod checkpoint;
+pub(crate) mod coin;
+pub(crate) mod display;
+pub(crate) mod dynamic_field;
 pub(crate) mod epoch;
+pub(crate) mod event;
 pub(crate) mod gas_input;
 pub(crate) mod move_package;
+pub(crate) mod move_type;
+pub(crate) mod move_value;
 pub(crate) mod object;
 pub(crate) mod object_change;
 pub(crate) mod protocol_configs;
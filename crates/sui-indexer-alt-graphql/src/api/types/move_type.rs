@@ -0,0 +1,47 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Context as _;
+use async_graphql::{Context, Object};
+use move_core_types::{annotated_value as A, language_storage::TypeTag};
+use sui_indexer_alt_reader::package_resolver::PackageResolver;
+
+use crate::error::RpcError;
+
+#[derive(Clone)]
+pub(crate) struct MoveType {
+    pub(crate) native: TypeTag,
+}
+
+/// Represents instances of concrete types (no type parameters, no references).
+#[Object]
+impl MoveType {
+    /// Flat representation of the type signature, as a displayable string.
+    async fn repr(&self) -> String {
+        self.native.to_canonical_string(/* with_prefix */ true)
+    }
+}
+
+impl MoveType {
+    /// Resolve the layout of this type, using the package resolver to look up the definitions of
+    /// any datatypes it references.
+    pub(crate) async fn layout(&self, ctx: &Context<'_>) -> Result<A::MoveTypeLayout, RpcError> {
+        let resolver: &PackageResolver = ctx.data()?;
+
+        Ok(resolver
+            .type_layout(self.native.clone())
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to resolve layout for type {}",
+                    self.native.to_canonical_display(/* with_prefix */ true)
+                )
+            })?)
+    }
+}
+
+impl From<TypeTag> for MoveType {
+    fn from(native: TypeTag) -> Self {
+        Self { native }
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, Context as _};
+use async_graphql::{Context, Name, Object, Value};
+use move_core_types::{
+    account_address::AccountAddress,
+    annotated_value as A, ident_str,
+    identifier::{IdentStr, Identifier},
+    language_storage::{StructTag, TypeTag},
+};
+use sui_types::object::bounded_visitor::BoundedVisitor;
+
+use crate::{
+    api::scalars::{base64::Base64, json::Json},
+    error::RpcError,
+};
+
+use super::move_type::MoveType;
+
+const STD: AccountAddress = AccountAddress::ONE;
+const SUI: AccountAddress = AccountAddress::TWO;
+
+const MOD_ASCII: &IdentStr = ident_str!("ascii");
+const MOD_OBJECT: &IdentStr = ident_str!("object");
+const MOD_OPTION: &IdentStr = ident_str!("option");
+const MOD_STRING: &IdentStr = ident_str!("string");
+
+const TYP_ID: &IdentStr = ident_str!("ID");
+const TYP_OPTION: &IdentStr = ident_str!("Option");
+const TYP_STRING: &IdentStr = ident_str!("String");
+const TYP_UID: &IdentStr = ident_str!("UID");
+
+#[derive(Clone)]
+pub(crate) struct MoveValue {
+    pub(crate) type_: MoveType,
+    pub(crate) native: Vec<u8>,
+}
+
+/// A Move value, paired with its type.
+#[Object]
+impl MoveValue {
+    /// The value's Move type.
+    #[graphql(name = "type")]
+    async fn type_(&self) -> &MoveType {
+        &self.type_
+    }
+
+    /// The BCS representation of this value, Base64-encoded.
+    async fn bcs(&self) -> Base64 {
+        Base64::from(self.native.clone())
+    }
+
+    /// Representation of a Move value in JSON, where:
+    ///
+    /// - Addresses, IDs, and UIDs are represented in canonical form, as JSON strings.
+    /// - Bools are represented by JSON boolean literals.
+    /// - u8, u16, and u32 are represented as JSON numbers.
+    /// - u64, u128, and u256 are represented as JSON strings.
+    /// - Vectors are represented by JSON arrays.
+    /// - Structs are represented by JSON objects.
+    /// - Enums are represented by JSON objects, with a single field, named after the variant, containing the variant's fields.
+    /// - Empty optional values are represented by `null`.
+    async fn json(&self, ctx: &Context<'_>) -> Result<Json, RpcError> {
+        let layout = self.type_.layout(ctx).await?;
+
+        let value =
+            BoundedVisitor::deserialize_value(&self.native, &layout).with_context(|| {
+                format!(
+                    "Failed to deserialize Move value for type {}",
+                    self.type_
+                        .native
+                        .to_canonical_display(/* with_prefix */ true)
+                )
+            })?;
+
+        Ok(to_json(value)?.into())
+    }
+}
+
+impl MoveValue {
+    pub(crate) fn new(type_: TypeTag, native: Vec<u8>) -> Self {
+        Self {
+            type_: MoveType::from(type_),
+            native,
+        }
+    }
+}
+
+/// Convert an annotated Move value into its JSON representation (see `MoveValue.json`).
+fn to_json(value: A::MoveValue) -> anyhow::Result<Value> {
+    use A::MoveValue as V;
+
+    Ok(match value {
+        V::U8(n) => Value::Number(n.into()),
+        V::U16(n) => Value::Number(n.into()),
+        V::U32(n) => Value::Number(n.into()),
+        V::U64(n) => Value::String(n.to_string()),
+        V::U128(n) => Value::String(n.to_string()),
+        V::U256(n) => Value::String(n.to_string()),
+
+        V::Bool(b) => Value::Boolean(b),
+        V::Address(a) => Value::String(a.to_canonical_string(/* with_prefix */ true)),
+
+        V::Vector(xs) => Value::List(xs.into_iter().map(to_json).collect::<Result<_, _>>()?),
+
+        V::Struct(A::MoveStruct { type_, fields }) => {
+            if is_type(&type_, &STD, MOD_OPTION, TYP_OPTION) {
+                // 0x1::option::Option
+                let V::Vector(mut elements) = extract_field(&type_, fields, "vec")? else {
+                    bail!("Expected Option.vec to be a vector");
+                };
+
+                if elements.len() > 1 {
+                    bail!("Expected Option.vec to contain at most one element");
+                }
+
+                match elements.pop() {
+                    Some(value) => to_json(value)?,
+                    None => Value::Null,
+                }
+            } else if is_type(&type_, &STD, MOD_ASCII, TYP_STRING)
+                || is_type(&type_, &STD, MOD_STRING, TYP_STRING)
+            {
+                // 0x1::ascii::String, 0x1::string::String
+                let bytes = extract_bytes(extract_field(&type_, fields, "bytes")?)?;
+                Value::String(String::from_utf8(bytes).context("Invalid UTF-8 in string")?)
+            } else if is_type(&type_, &SUI, MOD_OBJECT, TYP_UID) {
+                // 0x2::object::UID
+                let V::Struct(A::MoveStruct { type_, fields }) =
+                    extract_field(&type_, fields, "id")?
+                else {
+                    bail!("Expected UID.id to be a struct");
+                };
+
+                Value::String(extract_id(&type_, fields)?)
+            } else if is_type(&type_, &SUI, MOD_OBJECT, TYP_ID) {
+                // 0x2::object::ID
+                Value::String(extract_id(&type_, fields)?)
+            } else {
+                // Arbitrary structs
+                to_json_object(fields)?
+            }
+        }
+
+        V::Variant(A::MoveVariant {
+            variant_name,
+            fields,
+            ..
+        }) => Value::Object(
+            [(Name::new(variant_name.as_str()), to_json_object(fields)?)]
+                .into_iter()
+                .collect(),
+        ),
+
+        // Sui does not support `signer` as a type.
+        V::Signer(_) => bail!("Unexpected value of type: signer"),
+    })
+}
+
+/// Convert the fields of a struct or enum variant into a JSON object, keyed by field name.
+fn to_json_object(fields: Vec<(Identifier, A::MoveValue)>) -> anyhow::Result<Value> {
+    Ok(Value::Object(
+        fields
+            .into_iter()
+            .map(|(name, value)| Ok((Name::new(name.as_str()), to_json(value)?)))
+            .collect::<anyhow::Result<_>>()?,
+    ))
+}
+
+fn is_type(tag: &StructTag, address: &AccountAddress, module: &IdentStr, name: &IdentStr) -> bool {
+    &tag.address == address
+        && tag.module.as_ident_str() == module
+        && tag.name.as_ident_str() == name
+}
+
+/// Find the field called `name` among the `fields` of a value of type `type_`.
+fn extract_field(
+    type_: &StructTag,
+    fields: Vec<(Identifier, A::MoveValue)>,
+    name: &str,
+) -> anyhow::Result<A::MoveValue> {
+    fields
+        .into_iter()
+        .find_map(|(n, value)| (n.as_str() == name).then_some(value))
+        .ok_or_else(|| anyhow!("Couldn't find expected field '{name}' of {type_}"))
+}
+
+/// Extracts a vector of bytes from `value`, assuming it's a `MoveValue::Vector` where all the
+/// values are `MoveValue::U8`s.
+fn extract_bytes(value: A::MoveValue) -> anyhow::Result<Vec<u8>> {
+    let A::MoveValue::Vector(elements) = value else {
+        bail!("Expected a vector");
+    };
+
+    elements
+        .into_iter()
+        .map(|element| match element {
+            A::MoveValue::U8(byte) => Ok(byte),
+            _ => Err(anyhow!("Expected a byte")),
+        })
+        .collect()
+}
+
+/// Extracts the canonical representation of an address from the contents of a Move struct
+/// matching `0x2::object::ID`, i.e. `{ bytes: address }`.
+fn extract_id(
+    type_: &StructTag,
+    fields: Vec<(Identifier, A::MoveValue)>,
+) -> anyhow::Result<String> {
+    let A::MoveValue::Address(addr) = extract_field(type_, fields, "bytes")? else {
+        bail!("Expected ID.bytes to have type address");
+    };
+
+    Ok(addr.to_canonical_string(/* with_prefix */ true))
+}
@@ -23,6 +23,7 @@ use sui_indexer_alt_schema::{objects::StoredObjVersion, schema::obj_versions};
 use sui_types::{
     base_types::{SequenceNumber, SuiAddress as NativeSuiAddress},
     digests::ObjectDigest,
+    dynamic_field::DynamicFieldType,
     object::Object as NativeObject,
 };
 use tokio::join;
@@ -37,6 +38,9 @@ use crate::{
 
 use super::{
     address::{Address, AddressableImpl},
+    coin::Coin,
+    display::DisplayEntry,
+    dynamic_field::{CDynamicField, DynamicField, DynamicFieldName},
     move_package::MovePackage,
     transaction::Transaction,
 };
@@ -96,6 +100,8 @@ use super::{
     )
 )]
 pub(crate) enum IObject {
+    Coin(Coin),
+    DynamicField(DynamicField),
     MovePackage(MovePackage),
     Object(Object),
 }
@@ -153,6 +159,9 @@ pub(crate) enum Error {
 
     #[error("Checkpoint {0} in the future")]
     Future(u64),
+
+    #[error("Display output exceeds the limit of {0} bytes")]
+    DisplayTooLarge(usize),
 }
 
 pub(crate) type CVersion = JsonCursor<u64>;
@@ -177,6 +186,11 @@ impl Object {
         ObjectImpl::from(self).digest()
     }
 
+    /// Attempts to convert the object into a Coin.
+    async fn as_coin(&self, ctx: &Context<'_>) -> Result<Option<Coin>, RpcError<Error>> {
+        Coin::from_object(self, ctx).await
+    }
+
     /// Attempts to convert the object into a MovePackage.
     async fn as_move_package(
         &self,
@@ -185,6 +199,81 @@ impl Object {
         MovePackage::from_object(self, ctx).await
     }
 
+    /// The rendered output of the `Display` registered for this object's type, as a list of key-value pairs.
+    ///
+    /// The `Display` is read as of the checkpoint being viewed. Returns `null` if the object is not a Move object, or if no `Display` had been registered for its type by then. Fields whose templates are malformed or that fail to render are returned with an `error` in place of their `value`, and the request fails if the rendered output exceeds `ServiceConfig.maxDisplayOutputSize`.
+    async fn display(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<Vec<DisplayEntry>>, RpcError<Error>> {
+        DisplayEntry::render(ctx, self).await
+    }
+
+    /// Access a dynamic field on this object using its type and BCS-encoded name.
+    ///
+    /// The field is fetched as of this object's version, so its contents reflect the state of the field when this version of the object was current. Returns `null` if a dynamic field with that name could not be found attached to this object.
+    async fn dynamic_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<Error>> {
+        DynamicField::by_name(
+            ctx,
+            self.super_.scope.clone(),
+            self.super_.address,
+            Some(self.version.into()),
+            DynamicFieldType::DynamicField,
+            name,
+        )
+        .await
+    }
+
+    /// Dynamic fields and dynamic object fields owned by this object, ordered by their IDs.
+    ///
+    /// The set of fields and their contents are both as of this object's version: the fields are the ones the object owned when this version was written, and each field's contents are from the latest version of the field at or before this object's version.
+    async fn dynamic_fields(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CDynamicField>,
+        last: Option<u64>,
+        before: Option<CDynamicField>,
+    ) -> Result<Option<Connection<String, DynamicField>>, RpcError<Error>> {
+        let pagination: &PaginationConfig = ctx.data()?;
+        let limits = pagination.limits("Object", "dynamicFields");
+        let page = Page::from_params(limits, first, after, last, before)?;
+
+        Ok(Some(
+            DynamicField::paginate(
+                ctx,
+                self.super_.scope.clone(),
+                page,
+                self.super_.address,
+                Some(self.version.into()),
+            )
+            .await?,
+        ))
+    }
+
+    /// Access a dynamic object field on this object using its type and BCS-encoded name.
+    ///
+    /// The field is fetched as of this object's version, so its contents reflect the state of the field when this version of the object was current. Returns `null` if a dynamic object field with that name could not be found attached to this object.
+    async fn dynamic_object_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, RpcError<Error>> {
+        DynamicField::by_name(
+            ctx,
+            self.super_.scope.clone(),
+            self.super_.address,
+            Some(self.version.into()),
+            DynamicFieldType::DynamicObject,
+            name,
+        )
+        .await
+    }
+
     /// Fetch the object with the same ID, at a different version, root version bound, or checkpoint.
     ///
     /// If no additional bound is provided, the latest version of this object is fetched at the latest checkpoint.
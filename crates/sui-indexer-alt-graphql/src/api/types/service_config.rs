@@ -171,4 +171,16 @@ impl ServiceConfig {
         let limits: &Limits = ctx.data()?;
         Ok(Some(limits.max_move_value_depth))
     }
+
+    /// Maximum depth of nested field access supported in Display format strings.
+    async fn max_display_field_depth(&self, ctx: &Context<'_>) -> Result<Option<usize>, RpcError> {
+        let limits: &Limits = ctx.data()?;
+        Ok(Some(limits.max_display_field_depth))
+    }
+
+    /// Maximum number of bytes occupied by Display field names and values in the output.
+    async fn max_display_output_size(&self, ctx: &Context<'_>) -> Result<Option<usize>, RpcError> {
+        let limits: &Limits = ctx.data()?;
+        Ok(Some(limits.max_display_output_size))
+    }
 }
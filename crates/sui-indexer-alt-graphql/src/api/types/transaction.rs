@@ -4,7 +4,10 @@
 use std::sync::Arc;
 
 use anyhow::Context as _;
-use async_graphql::{Context, Object};
+use async_graphql::{
+    connection::{Connection, CursorType, Edge},
+    Context, Object,
+};
 use fastcrypto::encoding::{Base58, Encoding};
 use sui_indexer_alt_reader::kv_loader::{
     KvLoader, TransactionContents as NativeTransactionContents,
@@ -17,15 +20,17 @@ use sui_types::{
 
 use crate::{
     api::{
-        scalars::{base64::Base64, digest::Digest},
+        scalars::{base64::Base64, cursor::JsonCursor, digest::Digest},
         types::epoch::Epoch,
     },
     error::RpcError,
+    pagination::{Page, PaginationConfig},
     scope::Scope,
 };
 
 use super::{
     address::Address,
+    event::Event,
     gas_input::GasInput,
     transaction_effects::{EffectsContents, TransactionEffects},
 };
@@ -42,6 +47,8 @@ pub(crate) struct TransactionContents {
     pub(crate) contents: Option<Arc<NativeTransactionContents>>,
 }
 
+type CEvent = JsonCursor<usize>;
+
 /// Description of a transaction, the unit of activity on Sui.
 #[Object]
 impl Transaction {
@@ -63,6 +70,45 @@ impl Transaction {
 
 #[Object]
 impl TransactionContents {
+    /// Events emitted by this transaction, in the order they were emitted.
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CEvent>,
+        last: Option<u64>,
+        before: Option<CEvent>,
+    ) -> Result<Option<Connection<String, Event>>, RpcError> {
+        let pagination: &PaginationConfig = ctx.data()?;
+        let limits = pagination.limits("Transaction", "events");
+        let page = Page::from_params(limits, first, after, last, before)?;
+
+        let Some(content) = &self.contents else {
+            return Ok(None);
+        };
+
+        let digest = content.digest()?;
+        let timestamp_ms = content.timestamp_ms();
+        let events = content.events()?;
+        let cursors = page.paginate_indices(events.len());
+
+        let mut conn = Connection::new(cursors.has_previous_page, cursors.has_next_page);
+        for edge in cursors.edges {
+            let event = Event {
+                scope: self.scope.clone(),
+                native: events[*edge.cursor].clone(),
+                transaction_digest: digest,
+                sequence_number: *edge.cursor as u64,
+                timestamp_ms,
+            };
+
+            conn.edges
+                .push(Edge::new(edge.cursor.encode_cursor(), event))
+        }
+
+        Ok(Some(conn))
+    }
+
     /// This field is set by senders of a transaction block. It is an epoch reference that sets a deadline after which validators will no longer consider the transaction valid. By default, there is no deadline for when a transaction must execute.
     async fn expiration(&self) -> Result<Option<Epoch>, RpcError> {
         let Some(content) = &self.contents else {
@@ -176,7 +222,10 @@ impl TransactionContents {
         };
 
         // Discard the loaded result if we are viewing it at a checkpoint before it existed.
-        if transaction.cp_sequence_number() > self.scope.checkpoint_viewed_at() {
+        if transaction
+            .cp_sequence_number()
+            .is_some_and(|cp| cp > self.scope.checkpoint_viewed_at())
+        {
             return Ok(self.clone());
         }
 
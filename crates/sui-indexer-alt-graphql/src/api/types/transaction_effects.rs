@@ -3,25 +3,35 @@
 
 use std::sync::Arc;
 
-use anyhow::Context as _;
+use anyhow::{anyhow, Context as _};
 use async_graphql::{
     connection::{Connection, Edge},
+    dataloader::DataLoader,
     Context, Object,
 };
 use fastcrypto::encoding::{Base58, Encoding};
-use sui_indexer_alt_reader::kv_loader::{
-    KvLoader, TransactionContents as NativeTransactionContents,
+use sui_indexer_alt_reader::{
+    error::Error as ReaderError,
+    fullnode_client::FullnodeClient,
+    kv_loader::{ExecutedTransaction, KvLoader, TransactionContents as NativeTransactionContents},
+    pg_reader::PgReader,
+    tx_balance_changes::TxBalanceChangeKey,
+};
+use sui_indexer_alt_schema::transactions::BalanceChange as StoredBalanceChange;
+use sui_types::{
+    crypto::ToFromBytes, digests::TransactionDigest, effects::TransactionEffectsAPI,
+    signature::GenericSignature, transaction::TransactionData,
 };
-use sui_types::{digests::TransactionDigest, effects::TransactionEffectsAPI};
 
 use crate::{
     api::scalars::{base64::Base64, cursor::JsonCursor, digest::Digest},
-    error::RpcError,
+    error::{bad_user_input, RpcError},
     pagination::{Page, PaginationConfig},
     scope::Scope,
 };
 
 use super::{
+    balance::BalanceChange,
     checkpoint::Checkpoint,
     object_change::ObjectChange,
     transaction::{Transaction, TransactionContents},
@@ -39,6 +49,19 @@ pub(crate) struct EffectsContents {
     pub(crate) contents: Option<Arc<NativeTransactionContents>>,
 }
 
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Invalid transaction bytes: {0}")]
+    BadTransaction(bcs::Error),
+
+    #[error("Invalid signature at position {0}: {1}")]
+    BadSignature(usize, String),
+
+    #[error("Transaction rejected by fullnode: {0}")]
+    Rejected(String),
+}
+
+type CBalanceChange = JsonCursor<usize>;
 type CObjectChange = JsonCursor<usize>;
 
 /// The results of executing a transaction.
@@ -64,13 +87,64 @@ impl TransactionEffects {
 
 #[Object]
 impl EffectsContents {
+    /// The effect this transaction had on the balances (sum of coin values per coin type) of addresses and objects.
+    async fn balance_changes(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<CBalanceChange>,
+        last: Option<u64>,
+        before: Option<CBalanceChange>,
+    ) -> Result<Option<Connection<CBalanceChange, BalanceChange>>, RpcError> {
+        let pagination: &PaginationConfig = ctx.data()?;
+        let limits = pagination.limits("TransactionEffects", "balanceChanges");
+        let page = Page::from_params(limits, first, after, last, before)?;
+
+        let Some(content) = &self.contents else {
+            return Ok(None);
+        };
+
+        // Transactions that were just executed or simulated have not been indexed yet, so their
+        // balance changes come from the fullnode instead.
+        let balance_changes: Vec<StoredBalanceChange> =
+            if let NativeTransactionContents::Executed(executed) = content.as_ref() {
+                executed.balance_changes.clone()
+            } else {
+                let pg_loader: &Arc<DataLoader<PgReader>> = ctx.data()?;
+                let Some(stored) = pg_loader
+                    .load_one(TxBalanceChangeKey(content.digest()?))
+                    .await
+                    .context("Failed to fetch balance changes")?
+                else {
+                    return Ok(None);
+                };
+
+                bcs::from_bytes(&stored.balance_changes)
+                    .context("Failed to deserialize balance changes")?
+            };
+
+        let cursors = page.paginate_indices(balance_changes.len());
+
+        let mut conn = Connection::new(cursors.has_previous_page, cursors.has_next_page);
+        for edge in cursors.edges {
+            let balance_change = BalanceChange {
+                scope: self.scope.clone(),
+                native: balance_changes[*edge.cursor].clone(),
+            };
+
+            conn.edges.push(Edge::new(edge.cursor, balance_change))
+        }
+
+        Ok(Some(conn))
+    }
+
     /// The checkpoint this transaction was finalized in.
     async fn checkpoint(&self) -> Option<Checkpoint> {
         let Some(content) = &self.contents else {
             return None;
         };
 
-        Checkpoint::with_sequence_number(self.scope.clone(), content.cp_sequence_number())
+        Checkpoint::with_sequence_number(self.scope.clone(), content.cp_sequence_number()?)
     }
 
     /// The Base64-encoded BCS serialization of these effects, as `TransactionEffects`.
@@ -147,6 +221,62 @@ impl TransactionEffects {
             contents,
         }))
     }
+
+    /// Submit a transaction to the fullnode for execution, and return its effects. The
+    /// transaction will not be visible to other queries until it has been indexed.
+    pub(crate) async fn execute(
+        ctx: &Context<'_>,
+        scope: Scope,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+    ) -> Result<Self, RpcError<Error>> {
+        let transaction: TransactionData =
+            bcs::from_bytes(&tx_bytes.0).map_err(|e| bad_user_input(Error::BadTransaction(e)))?;
+
+        let signatures = signatures
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                GenericSignature::from_bytes(&s.0)
+                    .map_err(|e| bad_user_input(Error::BadSignature(i, e.to_string())))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let executed = fullnode_client(ctx)?
+            .execute_transaction(transaction, signatures)
+            .await
+            .map_err(|e| fullnode_error(e, "Failed to execute transaction"))?;
+
+        Ok(Self::from_executed(scope, executed))
+    }
+
+    /// Ask the fullnode to simulate a transaction (without signatures), and return its effects.
+    pub(crate) async fn simulate(
+        ctx: &Context<'_>,
+        scope: Scope,
+        tx_bytes: Base64,
+        checks_enabled: bool,
+    ) -> Result<Self, RpcError<Error>> {
+        let transaction: TransactionData =
+            bcs::from_bytes(&tx_bytes.0).map_err(|e| bad_user_input(Error::BadTransaction(e)))?;
+
+        let executed = fullnode_client(ctx)?
+            .simulate_transaction(transaction, checks_enabled)
+            .await
+            .map_err(|e| fullnode_error(e, "Failed to simulate transaction"))?;
+
+        Ok(Self::from_executed(scope, executed))
+    }
+
+    fn from_executed(scope: Scope, executed: ExecutedTransaction) -> Self {
+        Self {
+            digest: executed.transaction.digest(),
+            contents: EffectsContents {
+                scope,
+                contents: Some(Arc::new(NativeTransactionContents::Executed(executed))),
+            },
+        }
+    }
 }
 
 impl EffectsContents {
@@ -179,7 +309,10 @@ impl EffectsContents {
         };
 
         // Discard the loaded result if we are viewing it at a checkpoint before it existed.
-        if transaction.cp_sequence_number() > self.scope.checkpoint_viewed_at() {
+        if transaction
+            .cp_sequence_number()
+            .is_some_and(|cp| cp > self.scope.checkpoint_viewed_at())
+        {
             return Ok(self.clone());
         }
 
@@ -200,3 +333,20 @@ impl From<Transaction> for TransactionEffects {
         }
     }
 }
+
+fn fullnode_client<'c>(ctx: &Context<'c>) -> Result<&'c FullnodeClient, RpcError<Error>> {
+    Ok(ctx
+        .data_opt()
+        .context("Fullnode RPC URL not configured, cannot execute or simulate transactions")?)
+}
+
+/// Requests that the fullnode rejected as invalid are the user's fault, everything else is ours.
+fn fullnode_error(err: ReaderError, context: &'static str) -> RpcError<Error> {
+    match err {
+        ReaderError::FullnodeRequest(status) if status.code() == tonic::Code::InvalidArgument => {
+            bad_user_input(Error::Rejected(status.message().to_owned()))
+        }
+
+        err => anyhow!(err).context(context).into(),
+    }
+}
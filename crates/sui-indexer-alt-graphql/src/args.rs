@@ -5,6 +5,7 @@ use std::path::PathBuf;
 
 use sui_indexer_alt_metrics::MetricsArgs;
 use sui_indexer_alt_reader::bigtable_reader::BigtableArgs;
+use sui_indexer_alt_reader::fullnode_client::FullnodeArgs;
 use sui_indexer_alt_reader::pg_reader::db::DbArgs;
 use sui_indexer_alt_reader::system_package_task::SystemPackageTaskArgs;
 use url::Url;
@@ -40,6 +41,9 @@ pub enum Command {
         #[command(flatten)]
         bigtable_args: BigtableArgs,
 
+        #[command(flatten)]
+        fullnode_args: FullnodeArgs,
+
         #[command(flatten)]
         rpc_args: RpcArgs,
 
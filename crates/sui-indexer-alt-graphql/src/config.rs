@@ -127,6 +127,12 @@ pub struct Limits {
 
     /// Maximum nesting allowed in datatype fields when calculating the layout of a single type.
     pub max_move_value_depth: usize,
+
+    /// Maximum depth of nested field access supported in Display format strings.
+    pub max_display_field_depth: usize,
+
+    /// Maximum number of bytes occupied by Display field names and values in the output.
+    pub max_display_output_size: usize,
 }
 
 #[DefaultConfig]
@@ -148,6 +154,8 @@ pub struct LimitsLayer {
     pub max_type_argument_width: Option<usize>,
     pub max_type_nodes: Option<usize>,
     pub max_move_value_depth: Option<usize>,
+    pub max_display_field_depth: Option<usize>,
+    pub max_display_output_size: Option<usize>,
 
     #[serde(flatten)]
     pub extra: toml::Table,
@@ -299,6 +307,12 @@ impl LimitsLayer {
             max_move_value_depth: self
                 .max_move_value_depth
                 .unwrap_or(base.max_move_value_depth),
+            max_display_field_depth: self
+                .max_display_field_depth
+                .unwrap_or(base.max_display_field_depth),
+            max_display_output_size: self
+                .max_display_output_size
+                .unwrap_or(base.max_display_output_size),
         }
     }
 }
@@ -343,6 +357,8 @@ impl From<Limits> for LimitsLayer {
             max_type_argument_width: Some(value.max_type_argument_width),
             max_type_nodes: Some(value.max_type_nodes),
             max_move_value_depth: Some(value.max_move_value_depth),
+            max_display_field_depth: Some(value.max_display_field_depth),
+            max_display_output_size: Some(value.max_display_output_size),
             extra: Default::default(),
         }
     }
@@ -409,6 +425,8 @@ impl Default for Limits {
             max_type_argument_width,
             max_type_nodes,
             max_move_value_depth,
+            max_display_field_depth: 10,
+            max_display_output_size: 1024 * 1024,
         }
     }
 }
@@ -3,6 +3,7 @@
 
 use std::{convert::Infallible, sync::Arc, time::Duration};
 
+use anyhow::anyhow;
 use async_graphql::{ErrorExtensionValues, ErrorExtensions, Response, Value};
 
 use crate::pagination;
@@ -121,6 +122,19 @@ pub(crate) fn bad_user_input<E: std::error::Error>(err: E) -> RpcError<E> {
     RpcError::BadUserInput(Arc::new(err))
 }
 
+/// Treat user errors from a nested request as internal errors. This is for requests whose inputs
+/// were constructed by the service rather than supplied by the user, so if they are invalid, it is
+/// the service's fault.
+pub(crate) fn user_input_as_internal<E: std::error::Error>(err: RpcError<E>) -> RpcError {
+    match err {
+        RpcError::BadUserInput(err) => RpcError::InternalError(Arc::new(anyhow!("{err}"))),
+        RpcError::Pagination(err) => RpcError::Pagination(err),
+        RpcError::GraphQlError(err) => RpcError::GraphQlError(err),
+        RpcError::InternalError(err) => RpcError::InternalError(err),
+        RpcError::RequestTimeout { kind, limit } => RpcError::RequestTimeout { kind, limit },
+    }
+}
+
 /// Signal a timeout. `kind` specifies what operation timed out and is included in the error
 /// message.
 pub(crate) fn request_timeout(kind: &'static str, limit: Duration) -> RpcError {
@@ -160,8 +174,6 @@ pub(crate) fn error_codes(response: &Response) -> Vec<&str> {
 
 #[cfg(test)]
 mod tests {
-    use anyhow::anyhow;
-
     use super::*;
 
     #[derive(thiserror::Error, Debug)]
@@ -178,6 +190,16 @@ mod tests {
         assert_eq!(ext.get("code"), Some(&code::BAD_USER_INPUT.into()));
     }
 
+    #[test]
+    fn test_user_input_as_internal() {
+        let err: async_graphql::Error = user_input_as_internal(bad_user_input(Error)).into();
+
+        assert_eq!(err.message, "Boom!");
+
+        let ext = err.extensions.as_ref().expect("No extensions");
+        assert_eq!(ext.get("code"), Some(&code::INTERNAL_SERVER_ERROR.into()));
+    }
+
     /// If the GraphQL error does not have a code, it should be set to `INTERNAL_SERVER_ERROR`.
     #[test]
     fn test_graphql_error() {
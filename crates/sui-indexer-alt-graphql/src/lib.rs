@@ -6,8 +6,8 @@ use std::{any::Any, net::SocketAddr, sync::Arc};
 use anyhow::{self, Context};
 use api::types::{address::IAddressable, object::IObject};
 use async_graphql::{
-    extensions::ExtensionFactory, http::GraphiQLSource, EmptyMutation, EmptySubscription,
-    ObjectType, Schema, SchemaBuilder, SubscriptionType,
+    extensions::ExtensionFactory, http::GraphiQLSource, EmptySubscription, ObjectType, Schema,
+    SchemaBuilder, SubscriptionType,
 };
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 use axum::{
@@ -30,6 +30,7 @@ use sui_indexer_alt_reader::pg_reader::db::DbArgs;
 use sui_indexer_alt_reader::system_package_task::{SystemPackageTask, SystemPackageTaskArgs};
 use sui_indexer_alt_reader::{
     bigtable_reader::{BigtableArgs, BigtableReader},
+    fullnode_client::{FullnodeArgs, FullnodeClient},
     kv_loader::KvLoader,
     package_resolver::{DbPackageStore, PackageCache},
     pg_reader::PgReader,
@@ -45,7 +46,7 @@ use tower_http::cors;
 use tracing::{error, info};
 use url::Url;
 
-use crate::api::query::Query;
+use crate::api::{mutation::Mutation, query::Query};
 use crate::extensions::logging::{Logging, Session};
 use crate::metrics::RpcMetrics;
 use crate::middleware::version::Version;
@@ -241,8 +242,8 @@ impl Default for RpcArgs {
 }
 
 /// The GraphQL schema this service will serve, without any extensions or context added.
-pub fn schema() -> SchemaBuilder<Query, EmptyMutation, EmptySubscription> {
-    Schema::build(Query::default(), EmptyMutation, EmptySubscription)
+pub fn schema() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
+    Schema::build(Query::default(), Mutation, EmptySubscription)
         .register_output_type::<IAddressable>()
         .register_output_type::<IObject>()
 }
@@ -257,6 +258,9 @@ pub fn schema() -> SchemaBuilder<Query, EmptyMutation, EmptySubscription> {
 /// provided, the `GOOGLE_APPLICATION_CREDENTIALS` environment variable must point to the
 /// credentials JSON file.
 ///
+/// Transaction execution and simulation requests are forwarded to the fullnode configured by
+/// `fullnode_args` -- if it does not specify a fullnode, those requests will fail.
+///
 /// `version` is the version string reported in response headers by the service as part of every
 /// request.
 ///
@@ -267,6 +271,7 @@ pub async fn start_rpc(
     bigtable_instance: Option<String>,
     db_args: DbArgs,
     bigtable_args: BigtableArgs,
+    fullnode_args: FullnodeArgs,
     args: RpcArgs,
     system_package_task_args: SystemPackageTaskArgs,
     version: &'static str,
@@ -301,6 +306,11 @@ pub async fn start_rpc(
         None
     };
 
+    let fullnode_client = fullnode_args
+        .fullnode_rpc_url
+        .map(FullnodeClient::new)
+        .transpose()?;
+
     let pg_loader = Arc::new(pg_reader.as_data_loader());
     let kv_loader = if let Some(reader) = bigtable_reader.as_ref() {
         KvLoader::new_with_bigtable(Arc::new(reader.as_data_loader()))
@@ -337,7 +347,7 @@ pub async fn start_rpc(
         cancel.child_token(),
     );
 
-    let rpc = rpc
+    let mut rpc = rpc
         .route("/graphql", post(graphql))
         .route("/graphql/health", get(health::check))
         .layer(watermark_task.watermarks())
@@ -356,6 +366,10 @@ pub async fn start_rpc(
         .data(kv_loader)
         .data(package_resolver);
 
+    if let Some(fullnode_client) = fullnode_client {
+        rpc = rpc.data(fullnode_client);
+    }
+
     let h_rpc = rpc.run().await?;
     let h_system_package_task = system_package_task.run();
     let h_watermark = watermark_task.run();
@@ -371,7 +385,7 @@ pub async fn start_rpc(
 /// Handler for RPC requests (POST requests making GraphQL queries).
 async fn graphql(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    Extension(schema): Extension<Schema<Query, EmptyMutation, EmptySubscription>>,
+    Extension(schema): Extension<Schema<Query, Mutation, EmptySubscription>>,
     Extension(watermark): Extension<WatermarksLock>,
     TypedHeader(content_length): TypedHeader<ContentLength>,
     show_usage: Option<TypedHeader<ShowUsage>>,
@@ -42,6 +42,7 @@ async fn main() -> anyhow::Result<()> {
             bigtable_instance,
             db_args,
             bigtable_args,
+            fullnode_args,
             rpc_args,
             system_package_task_args,
             metrics_args,
@@ -104,6 +105,7 @@ async fn main() -> anyhow::Result<()> {
                 bigtable_instance,
                 db_args,
                 bigtable_args,
+                fullnode_args,
                 rpc_args,
                 system_package_task_args,
                 VERSION,
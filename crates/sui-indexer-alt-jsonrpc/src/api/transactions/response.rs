@@ -69,8 +69,8 @@ pub(super) async fn transaction(
 
     let mut response = SuiTransactionBlockResponse::new(digest);
 
-    response.timestamp_ms = Some(tx.timestamp_ms());
-    response.checkpoint = Some(tx.cp_sequence_number());
+    response.timestamp_ms = tx.timestamp_ms();
+    response.checkpoint = tx.cp_sequence_number();
 
     if options.show_input {
         response.transaction = Some(input(ctx, &tx).await?);
@@ -155,9 +155,8 @@ async fn events(
             ),
         };
 
-        let sui_event =
-            SuiEvent::try_from(event, digest, ix as u64, Some(tx.timestamp_ms()), layout)
-                .with_context(|| format!("Failed to convert Event {ix} into response"))?;
+        let sui_event = SuiEvent::try_from(event, digest, ix as u64, tx.timestamp_ms(), layout)
+            .with_context(|| format!("Failed to convert Event {ix} into response"))?;
 
         sui_events.push(sui_event)
     }
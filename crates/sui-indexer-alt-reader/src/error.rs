@@ -20,6 +20,12 @@ pub enum Error {
     #[error(transparent)]
     BigtableRead(anyhow::Error),
 
+    #[error(transparent)]
+    FullnodeCreate(anyhow::Error),
+
+    #[error(transparent)]
+    FullnodeRequest(tonic::Status),
+
     #[error(transparent)]
     Serde(anyhow::Error),
 }
@@ -0,0 +1,217 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Context};
+use prost_types::FieldMask;
+use sui_indexer_alt_schema::transactions::BalanceChange;
+use sui_rpc::field::FieldMaskUtil;
+use sui_rpc::proto::sui::rpc::v2beta2::{
+    live_data_service_client::LiveDataServiceClient,
+    simulate_transaction_request::TransactionChecks,
+    transaction_execution_service_client::TransactionExecutionServiceClient,
+    BalanceChange as ProtoBalanceChange, Bcs, ExecuteTransactionRequest,
+    ExecutedTransaction as ProtoExecutedTransaction, SimulateTransactionRequest, Transaction,
+    UserSignature,
+};
+use sui_types::{
+    base_types::SuiAddress,
+    effects::{TransactionEffects, TransactionEvents},
+    object::Owner,
+    signature::GenericSignature,
+    transaction::TransactionData,
+    TypeTag,
+};
+use tonic::transport::{Channel, Endpoint};
+
+use crate::{error::Error, kv_loader::ExecutedTransaction};
+
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct FullnodeArgs {
+    /// gRPC URL of a fullnode to forward transaction execution and simulation requests to. If this
+    /// is not provided, those requests will fail.
+    #[clap(long)]
+    pub fullnode_rpc_url: Option<String>,
+}
+
+/// A client for a fullnode's gRPC API, used to execute and simulate transactions that have not
+/// been indexed yet.
+#[derive(Clone)]
+pub struct FullnodeClient {
+    execution: TransactionExecutionServiceClient<Channel>,
+    live_data: LiveDataServiceClient<Channel>,
+}
+
+impl FullnodeClient {
+    /// Create a client talking to the fullnode at `url`. The connection is established lazily, on
+    /// the first request, so that the fullnode being unavailable does not prevent start-up.
+    pub fn new(url: String) -> Result<Self, Error> {
+        let channel = Endpoint::from_shared(url)
+            .context("Invalid fullnode RPC URL")
+            .map_err(Error::FullnodeCreate)?
+            .connect_lazy();
+
+        Ok(Self {
+            execution: TransactionExecutionServiceClient::new(channel.clone()),
+            live_data: LiveDataServiceClient::new(channel),
+        })
+    }
+
+    /// Submit `transaction`, signed by `signatures`, for execution, and wait for its effects.
+    pub async fn execute_transaction(
+        &self,
+        transaction: TransactionData,
+        signatures: Vec<GenericSignature>,
+    ) -> Result<ExecutedTransaction, Error> {
+        let request = ExecuteTransactionRequest {
+            transaction: Some(serialize_transaction(&transaction)?),
+            signatures: signatures
+                .iter()
+                .map(|s| UserSignature {
+                    bcs: Some(Bcs {
+                        name: None,
+                        value: Some(s.as_ref().to_owned().into()),
+                    }),
+                    ..Default::default()
+                })
+                .collect(),
+            read_mask: Some(FieldMask::from_paths([
+                "transaction.effects.bcs",
+                "transaction.events.bcs",
+                "transaction.balance_changes",
+            ])),
+        };
+
+        let response = self
+            .execution
+            .clone()
+            .execute_transaction(request)
+            .await
+            .map_err(Error::FullnodeRequest)?
+            .into_inner();
+
+        let executed = response
+            .transaction
+            .ok_or_else(|| Error::Serde(anyhow!("Missing transaction in execution response")))?;
+
+        decode_executed(transaction, signatures, executed)
+    }
+
+    /// Simulate `transaction` without signatures. If `checks_enabled` is false, the fullnode will
+    /// relax some of the checks it normally performs on a transaction before running it.
+    pub async fn simulate_transaction(
+        &self,
+        transaction: TransactionData,
+        checks_enabled: bool,
+    ) -> Result<ExecutedTransaction, Error> {
+        let mut request = SimulateTransactionRequest {
+            transaction: Some(serialize_transaction(&transaction)?),
+            read_mask: Some(FieldMask::from_paths([
+                "transaction.effects.bcs",
+                "transaction.events.bcs",
+                "transaction.balance_changes",
+            ])),
+            ..Default::default()
+        };
+
+        request.set_checks(if checks_enabled {
+            TransactionChecks::Enabled
+        } else {
+            TransactionChecks::Disabled
+        });
+
+        let response = self
+            .live_data
+            .clone()
+            .simulate_transaction(request)
+            .await
+            .map_err(Error::FullnodeRequest)?
+            .into_inner();
+
+        let executed = response
+            .transaction
+            .ok_or_else(|| Error::Serde(anyhow!("Missing transaction in simulation response")))?;
+
+        decode_executed(transaction, vec![], executed)
+    }
+}
+
+fn serialize_transaction(transaction: &TransactionData) -> Result<Transaction, Error> {
+    Ok(Transaction {
+        bcs: Some(
+            Bcs::serialize(transaction)
+                .context("Failed to serialize transaction")
+                .map_err(Error::Serde)?,
+        ),
+        ..Default::default()
+    })
+}
+
+/// Combine the effects, events and balance changes returned by the fullnode with the transaction
+/// that was sent to it.
+fn decode_executed(
+    transaction: TransactionData,
+    signatures: Vec<GenericSignature>,
+    executed: ProtoExecutedTransaction,
+) -> Result<ExecutedTransaction, Error> {
+    let effects: TransactionEffects = executed
+        .effects
+        .and_then(|e| e.bcs)
+        .ok_or_else(|| anyhow!("Missing effects in fullnode response"))
+        .and_then(|bcs| {
+            bcs.deserialize()
+                .context("Failed to deserialize transaction effects")
+        })
+        .map_err(Error::Serde)?;
+
+    let events = executed
+        .events
+        .and_then(|e| e.bcs)
+        .map(|bcs| {
+            bcs.deserialize::<TransactionEvents>()
+                .context("Failed to deserialize transaction events")
+        })
+        .transpose()
+        .map_err(Error::Serde)?
+        .map(|events| events.data)
+        .unwrap_or_default();
+
+    let balance_changes = executed
+        .balance_changes
+        .iter()
+        .map(decode_balance_change)
+        .collect::<anyhow::Result<_>>()
+        .map_err(Error::Serde)?;
+
+    Ok(ExecutedTransaction {
+        transaction,
+        signatures,
+        effects,
+        events,
+        balance_changes,
+    })
+}
+
+/// Convert a balance change returned by the fullnode into the representation the indexer stores
+/// for transactions that have been indexed.
+fn decode_balance_change(change: &ProtoBalanceChange) -> anyhow::Result<BalanceChange> {
+    let address: SuiAddress = change
+        .address()
+        .parse()
+        .context("Failed to parse balance change address")?;
+
+    let coin_type: TypeTag = change
+        .coin_type()
+        .parse()
+        .context("Failed to parse balance change coin type")?;
+
+    let amount: i128 = change
+        .amount()
+        .parse()
+        .context("Failed to parse balance change amount")?;
+
+    Ok(BalanceChange::V1 {
+        owner: Owner::AddressOwner(address),
+        coin_type: coin_type.to_canonical_string(/* with_prefix */ true),
+        amount,
+    })
+}
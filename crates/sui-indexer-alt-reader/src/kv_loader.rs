@@ -6,7 +6,7 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use async_graphql::dataloader::DataLoader;
-use sui_indexer_alt_schema::transactions::StoredTransaction;
+use sui_indexer_alt_schema::transactions::{BalanceChange, StoredTransaction};
 use sui_kvstore::TransactionData as KVTransactionData;
 use sui_types::{
     base_types::ObjectID,
@@ -37,10 +37,24 @@ pub enum KvLoader {
     Pg(Arc<DataLoader<PgReader>>),
 }
 
-/// A wrapper for the contents of a transaction, either from Bigtable or Postgres.
+/// A wrapper for the contents of a transaction, either from Bigtable or Postgres, or one that has
+/// just been executed or simulated by a fullnode, and has not been indexed yet.
 pub enum TransactionContents {
     Bigtable(KVTransactionData),
     Pg(StoredTransaction),
+    Executed(ExecutedTransaction),
+}
+
+/// The contents of a transaction that was executed or simulated by a fullnode. Unlike the other
+/// variants, this transaction is not (yet) associated with a checkpoint.
+pub struct ExecutedTransaction {
+    pub transaction: TransactionData,
+    pub signatures: Vec<GenericSignature>,
+    pub effects: TransactionEffects,
+    pub events: Vec<Event>,
+    /// Balance changes calculated by the fullnode, from the transaction's input and output
+    /// objects.
+    pub balance_changes: Vec<BalanceChange>,
 }
 
 impl KvLoader {
@@ -155,6 +169,7 @@ impl TransactionContents {
             Self::Pg(stored) => bcs::from_bytes(&stored.raw_transaction)
                 .context("Failed to deserialize transaction data"),
             Self::Bigtable(kv) => Ok(kv.transaction.data().transaction_data().clone()),
+            Self::Executed(tx) => Ok(tx.transaction.clone()),
         }
     }
 
@@ -163,6 +178,7 @@ impl TransactionContents {
             Self::Pg(stored) => TransactionDigest::try_from(stored.tx_digest.clone())
                 .context("Failed to deserialize transaction digest"),
             Self::Bigtable(kv) => Ok(*kv.transaction.digest()),
+            Self::Executed(tx) => Ok(tx.transaction.digest()),
         }
     }
 
@@ -175,6 +191,7 @@ impl TransactionContents {
                 Ok(effects.digest())
             }
             Self::Bigtable(kv) => Ok(kv.effects.digest()),
+            Self::Executed(tx) => Ok(tx.effects.digest()),
         }
     }
 
@@ -184,6 +201,7 @@ impl TransactionContents {
                 bcs::from_bytes(&stored.user_signatures).context("Failed to deserialize signatures")
             }
             Self::Bigtable(kv) => Ok(kv.transaction.tx_signatures().to_vec()),
+            Self::Executed(tx) => Ok(tx.signatures.clone()),
         }
     }
 
@@ -193,6 +211,7 @@ impl TransactionContents {
                 bcs::from_bytes(&stored.raw_effects).context("Failed to deserialize effects")
             }
             Self::Bigtable(kv) => Ok(kv.effects.clone()),
+            Self::Executed(tx) => Ok(tx.effects.clone()),
         }
     }
 
@@ -202,6 +221,7 @@ impl TransactionContents {
                 bcs::from_bytes(&stored.events).context("Failed to deserialize events")
             }
             Self::Bigtable(kv) => Ok(kv.events.clone().unwrap_or_default().data),
+            Self::Executed(tx) => Ok(tx.events.clone()),
         }
     }
 
@@ -210,6 +230,9 @@ impl TransactionContents {
             Self::Pg(stored) => Ok(stored.raw_transaction.clone()),
             Self::Bigtable(kv) => bcs::to_bytes(kv.transaction.data().transaction_data())
                 .context("Failed to serialize transaction"),
+            Self::Executed(tx) => {
+                bcs::to_bytes(&tx.transaction).context("Failed to serialize transaction")
+            }
         }
     }
 
@@ -217,20 +240,27 @@ impl TransactionContents {
         match self {
             Self::Pg(stored) => Ok(stored.raw_effects.clone()),
             Self::Bigtable(kv) => bcs::to_bytes(&kv.effects).context("Failed to serialize effects"),
+            Self::Executed(tx) => bcs::to_bytes(&tx.effects).context("Failed to serialize effects"),
         }
     }
 
-    pub fn timestamp_ms(&self) -> u64 {
+    /// The timestamp of the checkpoint this transaction was included in, or `None` if the
+    /// transaction has not been included in a checkpoint yet.
+    pub fn timestamp_ms(&self) -> Option<u64> {
         match self {
-            Self::Pg(stored) => stored.timestamp_ms as u64,
-            Self::Bigtable(kv) => kv.timestamp,
+            Self::Pg(stored) => Some(stored.timestamp_ms as u64),
+            Self::Bigtable(kv) => Some(kv.timestamp),
+            Self::Executed(_) => None,
         }
     }
 
-    pub fn cp_sequence_number(&self) -> u64 {
+    /// The sequence number of the checkpoint this transaction was included in, or `None` if the
+    /// transaction has not been included in a checkpoint yet.
+    pub fn cp_sequence_number(&self) -> Option<u64> {
         match self {
-            Self::Pg(stored) => stored.cp_sequence_number as u64,
-            Self::Bigtable(kv) => kv.checkpoint_number,
+            Self::Pg(stored) => Some(stored.cp_sequence_number as u64),
+            Self::Bigtable(kv) => Some(kv.checkpoint_number),
+            Self::Executed(_) => None,
         }
     }
 }
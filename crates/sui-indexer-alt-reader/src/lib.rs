@@ -7,6 +7,7 @@ pub mod coin_metadata;
 pub mod displays;
 pub mod epochs;
 pub mod error;
+pub mod fullnode_client;
 pub mod kv_loader;
 pub(crate) mod metrics;
 pub mod object_versions;
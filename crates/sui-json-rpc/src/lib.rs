@@ -139,6 +139,10 @@ impl JsonRpcServerBuilder {
             .policy_config
             .clone()
             .map(|policy| policy.client_id_source);
+        let api_key_header = self
+            .policy_config
+            .as_ref()
+            .and_then(|policy| policy.api_key_header.clone());
 
         let metrics_clone = metrics.clone();
         let middleware = ServiceBuilder::new()
@@ -148,6 +152,9 @@ impl JsonRpcServerBuilder {
                 if let Some(client_id_source) = client_id_source.clone() {
                     traffic_control::determine_client_ip(client_id_source, &mut request);
                 }
+                if let Some(api_key_header) = &api_key_header {
+                    traffic_control::determine_client_api_key(api_key_header, &mut request);
+                }
                 request
             });
 
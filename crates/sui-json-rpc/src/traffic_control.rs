@@ -17,6 +17,11 @@ use tracing::error;
 
 const TOO_MANY_REQUESTS_MSG: &str = "Too many requests";
 
+/// API key presented by the client, as read from the header configured by
+/// `PolicyConfig::api_key_header`.
+#[derive(Clone)]
+pub struct ApiKey(pub String);
+
 #[derive(Clone)]
 pub struct TrafficControllerService<S> {
     inner: S,
@@ -46,11 +51,16 @@ where
         async move {
             if let Some(traffic_controller) = traffic_controller {
                 let client = req.extensions().get::<IpAddr>().cloned();
-                if let Err(response) = handle_traffic_req(&traffic_controller, &client).await {
+                let api_key = req.extensions().get::<ApiKey>().map(|key| key.0.clone());
+                let method = req.method_name().to_string();
+                if let Err(response) =
+                    handle_traffic_req(&traffic_controller, &client, &api_key).await
+                {
                     response
                 } else {
                     let response = service.call(req).await;
-                    handle_traffic_resp(&traffic_controller, client, &response).await;
+                    handle_traffic_resp(&traffic_controller, client, api_key, method, &response)
+                        .await;
                     response
                 }
             } else {
@@ -64,8 +74,9 @@ where
 async fn handle_traffic_req(
     traffic_controller: &Arc<TrafficController>,
     client: &Option<IpAddr>,
+    api_key: &Option<String>,
 ) -> Result<(), MethodResponse> {
-    if !traffic_controller.check(client, &None).await {
+    if !traffic_controller.check(client, &None, api_key).await {
         // Entity in blocklist
         let err_obj =
             ErrorObject::borrowed(ErrorCode::ServerIsBusy.code(), TOO_MANY_REQUESTS_MSG, None);
@@ -78,6 +89,8 @@ async fn handle_traffic_req(
 async fn handle_traffic_resp(
     traffic_controller: &Arc<TrafficController>,
     client: Option<IpAddr>,
+    api_key: Option<String>,
+    method: String,
     response: &MethodResponse,
 ) {
     let error = response.as_error_code().map(ErrorCode::from);
@@ -89,14 +102,16 @@ async fn handle_traffic_resp(
             let error_weight = normalize(e);
             (error_weight, error_type)
         }),
-        // For now, count everything as spam with equal weight
-        // on the rpc node side, including gas-charging endpoints
-        // such as `sui_executeTransactionBlock`, as this can enable
-        // node operators who wish to rate limit their transcation
-        // traffic and incentivize high volume clients to choose a
-        // suitable rpc provider (or run their own). Later we may want
-        // to provide a weight distribution based on the method being called.
+        // Count everything as spam with equal weight on the rpc node
+        // side, including gas-charging endpoints such as
+        // `sui_executeTransactionBlock`, as this can enable node operators
+        // who wish to rate limit their transcation traffic and incentivize
+        // high volume clients to choose a suitable rpc provider (or run
+        // their own). Policies that weigh methods differently (e.g.
+        // `TokenBucket`) do so based on `method`.
         spam_weight: Weight::one(),
+        api_key,
+        method: Some(method),
         timestamp: SystemTime::now(),
     });
 }
@@ -168,3 +183,15 @@ pub fn determine_client_ip<T>(
         request.extensions_mut().insert(ip);
     }
 }
+
+pub fn determine_client_api_key<T>(api_key_header: &str, request: &mut axum::http::Request<T>) {
+    let api_key = request
+        .headers()
+        .get(api_key_header)
+        .and_then(|header| header.to_str().ok())
+        .map(|key| ApiKey(key.to_owned()));
+
+    if let Some(api_key) = api_key {
+        request.extensions_mut().insert(api_key);
+    }
+}
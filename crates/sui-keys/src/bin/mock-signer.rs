@@ -0,0 +1,82 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A mock external signer, for testing `ExternalKeystore`. It signs with keys from a plaintext
+//! keystore file.
+//!
+//! Usage:
+//!
+//!   mock-signer <KEYSTORE_PATH>                  Serve one request from stdin, reply on stdout.
+//!   mock-signer <KEYSTORE_PATH> --socket <PATH>  Serve requests on a Unix domain socket.
+
+use anyhow::{bail, Context};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use sui_keys::external_keystore::{respond, SignerRequest, SignerResponse};
+use sui_keys::keystore::FileBasedKeystore;
+
+fn main() -> Result<(), anyhow::Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (keystore_path, socket) = match args.as_slice() {
+        [keystore] => (PathBuf::from(keystore), None),
+        [keystore, flag, socket] if flag == "--socket" => {
+            (PathBuf::from(keystore), Some(PathBuf::from(socket)))
+        }
+        _ => bail!("Usage: mock-signer <KEYSTORE_PATH> [--socket <PATH>]"),
+    };
+
+    let keystore = FileBasedKeystore::new(&keystore_path)?;
+
+    let Some(socket) = socket else {
+        return serve(&keystore, std::io::stdin().lock(), std::io::stdout().lock());
+    };
+
+    serve_socket(&keystore, socket)
+}
+
+#[cfg(unix)]
+fn serve_socket(keystore: &FileBasedKeystore, socket: PathBuf) -> Result<(), anyhow::Error> {
+    use std::os::unix::net::UnixListener;
+
+    let listener = UnixListener::bind(&socket)
+        .with_context(|| format!("Cannot bind to socket {}", socket.display()))?;
+
+    for stream in listener.incoming() {
+        let stream = stream.context("Cannot accept connection")?;
+        let reader = stream.try_clone().context("Cannot clone connection")?;
+        if let Err(e) = serve(keystore, reader, stream) {
+            eprintln!("Failed to serve request: {e:#}");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn serve_socket(_keystore: &FileBasedKeystore, _socket: PathBuf) -> Result<(), anyhow::Error> {
+    bail!("Serving on a socket is only supported on Unix")
+}
+
+/// Read one request from `reader`, and write the response to `writer`.
+fn serve(
+    keystore: &FileBasedKeystore,
+    reader: impl Read,
+    mut writer: impl Write,
+) -> Result<(), anyhow::Error> {
+    let mut line = String::new();
+    BufReader::new(reader)
+        .read_line(&mut line)
+        .context("Cannot read request")?;
+
+    let response = match serde_json::from_str::<SignerRequest>(&line) {
+        Ok(request) => respond(keystore, request),
+        Err(e) => SignerResponse::Error(format!("Invalid request: {e}")),
+    };
+
+    let mut response = serde_json::to_string(&response).context("Cannot serialize response")?;
+    response.push('\n');
+    writer
+        .write_all(response.as_bytes())
+        .context("Cannot write response")?;
+    writer.flush().context("Cannot write response")
+}
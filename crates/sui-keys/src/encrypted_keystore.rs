@@ -0,0 +1,685 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::keystore::{validate_alias, AccountKeystore, Alias, FileBasedKeystore};
+use crate::random_names::{random_name, random_names};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, ensure, Context};
+use argon2::{Algorithm, Argon2, Params, Version};
+use fastcrypto::encoding::{Base64, Encoding};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair};
+use zeroize::Zeroizing;
+
+/// Version of the encrypted keystore file format.
+const FORMAT_VERSION: u8 = 1;
+
+const KDF_ALGORITHM: &str = "argon2id";
+const CIPHER_ALGORITHM: &str = "aes-256-gcm";
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+/// A keystore whose private keys are stored encrypted under a key derived from a passphrase
+/// (Argon2id), using authenticated encryption (AES-256-GCM). Aliases and public keys are stored
+/// unencrypted in a sidecar `.aliases` file, like in [FileBasedKeystore], so that addresses can be
+/// listed while the keystore is locked.
+///
+/// The keystore starts locked when it is loaded from disk. Signing, exporting, importing and
+/// removing keys require it to be unlocked with [Self::unlock] first, and it can be locked again
+/// (forgetting its private keys and derived key) with [Self::lock].
+pub struct EncryptedFileBasedKeystore {
+    aliases: BTreeMap<SuiAddress, Alias>,
+    /// Decrypted key material, only present while the keystore is unlocked.
+    unlocked: Option<Unlocked>,
+    path: PathBuf,
+}
+
+struct Unlocked {
+    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    kdf: KdfParams,
+    key: Zeroizing<[u8; KEY_LENGTH]>,
+}
+
+/// The on-disk representation of the encrypted keystore.
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystoreFile {
+    version: u8,
+    kdf: KdfParams,
+    cipher: String,
+    /// Base64 encoded nonce.
+    nonce: String,
+    /// Base64 encoded ciphertext of the JSON array of Base64 encoded `flag || privkey` keys (the
+    /// same contents as a plaintext keystore file).
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+struct KdfParams {
+    algorithm: String,
+    /// Base64 encoded salt.
+    salt: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl Serialize for EncryptedFileBasedKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.path.to_str().unwrap_or(""))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedFileBasedKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        EncryptedFileBasedKeystore::new(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for EncryptedFileBasedKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        Ok(Signature::new_hashed(msg, self.signing_key(address)?))
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        Ok(Signature::new_secure(
+            &IntentMessage::new(intent, msg),
+            self.signing_key(address)?,
+        ))
+    }
+
+    fn import(&mut self, alias: Option<String>, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        let alias = self.create_alias(alias)?;
+        let unlocked = self.unlocked_mut()?;
+        let address: SuiAddress = (&keypair.public()).into();
+        let public_key_base64 = keypair.public().encode_base64();
+        unlocked.keys.insert(address, keypair);
+        self.aliases.insert(
+            address,
+            Alias {
+                alias,
+                public_key_base64,
+            },
+        );
+        self.save()
+    }
+
+    fn remove(&mut self, address: SuiAddress) -> Result<(), anyhow::Error> {
+        self.unlocked_mut()?.keys.remove(&address);
+        self.aliases.remove(&address);
+        self.save()
+    }
+
+    /// Public keys are read from the aliases, so that they are available while the keystore is
+    /// locked.
+    fn entries(&self) -> Vec<PublicKey> {
+        self.aliases
+            .values()
+            .filter_map(|alias| PublicKey::decode_base64(&alias.public_key_base64).ok())
+            .collect()
+    }
+
+    fn export(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        let unlocked = self.unlocked()?;
+        unlocked
+            .keys
+            .get(address)
+            .ok_or_else(|| anyhow!("Cannot find key for address: [{address}]"))
+    }
+
+    fn addresses_with_alias(&self) -> Vec<(&SuiAddress, &Alias)> {
+        self.aliases.iter().collect()
+    }
+
+    fn aliases(&self) -> Vec<&Alias> {
+        self.aliases.values().collect()
+    }
+
+    fn aliases_mut(&mut self) -> Vec<&mut Alias> {
+        self.aliases.values_mut().collect()
+    }
+
+    fn get_alias(&self, address: &SuiAddress) -> Result<String, anyhow::Error> {
+        match self.aliases.get(address) {
+            Some(alias) => Ok(alias.alias.clone()),
+            None => bail!("Cannot find alias for address {address}"),
+        }
+    }
+
+    /// This function returns an error if the provided alias already exists. If the alias
+    /// has not already been used, then it returns the alias.
+    /// If no alias has been passed, it will generate a new alias.
+    fn create_alias(&self, alias: Option<String>) -> Result<String, anyhow::Error> {
+        match alias {
+            Some(a) if self.alias_exists(&a) => {
+                bail!("Alias {a} already exists. Please choose another alias.")
+            }
+            Some(a) => validate_alias(&a),
+            None => Ok(random_name(
+                &self
+                    .aliases()
+                    .into_iter()
+                    .map(|x| x.alias.to_string())
+                    .collect::<HashSet<_>>(),
+            )),
+        }
+    }
+
+    /// Updates an old alias to the new alias and saves it to the alias file. Aliases are not
+    /// encrypted, so this does not require the keystore to be unlocked.
+    fn update_alias(
+        &mut self,
+        old_alias: &str,
+        new_alias: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        let new_alias_name = self.update_alias_value(old_alias, new_alias)?;
+        self.save_aliases()?;
+        Ok(new_alias_name)
+    }
+}
+
+impl EncryptedFileBasedKeystore {
+    /// Load the encrypted keystore at `path`, in a locked state. Only its aliases and public keys
+    /// are read until it is unlocked.
+    pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        let aliases_path = aliases_path(path);
+        let aliases = if aliases_path.exists() {
+            let contents = fs::read_to_string(&aliases_path).with_context(|| {
+                format!(
+                    "Cannot open aliases file in keystore: {}",
+                    aliases_path.display()
+                )
+            })?;
+
+            let aliases: Vec<Alias> = serde_json::from_str(&contents).with_context(|| {
+                format!(
+                    "Cannot deserialize aliases file in keystore: {}",
+                    aliases_path.display(),
+                )
+            })?;
+
+            aliases
+                .into_iter()
+                .map(|alias| {
+                    let key = PublicKey::decode_base64(&alias.public_key_base64);
+                    key.map(|k| (Into::<SuiAddress>::into(&k), alias))
+                })
+                .collect::<Result<BTreeMap<_, _>, _>>()
+                .map_err(|e| {
+                    anyhow!(
+                        "Invalid aliases file in keystore: {}. {}",
+                        aliases_path.display(),
+                        e
+                    )
+                })?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            aliases,
+            unlocked: None,
+            path: path.clone(),
+        })
+    }
+
+    /// Create a new, empty, encrypted keystore at `path`, protected by `passphrase`. The keystore
+    /// is returned unlocked. Fails if a keystore already exists at `path`.
+    pub fn create(path: &PathBuf, passphrase: &str) -> Result<Self, anyhow::Error> {
+        ensure!(
+            !path.exists(),
+            "Cannot create encrypted keystore, file already exists: {}",
+            path.display()
+        );
+
+        let kdf = KdfParams::generate();
+        let key = kdf.derive_key(passphrase)?;
+        let keystore = Self {
+            aliases: BTreeMap::new(),
+            unlocked: Some(Unlocked {
+                keys: BTreeMap::new(),
+                kdf,
+                key,
+            }),
+            path: path.clone(),
+        };
+
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    /// Encrypt the keys and aliases from a plaintext `keystore` under `passphrase`, and write them
+    /// to `path`, which may be the same path as the plaintext keystore, in which case it is
+    /// overwritten. The file at `path` is backed up until the encrypted keystore has been written
+    /// and read back successfully. The keystore is returned unlocked.
+    pub fn from_file_keystore(
+        keystore: &FileBasedKeystore,
+        path: &Path,
+        passphrase: &str,
+    ) -> Result<Self, anyhow::Error> {
+        let keys: BTreeMap<_, _> = keystore
+            .key_pairs()
+            .into_iter()
+            .map(|kp| (SuiAddress::from(&kp.public()), kp.copy()))
+            .collect();
+
+        let mut aliases: BTreeMap<_, _> = keystore
+            .addresses_with_alias()
+            .into_iter()
+            .map(|(address, alias)| (*address, alias.clone()))
+            .collect();
+
+        let kdf = KdfParams::generate();
+        let key = kdf.derive_key(passphrase)?;
+
+        fill_missing_aliases(&mut aliases, &keys);
+        let keystore = Self {
+            aliases,
+            unlocked: Some(Unlocked { keys, kdf, key }),
+            path: path.to_path_buf(),
+        };
+
+        keystore.save_with_backup()?;
+        Ok(keystore)
+    }
+
+    /// Whether the file at `path` is an encrypted keystore (as opposed to a plaintext keystore,
+    /// or not a keystore at all).
+    pub fn is_encrypted_keystore(path: &Path) -> bool {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<EncryptedKeystoreFile>(&contents).ok())
+            .is_some()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.unlocked.is_none()
+    }
+
+    /// Decrypt the keystore's private keys using `passphrase`, so that they can be used for
+    /// signing until the keystore is locked again. Fails without modifying the keystore if the
+    /// passphrase is incorrect.
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        let file = self.read_file()?;
+        let key = file.kdf.derive_key(passphrase)?;
+        let keys = file.decrypt(&key)?;
+
+        fill_missing_aliases(&mut self.aliases, &keys);
+        self.unlocked = Some(Unlocked {
+            keys,
+            kdf: file.kdf,
+            key,
+        });
+
+        self.save_aliases()
+    }
+
+    /// Forget the keystore's decrypted private keys, and the key derived from its passphrase.
+    pub fn lock(&mut self) {
+        self.unlocked = None;
+    }
+
+    /// Re-encrypt the keystore under `new_passphrase`, after checking that `old_passphrase`
+    /// unlocks it. The file encrypted under the old passphrase is backed up until the re-encrypted
+    /// keystore has been written and read back successfully. The keystore is left unlocked.
+    pub fn change_passphrase(
+        &mut self,
+        old_passphrase: &str,
+        new_passphrase: &str,
+    ) -> Result<(), anyhow::Error> {
+        // Always check the old passphrase, even if the keystore is already unlocked.
+        self.unlock(old_passphrase)?;
+
+        let kdf = KdfParams::generate();
+        let key = kdf.derive_key(new_passphrase)?;
+
+        let unlocked = self.unlocked_mut()?;
+        unlocked.kdf = kdf;
+        unlocked.key = key;
+
+        self.save_with_backup()
+    }
+
+    pub fn save_aliases(&self) -> Result<(), anyhow::Error> {
+        let aliases_store = serde_json::to_string_pretty(
+            &self.aliases.values().collect::<Vec<_>>(),
+        )
+        .with_context(|| {
+            format!(
+                "Cannot serialize aliases to file in keystore: {}",
+                self.path.display()
+            )
+        })?;
+
+        write_atomic(&aliases_path(&self.path), aliases_store.as_bytes())
+    }
+
+    /// Encrypt the keystore's private keys and write them to its file, under a fresh nonce.
+    /// Requires the keystore to be unlocked.
+    pub fn save_keystore(&self) -> Result<(), anyhow::Error> {
+        let unlocked = self.unlocked()?;
+        let kp_strings = Zeroizing::new(
+            unlocked
+                .keys
+                .values()
+                .map(|k| k.encode_base64())
+                .collect::<Vec<_>>(),
+        );
+
+        let plaintext =
+            Zeroizing::new(serde_json::to_vec(&*kp_strings).context("Cannot serialize keystore")?);
+
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+
+        let aad = unlocked.kdf.aad()?;
+        let ciphertext = cipher(&unlocked.key)?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext.as_slice(),
+                    aad: &aad,
+                },
+            )
+            .map_err(|e| anyhow!("Cannot encrypt keystore: {e}"))?;
+
+        let file = EncryptedKeystoreFile {
+            version: FORMAT_VERSION,
+            kdf: unlocked.kdf.clone(),
+            cipher: CIPHER_ALGORITHM.to_string(),
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        };
+
+        let store = serde_json::to_string_pretty(&file).with_context(|| {
+            format!("Cannot serialize keystore to file: {}", self.path.display())
+        })?;
+
+        write_atomic(&self.path, store.as_bytes())
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        self.save_aliases()?;
+        self.save_keystore()?;
+        Ok(())
+    }
+
+    /// Save the keystore, keeping a copy of the file it replaces (if any) next to it until the
+    /// new file has been read back and decrypts to the same keys. If that check fails, the backup
+    /// is left in place, and the error says where to find it.
+    fn save_with_backup(&self) -> Result<(), anyhow::Error> {
+        let backup = backup_path(&self.path);
+        let backed_up = self.path.exists();
+        if backed_up {
+            fs::copy(&self.path, &backup).with_context(|| {
+                format!("Cannot back up the keystore file: {}", self.path.display())
+            })?;
+        }
+
+        self.save()
+            .and_then(|_| self.verify_saved())
+            .with_context(|| {
+                if backed_up {
+                    format!(
+                        "Failed to write the keystore file, the previous file is backed up at: {}",
+                        backup.display()
+                    )
+                } else {
+                    format!("Failed to write the keystore file: {}", self.path.display())
+                }
+            })?;
+
+        if backed_up {
+            fs::remove_file(&backup).with_context(|| {
+                format!("Cannot remove keystore backup file: {}", backup.display())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that the keystore file on disk decrypts, under the current key, to the keys held in
+    /// memory.
+    fn verify_saved(&self) -> Result<(), anyhow::Error> {
+        let unlocked = self.unlocked()?;
+        let keys = self.read_file()?.decrypt(&unlocked.key)?;
+        ensure!(
+            keys.keys().eq(unlocked.keys.keys()),
+            "Keystore file does not contain the expected keys: {}",
+            self.path.display()
+        );
+
+        Ok(())
+    }
+
+    fn read_file(&self) -> Result<EncryptedKeystoreFile, anyhow::Error> {
+        let contents = fs::read_to_string(&self.path)
+            .with_context(|| format!("Cannot open the keystore file: {}", self.path.display()))?;
+
+        let file: EncryptedKeystoreFile = serde_json::from_str(&contents).with_context(|| {
+            format!(
+                "Cannot deserialize the encrypted keystore file: {}",
+                self.path.display()
+            )
+        })?;
+
+        ensure!(
+            file.version == FORMAT_VERSION,
+            "Unsupported encrypted keystore version: {}",
+            file.version
+        );
+
+        ensure!(
+            file.cipher == CIPHER_ALGORITHM,
+            "Unsupported encrypted keystore cipher: {}",
+            file.cipher
+        );
+
+        Ok(file)
+    }
+
+    fn unlocked(&self) -> Result<&Unlocked, anyhow::Error> {
+        self.unlocked
+            .as_ref()
+            .ok_or_else(|| anyhow!("Keystore is locked: {}", self.path.display()))
+    }
+
+    fn unlocked_mut(&mut self) -> Result<&mut Unlocked, anyhow::Error> {
+        let path = &self.path;
+        self.unlocked
+            .as_mut()
+            .ok_or_else(|| anyhow!("Keystore is locked: {}", path.display()))
+    }
+
+    fn signing_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, signature::Error> {
+        let unlocked = self
+            .unlocked()
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+
+        unlocked.keys.get(address).ok_or_else(|| {
+            signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+        })
+    }
+}
+
+impl EncryptedKeystoreFile {
+    fn decrypt(
+        &self,
+        key: &[u8; KEY_LENGTH],
+    ) -> Result<BTreeMap<SuiAddress, SuiKeyPair>, anyhow::Error> {
+        let nonce = Base64::decode(&self.nonce).map_err(|e| anyhow!("Invalid nonce: {e}"))?;
+        ensure!(nonce.len() == NONCE_LENGTH, "Invalid nonce length");
+
+        let ciphertext =
+            Base64::decode(&self.ciphertext).map_err(|e| anyhow!("Invalid ciphertext: {e}"))?;
+
+        let aad = self.kdf.aad()?;
+        let plaintext = Zeroizing::new(
+            cipher(key)?
+                .decrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: &ciphertext,
+                        aad: &aad,
+                    },
+                )
+                .map_err(|_| anyhow!("Incorrect passphrase, or the keystore is corrupted"))?,
+        );
+
+        let kp_strings: Zeroizing<Vec<String>> = Zeroizing::new(
+            serde_json::from_slice(&plaintext).context("Cannot deserialize decrypted keystore")?,
+        );
+
+        kp_strings
+            .iter()
+            .map(|kpstr| {
+                let key = SuiKeyPair::decode_base64(kpstr);
+                key.map(|k| (SuiAddress::from(&k.public()), k))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| anyhow!("Invalid key in encrypted keystore: {e}"))
+    }
+}
+
+impl KdfParams {
+    /// Fresh parameters, with a random salt and the default Argon2id costs.
+    fn generate() -> Self {
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+
+        Self {
+            algorithm: KDF_ALGORITHM.to_string(),
+            salt: Base64::encode(salt),
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<Zeroizing<[u8; KEY_LENGTH]>, anyhow::Error> {
+        ensure!(
+            self.algorithm == KDF_ALGORITHM,
+            "Unsupported encrypted keystore KDF: {}",
+            self.algorithm
+        );
+
+        let salt = Base64::decode(&self.salt).map_err(|e| anyhow!("Invalid salt: {e}"))?;
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(KEY_LENGTH))
+            .map_err(|e| anyhow!("Invalid KDF parameters: {e}"))?;
+
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key[..])
+            .map_err(|e| anyhow!("Cannot derive key from passphrase: {e}"))?;
+
+        Ok(key)
+    }
+
+    /// The KDF parameters are authenticated along with the ciphertext, so that they cannot be
+    /// tampered with.
+    fn aad(&self) -> Result<Vec<u8>, anyhow::Error> {
+        serde_json::to_vec(self).context("Cannot serialize KDF parameters")
+    }
+}
+
+fn cipher(key: &[u8; KEY_LENGTH]) -> Result<Aes256Gcm, anyhow::Error> {
+    Aes256Gcm::new_from_slice(key).map_err(|e| anyhow!("Invalid encryption key: {e}"))
+}
+
+/// Replace the contents of the file at `path` with `contents`, by writing them to a temporary file
+/// in the same directory, syncing it to disk, and renaming it over `path`, so that a crash or a
+/// failed write never leaves a truncated file behind.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), anyhow::Error> {
+    let tmp_path = with_suffix(path, ".tmp");
+    let written = File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp_path, path));
+
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e).with_context(|| format!("Cannot write file: {}", path.display()));
+    }
+
+    // Sync the directory as well, so that the rename itself is durable.
+    #[cfg(unix)]
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        File::open(dir)
+            .and_then(|dir| dir.sync_all())
+            .with_context(|| format!("Cannot sync directory: {}", dir.display()))?;
+    }
+
+    Ok(())
+}
+
+fn aliases_path(path: &Path) -> PathBuf {
+    let mut aliases_path = path.to_path_buf();
+    aliases_path.set_extension("aliases");
+    aliases_path
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+/// `path` with `suffix` appended to its file name.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Generate random aliases for any keys that don't have one.
+fn fill_missing_aliases(
+    aliases: &mut BTreeMap<SuiAddress, Alias>,
+    keys: &BTreeMap<SuiAddress, SuiKeyPair>,
+) {
+    let missing: Vec<_> = keys
+        .iter()
+        .filter(|(address, _)| !aliases.contains_key(address))
+        .collect();
+
+    let existing = aliases.values().map(|a| a.alias.clone()).collect();
+    let names = random_names(existing, missing.len());
+    for ((address, kp), alias) in missing.into_iter().zip(names) {
+        aliases.insert(
+            *address,
+            Alias {
+                alias,
+                public_key_base64: kp.public().encode_base64(),
+            },
+        );
+    }
+}
@@ -0,0 +1,365 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A keystore that delegates signing to an external signer process, so that keys held by an HSM,
+//! a KMS, or a hardware wallet can be used without exporting them.
+//!
+//! The keystore talks to the signer using a line-delimited JSON protocol: it writes a single
+//! [SignerRequest] as one line of JSON, and reads a single [SignerResponse] as one line of JSON in
+//! reply. The signer can either be a command that is run for each request (reading the request
+//! from its stdin and writing the response to its stdout), or a server listening on a local
+//! (Unix domain) socket, that accepts a connection for each request.
+
+use crate::keystore::{AccountKeystore, Alias};
+use anyhow::{anyhow, bail, Context};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::HashFunction;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{
+    DefaultHash, EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair, SuiSignature, ToFromBytes,
+};
+
+/// How to reach the external signer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerTransport {
+    /// Run `command` with `args` for each request.
+    Command {
+        command: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+    },
+    /// Connect to a signer listening on the Unix domain socket at `path` for each request.
+    Socket { path: PathBuf },
+}
+
+/// A request sent to the external signer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum SignerRequest {
+    /// List the keys that the signer can sign with.
+    Keys,
+    /// Sign `message` (Base64 encoded), which has already been hashed, with the key for `address`.
+    Sign {
+        address: SuiAddress,
+        message: String,
+    },
+}
+
+/// The external signer's response to a [SignerRequest].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerResponse {
+    /// The keys that the signer can sign with, in response to [SignerRequest::Keys].
+    Keys(Vec<ExternalKey>),
+    /// A Base64 encoded `flag || signature || pubkey`, in response to [SignerRequest::Sign].
+    Signature(String),
+    /// The request failed.
+    Error(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ExternalKey {
+    /// Base64 encoded `flag || pubkey`.
+    pub public_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+/// A keystore whose keys are held by an external signer. Its keys are listed when it is created,
+/// and every signature is requested from the signer. Keys cannot be imported, exported or removed
+/// through this keystore, and aliases are assigned by the signer.
+pub struct ExternalKeystore {
+    transport: SignerTransport,
+    keys: BTreeMap<SuiAddress, PublicKey>,
+    aliases: BTreeMap<SuiAddress, Alias>,
+}
+
+impl Serialize for ExternalKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.transport.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExternalKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        ExternalKeystore::new(SignerTransport::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for ExternalKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        if !self.keys.contains_key(address) {
+            return Err(signature::Error::from_source(format!(
+                "Cannot find key for address: [{address}]"
+            )));
+        }
+
+        self.request_signature(address, msg)
+            .map_err(|e| signature::Error::from_source(format!("{e:#}")))
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        // Hash the intent message the same way as `Signature::new_secure`, so that the signer only
+        // needs to support signing pre-hashed messages.
+        let mut hasher = DefaultHash::default();
+        hasher.update(
+            bcs::to_bytes(&IntentMessage::new(intent, msg))
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
+        );
+
+        self.sign_hashed(address, &hasher.finalize().digest)
+    }
+
+    fn import(
+        &mut self,
+        _alias: Option<String>,
+        _keypair: SuiKeyPair,
+    ) -> Result<(), anyhow::Error> {
+        bail!("Cannot import keys into an external signer, add them to the signer directly")
+    }
+
+    fn remove(&mut self, _address: SuiAddress) -> Result<(), anyhow::Error> {
+        bail!("Cannot remove keys from an external signer, remove them from the signer directly")
+    }
+
+    fn entries(&self) -> Vec<PublicKey> {
+        self.keys.values().cloned().collect()
+    }
+
+    fn export(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        bail!("Cannot export the key for address [{address}] from an external signer")
+    }
+
+    fn addresses_with_alias(&self) -> Vec<(&SuiAddress, &Alias)> {
+        self.aliases.iter().collect()
+    }
+
+    fn aliases(&self) -> Vec<&Alias> {
+        self.aliases.values().collect()
+    }
+
+    fn aliases_mut(&mut self) -> Vec<&mut Alias> {
+        self.aliases.values_mut().collect()
+    }
+
+    fn get_alias(&self, address: &SuiAddress) -> Result<String, anyhow::Error> {
+        match self.aliases.get(address) {
+            Some(alias) => Ok(alias.alias.clone()),
+            None => bail!("Cannot find alias for address {address}"),
+        }
+    }
+
+    fn create_alias(&self, _alias: Option<String>) -> Result<String, anyhow::Error> {
+        bail!("Aliases of keys in an external signer are assigned by the signer")
+    }
+
+    fn update_alias(
+        &mut self,
+        _old_alias: &str,
+        _new_alias: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        bail!("Aliases of keys in an external signer are assigned by the signer")
+    }
+}
+
+impl ExternalKeystore {
+    /// Connect to the signer over `transport`, and fetch the list of keys it holds.
+    pub fn new(transport: SignerTransport) -> Result<Self, anyhow::Error> {
+        let SignerResponse::Keys(external_keys) = call(&transport, &SignerRequest::Keys)? else {
+            bail!("Unexpected response from external signer, expected keys");
+        };
+
+        let mut keys = BTreeMap::new();
+        let mut aliases = BTreeMap::new();
+        for ExternalKey { public_key, alias } in external_keys {
+            let public_key = PublicKey::decode_base64(&public_key)
+                .map_err(|e| anyhow!("Invalid public key from external signer: {e}"))?;
+            let address = SuiAddress::from(&public_key);
+
+            // Keys without an alias get one derived from their address, so that it is stable
+            // across sessions.
+            let alias =
+                alias.unwrap_or_else(|| format!("external-{}", &address.to_string()[2..10]));
+
+            aliases.insert(
+                address,
+                Alias {
+                    alias,
+                    public_key_base64: public_key.encode_base64(),
+                },
+            );
+            keys.insert(address, public_key);
+        }
+
+        Ok(Self {
+            transport,
+            keys,
+            aliases,
+        })
+    }
+
+    pub fn transport(&self) -> &SignerTransport {
+        &self.transport
+    }
+
+    fn request_signature(
+        &self,
+        address: &SuiAddress,
+        msg: &[u8],
+    ) -> Result<Signature, anyhow::Error> {
+        let request = SignerRequest::Sign {
+            address: *address,
+            message: Base64::encode(msg),
+        };
+
+        let SignerResponse::Signature(signature) = call(&self.transport, &request)? else {
+            bail!("Unexpected response from external signer, expected a signature");
+        };
+
+        let bytes = Base64::decode(&signature)
+            .map_err(|e| anyhow!("Invalid signature encoding from external signer: {e}"))?;
+        let signature = Signature::from_bytes(&bytes)
+            .map_err(|e| anyhow!("Invalid signature from external signer: {e}"))?;
+
+        // Make sure the signer signed with the key that was asked for.
+        let public_key =
+            PublicKey::try_from_bytes(signature.scheme(), signature.public_key_bytes())
+                .map_err(|e| anyhow!("Invalid public key in external signature: {e}"))?;
+        if SuiAddress::from(&public_key) != *address {
+            bail!("External signer signed with the wrong key, expected one for [{address}]");
+        }
+
+        Ok(signature)
+    }
+}
+
+/// Respond to `request` using the keys in `keystore`. A building block for implementing an external
+/// signer on top of another keystore (e.g. for testing).
+pub fn respond(keystore: &impl AccountKeystore, request: SignerRequest) -> SignerResponse {
+    match request {
+        SignerRequest::Keys => SignerResponse::Keys(
+            keystore
+                .entries()
+                .into_iter()
+                .map(|public_key| ExternalKey {
+                    alias: keystore.get_alias(&SuiAddress::from(&public_key)).ok(),
+                    public_key: public_key.encode_base64(),
+                })
+                .collect(),
+        ),
+
+        SignerRequest::Sign { address, message } => {
+            let message = match Base64::decode(&message) {
+                Ok(message) => message,
+                Err(e) => return SignerResponse::Error(format!("Invalid message encoding: {e}")),
+            };
+
+            match keystore.sign_hashed(&address, &message) {
+                Ok(signature) => SignerResponse::Signature(Base64::encode(signature.as_ref())),
+                Err(e) => SignerResponse::Error(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Send `request` to the signer over `transport`, and wait for its response. Error responses are
+/// turned into errors.
+fn call(
+    transport: &SignerTransport,
+    request: &SignerRequest,
+) -> Result<SignerResponse, anyhow::Error> {
+    let mut line = serde_json::to_string(request).context("Cannot serialize signer request")?;
+    line.push('\n');
+
+    let response = match transport {
+        SignerTransport::Command { command, args } => {
+            let mut child = Command::new(command)
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::inherit())
+                .spawn()
+                .with_context(|| format!("Cannot start external signer: {command}"))?;
+
+            // Dropping stdin after writing the request closes it, to signal the end of input.
+            child
+                .stdin
+                .take()
+                .context("External signer has no stdin")?
+                .write_all(line.as_bytes())
+                .context("Cannot send request to external signer")?;
+
+            let stdout = child
+                .stdout
+                .take()
+                .context("External signer has no stdout")?;
+            let response = read_response(stdout)?;
+
+            let status = child.wait().context("External signer did not exit")?;
+            if !status.success() {
+                bail!("External signer exited with {status}");
+            }
+
+            response
+        }
+
+        #[cfg(unix)]
+        SignerTransport::Socket { path } => {
+            use std::os::unix::net::UnixStream;
+
+            let mut stream = UnixStream::connect(path).with_context(|| {
+                format!("Cannot connect to external signer at {}", path.display())
+            })?;
+
+            stream
+                .write_all(line.as_bytes())
+                .context("Cannot send request to external signer")?;
+            stream
+                .shutdown(std::net::Shutdown::Write)
+                .context("Cannot send request to external signer")?;
+
+            read_response(stream)?
+        }
+
+        #[cfg(not(unix))]
+        SignerTransport::Socket { .. } => {
+            bail!("Socket transport for external signers is only supported on Unix")
+        }
+    };
+
+    match response {
+        SignerResponse::Error(e) => Err(anyhow!("External signer error: {e}")),
+        response => Ok(response),
+    }
+}
+
+fn read_response(reader: impl std::io::Read) -> Result<SignerResponse, anyhow::Error> {
+    let mut line = String::new();
+    BufReader::new(reader)
+        .read_line(&mut line)
+        .context("Cannot read response from external signer")?;
+
+    serde_json::from_str(&line).context("Cannot deserialize response from external signer")
+}
@@ -6,11 +6,14 @@ use std::str::FromStr;
 
 use fastcrypto::hash::HashFunction;
 use fastcrypto::traits::EncodeDecodeBase64;
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_keys::encrypted_keystore::EncryptedFileBasedKeystore;
+use sui_keys::external_keystore::{ExternalKeystore, SignerTransport};
 use sui_keys::key_derive::generate_new_key;
 use tempfile::TempDir;
 
 use sui_keys::keystore::{AccountKeystore, Alias, FileBasedKeystore, InMemKeystore, Keystore};
-use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignatureInner};
+use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignature, SuiSignatureInner};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -308,6 +311,226 @@ fn remove_key_test() {
     assert!(!aliases_content.contains("test_key"));
 }
 
+#[test]
+fn encrypted_keystore_round_trip_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = Keystore::from(
+        EncryptedFileBasedKeystore::create(&keystore_path, "correct horse").unwrap(),
+    );
+
+    let (addr, _, _) = keystore
+        .generate(
+            SignatureScheme::ED25519,
+            Some("my_key".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+
+    // No private key material is stored in plaintext.
+    let contents = fs::read_to_string(&keystore_path).unwrap();
+    let exported = keystore.export(&addr).unwrap().encode_base64();
+    assert!(!contents.contains(&exported));
+    assert!(EncryptedFileBasedKeystore::is_encrypted_keystore(
+        &keystore_path
+    ));
+
+    // Loading the keystore again starts locked, but addresses and aliases are available.
+    let mut reloaded = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    assert!(reloaded.is_locked());
+    assert_eq!(vec![addr], reloaded.addresses());
+    assert_eq!("my_key", reloaded.get_alias(&addr).unwrap());
+    assert!(reloaded.export(&addr).is_err());
+    assert!(reloaded
+        .sign_secure(&addr, &"hello", Intent::sui_transaction())
+        .is_err());
+
+    // A wrong passphrase does not unlock the keystore.
+    assert!(reloaded.unlock("wrong horse").is_err());
+    assert!(reloaded.is_locked());
+
+    reloaded.unlock("correct horse").unwrap();
+    assert_eq!(exported, reloaded.export(&addr).unwrap().encode_base64());
+    assert!(reloaded
+        .sign_secure(&addr, &"hello", Intent::sui_transaction())
+        .is_ok());
+
+    reloaded.lock();
+    assert!(reloaded.is_locked());
+    assert!(reloaded.export(&addr).is_err());
+}
+
+#[test]
+fn encrypted_keystore_locked_mutation_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = EncryptedFileBasedKeystore::create(&keystore_path, "passphrase").unwrap();
+    keystore.lock();
+
+    assert!(keystore
+        .generate(SignatureScheme::ED25519, None, None, None)
+        .is_err());
+    assert!(keystore.addresses().is_empty());
+}
+
+#[test]
+fn encrypted_keystore_change_passphrase_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = EncryptedFileBasedKeystore::create(&keystore_path, "old").unwrap();
+    let (addr, _, _) = keystore
+        .generate(SignatureScheme::ED25519, None, None, None)
+        .unwrap();
+
+    assert!(keystore.change_passphrase("wrong", "new").is_err());
+    keystore.change_passphrase("old", "new").unwrap();
+    assert!(!temp_dir.path().join("sui.keystore.bak").exists());
+
+    let mut reloaded = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    assert!(reloaded.unlock("old").is_err());
+    reloaded.unlock("new").unwrap();
+    assert!(reloaded.export(&addr).is_ok());
+}
+
+#[test]
+fn encrypted_keystore_migration_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut plaintext = FileBasedKeystore::new(&keystore_path).unwrap();
+    let (addr, _, _) = plaintext
+        .generate(
+            SignatureScheme::ED25519,
+            Some("migrated".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+    let exported = plaintext.export(&addr).unwrap().encode_base64();
+    assert!(!EncryptedFileBasedKeystore::is_encrypted_keystore(
+        &keystore_path
+    ));
+
+    // Migrate in place.
+    EncryptedFileBasedKeystore::from_file_keystore(&plaintext, &keystore_path, "passphrase")
+        .unwrap();
+
+    // The plaintext backup is only kept until the encrypted keystore has been verified.
+    assert!(!temp_dir.path().join("sui.keystore.bak").exists());
+    assert!(!temp_dir.path().join("sui.keystore.tmp").exists());
+
+    assert!(EncryptedFileBasedKeystore::is_encrypted_keystore(
+        &keystore_path
+    ));
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+
+    let mut migrated = EncryptedFileBasedKeystore::new(&keystore_path).unwrap();
+    assert_eq!("migrated", migrated.get_alias(&addr).unwrap());
+    migrated.unlock("passphrase").unwrap();
+    assert_eq!(exported, migrated.export(&addr).unwrap().encode_base64());
+}
+
+#[test]
+fn external_keystore_command_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("signer.keystore");
+    let mut signer_keys = FileBasedKeystore::new(&keystore_path).unwrap();
+    let (addr, _, _) = signer_keys
+        .generate(
+            SignatureScheme::ED25519,
+            Some("remote".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+
+    let mut keystore = Keystore::from(
+        ExternalKeystore::new(SignerTransport::Command {
+            command: env!("CARGO_BIN_EXE_mock-signer").to_string(),
+            args: vec![keystore_path.to_str().unwrap().to_string()],
+        })
+        .unwrap(),
+    );
+
+    assert_eq!(vec![addr], keystore.addresses());
+    assert_eq!("remote", keystore.get_alias(&addr).unwrap());
+
+    let intent_msg = IntentMessage::new(Intent::sui_transaction(), "hello".to_string());
+    let signature = keystore
+        .sign_secure(&addr, &intent_msg.value, intent_msg.intent.clone())
+        .unwrap();
+    signature
+        .verify_secure(&intent_msg, addr, SignatureScheme::ED25519)
+        .unwrap();
+
+    // The signature is the same as if the key had been used directly.
+    let expected = signer_keys
+        .sign_secure(&addr, &intent_msg.value, intent_msg.intent)
+        .unwrap();
+    assert_eq!(expected, signature);
+
+    // Keys can't be extracted from, or added to, the external signer.
+    assert!(keystore.export(&addr).is_err());
+    assert!(keystore
+        .generate(SignatureScheme::ED25519, None, None, None)
+        .is_err());
+
+    // Unknown addresses are rejected without consulting the signer.
+    let (other, _, _, _) = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
+    assert!(keystore
+        .sign_secure(&other, &"hello", Intent::sui_transaction())
+        .is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn external_keystore_socket_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("signer.keystore");
+    let socket_path = temp_dir.path().join("signer.sock");
+    let mut signer_keys = FileBasedKeystore::new(&keystore_path).unwrap();
+    let (addr, _, _) = signer_keys
+        .generate(SignatureScheme::ED25519, None, None, None)
+        .unwrap();
+
+    let mut signer = std::process::Command::new(env!("CARGO_BIN_EXE_mock-signer"))
+        .arg(&keystore_path)
+        .arg("--socket")
+        .arg(&socket_path)
+        .spawn()
+        .unwrap();
+
+    for _ in 0..100 {
+        if socket_path.exists() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+
+    let keystore = ExternalKeystore::new(SignerTransport::Socket {
+        path: socket_path.clone(),
+    });
+
+    let signature = keystore.as_ref().map(|keystore| {
+        keystore.sign_secure(&addr, &"hello".to_string(), Intent::sui_transaction())
+    });
+
+    signer.kill().unwrap();
+    signer.wait().unwrap();
+
+    let keystore = keystore.unwrap();
+    assert_eq!(vec![addr], keystore.addresses());
+    signature
+        .unwrap()
+        .unwrap()
+        .verify_secure(
+            &IntentMessage::new(Intent::sui_transaction(), "hello".to_string()),
+            addr,
+            SignatureScheme::ED25519,
+        )
+        .unwrap();
+}
+
 fn alias_names(aliases: Vec<&Alias>) -> Vec<&str> {
     aliases
         .into_iter()
@@ -6,12 +6,15 @@ use async_trait::async_trait;
 use sui_data_ingestion_core::Worker;
 use sui_types::full_checkpoint_content::CheckpointData;
 
-pub struct KvWorker {
-    pub client: BigTableClient,
+pub struct KvWorker<C = BigTableClient> {
+    pub client: C,
 }
 
 #[async_trait]
-impl Worker for KvWorker {
+impl<C> Worker for KvWorker<C>
+where
+    C: KeyValueStoreReader + KeyValueStoreWriter + Clone + Send + Sync + 'static,
+{
     type Result = ();
 
     async fn process_checkpoint(&self, checkpoint: &CheckpointData) -> anyhow::Result<()> {
@@ -4,9 +4,15 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use prometheus::Registry;
 use std::io::{self, Write};
+use std::path::PathBuf;
 use std::str::FromStr;
-use sui_data_ingestion_core::{DataIngestionMetrics, IndexerExecutor, ReaderOptions, WorkerPool};
-use sui_kvstore::{BigTableClient, BigTableProgressStore, KeyValueStoreReader, KvWorker};
+use sui_data_ingestion_core::{
+    DataIngestionMetrics, IndexerExecutor, ProgressStore, ReaderOptions, Worker, WorkerPool,
+};
+use sui_kvstore::{
+    BigTableClient, BigTableProgressStore, KeyValueStoreReader, KvWorker, RocksDbClient,
+    RocksDbProgressStore,
+};
 use sui_types::base_types::ObjectID;
 use sui_types::digests::TransactionDigest;
 use sui_types::storage::ObjectKey;
@@ -15,7 +21,12 @@ use tokio::sync::oneshot;
 
 #[derive(Parser)]
 struct App {
-    instance_id: String,
+    /// BigTable instance to use. Not needed when a local store is used with `--rocksdb-path`.
+    #[arg(required_unless_present = "rocksdb_path")]
+    instance_id: Option<String>,
+    /// Path of a local RocksDB store to use instead of BigTable.
+    #[arg(long, conflicts_with = "instance_id")]
+    rocksdb_path: Option<PathBuf>,
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -45,74 +56,112 @@ async fn main() -> Result<()> {
     let _guard = TelemetryConfig::new().with_env().init();
     let app = App::parse();
     match app.command {
-        Some(Command::Ingestion { network }) => {
-            let client = BigTableClient::new_remote(
-                app.instance_id,
-                false,
-                None,
-                "ingestion".to_string(),
-                None,
-                None,
-            )
-            .await?;
-            let (_exit_sender, exit_receiver) = oneshot::channel();
-            let mut executor = IndexerExecutor::new(
-                BigTableProgressStore::new(client.clone()),
-                1,
-                DataIngestionMetrics::new(&Registry::new()),
-            );
-            let worker_pool = WorkerPool::new(KvWorker { client }, "bigtable".to_string(), 50);
-            executor.register(worker_pool).await?;
-            executor
-                .run(
-                    tempfile::tempdir()?.keep(),
-                    Some(format!("https://checkpoints.{}.sui.io", network)),
-                    vec![],
-                    ReaderOptions::default(),
-                    exit_receiver,
+        Some(Command::Ingestion { network }) => match (app.rocksdb_path, app.instance_id) {
+            (Some(path), _) => {
+                let client = RocksDbClient::new(&path)?;
+                ingest(
+                    RocksDbProgressStore::new(client.clone()),
+                    KvWorker { client },
+                    "rocksdb",
+                    network,
                 )
                 .await?;
-        }
-        Some(Command::Fetch { entry }) => {
-            let mut client = BigTableClient::new_remote(
-                app.instance_id,
-                true,
-                None,
-                "cli".to_string(),
-                None,
-                None,
-            )
-            .await?;
-            let result = match entry {
-                Entry::Epoch { id } => client.get_epoch(id).await?.map(|e| bcs::to_bytes(&e)),
-                Entry::Object { id, version } => {
-                    let objects = client
-                        .get_objects(&[ObjectKey(ObjectID::from_str(&id)?, version.into())])
-                        .await?;
-                    objects.first().map(bcs::to_bytes)
-                }
-                Entry::Checkpoint { id } => {
-                    let checkpoints = client.get_checkpoints(&[id]).await?;
-                    checkpoints.first().map(bcs::to_bytes)
-                }
-                Entry::Transaction { id } => {
-                    let transactions = client
-                        .get_transactions(&[TransactionDigest::from_str(&id)?])
-                        .await?;
-                    transactions.first().map(bcs::to_bytes)
-                }
-                Entry::Watermark => {
-                    let watermark = client.get_latest_checkpoint().await?;
-                    println!("watermark is {}", watermark);
-                    return Ok(());
-                }
-            };
-            match result {
-                Some(bytes) => io::stdout().write_all(&bytes?)?,
-                None => println!("not found"),
             }
-        }
+            (None, Some(instance_id)) => {
+                let client = BigTableClient::new_remote(
+                    instance_id,
+                    false,
+                    None,
+                    "ingestion".to_string(),
+                    None,
+                    None,
+                )
+                .await?;
+                ingest(
+                    BigTableProgressStore::new(client.clone()),
+                    KvWorker { client },
+                    "bigtable",
+                    network,
+                )
+                .await?;
+            }
+            (None, None) => unreachable!("enforced by the argument parser"),
+        },
+        Some(Command::Fetch { entry }) => match (app.rocksdb_path, app.instance_id) {
+            (Some(path), _) => fetch(RocksDbClient::new(&path)?, entry).await?,
+            (None, Some(instance_id)) => {
+                let client = BigTableClient::new_remote(
+                    instance_id,
+                    true,
+                    None,
+                    "cli".to_string(),
+                    None,
+                    None,
+                )
+                .await?;
+                fetch(client, entry).await?
+            }
+            (None, None) => unreachable!("enforced by the argument parser"),
+        },
         None => println!("no command provided"),
     }
     Ok(())
 }
+
+async fn ingest<P: ProgressStore, W: Worker + 'static>(
+    progress_store: P,
+    worker: W,
+    name: &str,
+    network: String,
+) -> Result<()> {
+    let (_exit_sender, exit_receiver) = oneshot::channel();
+    let mut executor = IndexerExecutor::new(
+        progress_store,
+        1,
+        DataIngestionMetrics::new(&Registry::new()),
+    );
+    let worker_pool = WorkerPool::new(worker, name.to_string(), 50);
+    executor.register(worker_pool).await?;
+    executor
+        .run(
+            tempfile::tempdir()?.keep(),
+            Some(format!("https://checkpoints.{}.sui.io", network)),
+            vec![],
+            ReaderOptions::default(),
+            exit_receiver,
+        )
+        .await?;
+    Ok(())
+}
+
+async fn fetch<C: KeyValueStoreReader>(mut client: C, entry: Entry) -> Result<()> {
+    let result = match entry {
+        Entry::Epoch { id } => client.get_epoch(id).await?.map(|e| bcs::to_bytes(&e)),
+        Entry::Object { id, version } => {
+            let objects = client
+                .get_objects(&[ObjectKey(ObjectID::from_str(&id)?, version.into())])
+                .await?;
+            objects.first().map(bcs::to_bytes)
+        }
+        Entry::Checkpoint { id } => {
+            let checkpoints = client.get_checkpoints(&[id]).await?;
+            checkpoints.first().map(bcs::to_bytes)
+        }
+        Entry::Transaction { id } => {
+            let transactions = client
+                .get_transactions(&[TransactionDigest::from_str(&id)?])
+                .await?;
+            transactions.first().map(bcs::to_bytes)
+        }
+        Entry::Watermark => {
+            let watermark = client.get_latest_checkpoint().await?;
+            println!("watermark is {}", watermark);
+            return Ok(());
+        }
+    };
+    match result {
+        Some(bytes) => io::stdout().write_all(&bytes?)?,
+        None => println!("not found"),
+    }
+    Ok(())
+}
@@ -0,0 +1,352 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Checkpoint, KeyValueStoreReader, KeyValueStoreWriter, TransactionData};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
+use sui_types::base_types::{EpochId, ObjectID, TransactionDigest};
+use sui_types::digests::CheckpointDigest;
+use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, CheckpointSummary};
+use sui_types::messages_consensus::TimestampMs;
+use sui_types::object::Object;
+use sui_types::storage::{EpochInfo, ObjectKey};
+use typed_store::rocks::{default_db_options, open_cf_opts, DBMap, MetricConf};
+use typed_store::{DBMapUtils, Map};
+
+/// The watermark table holds a single row.
+const WATERMARK_KEY: u8 = 0;
+
+/// Local tables mirroring the BigTable schema (see `bigtable/client.rs`).
+#[derive(DBMapUtils)]
+pub struct KvStoreTables {
+    /// Objects by (id, version). Versions of an object are adjacent and sorted, which makes
+    /// a reversed scan from `ObjectKey::max_for_id` return the latest version.
+    objects: DBMap<ObjectKey, Object>,
+    transactions: DBMap<TransactionDigest, TransactionData>,
+    checkpoints: DBMap<CheckpointSequenceNumber, Checkpoint>,
+    checkpoints_by_digest: DBMap<CheckpointDigest, CheckpointSequenceNumber>,
+    /// The next checkpoint to ingest
+    watermark: DBMap<u8, CheckpointSequenceNumber>,
+    epochs: DBMap<EpochId, EpochInfo>,
+}
+
+/// Key-value store backed by a local RocksDB instance, an alternative to `BigTableClient`
+/// that does not need a cloud account.
+#[derive(Clone)]
+pub struct RocksDbClient {
+    tables: Arc<KvStoreTables>,
+}
+
+#[async_trait]
+impl KeyValueStoreWriter for RocksDbClient {
+    async fn save_objects(&mut self, objects: &[&Object], _: TimestampMs) -> Result<()> {
+        let mut batch = self.tables.objects.batch();
+        batch.insert_batch(
+            &self.tables.objects,
+            objects
+                .iter()
+                .map(|object| (ObjectKey(object.id(), object.version()), *object)),
+        )?;
+        Ok(batch.write()?)
+    }
+
+    async fn save_transactions(&mut self, transactions: &[TransactionData]) -> Result<()> {
+        let mut batch = self.tables.transactions.batch();
+        batch.insert_batch(
+            &self.tables.transactions,
+            transactions
+                .iter()
+                .map(|transaction| (*transaction.transaction.digest(), transaction)),
+        )?;
+        Ok(batch.write()?)
+    }
+
+    async fn save_checkpoint(&mut self, checkpoint: &CheckpointData) -> Result<()> {
+        let summary = checkpoint.checkpoint_summary.data();
+        let sequence_number = summary.sequence_number;
+        let entry = Checkpoint {
+            summary: summary.clone(),
+            contents: checkpoint.checkpoint_contents.clone(),
+            signatures: checkpoint.checkpoint_summary.auth_sig().clone(),
+        };
+        let mut batch = self.tables.checkpoints.batch();
+        batch.insert_batch(&self.tables.checkpoints, [(sequence_number, entry)])?;
+        batch.insert_batch(
+            &self.tables.checkpoints_by_digest,
+            [(*checkpoint.checkpoint_summary.digest(), sequence_number)],
+        )?;
+        Ok(batch.write()?)
+    }
+
+    async fn save_watermark(&mut self, watermark: CheckpointSequenceNumber) -> Result<()> {
+        // the watermark only moves forward, as the latest row in the BigTable watermark table
+        if self.get_latest_checkpoint().await? < watermark {
+            self.tables.watermark.insert(&WATERMARK_KEY, &watermark)?;
+        }
+        Ok(())
+    }
+
+    async fn save_epoch(&mut self, epoch: EpochInfo) -> Result<()> {
+        Ok(self.tables.epochs.insert(&epoch.epoch, &epoch)?)
+    }
+}
+
+#[async_trait]
+impl KeyValueStoreReader for RocksDbClient {
+    async fn get_objects(&mut self, object_keys: &[ObjectKey]) -> Result<Vec<Object>> {
+        Ok(self
+            .tables
+            .objects
+            .multi_get(object_keys)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    async fn get_transactions(
+        &mut self,
+        transactions: &[TransactionDigest],
+    ) -> Result<Vec<TransactionData>> {
+        Ok(self
+            .tables
+            .transactions
+            .multi_get(transactions)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    async fn get_checkpoints(
+        &mut self,
+        sequence_numbers: &[CheckpointSequenceNumber],
+    ) -> Result<Vec<Checkpoint>> {
+        Ok(self
+            .tables
+            .checkpoints
+            .multi_get(sequence_numbers)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    async fn get_checkpoint_by_digest(
+        &mut self,
+        digest: CheckpointDigest,
+    ) -> Result<Option<Checkpoint>> {
+        let Some(sequence_number) = self.tables.checkpoints_by_digest.get(&digest)? else {
+            return Ok(None);
+        };
+        Ok(self.tables.checkpoints.get(&sequence_number)?)
+    }
+
+    async fn get_latest_checkpoint(&mut self) -> Result<CheckpointSequenceNumber> {
+        Ok(self
+            .tables
+            .watermark
+            .get(&WATERMARK_KEY)?
+            .unwrap_or_default())
+    }
+
+    async fn get_latest_checkpoint_summary(&mut self) -> Result<Option<CheckpointSummary>> {
+        let sequence_number = self.get_latest_checkpoint().await?;
+        if sequence_number == 0 {
+            return Ok(None);
+        }
+        Ok(self
+            .tables
+            .checkpoints
+            .get(&(sequence_number - 1))?
+            .map(|checkpoint| checkpoint.summary))
+    }
+
+    async fn get_latest_object(&mut self, object_id: &ObjectID) -> Result<Option<Object>> {
+        let mut iter = self.tables.objects.reversed_safe_iter_with_bounds(
+            Some(ObjectKey::min_for_id(object_id)),
+            Some(ObjectKey::max_for_id(object_id)),
+        )?;
+        Ok(iter.next().transpose()?.map(|(_, object)| object))
+    }
+
+    async fn get_epoch(&mut self, epoch_id: EpochId) -> Result<Option<EpochInfo>> {
+        Ok(self.tables.epochs.get(&epoch_id)?)
+    }
+
+    async fn get_latest_epoch(&mut self) -> Result<Option<EpochInfo>> {
+        let mut iter = self
+            .tables
+            .epochs
+            .reversed_safe_iter_with_bounds(None, None)?;
+        Ok(iter.next().transpose()?.map(|(_, epoch)| epoch))
+    }
+}
+
+impl RocksDbClient {
+    /// Opens (or creates) the store at `path`, failing if it is not a RocksDB directory or is
+    /// locked by another process.
+    pub fn new(path: &Path) -> Result<Self> {
+        let options = default_db_options();
+        let table_names = KvStoreTables::describe_tables();
+        let cfs: Vec<_> = table_names
+            .keys()
+            .map(|name| (name.as_str(), options.options.clone()))
+            .collect();
+        let db = open_cf_opts(path, None, MetricConf::new("kvstore"), &cfs)
+            .with_context(|| format!("Cannot open RocksDB at {}", path.display()))?;
+        let rw_options = &options.rw_options;
+        let tables = KvStoreTables {
+            objects: DBMap::reopen(&db, Some("objects"), rw_options, false)?,
+            transactions: DBMap::reopen(&db, Some("transactions"), rw_options, false)?,
+            checkpoints: DBMap::reopen(&db, Some("checkpoints"), rw_options, false)?,
+            checkpoints_by_digest: DBMap::reopen(
+                &db,
+                Some("checkpoints_by_digest"),
+                rw_options,
+                false,
+            )?,
+            watermark: DBMap::reopen(&db, Some("watermark"), rw_options, false)?,
+            epochs: DBMap::reopen(&db, Some("epochs"), rw_options, false)?,
+        };
+        Ok(Self {
+            tables: Arc::new(tables),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KvWorker;
+    use sui_data_ingestion_core::Worker;
+    use sui_types::test_checkpoint_data_builder::TestCheckpointDataBuilder;
+
+    fn find_object(checkpoint: &CheckpointData, object_id: ObjectID) -> Object {
+        checkpoint
+            .transactions
+            .iter()
+            .flat_map(|transaction| &transaction.output_objects)
+            .find(|object| object.id() == object_id)
+            .unwrap()
+            .clone()
+    }
+
+    #[tokio::test]
+    async fn checkpoints_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut client = RocksDbClient::new(dir.path())?;
+        assert_eq!(client.get_latest_checkpoint().await?, 0);
+        assert!(client.get_latest_checkpoint_summary().await?.is_none());
+
+        // Checkpoint 0 must carry the system state, so start from checkpoint 1.
+        let mut builder = TestCheckpointDataBuilder::new(1)
+            .start_transaction(0)
+            .create_owned_object(0)
+            .finish_transaction();
+        let first = builder.build_checkpoint();
+        let mut builder = builder
+            .start_transaction(0)
+            .mutate_owned_object(0)
+            .finish_transaction();
+        let second = builder.build_checkpoint();
+
+        let worker = KvWorker {
+            client: client.clone(),
+        };
+        worker.process_checkpoint(&first).await?;
+        worker.process_checkpoint(&second).await?;
+        client.save_watermark(3).await?;
+
+        let checkpoints = client.get_checkpoints(&[1, 2, 3]).await?;
+        assert_eq!(checkpoints.len(), 2);
+        assert_eq!(&checkpoints[0].summary, first.checkpoint_summary.data());
+        assert_eq!(&checkpoints[1].summary, second.checkpoint_summary.data());
+
+        let by_digest = client
+            .get_checkpoint_by_digest(*second.checkpoint_summary.digest())
+            .await?
+            .unwrap();
+        assert_eq!(by_digest.summary.sequence_number, 2);
+        assert!(client
+            .get_checkpoint_by_digest(CheckpointDigest::random())
+            .await?
+            .is_none());
+
+        let digests: Vec<_> = [&first, &second]
+            .iter()
+            .map(|checkpoint| *checkpoint.transactions[0].transaction.digest())
+            .collect();
+        let transactions = client.get_transactions(&digests).await?;
+        assert_eq!(transactions.len(), 2);
+        for (transaction, (digest, checkpoint)) in transactions.iter().zip(digests.iter().zip(1..))
+        {
+            assert_eq!(transaction.transaction.digest(), digest);
+            assert_eq!(transaction.checkpoint_number, checkpoint);
+        }
+
+        let object_id = TestCheckpointDataBuilder::derive_object_id(0);
+        let created = find_object(&first, object_id);
+        let mutated = find_object(&second, object_id);
+        assert!(created.version() < mutated.version());
+
+        let objects = client
+            .get_objects(&[
+                ObjectKey(object_id, created.version()),
+                ObjectKey(object_id, mutated.version()),
+            ])
+            .await?;
+        assert_eq!(objects, vec![created, mutated.clone()]);
+        assert_eq!(client.get_latest_object(&object_id).await?, Some(mutated));
+        assert!(client
+            .get_latest_object(&ObjectID::random())
+            .await?
+            .is_none());
+
+        assert_eq!(client.get_latest_checkpoint().await?, 3);
+        let summary = client.get_latest_checkpoint_summary().await?.unwrap();
+        assert_eq!(summary.sequence_number, 2);
+
+        // The watermark never moves backwards.
+        client.save_watermark(2).await?;
+        assert_eq!(client.get_latest_checkpoint().await?, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn epochs_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut client = RocksDbClient::new(dir.path())?;
+        assert!(client.get_latest_epoch().await?.is_none());
+
+        let epochs: Vec<_> = (0..3)
+            .map(|epoch| EpochInfo {
+                epoch,
+                start_checkpoint: Some(epoch * 10),
+                ..Default::default()
+            })
+            .collect();
+        for epoch in epochs.iter().rev() {
+            client.save_epoch(epoch.clone()).await?;
+        }
+
+        assert_eq!(client.get_epoch(1).await?, Some(epochs[1].clone()));
+        assert!(client.get_epoch(3).await?.is_none());
+        assert_eq!(client.get_latest_epoch().await?, Some(epochs[2].clone()));
+        Ok(())
+    }
+
+    #[test]
+    fn open_fails_without_panicking() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("file");
+        std::fs::write(&file, b"not a database")?;
+        assert!(RocksDbClient::new(&file).is_err());
+
+        // The first client holds the lock on the database.
+        let _client = RocksDbClient::new(dir.path())?;
+        assert!(RocksDbClient::new(dir.path()).is_err());
+        Ok(())
+    }
+}
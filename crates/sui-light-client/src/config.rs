@@ -58,6 +58,10 @@ impl Config {
     pub fn genesis_path(&self) -> PathBuf {
         self.checkpoint_summary_dir.join(&self.genesis_filename)
     }
+
+    pub fn trusted_state_path(&self) -> PathBuf {
+        self.checkpoint_summary_dir.join("trusted_state.bcs")
+    }
 }
 
 #[cfg(test)]
@@ -15,8 +15,13 @@ pub mod graphql;
 
 pub mod verifier;
 
+pub mod light_client;
+
 #[doc(inline)]
 pub use proof::*;
 
 #[doc(inline)]
 pub use construct::*;
+
+#[doc(inline)]
+pub use light_client::LightClient;
@@ -0,0 +1,313 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::Config;
+use crate::construct::construct_proof;
+use crate::object_store::SuiObjectStore;
+use crate::proof::{verify_proof, Proof, ProofTarget};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use sui_config::genesis::Genesis;
+use sui_types::committee::{Committee, EpochId};
+use sui_types::event::{Event, EventID};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointSequenceNumber, EndOfEpochData,
+};
+use sui_types::object::Object;
+use tracing::info;
+
+/// A light client that follows the chain from a trusted starting point, by downloading and
+/// verifying the last checkpoint of every epoch from the checkpoint object store, and uses the
+/// committees it learns about to verify objects and events.
+///
+/// The committees the client trusts are persisted (at `Config::trusted_state_path`), so that a
+/// client can pick up where it left off.
+pub struct LightClient {
+    config: Config,
+    object_store: SuiObjectStore,
+    state: TrustedState,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TrustedState {
+    /// Every committee the client has verified, by epoch.
+    committees: BTreeMap<EpochId, Committee>,
+
+    /// The last end-of-epoch checkpoint the client has verified, or `None` if it has not verified
+    /// any yet (i.e. it started from genesis).
+    last_end_of_epoch: Option<CheckpointSequenceNumber>,
+}
+
+impl LightClient {
+    /// Resume from the state persisted by a previous client, or start from the genesis blob
+    /// referenced by the config if there isn't one.
+    pub fn new(config: Config) -> Result<Self> {
+        let state_path = config.trusted_state_path();
+        if !state_path.exists() {
+            let genesis = Genesis::load(config.genesis_path())?;
+            return Self::from_genesis(config, &genesis);
+        }
+
+        let bytes = fs::read(&state_path)?;
+        let state: TrustedState = bcs::from_bytes(&bytes)
+            .map_err(|e| anyhow!("Unable to parse trusted state file: {e}"))?;
+
+        Self::with_state(config, state)
+    }
+
+    /// Start from the genesis committee.
+    pub fn from_genesis(config: Config, genesis: &Genesis) -> Result<Self> {
+        let committee = genesis
+            .committee()
+            .map_err(|e| anyhow!("Cannot load Genesis: {e}"))?;
+
+        let state = TrustedState {
+            committees: BTreeMap::from([(committee.epoch, committee)]),
+            last_end_of_epoch: None,
+        };
+
+        let client = Self::with_state(config, state)?;
+        client.persist()?;
+        Ok(client)
+    }
+
+    /// Start from an end-of-epoch checkpoint that is trusted by some other means, e.g. because it
+    /// was verified by another light client. The client trusts the committee it sets for the next
+    /// epoch, but will not be able to verify anything from earlier epochs.
+    pub fn from_trusted_checkpoint(
+        config: Config,
+        checkpoint: &CertifiedCheckpointSummary,
+    ) -> Result<Self> {
+        let committee = next_committee(checkpoint)?;
+        let state = TrustedState {
+            committees: BTreeMap::from([(committee.epoch, committee)]),
+            last_end_of_epoch: Some(*checkpoint.sequence_number()),
+        };
+
+        let client = Self::with_state(config, state)?;
+        client.persist()?;
+        Ok(client)
+    }
+
+    fn with_state(config: Config, state: TrustedState) -> Result<Self> {
+        if state.committees.is_empty() {
+            return Err(anyhow!("Light client must trust at least one committee"));
+        }
+
+        let object_store = SuiObjectStore::new(&config)?;
+        Ok(Self {
+            config,
+            object_store,
+            state,
+        })
+    }
+
+    /// The committee for the latest epoch the client knows about.
+    pub fn committee(&self) -> &Committee {
+        // Safe to unwrap, because the client always trusts at least one committee.
+        self.state.committees.values().next_back().unwrap()
+    }
+
+    /// The committee for `epoch`, if the client has verified it.
+    pub fn committee_for_epoch(&self, epoch: EpochId) -> Option<&Committee> {
+        self.state.committees.get(&epoch)
+    }
+
+    /// The latest epoch the client knows the committee for.
+    pub fn epoch(&self) -> EpochId {
+        self.committee().epoch
+    }
+
+    /// Follow the chain through every epoch that has ended since the client last synced, verifying
+    /// each epoch's last checkpoint with the committee of that epoch, and trusting the committee it
+    /// sets for the next. Progress is persisted after every epoch.
+    pub async fn sync(&mut self) -> Result<()> {
+        // The store's list of end-of-epoch checkpoints saves searching for them, but it is not
+        // authenticated, so every checkpoint it points to is still verified.
+        let hints = match self.object_store.end_of_epoch_checkpoints().await {
+            Ok(hints) => hints,
+            Err(e) => {
+                info!("Could not read end-of-epoch checkpoints from the object store: {e}");
+                vec![]
+            }
+        };
+
+        while let Some(checkpoint) = self.find_end_of_epoch_checkpoint(&hints).await? {
+            let committee = self.committee();
+            checkpoint
+                .verify_authority_signatures(committee)
+                .map_err(|e| anyhow!("Cannot verify checkpoint {}: {e}", checkpoint.digest()))?;
+
+            let next_committee = next_committee(&checkpoint)?;
+            info!(
+                "Epoch: {} Last Checkpoint: {}",
+                checkpoint.epoch(),
+                checkpoint.sequence_number()
+            );
+
+            self.state
+                .committees
+                .insert(next_committee.epoch, next_committee);
+            self.state.last_end_of_epoch = Some(*checkpoint.sequence_number());
+            self.persist()?;
+        }
+
+        Ok(())
+    }
+
+    /// Verify a proof, using the committee of the epoch its checkpoint belongs to.
+    pub fn verify_proof(&self, proof: &Proof) -> Result<()> {
+        let epoch = proof.checkpoint_summary.epoch();
+        let committee = self
+            .committee_for_epoch(epoch)
+            .ok_or_else(|| anyhow!("No trusted committee for epoch {epoch}, need to sync"))?;
+
+        verify_proof(committee, proof)
+    }
+
+    /// Verify that `object` was written by a transaction in checkpoint `checkpoint`.
+    pub async fn verify_object(
+        &self,
+        object: &Object,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<()> {
+        let target =
+            ProofTarget::new().add_object(object.compute_object_reference(), object.clone());
+        self.verify_target(target, checkpoint).await
+    }
+
+    /// Verify that `event` was emitted by a transaction in checkpoint `checkpoint`, with ID
+    /// `event_id`.
+    pub async fn verify_event(
+        &self,
+        event_id: EventID,
+        event: &Event,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<()> {
+        let target = ProofTarget::new().add_event(event_id, event.clone());
+        self.verify_target(target, checkpoint).await
+    }
+
+    async fn verify_target(
+        &self,
+        target: ProofTarget,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Result<()> {
+        let data = self
+            .object_store
+            .get_full_checkpoint(checkpoint)
+            .await
+            .map_err(|e| anyhow!("Cannot get full checkpoint: {e}"))?;
+
+        let proof = construct_proof(target, &data)?;
+        self.verify_proof(&proof)
+    }
+
+    /// Find the last checkpoint of the latest epoch the client knows the committee for, or `None`
+    /// if that epoch has not ended yet. The checkpoint returned has not been verified.
+    async fn find_end_of_epoch_checkpoint(
+        &self,
+        hints: &[CheckpointSequenceNumber],
+    ) -> Result<Option<CertifiedCheckpointSummary>> {
+        let epoch = self.epoch();
+        let start = self.state.last_end_of_epoch.unwrap_or(0);
+
+        if let Some(hint) = hints.iter().find(|seq| **seq > start) {
+            if let Some(checkpoint) = self
+                .object_store
+                .try_download_checkpoint_summary(*hint)
+                .await?
+            {
+                if checkpoint.epoch() == epoch && checkpoint.end_of_epoch_data.is_some() {
+                    return Ok(Some(checkpoint));
+                }
+            }
+        }
+
+        // Without a (good) hint, search for the last checkpoint in the store that belongs to the
+        // epoch, or an earlier one: Gallop forward from the start to find a checkpoint past it,
+        // and then binary search between the two.
+        let mut lo = start;
+        let mut step = 1;
+        let mut hi = loop {
+            let seq = lo + step;
+            if self.is_at_or_before_epoch(seq, epoch).await? {
+                lo = seq;
+                step *= 2;
+            } else {
+                break seq;
+            }
+        };
+
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if self.is_at_or_before_epoch(mid, epoch).await? {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        let Some(checkpoint) = self
+            .object_store
+            .try_download_checkpoint_summary(lo)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // The last checkpoint in the store may belong to the epoch without being its last
+        // checkpoint, if the epoch is still in progress.
+        if checkpoint.epoch() == epoch && checkpoint.end_of_epoch_data.is_some() {
+            Ok(Some(checkpoint))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Whether checkpoint `seq` is in the store, and belongs to `epoch` or an earlier epoch.
+    async fn is_at_or_before_epoch(
+        &self,
+        seq: CheckpointSequenceNumber,
+        epoch: EpochId,
+    ) -> Result<bool> {
+        Ok(self
+            .object_store
+            .try_download_checkpoint_summary(seq)
+            .await?
+            .is_some_and(|checkpoint| checkpoint.epoch() <= epoch))
+    }
+
+    fn persist(&self) -> Result<()> {
+        let bytes = bcs::to_bytes(&self.state)
+            .map_err(|e| anyhow!("Unable to serialize trusted state: {e}"))?;
+
+        // Write to a temporary file first, so that a crash can't leave a partially written state.
+        let path = self.config.trusted_state_path();
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+}
+
+/// The committee for the epoch after the one that `checkpoint` ends.
+fn next_committee(checkpoint: &CertifiedCheckpointSummary) -> Result<Committee> {
+    let Some(EndOfEpochData {
+        next_epoch_committee,
+        ..
+    }) = &checkpoint.end_of_epoch_data
+    else {
+        return Err(anyhow!(
+            "Checkpoint {} is not an end-of-epoch checkpoint",
+            checkpoint.sequence_number()
+        ));
+    };
+
+    Ok(Committee::new(
+        checkpoint.epoch().checked_add(1).unwrap(),
+        next_epoch_committee.iter().cloned().collect(),
+    ))
+}
@@ -35,6 +35,32 @@ impl SuiObjectStore {
         Ok(blob.checkpoint_summary)
     }
 
+    /// Like `download_checkpoint_summary`, but returns `None` if the store does not have the
+    /// checkpoint (yet).
+    pub async fn try_download_checkpoint_summary(
+        &self,
+        checkpoint_number: u64,
+    ) -> Result<Option<CertifiedCheckpointSummary>> {
+        let path = Path::from(format!("{}.chk", checkpoint_number));
+        let response = match self.store.get(&path).await {
+            Ok(response) => response,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let bytes = response.bytes().await?;
+        let (_, blob) = bcs::from_bytes::<(u8, CheckpointData)>(&bytes)?;
+        Ok(Some(blob.checkpoint_summary))
+    }
+
+    /// The sequence numbers of end-of-epoch checkpoints, as listed in the store's `epochs.json`.
+    /// This list is not authenticated, and should only be used as a hint.
+    pub async fn end_of_epoch_checkpoints(&self) -> Result<Vec<u64>> {
+        let response = self.store.get(&Path::from("epochs.json")).await?;
+        let bytes = response.bytes().await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
     pub async fn get_full_checkpoint(&self, checkpoint_number: u64) -> Result<CheckpointData> {
         let path = Path::from(format!("{}.chk", checkpoint_number));
         info!("Request full checkpoint: {}", path);
@@ -0,0 +1,108 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::anyhow;
+
+use sui_light_client::config::Config;
+use sui_light_client::LightClient;
+
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::event::EventID;
+use sui_types::full_checkpoint_content::CheckpointData;
+
+use std::{fs, path::PathBuf};
+use tempfile::TempDir;
+
+fn test_file(name: &str) -> PathBuf {
+    let mut d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    d.push("test_files");
+    d.push(name);
+    d
+}
+
+fn read_full_checkpoint(seq: u64) -> anyhow::Result<CheckpointData> {
+    let buffer = fs::read(test_file(&format!("{}.chk", seq)))?;
+    let (_, data): (u8, CheckpointData) =
+        bcs::from_bytes(&buffer).map_err(|e| anyhow!("Unable to parse checkpoint file: {}", e))?;
+    Ok(data)
+}
+
+/// Set up an object store containing the end-of-epoch checkpoints in `checkpoints`, optionally
+/// listing them in `epochs.json`, and a config pointing at it.
+fn setup(checkpoints: &[u64], with_epochs_json: bool) -> (Config, TempDir, TempDir) {
+    let store_dir = TempDir::new().unwrap();
+    for seq in checkpoints {
+        let name = format!("{}.chk", seq);
+        fs::copy(test_file(&name), store_dir.path().join(&name)).unwrap();
+    }
+
+    if with_epochs_json {
+        let epochs = serde_json::to_vec(checkpoints).unwrap();
+        fs::write(store_dir.path().join("epochs.json"), epochs).unwrap();
+    }
+
+    let summary_dir = TempDir::new().unwrap();
+    let config = Config {
+        checkpoint_summary_dir: summary_dir.path().to_path_buf(),
+        object_store_url: format!("file://{}", store_dir.path().display()),
+        ..Default::default()
+    };
+
+    (config, store_dir, summary_dir)
+}
+
+#[tokio::test]
+async fn test_sync_through_epoch_change() {
+    let (config, _store_dir, _summary_dir) = setup(&[15918264, 16005062], true);
+    let trusted = read_full_checkpoint(15918264).unwrap().checkpoint_summary;
+    let end_of_next_epoch = read_full_checkpoint(16005062).unwrap().checkpoint_summary;
+
+    let mut client = LightClient::from_trusted_checkpoint(config.clone(), &trusted).unwrap();
+    assert_eq!(client.epoch(), trusted.epoch() + 1);
+
+    client.sync().await.unwrap();
+    assert_eq!(client.epoch(), end_of_next_epoch.epoch() + 1);
+
+    // The committees the client learned about survive restarting it.
+    let client = LightClient::new(config).unwrap();
+    assert_eq!(client.epoch(), end_of_next_epoch.epoch() + 1);
+    assert!(client.committee_for_epoch(trusted.epoch() + 1).is_some());
+}
+
+#[tokio::test]
+async fn test_sync_without_hints() {
+    // Without `epochs.json`, the client has to search for the end of the epoch, and there are no
+    // checkpoints after the trusted one in the store, so it can't make progress.
+    let (config, _store_dir, _summary_dir) = setup(&[15918264], false);
+    let trusted = read_full_checkpoint(15918264).unwrap().checkpoint_summary;
+
+    let mut client = LightClient::from_trusted_checkpoint(config, &trusted).unwrap();
+    client.sync().await.unwrap();
+    assert_eq!(client.epoch(), trusted.epoch() + 1);
+}
+
+#[tokio::test]
+async fn test_verify_object_and_event() {
+    let (config, _store_dir, _summary_dir) = setup(&[15918264, 16005062], true);
+    let trusted = read_full_checkpoint(15918264).unwrap().checkpoint_summary;
+    let full_checkpoint = read_full_checkpoint(16005062).unwrap();
+    let seq = *full_checkpoint.checkpoint_summary.sequence_number();
+
+    let client = LightClient::from_trusted_checkpoint(config, &trusted).unwrap();
+
+    let object = &full_checkpoint.transactions[0].output_objects[0];
+    client.verify_object(object, seq).await.unwrap();
+
+    // An object written by a different transaction does not verify.
+    let mut wrong_object = full_checkpoint.transactions[1].output_objects[1].clone();
+    wrong_object.previous_transaction = object.previous_transaction;
+    assert!(client.verify_object(&wrong_object, seq).await.is_err());
+
+    let tx = &full_checkpoint.transactions[1];
+    let event = &tx.events.as_ref().unwrap().data[0];
+    let event_id = EventID::from((*tx.effects.transaction_digest(), 0));
+    client.verify_event(event_id, event, seq).await.unwrap();
+
+    let wrong_id = EventID::from((*tx.effects.transaction_digest(), 1));
+    assert!(client.verify_event(wrong_id, event, seq).await.is_err());
+}
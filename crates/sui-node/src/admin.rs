@@ -16,6 +16,7 @@ use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     str::FromStr,
 };
+use sui_config::transaction_deny_config::DenyRule;
 use sui_types::{
     base_types::AuthorityName,
     crypto::{RandomnessPartialSignature, RandomnessRound, RandomnessSignature},
@@ -77,6 +78,17 @@ use tracing::info;
 // Reconfigure traffic control policy
 //
 //  $ curl 'http://127.0.0.1:1337/traffic-control?error_threshold=100&spam_threshold=100&dry_run=true'
+//
+// List the transaction deny rules in effect (from the node config, and added at runtime):
+//
+//  $ curl 'http://127.0.0.1:1337/transaction-deny-rules'
+//
+// Deny transactions that use an object, package, address, function or type, until the rule is
+// removed (rules added or removed at runtime are persisted across restarts):
+//
+//  $ curl -X POST 'http://127.0.0.1:1337/add-transaction-deny-rule?rule=function:0x123::module::function'
+//  $ curl -X POST 'http://127.0.0.1:1337/add-transaction-deny-rule?rule=type:0x123::module::Type'
+//  $ curl -X POST 'http://127.0.0.1:1337/remove-transaction-deny-rule?rule=object:0x456'
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -92,6 +104,9 @@ const RANDOMNESS_INJECT_FULL_SIG_ROUTE: &str = "/randomness-inject-full-sig";
 const GET_TX_COST_ROUTE: &str = "/get-tx-cost";
 const DUMP_CONSENSUS_TX_COST_ESTIMATES_ROUTE: &str = "/dump-consensus-tx-cost-estimates";
 const TRAFFIC_CONTROL: &str = "/traffic-control";
+const TRANSACTION_DENY_RULES: &str = "/transaction-deny-rules";
+const ADD_TRANSACTION_DENY_RULE: &str = "/add-transaction-deny-rule";
+const REMOVE_TRANSACTION_DENY_RULE: &str = "/remove-transaction-deny-rule";
 
 struct AppState {
     node: Arc<SuiNode>,
@@ -137,6 +152,12 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
             get(dump_consensus_tx_cost_estimates),
         )
         .route(TRAFFIC_CONTROL, post(traffic_control))
+        .route(TRANSACTION_DENY_RULES, get(transaction_deny_rules))
+        .route(ADD_TRANSACTION_DENY_RULE, post(add_transaction_deny_rule))
+        .route(
+            REMOVE_TRANSACTION_DENY_RULE,
+            post(remove_transaction_deny_rule),
+        )
         .with_state(Arc::new(app_state));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -523,3 +544,63 @@ async fn traffic_control(
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
     }
 }
+
+async fn transaction_deny_rules(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let mut output = String::new();
+    for rule in state.node.state().transaction_deny_rules() {
+        output.push_str(&format!("{rule}\n"));
+    }
+
+    (StatusCode::OK, output)
+}
+
+#[derive(Deserialize)]
+struct TransactionDenyRule {
+    rule: String,
+}
+
+async fn add_transaction_deny_rule(
+    State(state): State<Arc<AppState>>,
+    args: Query<TransactionDenyRule>,
+) -> (StatusCode, String) {
+    let Query(TransactionDenyRule { rule }) = args;
+
+    let rule = match DenyRule::from_str(&rule) {
+        Ok(rule) => rule,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()),
+    };
+
+    match state.node.state().add_transaction_deny_rule(rule.clone()) {
+        Ok(true) => (StatusCode::OK, format!("added deny rule {rule}\n")),
+        Ok(false) => (
+            StatusCode::OK,
+            format!("deny rule {rule} already in effect\n"),
+        ),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn remove_transaction_deny_rule(
+    State(state): State<Arc<AppState>>,
+    args: Query<TransactionDenyRule>,
+) -> (StatusCode, String) {
+    let Query(TransactionDenyRule { rule }) = args;
+
+    let rule = match DenyRule::from_str(&rule) {
+        Ok(rule) => rule,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()),
+    };
+
+    match state
+        .node
+        .state()
+        .remove_transaction_deny_rule(rule.clone())
+    {
+        Ok(true) => (StatusCode::OK, format!("removed deny rule {rule}\n")),
+        Ok(false) => (
+            StatusCode::OK,
+            format!("deny rule {rule} was not in effect\n"),
+        ),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
@@ -0,0 +1,529 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An implementation of the replay interfaces: `TransactionStore`, `EpochStore`, and `ObjectStore`
+//! backed by a self-contained directory on disk (a "bundle").
+//! A bundle is written by a `BundleRecorder` while replaying against another store
+//! (e.g. the GQL `DataStore`), and can be used later to replay the same transactions
+//! with no network access and with exactly the same inputs.
+//!
+//! The layout of a bundle is:
+//! ```text
+//! <root>/
+//!   manifest.json                      -- format version and chain of the bundle
+//!   transactions/<digest>.bcs          -- bcs of (TransactionData, TransactionEffects, checkpoint)
+//!   epochs/<epoch>.json                -- `EpochData`
+//!   objects/<object_id>/<version>.bcs  -- bcs of an `Object` at a version
+//!   objects/<object_id>/queries.json   -- resolution of each `VersionQuery` seen for the object
+//! ```
+//! The query resolutions are what make the bundle self-contained: queries like `RootVersion`
+//! or `AtCheckpoint` are answered by the remote store and the answer (a version or nothing)
+//! is saved so that the exact same object is returned offline.
+
+use crate::replay_interface::{
+    EpochData, EpochStore, ObjectKey, ObjectStore, TransactionStore, VersionQuery,
+};
+use anyhow::{anyhow, bail, Context};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use sui_types::{
+    base_types::ObjectID,
+    committee::ProtocolVersion,
+    effects::TransactionEffects,
+    object::Object,
+    supported_protocol_versions::{Chain, ProtocolConfig},
+    transaction::TransactionData,
+};
+use tempfile::NamedTempFile;
+use tracing::debug;
+
+/// Version of the on-disk format. Bumped on any incompatible change to the layout.
+const BUNDLE_FORMAT_VERSION: u64 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const TRANSACTIONS_DIR: &str = "transactions";
+const EPOCHS_DIR: &str = "epochs";
+const OBJECTS_DIR: &str = "objects";
+const QUERIES_FILE: &str = "queries.json";
+
+/// Description of a bundle, saved at the root of the bundle directory.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u64,
+    pub chain: Chain,
+}
+
+/// A `VersionQuery` for an object resolved to the version of the object returned
+/// (`None` if no object was returned).
+type QueryResolutions = BTreeMap<String, Option<u64>>;
+
+/// Store over a bundle directory.
+pub struct BundleStore {
+    root: PathBuf,
+    manifest: BundleManifest,
+}
+
+impl TransactionStore for BundleStore {
+    fn transaction_data_and_effects(
+        &self,
+        tx_digest: &str,
+    ) -> Result<(TransactionData, TransactionEffects, u64), anyhow::Error> {
+        self.try_transaction(tx_digest)?.ok_or_else(|| {
+            anyhow!(
+                "Transaction {} not found in bundle {}",
+                tx_digest,
+                self.root.display()
+            )
+        })
+    }
+}
+
+impl EpochStore for BundleStore {
+    fn epoch_info(&self, epoch: u64) -> Result<EpochData, anyhow::Error> {
+        self.try_epoch(epoch)?.ok_or_else(|| {
+            anyhow!(
+                "Epoch {} not found in bundle {}",
+                epoch,
+                self.root.display()
+            )
+        })
+    }
+
+    // The protocol config is not saved, it is derived from the epoch protocol version
+    // and the chain of the bundle.
+    fn protocol_config(&self, epoch: u64) -> Result<ProtocolConfig, anyhow::Error> {
+        let epoch = self.epoch_info(epoch)?;
+        Ok(ProtocolConfig::get_for_version(
+            ProtocolVersion::new(epoch.protocol_version),
+            self.manifest.chain,
+        ))
+    }
+}
+
+impl ObjectStore for BundleStore {
+    fn get_objects(&self, keys: &[ObjectKey]) -> Result<Vec<Option<Object>>, anyhow::Error> {
+        keys.iter()
+            .map(|key| {
+                self.try_object(key)?.ok_or_else(|| {
+                    anyhow!(
+                        "Object {} ({:?}) not found in bundle {}",
+                        key.object_id,
+                        key.version_query,
+                        self.root.display(),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+impl BundleStore {
+    /// Open an existing bundle for reading.
+    pub fn open(root: &Path) -> Result<Self, anyhow::Error> {
+        let manifest_path = root.join(MANIFEST_FILE);
+        if !manifest_path.exists() {
+            bail!(
+                "No replay bundle found at {} (missing {})",
+                root.display(),
+                MANIFEST_FILE
+            );
+        }
+        let manifest: BundleManifest = read_json(&manifest_path)?;
+        if manifest.format_version != BUNDLE_FORMAT_VERSION {
+            bail!(
+                "Unsupported bundle format version {} in {} (expected {})",
+                manifest.format_version,
+                root.display(),
+                BUNDLE_FORMAT_VERSION
+            );
+        }
+        debug!("Opened replay bundle at {}", root.display());
+        Ok(Self {
+            root: root.to_path_buf(),
+            manifest,
+        })
+    }
+
+    /// Open a bundle for writing, creating it if it does not exist.
+    /// An existing bundle can only be extended with data from the same chain.
+    pub fn create(root: &Path, chain: Chain) -> Result<Self, anyhow::Error> {
+        if root.join(MANIFEST_FILE).exists() {
+            let store = Self::open(root)?;
+            if store.manifest.chain != chain {
+                bail!(
+                    "Bundle at {} was recorded for chain {:?}, cannot add data for chain {:?}",
+                    root.display(),
+                    store.manifest.chain,
+                    chain
+                );
+            }
+            return Ok(store);
+        }
+        for dir in [TRANSACTIONS_DIR, EPOCHS_DIR, OBJECTS_DIR] {
+            std::fs::create_dir_all(root.join(dir))
+                .with_context(|| format!("Failed to create bundle directory {}", root.display()))?;
+        }
+        let manifest = BundleManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            chain,
+        };
+        write_json(&root.join(MANIFEST_FILE), &manifest)?;
+        debug!("Created replay bundle at {}", root.display());
+        Ok(Self {
+            root: root.to_path_buf(),
+            manifest,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn chain(&self) -> Chain {
+        self.manifest.chain
+    }
+
+    //
+    // Writers, used by `BundleRecorder` and `CachingStore`
+    //
+
+    pub fn write_transaction(
+        &self,
+        tx_digest: &str,
+        data: &(TransactionData, TransactionEffects, u64),
+    ) -> Result<(), anyhow::Error> {
+        write_bcs(&self.transaction_path(tx_digest), data)
+    }
+
+    pub fn write_epoch(&self, epoch_data: &EpochData) -> Result<(), anyhow::Error> {
+        write_json(&self.epoch_path(epoch_data.epoch_id), epoch_data)
+    }
+
+    /// Save the result of an object query: the object itself (if any) and
+    /// how the query resolved.
+    pub fn write_object(
+        &self,
+        key: &ObjectKey,
+        object: Option<&Object>,
+    ) -> Result<(), anyhow::Error> {
+        let object_dir = self.object_dir(&key.object_id);
+        std::fs::create_dir_all(&object_dir).with_context(|| {
+            format!("Failed to create object directory {}", object_dir.display())
+        })?;
+        let version = object.map(|obj| obj.version().value());
+        if let Some(obj) = object {
+            let path = self.object_path(&key.object_id, obj.version().value());
+            // objects are immutable at a given version
+            if !path.exists() {
+                write_bcs(&path, obj)?;
+            }
+        }
+        // an existing object at the exact version requested is its own resolution
+        if matches!(key.version_query, VersionQuery::Version(_)) && version.is_some() {
+            return Ok(());
+        }
+        let mut queries = self.query_resolutions(&key.object_id)?;
+        queries.insert(query_key(&key.version_query), version);
+        write_json(&object_dir.join(QUERIES_FILE), &queries)
+    }
+
+    //
+    // Readers
+    //
+
+    /// Return the transaction if it is in the bundle.
+    pub fn try_transaction(
+        &self,
+        tx_digest: &str,
+    ) -> Result<Option<(TransactionData, TransactionEffects, u64)>, anyhow::Error> {
+        let path = self.transaction_path(tx_digest);
+        if path.exists() {
+            read_bcs(&path).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Return the epoch data if it is in the bundle.
+    pub fn try_epoch(&self, epoch: u64) -> Result<Option<EpochData>, anyhow::Error> {
+        let path = self.epoch_path(epoch);
+        if path.exists() {
+            read_json(&path).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Return the result of an object query if the query was saved in the bundle.
+    /// `Some(None)` means the query was saved and returned no object.
+    pub fn try_object(&self, key: &ObjectKey) -> Result<Option<Option<Object>>, anyhow::Error> {
+        if let VersionQuery::Version(version) = key.version_query {
+            let path = self.object_path(&key.object_id, version);
+            if path.exists() {
+                return read_bcs(&path).map(|obj| Some(Some(obj)));
+            }
+        }
+        let queries = self.query_resolutions(&key.object_id)?;
+        match queries.get(&query_key(&key.version_query)) {
+            Some(Some(version)) => {
+                read_bcs(&self.object_path(&key.object_id, *version)).map(|obj| Some(Some(obj)))
+            }
+            Some(None) => Ok(Some(None)),
+            None => Ok(None),
+        }
+    }
+
+    fn query_resolutions(&self, object_id: &ObjectID) -> Result<QueryResolutions, anyhow::Error> {
+        let path = self.object_dir(object_id).join(QUERIES_FILE);
+        if path.exists() {
+            read_json(&path)
+        } else {
+            Ok(QueryResolutions::new())
+        }
+    }
+
+    //
+    // Paths
+    //
+
+    fn transaction_path(&self, tx_digest: &str) -> PathBuf {
+        self.root
+            .join(TRANSACTIONS_DIR)
+            .join(format!("{}.bcs", tx_digest))
+    }
+
+    fn epoch_path(&self, epoch: u64) -> PathBuf {
+        self.root.join(EPOCHS_DIR).join(format!("{}.json", epoch))
+    }
+
+    fn object_dir(&self, object_id: &ObjectID) -> PathBuf {
+        self.root.join(OBJECTS_DIR).join(object_id.to_string())
+    }
+
+    fn object_path(&self, object_id: &ObjectID, version: u64) -> PathBuf {
+        self.object_dir(object_id).join(format!("{}.bcs", version))
+    }
+}
+
+/// Wraps a store and saves in a `BundleStore` every piece of data returned.
+/// Replaying through a `BundleRecorder` produces a bundle that is sufficient to
+/// replay the same transactions offline.
+pub struct BundleRecorder<S> {
+    inner: S,
+    bundle: BundleStore,
+}
+
+impl<S> BundleRecorder<S> {
+    pub fn new(inner: S, bundle: BundleStore) -> Self {
+        Self { inner, bundle }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn bundle(&self) -> &BundleStore {
+        &self.bundle
+    }
+}
+
+impl<S: TransactionStore> TransactionStore for BundleRecorder<S> {
+    fn transaction_data_and_effects(
+        &self,
+        tx_digest: &str,
+    ) -> Result<(TransactionData, TransactionEffects, u64), anyhow::Error> {
+        let data = self.inner.transaction_data_and_effects(tx_digest)?;
+        self.bundle.write_transaction(tx_digest, &data)?;
+        Ok(data)
+    }
+}
+
+impl<S: EpochStore> EpochStore for BundleRecorder<S> {
+    fn epoch_info(&self, epoch: u64) -> Result<EpochData, anyhow::Error> {
+        let epoch_data = self.inner.epoch_info(epoch)?;
+        self.bundle.write_epoch(&epoch_data)?;
+        Ok(epoch_data)
+    }
+
+    // The bundle derives the protocol config from the epoch data, make sure it is recorded
+    fn protocol_config(&self, epoch: u64) -> Result<ProtocolConfig, anyhow::Error> {
+        self.epoch_info(epoch)?;
+        self.inner.protocol_config(epoch)
+    }
+}
+
+impl<S: ObjectStore> ObjectStore for BundleRecorder<S> {
+    fn get_objects(&self, keys: &[ObjectKey]) -> Result<Vec<Option<Object>>, anyhow::Error> {
+        let objects = self.inner.get_objects(keys)?;
+        for (key, object) in keys.iter().zip(objects.iter()) {
+            self.bundle.write_object(key, object.as_ref())?;
+        }
+        Ok(objects)
+    }
+}
+
+// Key of a `VersionQuery` in the `queries.json` file of an object
+fn query_key(query: &VersionQuery) -> String {
+    match query {
+        VersionQuery::Version(v) => format!("version:{}", v),
+        VersionQuery::RootVersion(v) => format!("root_version:{}", v),
+        VersionQuery::AtCheckpoint(cp) => format!("at_checkpoint:{}", cp),
+        VersionQuery::ImmutableOrLatest => "immutable_or_latest".to_string(),
+    }
+}
+
+//
+// File utilities
+//
+
+fn read_bcs<T: DeserializeOwned>(path: &Path) -> Result<T, anyhow::Error> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    bcs::from_bytes(&bytes).with_context(|| format!("Failed to deserialize {}", path.display()))
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, anyhow::Error> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_slice(&bytes)
+        .with_context(|| format!("Failed to deserialize {}", path.display()))
+}
+
+fn write_bcs<T: Serialize>(path: &Path, data: &T) -> Result<(), anyhow::Error> {
+    let bytes =
+        bcs::to_bytes(data).with_context(|| format!("Failed to serialize {}", path.display()))?;
+    write_atomic(path, &bytes)
+}
+
+fn write_json<T: Serialize>(path: &Path, data: &T) -> Result<(), anyhow::Error> {
+    let bytes = serde_json::to_vec_pretty(data)
+        .with_context(|| format!("Failed to serialize {}", path.display()))?;
+    write_atomic(path, &bytes)
+}
+
+// Write to a uniquely named temporary file next to `path` and rename it into place, so an
+// interrupted replay never leaves a truncated file in the bundle, and replays writing the same
+// file at the same time never share a temporary file.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), anyhow::Error> {
+    let dir = path
+        .parent()
+        .ok_or_else(|| anyhow!("Invalid bundle file path {}", path.display()))?;
+    let mut tmp = NamedTempFile::new_in(dir)
+        .with_context(|| format!("Failed to create a temporary file in {}", dir.display()))?;
+    tmp.write_all(bytes)
+        .with_context(|| format!("Failed to write {}", tmp.path().display()))?;
+    tmp.persist(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::{
+        base_types::{SequenceNumber, SuiAddress},
+        object::Owner,
+    };
+
+    fn object(id: ObjectID, version: u64) -> Object {
+        Object::with_id_owner_version_for_testing(
+            id,
+            SequenceNumber::from_u64(version),
+            Owner::AddressOwner(SuiAddress::ZERO),
+        )
+    }
+
+    fn key(object_id: ObjectID, version_query: VersionQuery) -> ObjectKey {
+        ObjectKey {
+            object_id,
+            version_query,
+        }
+    }
+
+    #[test]
+    fn write_atomic_replaces_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.json");
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+
+        // No temporary files are left behind.
+        let files: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(files, vec!["file.json"]);
+    }
+
+    #[test]
+    fn epochs_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = BundleStore::create(dir.path(), Chain::Testnet).unwrap();
+        assert!(bundle.try_epoch(1).unwrap().is_none());
+        assert!(bundle.epoch_info(1).is_err());
+
+        bundle
+            .write_epoch(&EpochData {
+                epoch_id: 1,
+                protocol_version: ProtocolVersion::MAX.as_u64(),
+                rgp: 1000,
+                start_timestamp: 42,
+            })
+            .unwrap();
+
+        let bundle = BundleStore::open(dir.path()).unwrap();
+        let epoch = bundle.epoch_info(1).unwrap();
+        assert_eq!(epoch.rgp, 1000);
+        assert_eq!(epoch.start_timestamp, 42);
+        assert_eq!(
+            bundle.protocol_config(1).unwrap().version,
+            ProtocolVersion::MAX
+        );
+    }
+
+    #[test]
+    fn object_queries_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = BundleStore::create(dir.path(), Chain::Testnet).unwrap();
+        let id = ObjectID::random();
+        let exact = key(id, VersionQuery::Version(3));
+        let bounded = key(id, VersionQuery::RootVersion(10));
+        let missing = key(id, VersionQuery::AtCheckpoint(5));
+        assert!(bundle.try_object(&exact).unwrap().is_none());
+
+        bundle.write_object(&exact, Some(&object(id, 3))).unwrap();
+        bundle.write_object(&bounded, Some(&object(id, 7))).unwrap();
+        bundle.write_object(&missing, None).unwrap();
+
+        let bundle = BundleStore::open(dir.path()).unwrap();
+        let objects = bundle.get_objects(&[exact, bounded]).unwrap();
+        assert_eq!(objects[0].as_ref().unwrap().version().value(), 3);
+        assert_eq!(objects[1].as_ref().unwrap().version().value(), 7);
+
+        // A query that returned no object is answered without an object, while a query that was
+        // never made is not answered at all.
+        assert!(matches!(bundle.try_object(&missing).unwrap(), Some(None)));
+        assert!(matches!(
+            bundle.get_objects(&[missing]).unwrap()[..],
+            [None]
+        ));
+        let unknown = key(id, VersionQuery::ImmutableOrLatest);
+        assert!(bundle.try_object(&unknown).unwrap().is_none());
+        assert!(bundle.get_objects(&[unknown]).is_err());
+    }
+
+    #[test]
+    fn bundle_is_tied_to_its_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        BundleStore::create(dir.path(), Chain::Testnet).unwrap();
+        assert!(BundleStore::create(dir.path(), Chain::Testnet).is_ok());
+        assert!(BundleStore::create(dir.path(), Chain::Mainnet).is_err());
+        assert!(BundleStore::open(&dir.path().join("missing")).is_err());
+    }
+}
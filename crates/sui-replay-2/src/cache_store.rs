@@ -0,0 +1,471 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A read-through cache over any implementation of the replay interfaces
+//! (`TransactionStore`, `EpochStore`, and `ObjectStore`).
+//! Replaying a batch of transactions (e.g. from a checkpoint range) loads the same packages,
+//! system objects and epoch data over and over. The `CachingStore` keeps the results in an
+//! in-memory LRU and, optionally, in a persistent on-disk cache so that data is fetched
+//! once across runs.
+//!
+//! The on-disk cache uses the `BundleStore` layout (`bundle_store.rs`), keyed by `ObjectKey`.
+//! Only results that cannot change over time are cached:
+//! - objects returned for `Version`, `RootVersion` and `AtCheckpoint` queries
+//! - user packages returned for `ImmutableOrLatest` queries (system packages are upgraded
+//!   in place, so "latest" is not stable for them)
+//!
+//! Queries that returned no object are never cached, as the remote store may be lagging.
+
+use crate::{
+    bundle_store::BundleStore,
+    replay_interface::{
+        EpochData, EpochStore, ObjectKey, ObjectStore, TransactionStore, VersionQuery,
+    },
+};
+use lru::LruCache;
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, RwLock,
+    },
+};
+use sui_types::{
+    effects::TransactionEffects, object::Object, supported_protocol_versions::ProtocolConfig,
+    transaction::TransactionData,
+};
+use tracing::{debug, warn};
+
+/// Default number of objects kept in the in-memory cache.
+pub const DEFAULT_OBJECT_CACHE_SIZE: usize = 10_000;
+
+// Transactions are rarely loaded more than once in a run, keep a small number around
+const TRANSACTION_CACHE_SIZE: usize = 128;
+
+type TransactionDataAndEffects = (TransactionData, TransactionEffects, u64);
+
+/// Read-through cache over a store.
+pub struct CachingStore<S> {
+    inner: S,
+    transactions: Mutex<LruCache<String, TransactionDataAndEffects>>,
+    // Keep all epochs considering their small size and footprint
+    epochs: RwLock<BTreeMap<u64, EpochData>>,
+    protocol_configs: RwLock<BTreeMap<u64, ProtocolConfig>>,
+    objects: Mutex<LruCache<ObjectKey, Object>>,
+    // Optional persistent cache
+    disk_cache: Option<BundleStore>,
+    stats: CacheStats,
+}
+
+/// Hit and miss counters for a `CachingStore`.
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    pub memory_hits: AtomicU64,
+    pub disk_hits: AtomicU64,
+    pub misses: AtomicU64,
+}
+
+impl<S> CachingStore<S> {
+    /// Create a cache over `inner`, holding up to `object_cache_size` objects in memory.
+    pub fn new(inner: S, object_cache_size: NonZeroUsize) -> Self {
+        Self {
+            inner,
+            transactions: Mutex::new(LruCache::new(
+                NonZeroUsize::new(TRANSACTION_CACHE_SIZE).unwrap(),
+            )),
+            epochs: RwLock::new(BTreeMap::new()),
+            protocol_configs: RwLock::new(BTreeMap::new()),
+            objects: Mutex::new(LruCache::new(object_cache_size)),
+            disk_cache: None,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Add a persistent cache. Data missing in memory is looked up on disk before
+    /// hitting the inner store, and data fetched from the inner store is saved on disk.
+    pub fn with_disk_cache(mut self, disk_cache: BundleStore) -> Self {
+        self.disk_cache = Some(disk_cache);
+        self
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn stats(&self) -> &CacheStats {
+        &self.stats
+    }
+
+    fn memory_hit(&self) {
+        self.stats.memory_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn disk_hit(&self) {
+        self.stats.disk_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn miss(&self) {
+        self.stats.misses.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<S: TransactionStore> TransactionStore for CachingStore<S> {
+    fn transaction_data_and_effects(
+        &self,
+        tx_digest: &str,
+    ) -> Result<(TransactionData, TransactionEffects, u64), anyhow::Error> {
+        if let Some(data) = self.transactions.lock().unwrap().get(tx_digest) {
+            self.memory_hit();
+            return Ok(data.clone());
+        }
+        if let Some(data) = self.disk_cache.as_ref().and_then(|disk| {
+            disk.try_transaction(tx_digest)
+                .unwrap_or_else(|e| disk_read_error(e, "transaction", tx_digest))
+        }) {
+            self.disk_hit();
+            self.transactions
+                .lock()
+                .unwrap()
+                .put(tx_digest.to_string(), data.clone());
+            return Ok(data);
+        }
+
+        self.miss();
+        let data = self.inner.transaction_data_and_effects(tx_digest)?;
+        if let Some(disk) = &self.disk_cache {
+            if let Err(e) = disk.write_transaction(tx_digest, &data) {
+                warn!("Failed to save transaction {} in cache: {:?}", tx_digest, e);
+            }
+        }
+        self.transactions
+            .lock()
+            .unwrap()
+            .put(tx_digest.to_string(), data.clone());
+        Ok(data)
+    }
+}
+
+impl<S: EpochStore> EpochStore for CachingStore<S> {
+    fn epoch_info(&self, epoch: u64) -> Result<EpochData, anyhow::Error> {
+        if let Some(epoch_data) = self.epochs.read().unwrap().get(&epoch) {
+            self.memory_hit();
+            return Ok(epoch_data.clone());
+        }
+        let epoch_data = match self.disk_cache.as_ref().and_then(|disk| {
+            disk.try_epoch(epoch)
+                .unwrap_or_else(|e| disk_read_error(e, "epoch", &epoch.to_string()))
+        }) {
+            Some(epoch_data) => {
+                self.disk_hit();
+                epoch_data
+            }
+            None => {
+                self.miss();
+                let epoch_data = self.inner.epoch_info(epoch)?;
+                if let Some(disk) = &self.disk_cache {
+                    if let Err(e) = disk.write_epoch(&epoch_data) {
+                        warn!("Failed to save epoch {} in cache: {:?}", epoch, e);
+                    }
+                }
+                epoch_data
+            }
+        };
+        self.epochs
+            .write()
+            .unwrap()
+            .insert(epoch, epoch_data.clone());
+        Ok(epoch_data)
+    }
+
+    // Protocol configs are not saved on disk, the disk cache derives them from the epoch
+    // protocol version instead.
+    fn protocol_config(&self, epoch: u64) -> Result<ProtocolConfig, anyhow::Error> {
+        if let Some(config) = self.protocol_configs.read().unwrap().get(&epoch) {
+            self.memory_hit();
+            return Ok(config.clone());
+        }
+        let config = match self.disk_cache.as_ref().and_then(|disk| {
+            disk.try_epoch(epoch)
+                .unwrap_or_else(|e| disk_read_error(e, "epoch", &epoch.to_string()))
+                .map(|_| disk.protocol_config(epoch))
+        }) {
+            Some(config) => {
+                self.disk_hit();
+                config?
+            }
+            None => {
+                self.miss();
+                self.inner.protocol_config(epoch)?
+            }
+        };
+        self.protocol_configs
+            .write()
+            .unwrap()
+            .insert(epoch, config.clone());
+        Ok(config)
+    }
+}
+
+impl<S: ObjectStore> ObjectStore for CachingStore<S> {
+    // Resolve as many keys as possible from the caches and query the inner store
+    // for the remaining ones in a single call.
+    fn get_objects(&self, keys: &[ObjectKey]) -> Result<Vec<Option<Object>>, anyhow::Error> {
+        let mut objects: Vec<Option<Object>> = vec![None; keys.len()];
+        let mut missing = vec![];
+        {
+            let mut memory = self.objects.lock().unwrap();
+            for (idx, key) in keys.iter().enumerate() {
+                if let Some(obj) = memory.get(key) {
+                    self.memory_hit();
+                    objects[idx] = Some(obj.clone());
+                } else {
+                    missing.push(idx);
+                }
+            }
+        }
+
+        if let Some(disk) = &self.disk_cache {
+            let mut still_missing = vec![];
+            for idx in missing {
+                let key = &keys[idx];
+                match disk
+                    .try_object(key)
+                    .unwrap_or_else(|e| disk_read_error(e, "object", &key.object_id.to_string()))
+                {
+                    Some(Some(obj)) => {
+                        self.disk_hit();
+                        self.objects.lock().unwrap().put(key.clone(), obj.clone());
+                        objects[idx] = Some(obj);
+                    }
+                    _ => still_missing.push(idx),
+                }
+            }
+            missing = still_missing;
+        }
+
+        if missing.is_empty() {
+            return Ok(objects);
+        }
+
+        debug!("Object cache misses: {}", missing.len());
+        let missing_keys = missing
+            .iter()
+            .map(|idx| keys[*idx].clone())
+            .collect::<Vec<_>>();
+        let fetched = self.inner.get_objects(&missing_keys)?;
+        for (idx, object) in missing.into_iter().zip(fetched) {
+            self.miss();
+            let key = &keys[idx];
+            if let Some(obj) = &object {
+                if is_cacheable(key, obj) {
+                    if let Some(disk) = &self.disk_cache {
+                        if let Err(e) = disk.write_object(key, Some(obj)) {
+                            warn!("Failed to save object {} in cache: {:?}", key.object_id, e);
+                        }
+                    }
+                    self.objects.lock().unwrap().put(key.clone(), obj.clone());
+                }
+            }
+            objects[idx] = object;
+        }
+        Ok(objects)
+    }
+}
+
+// Whether the result of a query is immutable and can be cached
+fn is_cacheable(key: &ObjectKey, object: &Object) -> bool {
+    match key.version_query {
+        VersionQuery::Version(_) | VersionQuery::RootVersion(_) | VersionQuery::AtCheckpoint(_) => {
+            true
+        }
+        VersionQuery::ImmutableOrLatest => {
+            object.is_package() && !sui_types::SYSTEM_PACKAGE_ADDRESSES.contains(&key.object_id)
+        }
+    }
+}
+
+// A corrupted or unreadable disk cache entry is treated as a miss
+fn disk_read_error<T>(e: anyhow::Error, kind: &str, key: &str) -> Option<T> {
+    warn!("Failed to read {} {} from disk cache: {:?}", kind, key, e);
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::{
+        base_types::{ObjectID, SequenceNumber, SuiAddress},
+        committee::ProtocolVersion,
+        object::Owner,
+        supported_protocol_versions::Chain,
+    };
+
+    /// Store that serves fixed epochs and objects, and records the requests it receives.
+    #[derive(Default)]
+    struct MockStore {
+        epochs: BTreeMap<u64, EpochData>,
+        objects: BTreeMap<ObjectKey, Object>,
+        epoch_requests: AtomicU64,
+        protocol_config_requests: AtomicU64,
+        object_requests: Mutex<Vec<Vec<ObjectKey>>>,
+    }
+
+    impl EpochStore for MockStore {
+        fn epoch_info(&self, epoch: u64) -> Result<EpochData, anyhow::Error> {
+            self.epoch_requests.fetch_add(1, Ordering::Relaxed);
+            self.epochs
+                .get(&epoch)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Epoch {} not found", epoch))
+        }
+
+        fn protocol_config(&self, epoch: u64) -> Result<ProtocolConfig, anyhow::Error> {
+            self.protocol_config_requests
+                .fetch_add(1, Ordering::Relaxed);
+            let epoch = self.epoch_info(epoch)?;
+            Ok(ProtocolConfig::get_for_version(
+                ProtocolVersion::new(epoch.protocol_version),
+                Chain::Unknown,
+            ))
+        }
+    }
+
+    impl ObjectStore for MockStore {
+        fn get_objects(&self, keys: &[ObjectKey]) -> Result<Vec<Option<Object>>, anyhow::Error> {
+            self.object_requests.lock().unwrap().push(keys.to_vec());
+            Ok(keys
+                .iter()
+                .map(|key| self.objects.get(key).cloned())
+                .collect())
+        }
+    }
+
+    fn epoch(epoch_id: u64) -> EpochData {
+        EpochData {
+            epoch_id,
+            protocol_version: ProtocolVersion::MAX.as_u64(),
+            rgp: 1000,
+            start_timestamp: 0,
+        }
+    }
+
+    fn key(object_id: ObjectID, version_query: VersionQuery) -> ObjectKey {
+        ObjectKey {
+            object_id,
+            version_query,
+        }
+    }
+
+    fn object(id: ObjectID, version: u64) -> Object {
+        Object::with_id_owner_version_for_testing(
+            id,
+            SequenceNumber::from_u64(version),
+            Owner::AddressOwner(SuiAddress::ZERO),
+        )
+    }
+
+    fn cache_size() -> NonZeroUsize {
+        NonZeroUsize::new(DEFAULT_OBJECT_CACHE_SIZE).unwrap()
+    }
+
+    #[test]
+    fn caches_epochs_and_protocol_configs() {
+        let inner = MockStore {
+            epochs: BTreeMap::from([(1, epoch(1))]),
+            ..Default::default()
+        };
+        let store = CachingStore::new(inner, cache_size());
+
+        for _ in 0..3 {
+            assert_eq!(store.epoch_info(1).unwrap().rgp, 1000);
+            assert_eq!(
+                store.protocol_config(1).unwrap().version,
+                ProtocolVersion::MAX
+            );
+        }
+        assert!(store.epoch_info(2).is_err());
+
+        let inner = store.inner();
+        assert_eq!(inner.protocol_config_requests.load(Ordering::Relaxed), 1);
+        // One request from `epoch_info(1)`, one from `protocol_config(1)`, and one for the
+        // missing epoch.
+        assert_eq!(inner.epoch_requests.load(Ordering::Relaxed), 3);
+        assert_eq!(store.stats().memory_hits.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn serves_epochs_and_protocol_configs_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner = MockStore {
+            epochs: BTreeMap::from([(1, epoch(1))]),
+            ..Default::default()
+        };
+        let store = CachingStore::new(inner, cache_size())
+            .with_disk_cache(BundleStore::create(dir.path(), Chain::Unknown).unwrap());
+        store.epoch_info(1).unwrap();
+
+        // A new cache over the same directory does not need the inner store.
+        let store = CachingStore::new(MockStore::default(), cache_size())
+            .with_disk_cache(BundleStore::open(dir.path()).unwrap());
+        assert_eq!(store.epoch_info(1).unwrap().rgp, 1000);
+        assert_eq!(
+            store.protocol_config(1).unwrap().version,
+            ProtocolVersion::MAX
+        );
+        assert_eq!(store.stats().disk_hits.load(Ordering::Relaxed), 2);
+        assert_eq!(store.stats().misses.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn fetches_only_missing_objects() {
+        let (a, b) = (ObjectID::random(), ObjectID::random());
+        let inner = MockStore {
+            objects: BTreeMap::from([
+                (key(a, VersionQuery::Version(1)), object(a, 1)),
+                (key(b, VersionQuery::RootVersion(5)), object(b, 4)),
+            ]),
+            ..Default::default()
+        };
+        let store = CachingStore::new(inner, cache_size());
+
+        let first = [key(a, VersionQuery::Version(1))];
+        let second = [
+            key(a, VersionQuery::Version(1)),
+            key(b, VersionQuery::RootVersion(5)),
+            key(b, VersionQuery::Version(7)),
+        ];
+        store.get_objects(&first).unwrap();
+        let objects = store.get_objects(&second).unwrap();
+        assert_eq!(objects[0].as_ref().unwrap().version().value(), 1);
+        assert_eq!(objects[1].as_ref().unwrap().version().value(), 4);
+        assert!(objects[2].is_none());
+
+        // Queries that returned no object are asked again.
+        store.get_objects(&second).unwrap();
+        assert_eq!(
+            *store.inner().object_requests.lock().unwrap(),
+            vec![
+                first.to_vec(),
+                second[1..].to_vec(),
+                vec![key(b, VersionQuery::Version(7))],
+            ],
+        );
+    }
+
+    #[test]
+    fn does_not_cache_latest_objects() {
+        let id = ObjectID::random();
+        let latest = key(id, VersionQuery::ImmutableOrLatest);
+        let inner = MockStore {
+            objects: BTreeMap::from([(latest.clone(), object(id, 1))]),
+            ..Default::default()
+        };
+        let store = CachingStore::new(inner, cache_size());
+
+        store.get_objects(&[latest.clone()]).unwrap();
+        store.get_objects(&[latest.clone()]).unwrap();
+        assert_eq!(store.inner().object_requests.lock().unwrap().len(), 2);
+        assert_eq!(store.stats().memory_hits.load(Ordering::Relaxed), 0);
+    }
+}
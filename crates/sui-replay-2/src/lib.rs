@@ -3,13 +3,17 @@
 
 use crate::artifacts::{Artifact, ArtifactManager};
 use crate::build::BuildCmdConfig;
+use crate::bundle_store::{BundleRecorder, BundleStore};
+use crate::cache_store::{CachingStore, DEFAULT_OBJECT_CACHE_SIZE};
 use crate::data_store::DataStore;
 use crate::displays::Pretty;
+use crate::replay_interface::{EpochStore, ObjectStore, ReplayDataStore, TransactionStore};
 use crate::replay_txn::replay_transaction;
 use anyhow::{anyhow, bail};
 use clap::{Parser, Subcommand};
 use similar::{ChangeTag, TextDiff};
 use std::io::Write;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use sui_json_rpc_types::SuiTransactionBlockEffects;
@@ -18,6 +22,8 @@ use sui_types::supported_protocol_versions::Chain;
 
 pub mod artifacts;
 pub mod build;
+pub mod bundle_store;
+pub mod cache_store;
 pub mod data_store;
 pub mod displays;
 pub mod execution;
@@ -85,6 +91,22 @@ pub struct ReplayConfig {
     /// should be overwritten or an error raised if they already exist.
     #[arg(long, default_value = "false")]
     pub overwrite_existing: bool,
+    /// Directory of a replay bundle. When replaying against a node, all the data fetched
+    /// is saved in the bundle so that the replay can be run again offline.
+    #[arg(long)]
+    pub bundle_dir: Option<PathBuf>,
+    /// Replay using only the data saved in `--bundle-dir`, without contacting any node.
+    #[arg(long, default_value = "false", requires = "bundle_dir")]
+    pub offline: bool,
+    /// Do not cache data fetched from the node. Every transaction fetches its own data.
+    #[arg(long, default_value = "false")]
+    pub no_cache: bool,
+    /// Number of objects and packages kept in the in-memory cache.
+    #[arg(long, default_value_t = NonZeroUsize::new(DEFAULT_OBJECT_CACHE_SIZE).unwrap())]
+    pub cache_size: NonZeroUsize,
+    /// Directory of a persistent cache for the data fetched from the node, shared across runs.
+    #[arg(long, conflicts_with = "no_cache")]
+    pub cache_dir: Option<PathBuf>,
 }
 
 /// Enum around rpc gql endpoints.
@@ -132,6 +154,11 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
         output_dir,
         show_effects: _,
         overwrite_existing,
+        bundle_dir,
+        offline,
+        no_cache,
+        cache_size,
+        cache_dir,
     } = config;
 
     let output_root_dir = if let Some(dir) = output_dir {
@@ -177,16 +204,69 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
 
     ::tracing::debug!("Binary version: {version}");
 
-    // `DataStore` implements `TransactionStore`, `EpochStore` and `ObjectStore`
-    let data_store = DataStore::new(node.clone(), version)
-        .map_err(|e| anyhow!("Failed to create data store: {:?}", e))?;
+    let data_store: Box<dyn ReplayDataStore> = match bundle_dir {
+        // `BundleStore` serves all the data from the bundle, no network access
+        Some(bundle_dir) if *offline => Box::new(
+            BundleStore::open(bundle_dir)
+                .map_err(|e| anyhow!("Failed to open replay bundle: {:?}", e))?,
+        ),
+        _ => {
+            // `DataStore` implements `TransactionStore`, `EpochStore` and `ObjectStore`
+            let mut data_store: Box<dyn ReplayDataStore> = Box::new(
+                DataStore::new(node.clone(), version)
+                    .map_err(|e| anyhow!("Failed to create data store: {:?}", e))?,
+            );
+            // Cache data across transactions unless told otherwise
+            if !no_cache {
+                let mut caching_store = CachingStore::new(data_store, *cache_size);
+                if let Some(cache_dir) = cache_dir {
+                    caching_store = caching_store.with_disk_cache(
+                        BundleStore::create(cache_dir, node.chain())
+                            .map_err(|e| anyhow!("Failed to create replay cache: {:?}", e))?,
+                    );
+                }
+                data_store = Box::new(caching_store);
+            }
+            // Record all data fetched from the node into the bundle
+            if let Some(bundle_dir) = bundle_dir {
+                let bundle_store = BundleStore::create(bundle_dir, node.chain())
+                    .map_err(|e| anyhow!("Failed to create replay bundle: {:?}", e))?;
+                data_store = Box::new(BundleRecorder::new(data_store, bundle_store));
+            }
+            data_store
+        }
+    };
+
+    replay_digests(
+        &digests,
+        &data_store,
+        &output_root_dir,
+        *trace,
+        terminate_early,
+        *overwrite_existing,
+    )
+    .await?;
 
-    // load and replay transactions
+    Ok(output_root_dir)
+}
+
+// Load and replay transactions from the given store
+async fn replay_digests<S>(
+    digests: &[String],
+    data_store: &S,
+    output_root_dir: &Path,
+    trace: bool,
+    terminate_early: bool,
+    overwrite_existing: bool,
+) -> anyhow::Result<()>
+where
+    S: TransactionStore + EpochStore + ObjectStore,
+{
     for tx_digest in digests {
-        let tx_dir = output_root_dir.join(&tx_digest);
+        let tx_dir = output_root_dir.join(tx_digest);
         let artifact_manager =
-            ArtifactManager::new(&tx_dir, *overwrite_existing /* overrides_allowed */)?;
-        match replay_transaction(&artifact_manager, &tx_digest, &data_store, *trace).await {
+            ArtifactManager::new(&tx_dir, overwrite_existing /* overrides_allowed */)?;
+        match replay_transaction(&artifact_manager, tx_digest, data_store, trace).await {
             Err(e) if terminate_early => {
                 ::tracing::error!("Error while replaying transaction {}: {:?}", tx_digest, e);
                 bail!("Replay terminated due to error: {}", e);
@@ -199,8 +279,7 @@ pub async fn handle_replay_config(config: &ReplayConfig, version: &str) -> anyho
             }
         }
     }
-
-    Ok(output_root_dir)
+    Ok(())
 }
 
 pub fn print_effects_or_fork<W: Write>(
@@ -12,7 +12,10 @@
 //! and work with them.
 //!
 //! A `DataStore` with reasonable defaults is provided for convenience (`data_store.rs`).
+//! A `BundleStore` serving the same data from a local directory is in `bundle_store.rs`.
+//! A `CachingStore` that caches the results of any store is in `cache_store.rs`.
 
+use serde::{Deserialize, Serialize};
 use sui_types::{
     base_types::ObjectID, effects::TransactionEffects, object::Object,
     supported_protocol_versions::ProtocolConfig, transaction::TransactionData,
@@ -33,7 +36,7 @@ pub trait TransactionStore {
 }
 
 /// Epoch data required to reaplay a transaction.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EpochData {
     pub epoch_id: u64,
     pub protocol_version: u64,
@@ -56,7 +59,7 @@ pub trait EpochStore {
 
 /// Query for an object.
 /// Specifies an `ObjectID` and the rule to retrieve it.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ObjectKey {
     pub object_id: ObjectID,
     pub version_query: VersionQuery,
@@ -67,7 +70,7 @@ pub struct ObjectKey {
 /// `RootVersion` request an object at a given version at most (<=)
 /// `AtCheckpoint` request an object at a given checkpoint. Useful for unknown `Version).
 /// `ImmutableOrLatest` requests an object assumed to be unversionalbe in the system (e.g. user packages)
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum VersionQuery {
     Version(u64),
     RootVersion(u64),
@@ -82,3 +85,34 @@ pub enum VersionQuery {
 pub trait ObjectStore {
     fn get_objects(&self, keys: &[ObjectKey]) -> Result<Vec<Option<Object>>, anyhow::Error>;
 }
+
+/// All the stores needed to replay a transaction.
+/// Used to compose stores (e.g. caching and recording wrappers) behind a single type.
+pub trait ReplayDataStore: TransactionStore + EpochStore + ObjectStore {}
+
+impl<T: TransactionStore + EpochStore + ObjectStore + ?Sized> ReplayDataStore for T {}
+
+impl<T: TransactionStore + ?Sized> TransactionStore for Box<T> {
+    fn transaction_data_and_effects(
+        &self,
+        tx_digest: &str,
+    ) -> Result<(TransactionData, TransactionEffects, u64), anyhow::Error> {
+        (**self).transaction_data_and_effects(tx_digest)
+    }
+}
+
+impl<T: EpochStore + ?Sized> EpochStore for Box<T> {
+    fn epoch_info(&self, epoch: u64) -> Result<EpochData, anyhow::Error> {
+        (**self).epoch_info(epoch)
+    }
+
+    fn protocol_config(&self, epoch: u64) -> Result<ProtocolConfig, anyhow::Error> {
+        (**self).protocol_config(epoch)
+    }
+}
+
+impl<T: ObjectStore + ?Sized> ObjectStore for Box<T> {
+    fn get_objects(&self, keys: &[ObjectKey]) -> Result<Vec<Option<Object>>, anyhow::Error> {
+        (**self).get_objects(keys)
+    }
+}
@@ -12,7 +12,6 @@
 
 use crate::{
     artifacts::{Artifact, ArtifactManager},
-    data_store::DataStore,
     execution::{execute_transaction_to_effects, ReplayExecutor},
     replay_interface::{EpochStore, ObjectKey, ObjectStore, TransactionStore, VersionQuery},
     tracing::save_trace_output,
@@ -55,12 +54,15 @@ pub struct ReplayTransaction {
 //
 // Run a single transaction and print results to stdout
 //
-pub(crate) async fn replay_transaction(
+pub(crate) async fn replay_transaction<S>(
     artifact_manager: &ArtifactManager<'_>,
     tx_digest: &str,
-    data_store: &DataStore,
+    data_store: &S,
     trace: bool,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<()>
+where
+    S: TransactionStore + EpochStore + ObjectStore,
+{
     // load a `ReplayTranaction`
     let replay_txn = match ReplayTransaction::load(tx_digest, data_store, data_store, data_store) {
         Ok(replay_txn) => replay_txn,
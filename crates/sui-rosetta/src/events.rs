@@ -0,0 +1,86 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+use tracing::debug;
+
+use sui_types::sui_serde::BigInt;
+
+use crate::types::{
+    BlockEvent, BlockEventType, BlockIdentifier, EventsBlocksRequest, EventsBlocksResponse,
+};
+use crate::{Error, OnlineServerContext, SuiEnv};
+
+// This module implements the [Rosetta Events API](https://www.rosetta-api.org/docs/EventsApi.html)
+
+/// The maximum number of events returned in a single response (also the most checkpoints a
+/// fullnode will return in a single page).
+const MAX_EVENTS_LIMIT: u64 = 100;
+
+/// Get the stream of block events, starting from `offset`. Blocks are checkpoints, and the event
+/// sequence number is the checkpoint's sequence number. Checkpoints are final once certified, so
+/// the stream only ever contains `block_added` events.
+///
+/// If `offset` is not provided, the most recent `limit` events are returned.
+/// [Rosetta API Spec](https://www.rosetta-api.org/docs/EventsApi.html#eventsblocks)
+pub async fn blocks(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<EventsBlocksRequest>, Error>,
+) -> Result<EventsBlocksResponse, Error> {
+    debug!(
+        "Called /events/blocks endpoint, offset: {:?}, limit: {:?}",
+        request.offset, request.limit
+    );
+    env.check_network_identifier(&request.network_identifier)?;
+
+    let max_sequence = context
+        .client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+
+    let limit = request
+        .limit
+        .unwrap_or(MAX_EVENTS_LIMIT)
+        .min(MAX_EVENTS_LIMIT);
+
+    let offset = request
+        .offset
+        .unwrap_or_else(|| (max_sequence + 1).saturating_sub(limit));
+
+    if limit == 0 || offset > max_sequence {
+        return Ok(EventsBlocksResponse {
+            max_sequence,
+            events: vec![],
+        });
+    }
+
+    // The checkpoint cursor is exclusive, so start from the checkpoint before `offset`.
+    let cursor = offset.checked_sub(1).map(BigInt::from);
+    let checkpoints = context
+        .client
+        .read_api()
+        .get_checkpoints(cursor, Some(limit as usize), false)
+        .await?;
+
+    let events = checkpoints
+        .data
+        .into_iter()
+        .map(|checkpoint| BlockEvent {
+            sequence: checkpoint.sequence_number,
+            block_identifier: BlockIdentifier {
+                index: checkpoint.sequence_number,
+                hash: checkpoint.digest,
+            },
+            type_: BlockEventType::BlockAdded,
+        })
+        .collect();
+
+    Ok(EventsBlocksResponse {
+        max_sequence,
+        events,
+    })
+}
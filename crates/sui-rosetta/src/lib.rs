@@ -30,8 +30,10 @@ mod account;
 mod block;
 mod construction;
 mod errors;
+mod events;
 mod network;
 pub mod operations;
+mod search;
 mod state;
 pub mod types;
 
@@ -70,9 +72,11 @@ impl RosettaOnlineServer {
             .route("/block/transaction", post(block::transaction))
             .route("/construction/submit", post(construction::submit))
             .route("/construction/metadata", post(construction::metadata))
+            .route("/events/blocks", post(events::blocks))
             .route("/network/status", post(network::status))
             .route("/network/list", post(network::list))
             .route("/network/options", post(network::options))
+            .route("/search/transactions", post(search::transactions))
             .layer(Extension(self.env))
             .with_state(self.context);
 
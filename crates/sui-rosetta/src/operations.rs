@@ -94,6 +94,10 @@ impl Operations {
         self.0.first().map(|op| op.type_)
     }
 
+    pub fn iter(&self) -> std::slice::Iter<'_, Operation> {
+        self.0.iter()
+    }
+
     /// Parse operation input from rosetta operation to intermediate internal operation;
     pub fn into_internal(self) -> Result<InternalOperation, Error> {
         let type_ = self
@@ -0,0 +1,309 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+use tracing::debug;
+
+use sui_json_rpc_types::{
+    CheckpointId, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions, SuiTransactionBlockResponseQuery, TransactionFilter,
+};
+use sui_sdk::SuiClient;
+use sui_types::base_types::TransactionDigest;
+
+use crate::operations::Operations;
+use crate::types::{
+    BlockIdentifier, BlockTransaction, Operator, SearchTransactionsRequest,
+    SearchTransactionsResponse, Transaction, TransactionIdentifier,
+};
+use crate::{Error, OnlineServerContext, SuiEnv};
+
+// This module implements the [Rosetta Search API](https://www.rosetta-api.org/docs/SearchApi.html)
+
+/// The number of transactions returned if the request does not specify a limit.
+const DEFAULT_SEARCH_LIMIT: u64 = 25;
+
+/// The maximum number of transactions returned in a single response.
+const MAX_SEARCH_LIMIT: u64 = 100;
+
+/// The number of transactions fetched from the fullnode at a time.
+const QUERY_PAGE_SIZE: usize = 50;
+
+/// The maximum number of transactions inspected while serving a single request. Searches only
+/// consider this many of the most recent transactions (at or before `max_block`), so clients
+/// looking further back should lower `max_block` rather than raise `offset`.
+const MAX_SCANNED_TRANSACTIONS: usize = 1000;
+
+/// Search for transactions matching a transaction identifier, an account identifier, an operation
+/// type, and/or a success status. Only the `and` operator is supported.
+///
+/// Results are returned most recent first, starting from `max_block` (or the latest checkpoint).
+/// Without an account identifier, the fullnode's transaction index is queried from `max_block`
+/// directly, while searches by account start from the account's most recent transaction. At most
+/// `MAX_SCANNED_TRANSACTIONS` transactions are inspected per request, so `total_count` is the
+/// number of matches found within that range (rather than across the entire history), and
+/// `next_offset` is only returned if there are more of those matches to page through.
+/// [Rosetta API Spec](https://www.rosetta-api.org/docs/SearchApi.html#searchtransactions)
+pub async fn transactions(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<SearchTransactionsRequest>, Error>,
+) -> Result<SearchTransactionsResponse, Error> {
+    debug!("Called /search/transactions endpoint");
+    env.check_network_identifier(&request.network_identifier)?;
+
+    if request.operator == Some(Operator::Or) {
+        return Err(Error::InvalidInput(
+            "Only the 'and' operator is supported".to_string(),
+        ));
+    }
+
+    let offset = request.offset.unwrap_or(0);
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .min(MAX_SEARCH_LIMIT);
+
+    let client = &context.client;
+    let mut streams = if let Some(identifier) = &request.transaction_identifier {
+        let response = client
+            .read_api()
+            .get_transaction_with_options(identifier.hash, response_options())
+            .await?;
+        vec![TransactionStream::single(client, response)]
+    } else if let Some(account) = &request.account_identifier {
+        // Fullnodes index senders and recipients separately, so look at both and merge.
+        vec![
+            TransactionStream::new(
+                client,
+                Some(TransactionFilter::FromAddress(account.address)),
+                None,
+            ),
+            TransactionStream::new(
+                client,
+                Some(TransactionFilter::ToAddress(account.address)),
+                None,
+            ),
+        ]
+    } else {
+        let cursor = match request.max_block {
+            Some(max_block) => first_transaction_after(client, max_block).await?,
+            None => None,
+        };
+
+        vec![TransactionStream::new(client, None, cursor)]
+    };
+
+    let mut seen = HashSet::new();
+    let mut block_identifiers: HashMap<u64, BlockIdentifier> = HashMap::new();
+    let mut scanned = 0;
+    let mut matched = 0;
+    let mut transactions = vec![];
+
+    while scanned < MAX_SCANNED_TRANSACTIONS {
+        let Some(response) = next_transaction(&mut streams, &mut seen).await? else {
+            break;
+        };
+
+        scanned += 1;
+        let Some(checkpoint) = response.checkpoint else {
+            continue;
+        };
+
+        if request.max_block.is_some_and(|max| checkpoint > max) {
+            continue;
+        }
+
+        if let Some(success) = request.success {
+            let succeeded = response
+                .effects
+                .as_ref()
+                .is_some_and(|effects| effects.status().is_ok());
+            if succeeded != success {
+                continue;
+            }
+        }
+
+        let hash = response.digest;
+        let operations =
+            Operations::try_from_response(response, &context.coin_metadata_cache).await?;
+
+        if let Some(account) = &request.account_identifier {
+            if !operations
+                .iter()
+                .any(|op| op.account.as_ref() == Some(account))
+            {
+                continue;
+            }
+        }
+
+        if let Some(type_) = request.type_ {
+            if !operations.iter().any(|op| op.type_ == type_) {
+                continue;
+            }
+        }
+
+        // Keep scanning past the requested page, to count all the matches in the scanned range.
+        matched += 1;
+        if matched <= offset || transactions.len() as u64 >= limit {
+            continue;
+        }
+
+        let block_identifier = match block_identifiers.get(&checkpoint) {
+            Some(identifier) => *identifier,
+            None => {
+                let identifier = context.blocks().create_block_identifier(checkpoint).await?;
+                block_identifiers.insert(checkpoint, identifier);
+                identifier
+            }
+        };
+
+        transactions.push(BlockTransaction {
+            block_identifier,
+            transaction: Transaction {
+                transaction_identifier: TransactionIdentifier { hash },
+                operations,
+                related_transactions: vec![],
+                metadata: None,
+            },
+        });
+    }
+
+    let end = offset + transactions.len() as u64;
+    let next_offset = (end < matched).then_some(end);
+    Ok(SearchTransactionsResponse {
+        transactions,
+        total_count: matched,
+        next_offset,
+    })
+}
+
+/// The digest of the first transaction in the checkpoint after `max_block`, to use as an
+/// (exclusive) cursor when paginating backwards from the end of `max_block`. Returns `None` if
+/// that checkpoint does not exist yet, meaning the search can start from the latest transaction.
+async fn first_transaction_after(
+    client: &SuiClient,
+    max_block: u64,
+) -> Result<Option<TransactionDigest>, Error> {
+    let latest = client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await?;
+
+    if max_block >= latest {
+        return Ok(None);
+    }
+
+    let checkpoint = client
+        .read_api()
+        .get_checkpoint(CheckpointId::SequenceNumber(max_block + 1))
+        .await?;
+
+    Ok(checkpoint.transactions.first().copied())
+}
+
+fn response_options() -> SuiTransactionBlockResponseOptions {
+    SuiTransactionBlockResponseOptions::new()
+        .with_input()
+        .with_effects()
+        .with_balance_changes()
+        .with_events()
+}
+
+/// Pop the next transaction from `streams`, most recent checkpoint first, skipping transactions
+/// that have already been returned by another stream.
+async fn next_transaction(
+    streams: &mut [TransactionStream<'_>],
+    seen: &mut HashSet<TransactionDigest>,
+) -> Result<Option<SuiTransactionBlockResponse>, Error> {
+    loop {
+        let mut next: Option<(usize, u64)> = None;
+        for (i, stream) in streams.iter_mut().enumerate() {
+            if let Some(response) = stream.peek().await? {
+                let checkpoint = response.checkpoint.unwrap_or(u64::MAX);
+                if next.is_none_or(|(_, cp)| checkpoint > cp) {
+                    next = Some((i, checkpoint));
+                }
+            }
+        }
+
+        let Some((i, _)) = next else {
+            return Ok(None);
+        };
+
+        let response = streams[i]
+            .buffer
+            .pop_front()
+            .expect("Peeked transaction should be buffered");
+
+        if seen.insert(response.digest) {
+            return Ok(Some(response));
+        }
+    }
+}
+
+/// Transactions matching a filter, most recent first, fetched from the fullnode a page at a time.
+struct TransactionStream<'c> {
+    client: &'c SuiClient,
+    filter: Option<TransactionFilter>,
+    cursor: Option<TransactionDigest>,
+    has_next_page: bool,
+    buffer: VecDeque<SuiTransactionBlockResponse>,
+}
+
+impl<'c> TransactionStream<'c> {
+    /// A stream of transactions matching `filter`, starting from the transaction before `cursor`
+    /// (or the latest transaction, if there is no cursor).
+    fn new(
+        client: &'c SuiClient,
+        filter: Option<TransactionFilter>,
+        cursor: Option<TransactionDigest>,
+    ) -> Self {
+        Self {
+            client,
+            filter,
+            cursor,
+            has_next_page: true,
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// A stream containing just `response`.
+    fn single(client: &'c SuiClient, response: SuiTransactionBlockResponse) -> Self {
+        Self {
+            client,
+            filter: None,
+            cursor: None,
+            has_next_page: false,
+            buffer: VecDeque::from([response]),
+        }
+    }
+
+    async fn peek(&mut self) -> Result<Option<&SuiTransactionBlockResponse>, Error> {
+        while self.buffer.is_empty() && self.has_next_page {
+            let page = self
+                .client
+                .read_api()
+                .query_transaction_blocks(
+                    SuiTransactionBlockResponseQuery::new(
+                        self.filter.clone(),
+                        Some(response_options()),
+                    ),
+                    self.cursor,
+                    Some(QUERY_PAGE_SIZE),
+                    /* descending_order */ true,
+                )
+                .await?;
+
+            self.buffer.extend(page.data);
+            self.cursor = page.next_cursor;
+            self.has_next_page = page.has_next_page;
+        }
+
+        Ok(self.buffer.front())
+    }
+}
@@ -1,7 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::types::{
-    AccountBalanceRequest, Amount, ConstructionMetadata, Currency, CurrencyMetadata,
+    AccountBalanceRequest, Amount, BlockEvent, BlockEventType, BlockIdentifier,
+    ConstructionMetadata, Currency, CurrencyMetadata, OperationType, Operator,
+    SearchTransactionsRequest,
 };
 use quick_js::Context;
 use serde::{Deserialize, Serialize};
@@ -123,3 +125,46 @@ async fn test_metadata_total_coin_value_js_conversion_for_large_balance() {
     assert_eq!("65000004233578500", js_test_total_coin_value);
     assert_eq!("65000004233578496", js_prod_total_coin_value);
 }
+
+#[test]
+fn test_search_transactions_request() {
+    let request: SearchTransactionsRequest = serde_json::from_value(json!(
+        {
+            "network_identifier": {
+                "blockchain": "sui",
+                "network": "mainnet"
+            },
+            "operator": "and",
+            "limit": 10,
+            "account_identifier": {
+                "address": "0xadc3a0bb21840f732435f8b649e99df6b29cd27854dfa4b020e3bee07ea09b96"
+            },
+            "type": "PaySui"
+        }
+    ))
+    .unwrap();
+
+    assert_eq!(request.operator, Some(Operator::And));
+    assert_eq!(request.limit, Some(10));
+    assert_eq!(request.offset, None);
+    assert_eq!(request.type_, Some(OperationType::PaySui));
+    assert!(request.transaction_identifier.is_none());
+    assert!(request.account_identifier.is_some());
+}
+
+#[test]
+fn test_block_event_serialization() {
+    let event = BlockEvent {
+        sequence: 42,
+        block_identifier: BlockIdentifier {
+            index: 42,
+            hash: Default::default(),
+        },
+        type_: BlockEventType::BlockAdded,
+    };
+
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["sequence"], json!(42));
+    assert_eq!(json["type"], json!("block_added"));
+    assert_eq!(json["block_identifier"]["index"], json!(42));
+}
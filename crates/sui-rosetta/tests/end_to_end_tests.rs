@@ -11,12 +11,15 @@ use sui_keys::keystore::AccountKeystore;
 use sui_rosetta::operations::Operations;
 use sui_rosetta::types::Currencies;
 use sui_rosetta::types::{
-    AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, Currency, NetworkIdentifier,
-    SubAccount, SubAccountType, SuiEnv,
+    AccountBalanceRequest, AccountBalanceResponse, AccountIdentifier, BlockEventType, Currency,
+    EventsBlocksResponse, NetworkIdentifier, SearchTransactionsResponse, SubAccount,
+    SubAccountType, SuiEnv,
 };
 use sui_rosetta::CoinMetadataCache;
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockEffectsAPI};
+use sui_sdk::SuiClient;
 use sui_swarm_config::genesis_config::{DEFAULT_GAS_AMOUNT, DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT};
+use sui_types::digests::TransactionDigest;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::utils::to_sender_signed_transaction;
 use test_cluster::TestClusterBuilder;
@@ -501,3 +504,215 @@ async fn test_pay_sui_multiple_times() {
         );
     }
 }
+
+#[tokio::test]
+async fn test_search_transactions() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let sender = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let client = test_cluster.wallet.get_client().await.unwrap();
+    let keystore = &test_cluster.wallet.config.keystore;
+
+    let (rosetta_client, _handle) = start_rosetta_test_server(client.clone()).await;
+
+    let ops: Operations = serde_json::from_value(json!(
+        [{
+            "operation_identifier":{"index":0},
+            "type":"PaySui",
+            "account": { "address" : recipient.to_string() },
+            "amount" : { "value": "1000000000" }
+        },{
+            "operation_identifier":{"index":1},
+            "type":"PaySui",
+            "account": { "address" : sender.to_string() },
+            "amount" : { "value": "-1000000000" }
+        }]
+    ))
+    .unwrap();
+
+    let first = rosetta_client.rosetta_flow(&ops, keystore).await;
+    let second = rosetta_client.rosetta_flow(&ops, keystore).await;
+    let first = first.transaction_identifier.hash;
+    let second = second.transaction_identifier.hash;
+
+    // Search results only include checkpointed transactions.
+    let first_checkpoint = wait_for_checkpoint(&client, first).await;
+    wait_for_checkpoint(&client, second).await;
+
+    let network_identifier = json!({ "blockchain": "sui", "network": "localnet" });
+
+    // Search by transaction identifier.
+    let response: SearchTransactionsResponse = rosetta_client
+        .call(
+            RosettaEndpoint::SearchTransactions,
+            &json!({
+                "network_identifier": network_identifier,
+                "transaction_identifier": { "hash": first },
+            }),
+        )
+        .await;
+
+    assert_eq!(response.transactions.len(), 1);
+    assert_eq!(response.total_count, 1);
+    assert_eq!(response.next_offset, None);
+    let found = &response.transactions[0];
+    assert_eq!(found.transaction.transaction_identifier.hash, first);
+    assert_eq!(found.block_identifier.index, first_checkpoint);
+    assert!(found.transaction.operations.contains(&ops));
+
+    // Search by account and operation type, a page at a time, most recent first.
+    let search_by_account = |offset: u64| {
+        json!({
+            "network_identifier": network_identifier,
+            "account_identifier": { "address": recipient.to_string() },
+            "type": "PaySui",
+            "offset": offset,
+            "limit": 1,
+        })
+    };
+
+    let response: SearchTransactionsResponse = rosetta_client
+        .call(RosettaEndpoint::SearchTransactions, &search_by_account(0))
+        .await;
+
+    assert_eq!(response.transactions.len(), 1);
+    assert_eq!(
+        response.transactions[0]
+            .transaction
+            .transaction_identifier
+            .hash,
+        second
+    );
+    assert_eq!(response.total_count, 2);
+    assert_eq!(response.next_offset, Some(1));
+
+    let response: SearchTransactionsResponse = rosetta_client
+        .call(RosettaEndpoint::SearchTransactions, &search_by_account(1))
+        .await;
+
+    assert_eq!(response.transactions.len(), 1);
+    assert_eq!(
+        response.transactions[0]
+            .transaction
+            .transaction_identifier
+            .hash,
+        first
+    );
+    assert_eq!(response.total_count, 2);
+    assert_eq!(response.next_offset, None);
+
+    // Transactions after `max_block` are excluded, with or without an account identifier.
+    for account_identifier in [json!(null), json!({ "address": recipient.to_string() })] {
+        let response: SearchTransactionsResponse = rosetta_client
+            .call(
+                RosettaEndpoint::SearchTransactions,
+                &json!({
+                    "network_identifier": network_identifier,
+                    "account_identifier": account_identifier,
+                    "max_block": first_checkpoint - 1,
+                    "type": "PaySui",
+                }),
+            )
+            .await;
+
+        assert!(response.transactions.iter().all(|tx| {
+            tx.block_identifier.index < first_checkpoint
+                && tx.transaction.transaction_identifier.hash != first
+                && tx.transaction.transaction_identifier.hash != second
+        }));
+    }
+
+    // Only the 'and' operator is supported.
+    let response: serde_json::Value = rosetta_client
+        .call(
+            RosettaEndpoint::SearchTransactions,
+            &json!({
+                "network_identifier": network_identifier,
+                "operator": "or",
+                "type": "PaySui",
+            }),
+        )
+        .await;
+
+    assert_eq!(
+        response["details"]["error"],
+        "Invalid input: Only the 'and' operator is supported"
+    );
+}
+
+#[tokio::test]
+async fn test_events_blocks() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let client = test_cluster.wallet.get_client().await.unwrap();
+
+    let (rosetta_client, _handle) = start_rosetta_test_server(client.clone()).await;
+    while client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await
+        .unwrap()
+        < 3
+    {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let network_identifier = json!({ "blockchain": "sui", "network": "localnet" });
+
+    let response: EventsBlocksResponse = rosetta_client
+        .call(
+            RosettaEndpoint::EventsBlocks,
+            &json!({
+                "network_identifier": network_identifier,
+                "offset": 1,
+                "limit": 2,
+            }),
+        )
+        .await;
+
+    assert!(response.max_sequence >= 3);
+    let sequences: Vec<_> = response.events.iter().map(|e| e.sequence).collect();
+    assert_eq!(sequences, vec![1, 2]);
+
+    for event in &response.events {
+        assert_eq!(event.type_, BlockEventType::BlockAdded);
+        assert_eq!(event.block_identifier.index, event.sequence);
+
+        let checkpoint = client
+            .read_api()
+            .get_checkpoint(event.sequence.into())
+            .await
+            .unwrap();
+        assert_eq!(event.block_identifier.hash, checkpoint.digest);
+    }
+
+    // Offsets past the latest checkpoint return no events.
+    let response: EventsBlocksResponse = rosetta_client
+        .call(
+            RosettaEndpoint::EventsBlocks,
+            &json!({
+                "network_identifier": network_identifier,
+                "offset": u64::MAX,
+            }),
+        )
+        .await;
+
+    assert!(response.events.is_empty());
+}
+
+/// Wait for the transaction with digest `digest` to be included in a checkpoint, and return that
+/// checkpoint's sequence number.
+async fn wait_for_checkpoint(client: &SuiClient, digest: TransactionDigest) -> u64 {
+    loop {
+        let response = client
+            .read_api()
+            .get_transaction_with_options(digest, SuiTransactionBlockResponseOptions::new())
+            .await
+            .unwrap();
+
+        if let Some(checkpoint) = response.checkpoint {
+            return checkpoint;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::FileType;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use sui_core::authority::authority_store_tables::{AuthorityPerpetualTables, LiveObject};
+use sui_core::authority::AuthorityStore;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress};
+use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
+use sui_types::object::Object;
+use sui_types::TypeTag;
+
+pub const ROOT_STATE_HASH_FILE_NAME: &str = "ROOT_STATE_HASH";
+pub const REFS_DIR_NAME: &str = "refs";
+
+/// LiveObjectFilter selects the live objects that a partial restore of a state snapshot keeps. An
+/// object is kept if it matches any of the filter's criteria. Wrapped objects are never kept.
+#[derive(Clone, Debug, Default)]
+pub struct LiveObjectFilter {
+    object_ids: BTreeSet<ObjectID>,
+    packages: BTreeSet<ObjectID>,
+    types: Vec<TypeTag>,
+    owners: BTreeSet<SuiAddress>,
+}
+
+impl LiveObjectFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the object with ID `id`.
+    pub fn add_object_id(mut self, id: ObjectID) -> Self {
+        self.object_ids.insert(id);
+        self
+    }
+
+    /// Keep package `package`, and all objects whose type is defined in it.
+    pub fn add_package(mut self, package: ObjectID) -> Self {
+        self.packages.insert(package);
+        self
+    }
+
+    /// Keep all objects of type `type_`. A struct type without type parameters (e.g.
+    /// `0x2::coin::Coin`) matches every instantiation of that type.
+    pub fn add_type(mut self, type_: TypeTag) -> Self {
+        self.types.push(type_);
+        self
+    }
+
+    /// Keep all objects owned by `owner`, which may be an address or an object.
+    pub fn add_owner(mut self, owner: SuiAddress) -> Self {
+        self.owners.insert(owner);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.object_ids.is_empty()
+            && self.packages.is_empty()
+            && self.types.is_empty()
+            && self.owners.is_empty()
+    }
+
+    pub fn matches(&self, object: &Object) -> bool {
+        if self.object_ids.contains(&object.id()) {
+            return true;
+        }
+        if object.is_package() {
+            return self.packages.contains(&object.id());
+        }
+        if object
+            .owner()
+            .get_owner_address()
+            .is_ok_and(|owner| self.owners.contains(&owner))
+        {
+            return true;
+        }
+        let Some(tag) = object.struct_tag() else {
+            return false;
+        };
+        self.packages.contains(&ObjectID::from(tag.address))
+            || self.types.iter().any(|type_| {
+                let TypeTag::Struct(filter_tag) = type_ else {
+                    return false;
+                };
+                filter_tag.address == tag.address
+                    && filter_tag.module == tag.module
+                    && filter_tag.name == tag.name
+                    && (filter_tag.type_params.is_empty()
+                        || filter_tag.type_params == tag.type_params)
+            })
+    }
+
+    /// The IDs of the only objects that the filter can match, if it only selects objects by ID.
+    pub(crate) fn only_object_ids(&self) -> Option<&BTreeSet<ObjectID>> {
+        (self.packages.is_empty() && self.types.is_empty() && self.owners.is_empty())
+            .then_some(&self.object_ids)
+    }
+}
+
+/// The object references in a REFERENCE (or REMOVED) file of a snapshot. A partial restore passes
+/// these on for every partition, including the ones it has no objects from, so that the restored
+/// objects can later be proven to be part of the snapshot's live object set.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SnapshotRefs {
+    pub epoch: u64,
+    pub file_type: FileType,
+    pub bucket_num: u32,
+    pub part_num: u32,
+    /// The sha3 digest of the object digests in `object_refs`, which is what the partition's
+    /// object file is checked against.
+    pub sha3_digest: [u8; 32],
+    pub object_refs: Vec<ObjectRef>,
+}
+
+/// LiveObjectSink is where a partial restore of a state snapshot writes the objects it keeps.
+pub trait LiveObjectSink: Send + Sync {
+    /// Write objects that are live as of the snapshot being restored.
+    fn insert(&self, objects: Vec<Object>) -> Result<()>;
+
+    /// Remove versions of objects that were inserted from an earlier snapshot in a chain of delta
+    /// snapshots, but are no longer live. These may include objects that were never inserted,
+    /// because they did not match the filter.
+    fn remove(&self, object_refs: &[ObjectRef]) -> Result<()>;
+
+    /// Record the object references of a partition of a snapshot in the chain being restored.
+    fn insert_refs(&self, _refs: SnapshotRefs) -> Result<()> {
+        Ok(())
+    }
+
+    /// Record the root state hash of the live object set that was restored from, accumulated from
+    /// the object references of every snapshot in the chain.
+    fn set_root_state_hash(&self, _root_state_hash: ECMHLiveObjectSetDigest) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl LiveObjectSink for AuthorityPerpetualTables {
+    fn insert(&self, objects: Vec<Object>) -> Result<()> {
+        AuthorityStore::insert_verified_live_objects(
+            self,
+            objects.into_iter().map(LiveObject::Normal),
+        )?;
+        Ok(())
+    }
+
+    fn remove(&self, object_refs: &[ObjectRef]) -> Result<()> {
+        AuthorityStore::bulk_remove_live_objects(self, object_refs.iter().copied())?;
+        Ok(())
+    }
+}
+
+/// BcsDirectorySink writes every object to its own file in a directory, named
+/// `<object id>_<version>.bcs`, which contains the BCS-encoded object. Next to them, it writes the
+/// BCS-encoded root state hash to `ROOT_STATE_HASH`, and the BCS-encoded [`SnapshotRefs`] of each
+/// partition to `refs/<epoch>_<bucket>_<part>.ref` (or `.rm`, for removed objects).
+pub struct BcsDirectorySink {
+    dir: PathBuf,
+}
+
+impl BcsDirectorySink {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    fn object_path(&self, id: ObjectID, version: SequenceNumber) -> PathBuf {
+        self.dir.join(format!("{}_{}.bcs", id, version.value()))
+    }
+
+    fn refs_path(&self, refs: &SnapshotRefs) -> PathBuf {
+        let suffix = match refs.file_type {
+            FileType::Removed => "rm",
+            _ => "ref",
+        };
+        self.dir.join(REFS_DIR_NAME).join(format!(
+            "{}_{}_{}.{suffix}",
+            refs.epoch, refs.bucket_num, refs.part_num
+        ))
+    }
+}
+
+impl LiveObjectSink for BcsDirectorySink {
+    fn insert(&self, objects: Vec<Object>) -> Result<()> {
+        for object in objects {
+            let bytes = bcs::to_bytes(&object)?;
+            fs::write(self.object_path(object.id(), object.version()), bytes)?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, object_refs: &[ObjectRef]) -> Result<()> {
+        for (id, version, _) in object_refs {
+            match fs::remove_file(self.object_path(*id, *version)) {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_refs(&self, refs: SnapshotRefs) -> Result<()> {
+        let path = self.refs_path(&refs);
+        fs::create_dir_all(self.dir.join(REFS_DIR_NAME))?;
+        fs::write(path, bcs::to_bytes(&refs)?)?;
+        Ok(())
+    }
+
+    fn set_root_state_hash(&self, root_state_hash: ECMHLiveObjectSetDigest) -> Result<()> {
+        fs::write(
+            self.dir.join(ROOT_STATE_HASH_FILE_NAME),
+            bcs::to_bytes(&root_state_hash)?,
+        )?;
+        Ok(())
+    }
+}
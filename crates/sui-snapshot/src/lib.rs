@@ -5,6 +5,7 @@
 #[cfg(test)]
 mod tests;
 
+pub mod filter;
 pub mod reader;
 pub mod uploader;
 mod writer;
@@ -73,6 +74,15 @@ use tokio::time::Instant;
 ///       - 1_1.obj
 ///       - ...
 ///
+/// Delta Snapshots
+/// A snapshot may instead be a delta on top of the snapshot of an earlier (base) epoch, which may in
+/// turn be a delta. Its *.obj and *.ref files only contain the objects that were created or mutated
+/// since the base epoch, and its *.rm files contain the references of the objects in the base
+/// snapshot that are no longer live (because they were deleted, wrapped or mutated). *.rm files use
+/// the REFERENCE file format. The MANIFEST of a delta snapshot records the base epoch and the sha3
+/// checksum of the base snapshot's MANIFEST, so restoring a delta snapshot means restoring the full
+/// snapshot at the start of its chain, and then applying every delta in the chain on top of it.
+///
 /// Object File Disk Format
 ///┌──────────────────────────────┐
 ///│  magic(0x00B7EC75) <4 byte>  │
@@ -143,6 +153,7 @@ const FILE_METADATA_BYTES: usize =
 pub enum FileType {
     Object = 0,
     Reference,
+    Removed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -163,6 +174,9 @@ impl FileMetadata {
             FileType::Reference => {
                 dir_path.child(&*format!("{}_{}.ref", self.bucket_num, self.part_num))
             }
+            FileType::Removed => {
+                dir_path.child(&*format!("{}_{}.rm", self.bucket_num, self.part_num))
+            }
         }
     }
     pub fn local_file_path(&self, root_path: &std::path::Path, dir_path: &Path) -> Result<PathBuf> {
@@ -178,30 +192,55 @@ pub struct ManifestV1 {
     pub epoch: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DeltaManifestV1 {
+    pub snapshot_version: u8,
+    pub address_length: u64,
+    pub file_metadata: Vec<FileMetadata>,
+    pub epoch: u64,
+    /// Epoch of the snapshot that this snapshot is a delta on top of.
+    pub base_epoch: u64,
+    /// Checksum of the base snapshot's MANIFEST file.
+    pub base_manifest_digest: [u8; 32],
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum Manifest {
     V1(ManifestV1),
+    DeltaV1(DeltaManifestV1),
 }
 
 impl Manifest {
     pub fn snapshot_version(&self) -> u8 {
         match self {
             Self::V1(manifest) => manifest.snapshot_version,
+            Self::DeltaV1(manifest) => manifest.snapshot_version,
         }
     }
     pub fn address_length(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.address_length,
+            Self::DeltaV1(manifest) => manifest.address_length,
         }
     }
     pub fn file_metadata(&self) -> &Vec<FileMetadata> {
         match self {
             Self::V1(manifest) => &manifest.file_metadata,
+            Self::DeltaV1(manifest) => &manifest.file_metadata,
         }
     }
     pub fn epoch(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.epoch,
+            Self::DeltaV1(manifest) => manifest.epoch,
+        }
+    }
+    /// The epoch and MANIFEST checksum of the snapshot this one is a delta on top of, or `None` if
+    /// this is a full snapshot.
+    pub fn base(&self) -> Option<(u64, [u8; 32])> {
+        match self {
+            Self::V1(_) => None,
+            Self::DeltaV1(manifest) => Some((manifest.base_epoch, manifest.base_manifest_digest)),
         }
     }
 }
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::filter::{LiveObjectFilter, LiveObjectSink, SnapshotRefs};
 use crate::{
     FileMetadata, FileType, Manifest, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
     OBJECT_ID_BYTES, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES, SHA3_BYTES,
@@ -15,10 +16,10 @@ use futures::{StreamExt, TryStreamExt};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use integer_encoding::VarIntReader;
 use object_store::path::Path;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::fs::File;
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::io::Read;
+use std::iter::Peekable;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
@@ -29,10 +30,11 @@ use sui_core::authority::AuthorityStore;
 use sui_indexer_alt_framework::task::TrySpawnStreamExt;
 use sui_storage::blob::{Blob, BlobEncoding};
 use sui_storage::object_store::http::HttpDownloaderBuilder;
-use sui_storage::object_store::util::{copy_file, copy_files, path_to_filesystem};
+use sui_storage::object_store::util::{copy_files, path_to_filesystem};
 use sui_storage::object_store::{ObjectStoreGetExt, ObjectStoreListExt, ObjectStorePutExt};
 use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber};
 use sui_types::global_state_hash::GlobalStateHash;
+use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tokio::time::Duration;
@@ -48,12 +50,153 @@ pub struct StateSnapshotReaderV1 {
     local_staging_dir_root: PathBuf,
     remote_object_store: Arc<dyn ObjectStoreGetExt>,
     local_object_store: Arc<dyn ObjectStorePutExt>,
-    ref_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
-    object_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    /// The full snapshot that the snapshot for `epoch` is restored from.
+    snapshot: SnapshotFiles,
+    /// The delta snapshots to apply on top of `snapshot` to get to `epoch`, oldest first. Empty if
+    /// the snapshot for `epoch` is a full snapshot.
+    deltas: Vec<SnapshotFiles>,
     m: MultiProgress,
     concurrency: usize,
 }
 
+/// The files of a single (full or delta) snapshot, by bucket and partition.
+#[derive(Clone)]
+struct SnapshotFiles {
+    epoch: u64,
+    ref_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    object_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    /// References to objects that are no longer live since the base snapshot. Always empty for a
+    /// full snapshot.
+    removed_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+}
+
+impl SnapshotFiles {
+    fn new(manifest: &Manifest) -> Self {
+        let mut object_files = BTreeMap::new();
+        let mut ref_files = BTreeMap::new();
+        let mut removed_files = BTreeMap::new();
+        for file_metadata in manifest.file_metadata() {
+            let files = match file_metadata.file_type {
+                FileType::Object => &mut object_files,
+                FileType::Reference => &mut ref_files,
+                FileType::Removed => &mut removed_files,
+            };
+            files
+                .entry(file_metadata.bucket_num)
+                .or_insert_with(BTreeMap::new)
+                .insert(file_metadata.part_num, file_metadata.clone());
+        }
+        SnapshotFiles {
+            epoch: manifest.epoch(),
+            ref_files,
+            object_files,
+            removed_files,
+        }
+    }
+
+    fn epoch_dir(&self) -> Path {
+        Path::from(format!("epoch_{}", self.epoch))
+    }
+
+    /// The files that are downloaded up front, rather than streamed during the restore.
+    fn local_files(&self) -> Vec<Path> {
+        let epoch_dir = self.epoch_dir();
+        self.ref_files
+            .values()
+            .chain(self.removed_files.values())
+            .flat_map(|entry| entry.values())
+            .map(|file_metadata| file_metadata.file_path(&epoch_dir))
+            .collect()
+    }
+
+    fn ref_iter(
+        &self,
+        local_staging_dir_root: PathBuf,
+        bucket_num: u32,
+        part_num: u32,
+    ) -> Result<ObjectRefIter> {
+        let file_metadata = self
+            .ref_files
+            .get(&bucket_num)
+            .context(format!("No ref files found for bucket: {bucket_num}"))?
+            .get(&part_num)
+            .context(format!(
+                "No ref files found for bucket: {bucket_num}, part: {part_num}"
+            ))?;
+        ObjectRefIter::new(file_metadata, local_staging_dir_root, self.epoch_dir())
+    }
+}
+
+/// Where a restore writes the live objects from a snapshot to.
+#[derive(Clone, Copy)]
+enum RestoreTarget<'a> {
+    /// All live objects, into a node's database.
+    Db(&'a AuthorityPerpetualTables),
+    /// Only the live objects that match the filter, into the sink.
+    Filtered(&'a LiveObjectFilter, &'a dyn LiveObjectSink),
+}
+
+impl RestoreTarget<'_> {
+    /// Writes the objects in an object file, after checking them against `sha3_digest`, the
+    /// digest of the references in the corresponding REFERENCE file.
+    fn insert(&self, live_objects: LiveObjectIter, sha3_digest: &[u8; 32]) -> Result<()> {
+        match self {
+            Self::Db(perpetual_db) => {
+                AuthorityStore::bulk_insert_live_objects(perpetual_db, live_objects, sha3_digest)?;
+            }
+            Self::Filtered(filter, sink) => {
+                let mut hasher = Sha3_256::default();
+                let mut objects = vec![];
+                for live_object in live_objects {
+                    hasher.update(live_object.object_reference().2.inner());
+                    if let LiveObject::Normal(object) = live_object {
+                        if filter.matches(&object) {
+                            objects.push(object);
+                        }
+                    }
+                }
+                let computed_digest = hasher.finalize().digest;
+                if computed_digest != *sha3_digest {
+                    return Err(anyhow!(
+                        "Checksum: {:?} don't match: {:?}",
+                        computed_digest,
+                        sha3_digest
+                    ));
+                }
+                sink.insert(objects)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn remove(&self, object_refs: &[ObjectRef]) -> Result<()> {
+        match self {
+            Self::Db(perpetual_db) => {
+                AuthorityStore::bulk_remove_live_objects(
+                    perpetual_db,
+                    object_refs.iter().copied(),
+                )?;
+            }
+            Self::Filtered(_, sink) => sink.remove(object_refs)?,
+        }
+        Ok(())
+    }
+
+    fn only_object_ids(&self) -> Option<&BTreeSet<ObjectID>> {
+        match self {
+            Self::Db(_) => None,
+            Self::Filtered(filter, _) => filter.only_object_ids(),
+        }
+    }
+
+    fn sink(&self) -> Option<&dyn LiveObjectSink> {
+        match self {
+            Self::Db(_) => None,
+            Self::Filtered(_, sink) => Some(*sink),
+        }
+    }
+}
+
 impl StateSnapshotReaderV1 {
     pub async fn new(
         epoch: u64,
@@ -63,7 +206,6 @@ impl StateSnapshotReaderV1 {
         m: MultiProgress,
         skip_reset_local_store: bool,
     ) -> Result<Self> {
-        let epoch_dir = format!("epoch_{}", epoch);
         let remote_object_store = if remote_store_config.no_sign_request {
             remote_store_config.make_http()?
         } else {
@@ -78,87 +220,41 @@ impl StateSnapshotReaderV1 {
             .as_ref()
             .context("No directory specified")?
             .clone();
-        if !skip_reset_local_store {
-            let local_epoch_dir_path = local_staging_dir_root.join(&epoch_dir);
-            if local_epoch_dir_path.exists() {
-                fs::remove_dir_all(&local_epoch_dir_path)?;
-            }
-            fs::create_dir_all(&local_epoch_dir_path)?;
-        }
-        // Download MANIFEST first
-        let manifest_file_path = Path::from(epoch_dir.clone()).child("MANIFEST");
-        copy_file(
-            &manifest_file_path,
-            &manifest_file_path,
-            &remote_object_store,
-            &local_object_store,
-        )
-        .await?;
-        let manifest = Self::read_manifest(path_to_filesystem(
-            local_staging_dir_root.clone(),
-            &manifest_file_path,
-        )?)?;
-        let snapshot_version = manifest.snapshot_version();
-        if snapshot_version != 1u8 {
-            return Err(anyhow!("Unexpected snapshot version: {}", snapshot_version));
-        }
-        if manifest.address_length() as usize > ObjectID::LENGTH {
-            return Err(anyhow!(
-                "Max possible address length is: {}",
-                ObjectID::LENGTH
-            ));
-        }
-        if manifest.epoch() != epoch {
-            return Err(anyhow!("Download manifest is not for epoch: {}", epoch,));
-        }
-        let mut object_files = BTreeMap::new();
-        let mut ref_files = BTreeMap::new();
-        for file_metadata in manifest.file_metadata() {
-            match file_metadata.file_type {
-                FileType::Object => {
-                    let entry = object_files
-                        .entry(file_metadata.bucket_num)
-                        .or_insert_with(BTreeMap::new);
-                    entry.insert(file_metadata.part_num, file_metadata.clone());
-                }
-                FileType::Reference => {
-                    let entry = ref_files
-                        .entry(file_metadata.bucket_num)
-                        .or_insert_with(BTreeMap::new);
-                    entry.insert(file_metadata.part_num, file_metadata.clone());
-                }
-            }
-        }
-        let epoch_dir_path = Path::from(epoch_dir);
-        let files: Vec<Path> = ref_files
-            .values()
-            .flat_map(|entry| {
-                let files: Vec<_> = entry
-                    .values()
-                    .map(|file_metadata| file_metadata.file_path(&epoch_dir_path))
-                    .collect();
-                files
-            })
+        // Download MANIFEST first, along with those of the snapshots it is a delta of, if any
+        let mut snapshots: Vec<SnapshotFiles> = read_manifest_chain(&remote_object_store, epoch)
+            .await?
+            .iter()
+            .map(|(manifest, _)| SnapshotFiles::new(manifest))
             .collect();
+        let snapshot = snapshots.remove(0);
+        let deltas = snapshots;
 
-        let files_to_download = if skip_reset_local_store {
+        let mut files_to_download = vec![];
+        for snapshot_files in std::iter::once(&snapshot).chain(&deltas) {
+            let local_epoch_dir_path =
+                path_to_filesystem(local_staging_dir_root.clone(), &snapshot_files.epoch_dir())?;
+            let files = snapshot_files.local_files();
+            if !skip_reset_local_store {
+                if local_epoch_dir_path.exists() {
+                    fs::remove_dir_all(&local_epoch_dir_path)?;
+                }
+                fs::create_dir_all(&local_epoch_dir_path)?;
+                files_to_download.extend(files);
+                continue;
+            }
             let mut list_stream = local_object_store_list
-                .list_objects(Some(&epoch_dir_path))
+                .list_objects(Some(&snapshot_files.epoch_dir()))
                 .await;
             let mut existing_files = std::collections::HashSet::new();
             while let Some(Ok(meta)) = list_stream.next().await {
                 existing_files.insert(meta.location);
             }
-            let mut missing_files = Vec::new();
-            for file in &files {
-                if !existing_files.contains(file) {
-                    missing_files.push(file.clone());
+            for file in files {
+                if !existing_files.contains(&file) {
+                    files_to_download.push(file);
                 }
             }
-            missing_files
-        } else {
-            files
-        };
+        }
         let progress_bar = m.add(
             ProgressBar::new(files_to_download.len() as u64).with_style(
                 ProgressStyle::with_template(
@@ -182,49 +278,174 @@ impl StateSnapshotReaderV1 {
             local_staging_dir_root,
             remote_object_store,
             local_object_store,
-            ref_files,
-            object_files,
+            snapshot,
+            deltas,
             m,
             concurrency: download_concurrency.get(),
         })
     }
 
+    /// Restores the live objects into `perpetual_db`.
+    ///
+    /// If `sender` is provided, the live object set is also accumulated, and sent to it in parts,
+    /// each alongside the change it makes to the number of live objects (negative for the objects
+    /// that a delta snapshot removes).
     pub async fn read(
         &mut self,
         perpetual_db: &AuthorityPerpetualTables,
         abort_registration: AbortRegistration,
-        sender: Option<tokio::sync::mpsc::Sender<(GlobalStateHash, u64)>>,
+        sender: Option<tokio::sync::mpsc::Sender<(GlobalStateHash, i64)>>,
+    ) -> Result<()> {
+        self.restore(RestoreTarget::Db(perpetual_db), abort_registration, sender)
+            .await
+    }
+
+    /// Restores only the live objects that match `filter`, into `sink`.
+    ///
+    /// Object files are still checked against the snapshot's REFERENCE files, and if `sender` is
+    /// provided, the references of all live objects (not only the matching ones) are accumulated
+    /// and sent to it (as in [`Self::read`]), so that the caller can check them against the root state hash committed to
+    /// at the end of the epoch. Together, these prove that the restored objects are part of the
+    /// live object set that the root state hash commits to.
+    ///
+    /// So that this proof can be checked again later, the object references and their sha3 digest
+    /// for every partition of every snapshot in the chain, and the root state hash accumulated
+    /// from them, are written to `sink` as well.
+    pub async fn read_filtered(
+        &mut self,
+        filter: &LiveObjectFilter,
+        sink: &dyn LiveObjectSink,
+        abort_registration: AbortRegistration,
+        sender: Option<tokio::sync::mpsc::Sender<(GlobalStateHash, i64)>>,
+    ) -> Result<()> {
+        self.restore(
+            RestoreTarget::Filtered(filter, sink),
+            abort_registration,
+            sender,
+        )
+        .await
+    }
+
+    async fn restore(
+        &self,
+        target: RestoreTarget<'_>,
+        abort_registration: AbortRegistration,
+        sender: Option<tokio::sync::mpsc::Sender<(GlobalStateHash, i64)>>,
+    ) -> Result<()> {
+        let snapshots: Vec<_> = std::iter::once(self.snapshot.clone())
+            .chain(self.deltas.clone())
+            .collect();
+        Abortable::new(
+            async move {
+                // Deltas only apply on top of the state that they are a delta of, so they are
+                // restored one after the other.
+                let mut root_acc = GlobalStateHash::default();
+                for snapshot in &snapshots {
+                    self.read_snapshot(&target, snapshot, sender.clone())
+                        .await?;
+                    if let Some(sink) = target.sink() {
+                        self.record_refs(sink, snapshot, &mut root_acc)?;
+                    }
+                }
+                if let Some(sink) = target.sink() {
+                    sink.set_root_state_hash(ECMHLiveObjectSetDigest::from(root_acc.digest()))?;
+                }
+                Ok::<(), anyhow::Error>(())
+            },
+            abort_registration,
+        )
+        .await?
+    }
+
+    async fn read_snapshot(
+        &self,
+        target: &RestoreTarget<'_>,
+        snapshot: &SnapshotFiles,
+        sender: Option<tokio::sync::mpsc::Sender<(GlobalStateHash, i64)>>,
     ) -> Result<()> {
         // This computes and stores the sha3 digest of object references in REFERENCE file for each
         // bucket partition. When downloading objects, we will match sha3 digest of object references
         // per *.obj file against this. We do this so during restore we can pre fetch object
         // references and start building state accumulator and fail early if the state root hash
         // doesn't match but we still need to ensure that objects match references exactly.
-        let (sha3_digests, num_part_files) = self.compute_checksum().await?;
-        let accum_handle =
-            sender.map(|sender| self.spawn_accumulation_tasks(sender, num_part_files));
-        self.sync_live_objects(perpetual_db, abort_registration, sha3_digests)
+        let (sha3_digests, num_part_files) = self.compute_snapshot_checksum(snapshot).await?;
+        let accum_handle = sender
+            .clone()
+            .map(|sender| self.spawn_accumulation_tasks(snapshot, sender, num_part_files));
+        self.sync_live_objects(target, snapshot, sha3_digests)
             .await?;
+        self.remove_live_objects(target, snapshot, sender).await?;
         if let Some(handle) = accum_handle {
             handle.await?;
         }
         Ok(())
     }
 
+    /// Passes the object references in each of `snapshot`'s REFERENCE and REMOVED files on to
+    /// `sink`, and adds them to (or removes them from) `root_acc`.
+    fn record_refs(
+        &self,
+        sink: &dyn LiveObjectSink,
+        snapshot: &SnapshotFiles,
+        root_acc: &mut GlobalStateHash,
+    ) -> Result<()> {
+        let epoch_dir = snapshot.epoch_dir();
+        for (file_type, files) in [
+            (FileType::Reference, &snapshot.ref_files),
+            (FileType::Removed, &snapshot.removed_files),
+        ] {
+            for file_metadata in files.values().flat_map(|part_files| part_files.values()) {
+                let object_refs: Vec<_> = ObjectRefIter::new(
+                    file_metadata,
+                    self.local_staging_dir_root.clone(),
+                    epoch_dir.clone(),
+                )?
+                .collect();
+                let mut hasher = Sha3_256::default();
+                for object_ref in &object_refs {
+                    hasher.update(object_ref.2.inner());
+                }
+                let digests = object_refs.iter().map(|object_ref| object_ref.2);
+                match file_type {
+                    FileType::Removed => root_acc.remove_all(digests),
+                    _ => root_acc.insert_all(digests),
+                }
+                sink.insert_refs(SnapshotRefs {
+                    epoch: snapshot.epoch,
+                    file_type,
+                    bucket_num: file_metadata.bucket_num,
+                    part_num: file_metadata.part_num,
+                    sha3_digest: hasher.finalize().digest,
+                    object_refs,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Computes the checksums of the full snapshot that the snapshot for this reader's epoch is
+    /// restored from.
     pub async fn compute_checksum(
         &mut self,
+    ) -> Result<(Arc<Mutex<BTreeMap<u32, BTreeMap<u32, [u8; 32]>>>>, usize), anyhow::Error> {
+        self.compute_snapshot_checksum(&self.snapshot).await
+    }
+
+    async fn compute_snapshot_checksum(
+        &self,
+        snapshot: &SnapshotFiles,
     ) -> Result<(Arc<Mutex<BTreeMap<u32, BTreeMap<u32, [u8; 32]>>>>, usize), anyhow::Error> {
         let sha3_digests: Arc<Mutex<DigestByBucketAndPartition>> =
             Arc::new(Mutex::new(BTreeMap::new()));
 
-        let num_part_files = self
+        let num_part_files = snapshot
             .ref_files
             .values()
             .map(|part_files| part_files.len())
             .sum::<usize>();
 
         // Generate checksums
-        info!("Computing checksums");
+        info!("Computing checksums for epoch: {}", snapshot.epoch);
         let checksum_progress_bar = self.m.add(
             ProgressBar::new(num_part_files as u64).with_style(
                 ProgressStyle::with_template(
@@ -234,7 +455,7 @@ impl StateSnapshotReaderV1 {
             ),
         );
 
-        let ref_files_iter = self.ref_files.clone().into_iter();
+        let ref_files_iter = snapshot.ref_files.clone().into_iter();
         futures::stream::iter(ref_files_iter)
             .flat_map(|(bucket, part_files)| {
                 futures::stream::iter(
@@ -245,16 +466,17 @@ impl StateSnapshotReaderV1 {
             })
             .try_for_each_spawned(self.concurrency, |(bucket, part, _part_file)| {
                 let sha3_digests = sha3_digests.clone();
-                let object_files = self.object_files.clone();
                 let bar = checksum_progress_bar.clone();
-                let this = self.clone();
+                let snapshot = snapshot.clone();
+                let local_staging_dir_root = self.local_staging_dir_root.clone();
 
                 async move {
-                    let ref_iter = this.ref_iter(bucket, part)?;
+                    let ref_iter = snapshot.ref_iter(local_staging_dir_root, bucket, part)?;
                     let mut hasher = Sha3_256::default();
                     let mut empty = true;
 
-                    object_files
+                    snapshot
+                        .object_files
                         .get(&bucket)
                         .context(format!("No bucket exists for: {bucket}"))?
                         .get(&part)
@@ -286,7 +508,8 @@ impl StateSnapshotReaderV1 {
 
     fn spawn_accumulation_tasks(
         &self,
-        sender: tokio::sync::mpsc::Sender<(GlobalStateHash, u64)>,
+        snapshot: &SnapshotFiles,
+        sender: tokio::sync::mpsc::Sender<(GlobalStateHash, i64)>,
         num_part_files: usize,
     ) -> JoinHandle<()> {
         // Spawn accumulation progress bar
@@ -321,8 +544,8 @@ impl StateSnapshotReaderV1 {
         });
 
         // spawn accumualation task
-        let ref_files = self.ref_files.clone();
-        let epoch_dir = self.epoch_dir();
+        let ref_files = snapshot.ref_files.clone();
+        let epoch_dir = snapshot.epoch_dir();
         let local_staging_dir_root = self.local_staging_dir_root.clone();
         tokio::task::spawn(async move {
             let local_staging_dir_root_clone = local_staging_dir_root.clone();
@@ -356,7 +579,7 @@ impl StateSnapshotReaderV1 {
                             let num_objects = obj_digests.len();
                             partial_acc.insert_all(obj_digests);
                             sender_clone
-                                .send((partial_acc, num_objects as u64))
+                                .send((partial_acc, num_objects as i64))
                                 .await
                                 .expect("Unable to send accumulator from snapshot reader");
                         })
@@ -376,14 +599,14 @@ impl StateSnapshotReaderV1 {
 
     async fn sync_live_objects(
         &self,
-        perpetual_db: &AuthorityPerpetualTables,
-        abort_registration: AbortRegistration,
+        target: &RestoreTarget<'_>,
+        snapshot: &SnapshotFiles,
         sha3_digests: Arc<Mutex<DigestByBucketAndPartition>>,
     ) -> Result<(), anyhow::Error> {
-        let epoch_dir = self.epoch_dir();
+        let epoch_dir = snapshot.epoch_dir();
         let concurrency = self.concurrency;
         let remote_object_store = self.remote_object_store.clone();
-        let input_files: Vec<_> = self
+        let mut input_files: Vec<_> = snapshot
             .object_files
             .iter()
             .flat_map(|(bucket, parts)| {
@@ -394,6 +617,19 @@ impl StateSnapshotReaderV1 {
                     .collect::<Vec<_>>()
             })
             .collect();
+        // Partitions that can't contain any objects that are restored are not downloaded
+        if let Some(object_ids) = target.only_object_ids() {
+            let mut skipped = BTreeSet::new();
+            for (bucket, (part, _)) in &input_files {
+                if !snapshot
+                    .ref_iter(self.local_staging_dir_root.clone(), **bucket, *part)?
+                    .any(|object_ref| object_ids.contains(&object_ref.0))
+                {
+                    skipped.insert((**bucket, *part));
+                }
+            }
+            input_files.retain(|(bucket, (part, _))| !skipped.contains(&(**bucket, *part)));
+        }
         let obj_progress_bar = self.m.add(
             ProgressBar::new(input_files.len() as u64).with_style(
                 ProgressStyle::with_template(
@@ -406,64 +642,86 @@ impl StateSnapshotReaderV1 {
         let instant = Instant::now();
         let downloaded_bytes = AtomicUsize::new(0);
 
-        let ret = Abortable::new(
-            async move {
-                futures::stream::iter(input_files.iter())
-                    .map(|(bucket, (part_num, file_metadata))| {
-                        let epoch_dir_clone = epoch_dir.clone();
-                        let remote_object_store_clone = remote_object_store.clone();
-                        let sha3_digests_clone = sha3_digests.clone();
-                        async move {
-                            // Download object file with retries
-                            let (bytes, sha3_digest) = download_bytes(
-                                remote_object_store_clone,
-                                file_metadata,
-                                epoch_dir_clone,
-                                sha3_digests_clone,
-                                bucket,
-                                part_num,
-                                None,
-                            )
-                            .await;
-                            Ok::<(Bytes, FileMetadata, [u8; 32]), anyhow::Error>((
-                                bytes,
-                                (*file_metadata).clone(),
-                                sha3_digest,
-                            ))
-                        }
-                    })
-                    .boxed()
-                    .buffer_unordered(concurrency)
-                    .try_for_each(|(bytes, file_metadata, sha3_digest)| {
-                        let bytes_len = bytes.len();
-                        let result: Result<(), anyhow::Error> =
-                            LiveObjectIter::new(&file_metadata, bytes).map(|obj_iter| {
-                                AuthorityStore::bulk_insert_live_objects(
-                                    perpetual_db,
-                                    obj_iter,
-                                    &sha3_digest,
-                                )
-                                .expect("Failed to insert live objects");
-                            });
-                        downloaded_bytes.fetch_add(bytes_len, Ordering::Relaxed);
-                        obj_progress_bar_clone.inc(1);
-                        obj_progress_bar_clone.set_message(format!(
-                            "Download speed: {} MiB/s",
-                            downloaded_bytes.load(Ordering::Relaxed) as f64
-                                / (1024 * 1024) as f64
-                                / instant.elapsed().as_secs_f64(),
-                        ));
-                        futures::future::ready(result)
-                    })
-                    .await
-            },
-            abort_registration,
-        )
-        .await?;
+        let ret = futures::stream::iter(input_files.iter())
+            .map(|(bucket, (part_num, file_metadata))| {
+                let epoch_dir_clone = epoch_dir.clone();
+                let remote_object_store_clone = remote_object_store.clone();
+                let sha3_digests_clone = sha3_digests.clone();
+                async move {
+                    // Download object file with retries
+                    let (bytes, sha3_digest) = download_bytes(
+                        remote_object_store_clone,
+                        file_metadata,
+                        epoch_dir_clone,
+                        sha3_digests_clone,
+                        bucket,
+                        part_num,
+                        None,
+                    )
+                    .await;
+                    Ok::<(Bytes, FileMetadata, [u8; 32]), anyhow::Error>((
+                        bytes,
+                        (*file_metadata).clone(),
+                        sha3_digest,
+                    ))
+                }
+            })
+            .boxed()
+            .buffer_unordered(concurrency)
+            .try_for_each(|(bytes, file_metadata, sha3_digest)| {
+                let bytes_len = bytes.len();
+                let result: Result<(), anyhow::Error> = LiveObjectIter::new(&file_metadata, bytes)
+                    .and_then(|obj_iter| target.insert(obj_iter, &sha3_digest))
+                    .context("Failed to insert live objects");
+                downloaded_bytes.fetch_add(bytes_len, Ordering::Relaxed);
+                obj_progress_bar_clone.inc(1);
+                obj_progress_bar_clone.set_message(format!(
+                    "Download speed: {} MiB/s",
+                    downloaded_bytes.load(Ordering::Relaxed) as f64
+                        / (1024 * 1024) as f64
+                        / instant.elapsed().as_secs_f64(),
+                ));
+                futures::future::ready(result)
+            })
+            .await;
         obj_progress_bar.finish_with_message("Objects download complete");
         ret
     }
 
+    /// Removes the objects that a delta snapshot records as no longer live from the restored
+    /// state, and from the state accumulator, along with their count.
+    async fn remove_live_objects(
+        &self,
+        target: &RestoreTarget<'_>,
+        snapshot: &SnapshotFiles,
+        sender: Option<tokio::sync::mpsc::Sender<(GlobalStateHash, i64)>>,
+    ) -> Result<()> {
+        let epoch_dir = snapshot.epoch_dir();
+        for file_metadata in snapshot
+            .removed_files
+            .values()
+            .flat_map(|part_files| part_files.values())
+        {
+            let object_refs: Vec<_> = ObjectRefIter::new(
+                file_metadata,
+                self.local_staging_dir_root.clone(),
+                epoch_dir.clone(),
+            )?
+            .collect();
+            target.remove(&object_refs)?;
+            if let Some(sender) = &sender {
+                let num_removed = object_refs.len() as i64;
+                let mut partial_acc = GlobalStateHash::default();
+                partial_acc.remove_all(object_refs.into_iter().map(|obj_ref| obj_ref.2));
+                sender
+                    .send((partial_acc, -num_removed))
+                    .await
+                    .context("Unable to send accumulator from snapshot reader")?;
+            }
+        }
+        Ok(())
+    }
+
     // NOTE: export these metadata for indexer restorer
     pub async fn export_metadata(
         &self,
@@ -476,10 +734,17 @@ impl StateSnapshotReaderV1 {
         ),
         anyhow::Error,
     > {
-        let epoch_dir = self.epoch_dir();
+        if !self.deltas.is_empty() {
+            return Err(anyhow!(
+                "Snapshot for epoch {} is a delta snapshot, which cannot be exported",
+                self.epoch
+            ));
+        }
+        let epoch_dir = self.snapshot.epoch_dir();
         let concurrency = self.concurrency;
         let remote_object_store = self.remote_object_store.clone();
         let input_files: Vec<(&u32, (u32, FileMetadata))> = self
+            .snapshot
             .object_files
             .iter()
             .flat_map(|(bucket, parts)| {
@@ -494,65 +759,241 @@ impl StateSnapshotReaderV1 {
     }
 
     pub fn ref_iter(&self, bucket_num: u32, part_num: u32) -> Result<ObjectRefIter> {
-        let file_metadata = self
-            .ref_files
-            .get(&bucket_num)
-            .context(format!("No ref files found for bucket: {bucket_num}"))?
-            .get(&part_num)
-            .context(format!(
-                "No ref files found for bucket: {bucket_num}, part: {part_num}"
-            ))?;
-        ObjectRefIter::new(
-            file_metadata,
-            self.local_staging_dir_root.clone(),
-            self.epoch_dir(),
-        )
+        self.snapshot
+            .ref_iter(self.local_staging_dir_root.clone(), bucket_num, part_num)
     }
 
     fn buckets(&self) -> Result<Vec<u32>> {
-        Ok(self.ref_files.keys().copied().collect())
+        Ok(self.snapshot.ref_files.keys().copied().collect())
     }
 
-    fn epoch_dir(&self) -> Path {
-        Path::from(format!("epoch_{}", self.epoch))
+    pub fn get_multi_progress(&self) -> MultiProgress {
+        self.m.clone()
     }
+}
 
-    fn read_manifest(path: PathBuf) -> anyhow::Result<Manifest> {
-        let manifest_file = File::open(path)?;
-        let manifest_file_size = manifest_file.metadata()?.len() as usize;
-        let mut manifest_reader = BufReader::new(manifest_file);
-        manifest_reader.rewind()?;
-        let magic = manifest_reader.read_u32::<BigEndian>()?;
-        if magic != MANIFEST_FILE_MAGIC {
-            return Err(anyhow!("Unexpected magic byte: {}", magic));
+/// Reads the MANIFEST of the snapshot for `epoch` from `store` and, if it is a delta snapshot, the
+/// MANIFESTs of the snapshots it is a delta of, back to the full snapshot they start from. Returns
+/// the manifests, along with their checksums, oldest first, so starting with the full snapshot.
+pub(crate) async fn read_manifest_chain<S: ObjectStoreGetExt>(
+    store: &S,
+    epoch: u64,
+) -> Result<Vec<(Manifest, [u8; SHA3_BYTES])>> {
+    let mut manifests = vec![];
+    let mut next = (epoch, None);
+    loop {
+        let (epoch, expected_digest) = next;
+        let manifest_file_path = Path::from(format!("epoch_{}", epoch)).child("MANIFEST");
+        let bytes = store.get_bytes(&manifest_file_path).await?;
+        let (manifest, sha3_digest) = parse_manifest(&bytes)?;
+        let snapshot_version = manifest.snapshot_version();
+        if snapshot_version != 1u8 {
+            return Err(anyhow!("Unexpected snapshot version: {}", snapshot_version));
         }
-        manifest_reader.seek(SeekFrom::End(-(SHA3_BYTES as i64)))?;
-        let mut sha3_digest = [0u8; SHA3_BYTES];
-        manifest_reader.read_exact(&mut sha3_digest)?;
-        manifest_reader.rewind()?;
-        let mut content_buf = vec![0u8; manifest_file_size - SHA3_BYTES];
-        manifest_reader.read_exact(&mut content_buf)?;
-        let mut hasher = Sha3_256::default();
-        hasher.update(&content_buf);
-        let computed_digest = hasher.finalize().digest;
-        if computed_digest != sha3_digest {
+        if manifest.address_length() as usize > ObjectID::LENGTH {
             return Err(anyhow!(
-                "Checksum: {:?} don't match: {:?}",
-                computed_digest,
-                sha3_digest
+                "Max possible address length is: {}",
+                ObjectID::LENGTH
             ));
         }
-        manifest_reader.rewind()?;
-        manifest_reader.seek(SeekFrom::Start(MAGIC_BYTES as u64))?;
-        let manifest = bcs::from_bytes(&content_buf[MAGIC_BYTES..])?;
-        Ok(manifest)
+        if manifest.epoch() != epoch {
+            return Err(anyhow!("Download manifest is not for epoch: {}", epoch,));
+        }
+        if expected_digest.is_some_and(|expected_digest| expected_digest != sha3_digest) {
+            return Err(anyhow!(
+                "Manifest for epoch: {} does not match the manifest its delta snapshot is based on",
+                epoch
+            ));
+        }
+        let base = manifest.base();
+        manifests.push((manifest, sha3_digest));
+        match base {
+            Some((base_epoch, base_manifest_digest)) if base_epoch < epoch => {
+                next = (base_epoch, Some(base_manifest_digest));
+            }
+            Some((base_epoch, _)) => {
+                return Err(anyhow!(
+                    "Delta snapshot for epoch: {} has invalid base epoch: {}",
+                    epoch,
+                    base_epoch
+                ));
+            }
+            None => break,
+        }
     }
+    manifests.reverse();
+    Ok(manifests)
+}
 
-    pub fn get_multi_progress(&self) -> MultiProgress {
-        self.m.clone()
+/// Local copies of the REFERENCE and *.rm files of a chain of snapshots (a full snapshot followed
+/// by the delta snapshots on top of it), to stream the references of the objects that are live as
+/// of the last snapshot in the chain from, without holding them all in memory.
+pub(crate) struct LiveObjectRefs {
+    local_dir: PathBuf,
+    snapshots: Vec<SnapshotFiles>,
+}
+
+impl LiveObjectRefs {
+    /// Downloads the REFERENCE and *.rm files of the chain of snapshots that the snapshot for
+    /// `epoch` consists of from `store` into `local_dir`. Also returns the checksum of the MANIFEST
+    /// of the snapshot for `epoch`.
+    pub(crate) async fn download<S: ObjectStoreGetExt>(
+        store: &S,
+        epoch: u64,
+        local_dir: PathBuf,
+    ) -> Result<(Self, [u8; SHA3_BYTES])> {
+        let manifests = read_manifest_chain(store, epoch).await?;
+        let (_, sha3_digest) = manifests.last().context("Empty snapshot chain")?;
+        let sha3_digest = *sha3_digest;
+        let snapshots: Vec<_> = manifests
+            .iter()
+            .map(|(manifest, _)| SnapshotFiles::new(manifest))
+            .collect();
+        for snapshot in &snapshots {
+            fs::create_dir_all(path_to_filesystem(
+                local_dir.clone(),
+                &snapshot.epoch_dir(),
+            )?)?;
+            for file_path in snapshot.local_files() {
+                let bytes = store.get_bytes(&file_path).await?;
+                fs::write(path_to_filesystem(local_dir.clone(), &file_path)?, bytes)?;
+            }
+        }
+        Ok((
+            LiveObjectRefs {
+                local_dir,
+                snapshots,
+            },
+            sha3_digest,
+        ))
+    }
+
+    /// The references of all objects that are live as of the last snapshot in the chain, in
+    /// object ID order. Every bucket of REFERENCE files is in object ID order, so these are merged
+    /// across buckets, and across the snapshots in the chain, after dropping the references that
+    /// each delta snapshot removes.
+    pub(crate) fn iter(&self) -> Result<Box<dyn Iterator<Item = ObjectRef>>> {
+        let mut live: Box<dyn Iterator<Item = ObjectRef>> = Box::new(std::iter::empty());
+        for snapshot in &self.snapshots {
+            let removed = self.sorted_refs(snapshot, &snapshot.removed_files)?;
+            let added = self.sorted_refs(snapshot, &snapshot.ref_files)?;
+            live = Box::new(MergedObjectRefs::new(vec![
+                Box::new(SubtractedObjectRefs::new(live, removed)),
+                added,
+            ]));
+        }
+        Ok(live)
+    }
+
+    fn sorted_refs(
+        &self,
+        snapshot: &SnapshotFiles,
+        files: &BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    ) -> Result<Box<dyn Iterator<Item = ObjectRef>>> {
+        let mut buckets = vec![];
+        for parts in files.values() {
+            let mut bucket: Box<dyn Iterator<Item = ObjectRef>> = Box::new(std::iter::empty());
+            for file_metadata in parts.values() {
+                let ref_iter = ObjectRefIter::new(
+                    file_metadata,
+                    self.local_dir.clone(),
+                    snapshot.epoch_dir(),
+                )?;
+                bucket = Box::new(bucket.chain(ref_iter));
+            }
+            buckets.push(bucket);
+        }
+        Ok(Box::new(MergedObjectRefs::new(buckets)))
+    }
+}
+
+/// Merges iterators over object references that are each in order into a single iterator in
+/// order.
+struct MergedObjectRefs {
+    iters: Vec<Peekable<Box<dyn Iterator<Item = ObjectRef>>>>,
+}
+
+impl MergedObjectRefs {
+    fn new(iters: Vec<Box<dyn Iterator<Item = ObjectRef>>>) -> Self {
+        MergedObjectRefs {
+            iters: iters.into_iter().map(Iterator::peekable).collect(),
+        }
+    }
+}
+
+impl Iterator for MergedObjectRefs {
+    type Item = ObjectRef;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (next, _) = self
+            .iters
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, iter)| Some((i, *iter.peek()?)))
+            .min_by_key(|(_, object_ref)| *object_ref)?;
+        self.iters[next].next()
+    }
+}
+
+/// The object references in one iterator that are not in another, where both are in order.
+struct SubtractedObjectRefs {
+    refs: Box<dyn Iterator<Item = ObjectRef>>,
+    removed: Peekable<Box<dyn Iterator<Item = ObjectRef>>>,
+}
+
+impl SubtractedObjectRefs {
+    fn new(
+        refs: Box<dyn Iterator<Item = ObjectRef>>,
+        removed: Box<dyn Iterator<Item = ObjectRef>>,
+    ) -> Self {
+        SubtractedObjectRefs {
+            refs,
+            removed: removed.peekable(),
+        }
     }
 }
 
+impl Iterator for SubtractedObjectRefs {
+    type Item = ObjectRef;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let object_ref = self.refs.next()?;
+            while self
+                .removed
+                .next_if(|removed| *removed < object_ref)
+                .is_some()
+            {}
+            if self.removed.next_if_eq(&object_ref).is_none() {
+                return Some(object_ref);
+            }
+        }
+    }
+}
+
+/// Parses a MANIFEST file, returning the manifest and the checksum it ends with.
+fn parse_manifest(bytes: &[u8]) -> Result<(Manifest, [u8; SHA3_BYTES])> {
+    if bytes.len() < MAGIC_BYTES + SHA3_BYTES {
+        return Err(anyhow!("Manifest file is too short: {} bytes", bytes.len()));
+    }
+    let magic = (&bytes[..MAGIC_BYTES]).read_u32::<BigEndian>()?;
+    if magic != MANIFEST_FILE_MAGIC {
+        return Err(anyhow!("Unexpected magic byte: {}", magic));
+    }
+    let (content_buf, sha3_digest) = bytes.split_at(bytes.len() - SHA3_BYTES);
+    let sha3_digest: [u8; SHA3_BYTES] = sha3_digest.try_into()?;
+    let mut hasher = Sha3_256::default();
+    hasher.update(content_buf);
+    let computed_digest = hasher.finalize().digest;
+    if computed_digest != sha3_digest {
+        return Err(anyhow!(
+            "Checksum: {:?} don't match: {:?}",
+            computed_digest,
+            sha3_digest
+        ));
+    }
+    let manifest = bcs::from_bytes(&content_buf[MAGIC_BYTES..])?;
+    Ok((manifest, sha3_digest))
+}
+
 pub async fn download_bytes(
     remote_object_store: Arc<dyn ObjectStoreGetExt>,
     file_metadata: &FileMetadata,
@@ -622,6 +1063,19 @@ impl ObjectRefIter {
         }
     }
 
+    pub fn from_bytes(file_metadata: &FileMetadata, bytes: Bytes) -> Result<Self> {
+        let mut reader = file_metadata.file_compression.bytes_decompress(bytes)?;
+        let magic = reader.read_u32::<BigEndian>()?;
+        if magic != REFERENCE_FILE_MAGIC {
+            Err(anyhow!(
+                "Unexpected magic string in REFERENCE file: {:?}",
+                magic
+            ))
+        } else {
+            Ok(ObjectRefIter { reader })
+        }
+    }
+
     fn next_ref(&mut self) -> Result<ObjectRef> {
         let mut buf = [0u8; OBJECT_REF_BYTES];
         self.reader.read_exact(&mut buf)?;
@@ -1,23 +1,32 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::filter::{
+    BcsDirectorySink, LiveObjectFilter, SnapshotRefs, REFS_DIR_NAME, ROOT_STATE_HASH_FILE_NAME,
+};
 use crate::reader::StateSnapshotReaderV1;
 use crate::writer::StateSnapshotWriterV1;
-use crate::FileCompression;
-use fastcrypto::hash::MultisetHash;
+use crate::{FileCompression, FileType};
+use fastcrypto::hash::{HashFunction, MultisetHash, Sha3_256};
 use futures::future::AbortHandle;
 use indicatif::MultiProgress;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::sync::Arc;
 use sui_config::object_storage_config::{ObjectStoreConfig, ObjectStoreType};
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::authority::AuthorityStore;
 use sui_core::global_state_hasher::GlobalStateHasher;
 use sui_protocol_config::ProtocolConfig;
-use sui_types::base_types::ObjectID;
+use sui_types::base_types::{
+    MoveObjectType, ObjectID, SequenceNumber, SuiAddress, TransactionDigest,
+};
 use sui_types::global_state_hash::GlobalStateHash;
 use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
-use sui_types::object::Object;
+use sui_types::move_package::MovePackage;
+use sui_types::object::{Data, MoveObject, Object, Owner};
+use sui_types::{parse_sui_struct_tag, parse_sui_type_tag};
 use tempfile::tempdir;
 
 fn temp_dir() -> std::path::PathBuf {
@@ -38,6 +47,33 @@ pub fn insert_keys(
     Ok(())
 }
 
+/// Inserts objects `created`, inserts a new version of objects `mutated`, and deletes objects
+/// `deleted`.
+fn update_keys(
+    db: &AuthorityPerpetualTables,
+    created: &[ObjectID],
+    mutated: &[ObjectID],
+    deleted: &[ObjectID],
+    version: SequenceNumber,
+) -> Result<(), anyhow::Error> {
+    for id in created {
+        db.insert_object_test_only(Object::immutable_with_id_for_testing(*id))?;
+    }
+    for id in mutated {
+        let owner = Owner::AddressOwner(SuiAddress::ZERO);
+        db.insert_object_test_only(Object::with_id_owner_version_for_testing(
+            *id, version, owner,
+        ))?;
+    }
+    let deleted_refs: Vec<_> = db
+        .iter_live_object_set(true)
+        .filter(|live_object| deleted.contains(&live_object.object_id()))
+        .map(|live_object| live_object.object_reference())
+        .collect();
+    AuthorityStore::bulk_remove_live_objects(db, deleted_refs.into_iter())?;
+    Ok(())
+}
+
 fn compare_live_objects(
     db1: &AuthorityPerpetualTables,
     db2: &AuthorityPerpetualTables,
@@ -68,6 +104,47 @@ fn accumulate_live_object_set(
     acc
 }
 
+/// Reads the objects that a `BcsDirectorySink` wrote to `dir`, ordered by ID.
+fn read_bcs_objects(dir: &Path) -> Result<Vec<Object>, anyhow::Error> {
+    let mut objects = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "bcs") {
+            objects.push(bcs::from_bytes::<Object>(&std::fs::read(path)?)?);
+        }
+    }
+    objects.sort_by_key(|object| object.id());
+    Ok(objects)
+}
+
+/// Restores the objects that match `filter` from the snapshot for `epoch` into `objects_path`.
+async fn restore_filtered(
+    epoch: u64,
+    remote_store_config: &ObjectStoreConfig,
+    filter: &LiveObjectFilter,
+    objects_path: &Path,
+) -> Result<(), anyhow::Error> {
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(temp_dir().join("local_dir_restore")),
+        ..Default::default()
+    };
+    let mut snapshot_reader = StateSnapshotReaderV1::new(
+        epoch,
+        remote_store_config,
+        &local_store_restore_config,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+        false, // skip_reset_local_store
+    )
+    .await?;
+    let sink = BcsDirectorySink::new(objects_path)?;
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    snapshot_reader
+        .read_filtered(filter, &sink, abort_registration, None)
+        .await
+}
+
 #[tokio::test]
 async fn test_snapshot_basic() -> Result<(), anyhow::Error> {
     let db_path = temp_dir();
@@ -181,3 +258,385 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     )?;
     Ok(())
 }
+
+#[tokio::test]
+async fn test_snapshot_delta() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let restored_db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let restored_local = temp_dir().join("local_dir_restore");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote),
+        ..Default::default()
+    };
+    let new_writer = || {
+        StateSnapshotWriterV1::new(
+            &local_store_config,
+            &remote_store_config,
+            FileCompression::Zstd,
+            NonZeroUsize::new(1).unwrap(),
+        )
+    };
+
+    // A full snapshot at epoch 0, followed by deltas at epochs 1 and 2, each of which creates,
+    // mutates and deletes objects, including ones from the previous delta.
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_keys(&perpetual_db, 1000)?;
+    let root_accumulator =
+        ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
+    new_writer()
+        .await?
+        .write_internal(0, true, perpetual_db.clone(), root_accumulator)
+        .await?;
+
+    let ids = ObjectID::in_range(ObjectID::ZERO, 1200)?;
+    update_keys(
+        &perpetual_db,
+        &ids[1000..1100],
+        &ids[0..10],
+        &ids[10..20],
+        SequenceNumber::from_u64(2),
+    )?;
+    let root_accumulator =
+        ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
+    new_writer()
+        .await?
+        .write_delta_internal(1, 0, true, perpetual_db.clone(), root_accumulator)
+        .await?;
+
+    update_keys(
+        &perpetual_db,
+        &ids[1100..1200],
+        &ids[0..5],
+        &ids[1000..1010],
+        SequenceNumber::from_u64(3),
+    )?;
+    let root_accumulator =
+        ECMHLiveObjectSetDigest::from(accumulate_live_object_set(&perpetual_db, true).digest());
+    new_writer()
+        .await?
+        .write_delta_internal(2, 1, true, perpetual_db.clone(), root_accumulator)
+        .await?;
+
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(restored_local),
+        ..Default::default()
+    };
+    let mut snapshot_reader = StateSnapshotReaderV1::new(
+        2,
+        &remote_store_config,
+        &local_store_restore_config,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+        false, // skip_reset_local_store
+    )
+    .await?;
+    let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+    let accumulation = tokio::spawn(async move {
+        let mut acc = GlobalStateHash::default();
+        let mut num_live_objects = 0;
+        while let Some((partial_acc, num_objects)) = receiver.recv().await {
+            acc.union(&partial_acc);
+            num_live_objects += num_objects;
+        }
+        (acc, num_live_objects)
+    });
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    snapshot_reader
+        .read(&restored_perpetual_db, abort_registration, Some(sender))
+        .await?;
+    compare_live_objects(&perpetual_db, &restored_perpetual_db, true)?;
+    let (acc, num_live_objects) = accumulation.await?;
+    assert_eq!(
+        acc.digest(),
+        accumulate_live_object_set(&perpetual_db, true).digest()
+    );
+
+    // Objects removed by the deltas are subtracted from the count of live objects.
+    assert_eq!(
+        num_live_objects,
+        perpetual_db.iter_live_object_set(true).count() as i64
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_filtered() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let objects_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let restored_local = temp_dir().join("local_dir_restore");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote),
+        ..Default::default()
+    };
+
+    let snapshot_writer = StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?;
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_keys(&perpetual_db, 1000)?;
+    let owner = SuiAddress::random_for_testing_only();
+    let ids = ObjectID::in_range(ObjectID::ZERO, 1010)?;
+    let mut expected = vec![Object::immutable_with_id_for_testing(ids[5])];
+    for id in &ids[1000..] {
+        let object = Object::with_id_owner_version_for_testing(
+            *id,
+            SequenceNumber::from_u64(1),
+            Owner::AddressOwner(owner),
+        );
+        perpetual_db.insert_object_test_only(object.clone())?;
+        expected.push(object);
+    }
+    let root_accumulator = accumulate_live_object_set(&perpetual_db, true);
+    snapshot_writer
+        .write_internal(
+            0,
+            true,
+            perpetual_db.clone(),
+            ECMHLiveObjectSetDigest::from(root_accumulator.digest()),
+        )
+        .await?;
+
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(restored_local),
+        ..Default::default()
+    };
+    let mut snapshot_reader = StateSnapshotReaderV1::new(
+        0,
+        &remote_store_config,
+        &local_store_restore_config,
+        NonZeroUsize::new(1).unwrap(),
+        MultiProgress::new(),
+        false, // skip_reset_local_store
+    )
+    .await?;
+    let filter = LiveObjectFilter::new()
+        .add_object_id(ids[5])
+        .add_owner(owner);
+    let sink = BcsDirectorySink::new(&objects_path)?;
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+    let accumulation = tokio::spawn(async move {
+        let mut acc = GlobalStateHash::default();
+        while let Some((partial_acc, _)) = receiver.recv().await {
+            acc.union(&partial_acc);
+        }
+        acc
+    });
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    snapshot_reader
+        .read_filtered(&filter, &sink, abort_registration, Some(sender))
+        .await?;
+
+    // Only the matching objects are restored, but all of them are accumulated.
+    assert_eq!(read_bcs_objects(&objects_path)?, expected);
+    assert_eq!(accumulation.await?.digest(), root_accumulator.digest());
+
+    // The references of all live objects, and the root state hash that they accumulate to, are
+    // written next to the restored objects.
+    let root_state_hash: ECMHLiveObjectSetDigest = bcs::from_bytes(&std::fs::read(
+        objects_path.join(ROOT_STATE_HASH_FILE_NAME),
+    )?)?;
+    assert_eq!(
+        root_state_hash,
+        ECMHLiveObjectSetDigest::from(root_accumulator.digest())
+    );
+    let mut object_refs = HashSet::new();
+    for entry in std::fs::read_dir(objects_path.join(REFS_DIR_NAME))? {
+        let refs: SnapshotRefs = bcs::from_bytes(&std::fs::read(entry?.path())?)?;
+        assert_eq!(refs.file_type, FileType::Reference);
+        let mut hasher = Sha3_256::default();
+        for object_ref in &refs.object_refs {
+            hasher.update(object_ref.2.inner());
+        }
+        assert_eq!(refs.sha3_digest, hasher.finalize().digest);
+        object_refs.extend(refs.object_refs);
+    }
+    let live_object_refs: HashSet<_> = perpetual_db
+        .iter_live_object_set(true)
+        .map(|live_object| live_object.object_reference())
+        .collect();
+    assert_eq!(object_refs, live_object_refs);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_snapshot_filtered_by_object_ids() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote.clone()),
+        ..Default::default()
+    };
+
+    let snapshot_writer = StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?;
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    insert_keys(&perpetual_db, 1000)?;
+    let root_accumulator = accumulate_live_object_set(&perpetual_db, true);
+    snapshot_writer
+        .write_internal(
+            0,
+            true,
+            perpetual_db.clone(),
+            ECMHLiveObjectSetDigest::from(root_accumulator.digest()),
+        )
+        .await?;
+
+    // The partition that holds the object is restored.
+    let ids = ObjectID::in_range(ObjectID::ZERO, 1000)?;
+    let objects_path = temp_dir();
+    let filter = LiveObjectFilter::new().add_object_id(ids[5]);
+    restore_filtered(0, &remote_store_config, &filter, &objects_path).await?;
+    assert_eq!(
+        read_bcs_objects(&objects_path)?,
+        vec![Object::immutable_with_id_for_testing(ids[5])]
+    );
+
+    // A partition that doesn't hold any of the objects is not downloaded at all, so corrupting
+    // its object file doesn't fail the restore. Its references are still written.
+    std::fs::write(remote.join("epoch_0").join("1_1.obj"), b"corrupted")?;
+    let objects_path = temp_dir();
+    let filter = LiveObjectFilter::new().add_object_id(ObjectID::random());
+    restore_filtered(0, &remote_store_config, &filter, &objects_path).await?;
+    assert!(read_bcs_objects(&objects_path)?.is_empty());
+    assert!(objects_path.join(REFS_DIR_NAME).join("0_1_1.ref").exists());
+    let root_state_hash: ECMHLiveObjectSetDigest = bcs::from_bytes(&std::fs::read(
+        objects_path.join(ROOT_STATE_HASH_FILE_NAME),
+    )?)?;
+    assert_eq!(
+        root_state_hash,
+        ECMHLiveObjectSetDigest::from(root_accumulator.digest())
+    );
+
+    // Once the filter selects objects by anything other than ID, every partition is downloaded.
+    let filter = LiveObjectFilter::new().add_owner(SuiAddress::random_for_testing_only());
+    assert!(
+        restore_filtered(0, &remote_store_config, &filter, &temp_dir())
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_live_object_filter_types_and_packages() -> Result<(), anyhow::Error> {
+    let package_id = ObjectID::random();
+    let package = Object::new_package_from_data(
+        Data::Package(MovePackage::new(
+            package_id,
+            SequenceNumber::from_u64(1),
+            BTreeMap::new(),
+            u64::MAX,
+            vec![],
+            BTreeMap::new(),
+        )?),
+        TransactionDigest::genesis_marker(),
+    );
+
+    // An object whose type is defined in the package, a coin whose type parameter is, and a gas
+    // coin.
+    let struct_tag = parse_sui_struct_tag(&format!("{package_id}::x::X"))?;
+    let object_id = ObjectID::random();
+    let object = Object::new_move(
+        unsafe {
+            MoveObject::new_from_execution_with_limit(
+                MoveObjectType::from(struct_tag.clone()),
+                true,
+                SequenceNumber::from_u64(1),
+                bcs::to_bytes(&object_id)?,
+                u64::MAX,
+            )?
+        },
+        Owner::Immutable,
+        TransactionDigest::genesis_marker(),
+    );
+    let coin = Object::new_move(
+        MoveObject::new_coin(
+            struct_tag.into(),
+            SequenceNumber::from_u64(1),
+            ObjectID::random(),
+            1,
+        ),
+        Owner::Immutable,
+        TransactionDigest::genesis_marker(),
+    );
+    let gas_coin = Object::immutable_with_id_for_testing(ObjectID::random());
+    let objects = [&package, &object, &coin, &gas_coin];
+    let matching = |filter: LiveObjectFilter| -> Vec<ObjectID> {
+        objects
+            .iter()
+            .filter(|object| filter.matches(object))
+            .map(|object| object.id())
+            .collect()
+    };
+
+    // A package matches itself, and objects whose type is defined in it, but not objects whose
+    // type only has a type parameter defined in it.
+    assert_eq!(
+        matching(LiveObjectFilter::new().add_package(package_id)),
+        vec![package.id(), object.id()]
+    );
+
+    // A type without type parameters matches all of its instantiations.
+    assert_eq!(
+        matching(LiveObjectFilter::new().add_type(parse_sui_type_tag("0x2::coin::Coin")?)),
+        vec![coin.id(), gas_coin.id()]
+    );
+
+    // A type with type parameters only matches that instantiation.
+    assert_eq!(
+        matching(
+            LiveObjectFilter::new().add_type(parse_sui_type_tag(&format!(
+                "0x2::coin::Coin<{package_id}::x::X>"
+            ))?)
+        ),
+        vec![coin.id()]
+    );
+    assert_eq!(
+        matching(
+            LiveObjectFilter::new().add_type(parse_sui_type_tag("0x2::coin::Coin<0x2::sui::SUI>")?)
+        ),
+        vec![gas_coin.id()]
+    );
+    assert_eq!(
+        matching(
+            LiveObjectFilter::new().add_type(parse_sui_type_tag(&format!("{package_id}::x::X"))?)
+        ),
+        vec![object.id()]
+    );
+    Ok(())
+}
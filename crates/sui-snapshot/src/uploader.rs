@@ -4,12 +4,13 @@
 use crate::writer::StateSnapshotWriterV1;
 use anyhow::Result;
 use bytes::Bytes;
+use object_store::path::Path;
 use object_store::DynObjectStore;
 use prometheus::{
     register_int_counter_with_registry, register_int_gauge_with_registry, IntCounter, IntGauge,
     Registry,
 };
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU64, NonZeroUsize};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -18,7 +19,7 @@ use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::checkpoints::CheckpointStore;
 use sui_core::db_checkpoint_handler::{STATE_SNAPSHOT_COMPLETED_MARKER, SUCCESS_MARKER};
 use sui_storage::object_store::util::{
-    find_all_dirs_with_epoch_prefix, find_missing_epochs_dirs, path_to_filesystem, put,
+    exists, find_all_dirs_with_epoch_prefix, find_missing_epochs_dirs, path_to_filesystem, put,
     run_manifest_update_loop,
 };
 use sui_storage::FileCompression;
@@ -66,6 +67,9 @@ pub struct StateSnapshotUploader {
     snapshot_store: Arc<DynObjectStore>,
     /// Time interval to check for presence of new db checkpoint
     interval: Duration,
+    /// If set, snapshots are written as deltas on top of the previous epoch's snapshot, except for
+    /// every epoch that is a multiple of this interval, which gets a full snapshot
+    full_snapshot_interval: Option<NonZeroU64>,
     metrics: Arc<StateSnapshotUploaderMetrics>,
     /// The chain identifier is derived from the genesis checkpoint and used to identify the
     /// network.
@@ -78,6 +82,7 @@ impl StateSnapshotUploader {
         staging_path: &std::path::Path,
         snapshot_store_config: ObjectStoreConfig,
         interval_s: u64,
+        full_snapshot_interval: Option<NonZeroU64>,
         registry: &Registry,
         checkpoint_store: Arc<CheckpointStore>,
         chain_identifier: ChainIdentifier,
@@ -100,6 +105,7 @@ impl StateSnapshotUploader {
             staging_store: staging_store_config.make()?,
             snapshot_store: snapshot_store_config.make()?,
             interval: Duration::from_secs(interval_s),
+            full_snapshot_interval,
             metrics: StateSnapshotUploaderMetrics::new(registry),
             chain_identifier,
         }))
@@ -145,9 +151,25 @@ impl StateSnapshotUploader {
                     .last()
                     .expect("Expected at least one commitment")
                     .clone();
-                state_snapshot_writer
-                    .write(*epoch, db, state_hash_commitment, self.chain_identifier)
-                    .await?;
+                match self.delta_base_epoch(*epoch).await {
+                    Some(base_epoch) => {
+                        info!("Writing delta state snapshot on top of epoch: {base_epoch}");
+                        state_snapshot_writer
+                            .write_delta(
+                                *epoch,
+                                base_epoch,
+                                db,
+                                state_hash_commitment,
+                                self.chain_identifier,
+                            )
+                            .await?
+                    }
+                    None => {
+                        state_snapshot_writer
+                            .write(*epoch, db, state_hash_commitment, self.chain_identifier)
+                            .await?
+                    }
+                }
                 info!("State snapshot creation successful for epoch: {}", *epoch);
                 // Drop marker in the output directory that upload completed successfully
                 let bytes = Bytes::from_static(b"success");
@@ -211,6 +233,21 @@ impl StateSnapshotUploader {
         Ok(())
     }
 
+    /// The epoch whose snapshot the snapshot for `epoch` should be a delta on top of, or `None` if
+    /// it should be a full snapshot, because delta snapshots are disabled, `epoch` is due a full
+    /// snapshot, or the previous epoch's snapshot has not been uploaded.
+    async fn delta_base_epoch(&self, epoch: u64) -> Option<u64> {
+        let interval = self.full_snapshot_interval?;
+        if epoch % interval.get() == 0 {
+            return None;
+        }
+        let base_epoch = epoch - 1;
+        let success_marker = Path::from(format!("epoch_{}", base_epoch)).child(SUCCESS_MARKER);
+        exists(&self.snapshot_store, &success_marker)
+            .await
+            .then_some(base_epoch)
+    }
+
     async fn get_missing_epochs(&self) -> Result<Vec<u64>> {
         let missing_epochs = find_missing_epochs_dirs(&self.snapshot_store, SUCCESS_MARKER).await?;
         Ok(missing_epochs.to_vec())
@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 #![allow(dead_code)]
 
+use crate::reader::LiveObjectRefs;
 use crate::{
-    compute_sha3_checksum, create_file_metadata, FileCompression, FileMetadata, FileType, Manifest,
-    ManifestV1, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
-    OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES,
+    compute_sha3_checksum, create_file_metadata, DeltaManifestV1, FileCompression, FileMetadata,
+    FileType, Manifest, ManifestV1, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC,
+    OBJECT_FILE_MAGIC, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES,
 };
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use byteorder::{BigEndian, ByteOrder};
 use fastcrypto::hash::MultisetHash;
 use futures::StreamExt;
@@ -46,7 +47,7 @@ struct LiveObjectSetWriterV1 {
     bucket_num: u32,
     current_part_num: u32,
     wbuf: BufWriter<File>,
-    ref_wbuf: BufWriter<File>,
+    ref_writer: ObjectRefFileWriterV1,
     n: usize,
     files: Vec<FileMetadata>,
     sender: Option<Sender<FileMetadata>>,
@@ -62,13 +63,18 @@ impl LiveObjectSetWriterV1 {
     ) -> Result<Self> {
         let part_num = 1;
         let (n, obj_file) = Self::object_file(dir_path.clone(), bucket_num, part_num)?;
-        let ref_file = Self::ref_file(dir_path.clone(), bucket_num, part_num)?;
+        let ref_writer = ObjectRefFileWriterV1::new(
+            dir_path.clone(),
+            FileType::Reference,
+            bucket_num,
+            part_num,
+        )?;
         Ok(LiveObjectSetWriterV1 {
             dir_path,
             bucket_num,
             current_part_num: part_num,
             wbuf: BufWriter::new(obj_file),
-            ref_wbuf: BufWriter::new(ref_file),
+            ref_writer,
             n,
             files: vec![],
             sender: Some(sender),
@@ -78,7 +84,7 @@ impl LiveObjectSetWriterV1 {
     pub fn write(&mut self, object: &LiveObject) -> Result<()> {
         let object_reference = object.object_reference();
         self.write_object(object)?;
-        self.write_object_ref(&object_reference)?;
+        self.ref_writer.write(&object_reference)?;
         Ok(())
     }
     pub fn done(mut self) -> Result<Vec<FileMetadata>> {
@@ -101,20 +107,6 @@ impl LiveObjectSetWriterV1 {
         f.seek(SeekFrom::Start(n as u64))?;
         Ok((n, f))
     }
-    fn ref_file(dir_path: PathBuf, bucket_num: u32, part_num: u32) -> Result<File> {
-        let ref_path = dir_path.join(format!("{bucket_num}_{part_num}.ref"));
-        let ref_tmp_path = dir_path.join(format!("{bucket_num}_{part_num}.ref.tmp"));
-        let mut f = File::create(ref_tmp_path.clone())?;
-        f.rewind()?;
-        let mut metab = [0u8; MAGIC_BYTES];
-        BigEndian::write_u32(&mut metab, REFERENCE_FILE_MAGIC);
-        let n = f.write(&metab)?;
-        drop(f);
-        fs::rename(ref_tmp_path, ref_path.clone())?;
-        let mut f = OpenOptions::new().append(true).open(ref_path)?;
-        f.seek(SeekFrom::Start(n as u64))?;
-        Ok(f)
-    }
     fn finalize(&mut self) -> Result<()> {
         self.wbuf.flush()?;
         self.wbuf.get_ref().sync_data()?;
@@ -137,20 +129,7 @@ impl LiveObjectSetWriterV1 {
         Ok(())
     }
     fn finalize_ref(&mut self) -> Result<()> {
-        self.ref_wbuf.flush()?;
-        self.ref_wbuf.get_ref().sync_data()?;
-        let off = self.ref_wbuf.get_ref().stream_position()?;
-        self.ref_wbuf.get_ref().set_len(off)?;
-        let file_path = self
-            .dir_path
-            .join(format!("{}_{}.ref", self.bucket_num, self.current_part_num));
-        let file_metadata = create_file_metadata(
-            &file_path,
-            self.file_compression,
-            FileType::Reference,
-            self.bucket_num,
-            self.current_part_num,
-        )?;
+        let file_metadata = self.ref_writer.finalize(self.file_compression)?;
         self.files.push(file_metadata.clone());
         if let Some(sender) = &self.sender {
             sender.blocking_send(file_metadata)?;
@@ -170,12 +149,12 @@ impl LiveObjectSetWriterV1 {
     }
     fn cut_reference_file(&mut self) -> Result<()> {
         self.finalize_ref()?;
-        let f = Self::ref_file(
+        self.ref_writer = ObjectRefFileWriterV1::new(
             self.dir_path.clone(),
+            FileType::Reference,
             self.bucket_num,
             self.current_part_num + 1,
         )?;
-        self.ref_wbuf = BufWriter::new(f);
         Ok(())
     }
     fn write_object(&mut self, object: &LiveObject) -> Result<()> {
@@ -192,7 +171,110 @@ impl LiveObjectSetWriterV1 {
         self.n += blob.write(&mut self.wbuf)?;
         Ok(())
     }
-    fn write_object_ref(&mut self, object_ref: &ObjectRef) -> Result<()> {
+}
+
+/// RemovedObjectRefWriterV1 writes the references of objects that a delta snapshot removes from its
+/// base snapshot to *.rm files
+struct RemovedObjectRefWriterV1 {
+    dir_path: PathBuf,
+    bucket_num: u32,
+    current_part_num: u32,
+    ref_writer: ObjectRefFileWriterV1,
+    files: Vec<FileMetadata>,
+    sender: Option<Sender<FileMetadata>>,
+    file_compression: FileCompression,
+}
+
+impl RemovedObjectRefWriterV1 {
+    fn new(
+        dir_path: PathBuf,
+        bucket_num: u32,
+        file_compression: FileCompression,
+        sender: Sender<FileMetadata>,
+    ) -> Result<Self> {
+        let part_num = 1;
+        let ref_writer =
+            ObjectRefFileWriterV1::new(dir_path.clone(), FileType::Removed, bucket_num, part_num)?;
+        Ok(RemovedObjectRefWriterV1 {
+            dir_path,
+            bucket_num,
+            current_part_num: part_num,
+            ref_writer,
+            files: vec![],
+            sender: Some(sender),
+            file_compression,
+        })
+    }
+    pub fn write(&mut self, object_ref: &ObjectRef) -> Result<()> {
+        if self.ref_writer.is_full() {
+            self.finalize()?;
+            self.current_part_num += 1;
+            self.ref_writer = ObjectRefFileWriterV1::new(
+                self.dir_path.clone(),
+                FileType::Removed,
+                self.bucket_num,
+                self.current_part_num,
+            )?;
+        }
+        self.ref_writer.write(object_ref)
+    }
+    pub fn done(mut self) -> Result<Vec<FileMetadata>> {
+        self.finalize()?;
+        self.sender = None;
+        Ok(self.files.clone())
+    }
+    fn finalize(&mut self) -> Result<()> {
+        let file_metadata = self.ref_writer.finalize(self.file_compression)?;
+        self.files.push(file_metadata.clone());
+        if let Some(sender) = &self.sender {
+            sender.blocking_send(file_metadata)?;
+        }
+        Ok(())
+    }
+}
+
+/// ObjectRefFileWriterV1 writes a single part of a file in the REFERENCE file format, which is
+/// either a *.ref file, or a *.rm file for the objects removed by a delta snapshot
+struct ObjectRefFileWriterV1 {
+    file_path: PathBuf,
+    file_type: FileType,
+    bucket_num: u32,
+    part_num: u32,
+    wbuf: BufWriter<File>,
+    n: usize,
+}
+
+impl ObjectRefFileWriterV1 {
+    fn new(dir_path: PathBuf, file_type: FileType, bucket_num: u32, part_num: u32) -> Result<Self> {
+        let extension = match file_type {
+            FileType::Reference => "ref",
+            FileType::Removed => "rm",
+            FileType::Object => return Err(anyhow!("Object files are not REFERENCE files")),
+        };
+        let file_path = dir_path.join(format!("{bucket_num}_{part_num}.{extension}"));
+        let tmp_path = dir_path.join(format!("{bucket_num}_{part_num}.{extension}.tmp"));
+        let mut f = File::create(tmp_path.clone())?;
+        f.rewind()?;
+        let mut metab = [0u8; MAGIC_BYTES];
+        BigEndian::write_u32(&mut metab, REFERENCE_FILE_MAGIC);
+        let n = f.write(&metab)?;
+        drop(f);
+        fs::rename(tmp_path, file_path.clone())?;
+        let mut f = OpenOptions::new().append(true).open(file_path.clone())?;
+        f.seek(SeekFrom::Start(n as u64))?;
+        Ok(ObjectRefFileWriterV1 {
+            file_path,
+            file_type,
+            bucket_num,
+            part_num,
+            wbuf: BufWriter::new(f),
+            n,
+        })
+    }
+    fn is_full(&self) -> bool {
+        self.n + OBJECT_REF_BYTES > FILE_MAX_BYTES
+    }
+    fn write(&mut self, object_ref: &ObjectRef) -> Result<()> {
         let mut buf = [0u8; OBJECT_REF_BYTES];
         buf[0..ObjectID::LENGTH].copy_from_slice(object_ref.0.as_ref());
         BigEndian::write_u64(
@@ -201,9 +283,33 @@ impl LiveObjectSetWriterV1 {
         );
         buf[ObjectID::LENGTH + SEQUENCE_NUM_BYTES..OBJECT_REF_BYTES]
             .copy_from_slice(object_ref.2.as_ref());
-        self.ref_wbuf.write_all(&buf)?;
+        self.wbuf.write_all(&buf)?;
+        self.n += OBJECT_REF_BYTES;
         Ok(())
     }
+    fn finalize(&mut self, file_compression: FileCompression) -> Result<FileMetadata> {
+        self.wbuf.flush()?;
+        self.wbuf.get_ref().sync_data()?;
+        let off = self.wbuf.get_ref().stream_position()?;
+        self.wbuf.get_ref().set_len(off)?;
+        create_file_metadata(
+            &self.file_path,
+            file_compression,
+            self.file_type,
+            self.bucket_num,
+            self.part_num,
+        )
+    }
+}
+
+/// The snapshot that a delta snapshot is written on top of.
+struct DeltaBase {
+    epoch: u64,
+    manifest_digest: [u8; 32],
+    /// Local copies of the REFERENCE and *.rm files of the chain of snapshots the base snapshot
+    /// consists of, to stream the references of the objects that are live as of the base snapshot
+    /// from.
+    object_refs: LiveObjectRefs,
 }
 
 /// StateSnapshotWriterV1 writes snapshot files to a local staging dir and simultaneously uploads them
@@ -262,14 +368,8 @@ impl StateSnapshotWriterV1 {
         root_state_hash: ECMHLiveObjectSetDigest,
         chain_identifier: ChainIdentifier,
     ) -> Result<()> {
-        let system_state_object = get_sui_system_state(&perpetual_db)?;
-
-        let protocol_version = system_state_object.protocol_version();
-        let protocol_config = ProtocolConfig::get_for_version(
-            ProtocolVersion::new(protocol_version),
-            chain_identifier.chain(),
-        );
-        let include_wrapped_tombstone = !protocol_config.simplified_unwrap_then_delete();
+        let include_wrapped_tombstone =
+            Self::include_wrapped_tombstone(&perpetual_db, chain_identifier)?;
         self.write_internal(
             epoch,
             include_wrapped_tombstone,
@@ -279,9 +379,96 @@ impl StateSnapshotWriterV1 {
         .await
     }
 
+    /// Writes a delta snapshot for `epoch`, which only contains the changes to the live object set
+    /// since the end of `base_epoch`. The snapshot for `base_epoch` (full or delta) must already
+    /// be in the remote object store. The references of all objects that were live at the end of
+    /// `base_epoch` are downloaded from it to the local staging dir, and compared against the live
+    /// object set as they are streamed back, in object ID order.
+    pub async fn write_delta(
+        self,
+        epoch: u64,
+        base_epoch: u64,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        root_state_hash: ECMHLiveObjectSetDigest,
+        chain_identifier: ChainIdentifier,
+    ) -> Result<()> {
+        let include_wrapped_tombstone =
+            Self::include_wrapped_tombstone(&perpetual_db, chain_identifier)?;
+        self.write_delta_internal(
+            epoch,
+            base_epoch,
+            include_wrapped_tombstone,
+            perpetual_db,
+            root_state_hash,
+        )
+        .await
+    }
+
     pub(crate) async fn write_internal(
+        self,
+        epoch: u64,
+        include_wrapped_tombstone: bool,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        root_state_hash: ECMHLiveObjectSetDigest,
+    ) -> Result<()> {
+        self.write_snapshot(
+            epoch,
+            None,
+            include_wrapped_tombstone,
+            perpetual_db,
+            root_state_hash,
+        )
+        .await
+    }
+
+    pub(crate) async fn write_delta_internal(
+        self,
+        epoch: u64,
+        base_epoch: u64,
+        include_wrapped_tombstone: bool,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        root_state_hash: ECMHLiveObjectSetDigest,
+    ) -> Result<()> {
+        if base_epoch >= epoch {
+            return Err(anyhow!(
+                "Base epoch: {} of delta snapshot must be before epoch: {}",
+                base_epoch,
+                epoch
+            ));
+        }
+        let base_dir = self.local_staging_dir.join(format!("epoch_{}_base", epoch));
+        if base_dir.exists() {
+            fs::remove_dir_all(&base_dir)?;
+        }
+        let (object_refs, manifest_digest) =
+            LiveObjectRefs::download(&self.remote_object_store, base_epoch, base_dir.clone())
+                .await
+                .context(format!(
+                    "Failed to read state snapshot for base epoch: {}",
+                    base_epoch
+                ))?;
+        let base = DeltaBase {
+            epoch: base_epoch,
+            manifest_digest,
+            object_refs,
+        };
+        let result = self
+            .write_snapshot(
+                epoch,
+                Some(base),
+                include_wrapped_tombstone,
+                perpetual_db,
+                root_state_hash,
+            )
+            .await;
+        fs::remove_dir_all(&base_dir)?;
+        result
+    }
+
+    async fn write_snapshot(
         mut self,
         epoch: u64,
+        base: Option<DeltaBase>,
         include_wrapped_tombstone: bool,
         perpetual_db: Arc<AuthorityPerpetualTables>,
         root_state_hash: ECMHLiveObjectSetDigest,
@@ -298,6 +485,7 @@ impl StateSnapshotWriterV1 {
         let write_handler = tokio::task::spawn_blocking(move || {
             self.write_live_object_set(
                 epoch,
+                base,
                 perpetual_db,
                 sender,
                 Self::bucket_func,
@@ -325,6 +513,20 @@ impl StateSnapshotWriterV1 {
         Ok(())
     }
 
+    fn include_wrapped_tombstone(
+        perpetual_db: &AuthorityPerpetualTables,
+        chain_identifier: ChainIdentifier,
+    ) -> Result<bool> {
+        let system_state_object = get_sui_system_state(perpetual_db)?;
+
+        let protocol_version = system_state_object.protocol_version();
+        let protocol_config = ProtocolConfig::get_for_version(
+            ProtocolVersion::new(protocol_version),
+            chain_identifier.chain(),
+        );
+        Ok(!protocol_config.simplified_unwrap_then_delete())
+    }
+
     fn start_upload(
         &self,
         epoch: u64,
@@ -367,6 +569,7 @@ impl StateSnapshotWriterV1 {
     fn write_live_object_set<F>(
         &mut self,
         epoch: u64,
+        base: Option<DeltaBase>,
         perpetual_db: Arc<AuthorityPerpetualTables>,
         sender: Sender<FileMetadata>,
         bucket_func: F,
@@ -376,12 +579,51 @@ impl StateSnapshotWriterV1 {
     where
         F: Fn(&LiveObject) -> u32,
     {
+        let (base_manifest, mut base_object_refs) = match &base {
+            Some(base) => (
+                Some((base.epoch, base.manifest_digest)),
+                Some(base.object_refs.iter()?.peekable()),
+            ),
+            None => (None, None),
+        };
         let mut object_writers: HashMap<u32, LiveObjectSetWriterV1> = HashMap::new();
         let local_staging_dir_path =
             path_to_filesystem(self.local_staging_dir.clone(), &self.epoch_dir(epoch))?;
+        // References from the base snapshot that are no longer live, which are all written to the
+        // same bucket, in object ID order
+        let mut removed_writer: Option<RemovedObjectRefWriterV1> = None;
+        let mut write_removed = |object_ref: &ObjectRef| -> Result<()> {
+            if removed_writer.is_none() {
+                removed_writer = Some(RemovedObjectRefWriterV1::new(
+                    local_staging_dir_path.clone(),
+                    1,
+                    self.file_compression,
+                    sender.clone(),
+                )?);
+            }
+            removed_writer
+                .as_mut()
+                .context("Unexpected missing removed object writer")?
+                .write(object_ref)
+        };
         let mut acc = GlobalStateHash::default();
         for object in perpetual_db.iter_live_object_set(include_wrapped_tombstone) {
             GlobalStateHasher::accumulate_live_object(&mut acc, &object);
+            if let Some(base_object_refs) = &mut base_object_refs {
+                // Both the live object set and the base snapshot's references are in object ID
+                // order, so base objects before this one are no longer live, and objects that have
+                // not changed since the base snapshot are not written again
+                let object_ref = object.object_reference();
+                while let Some(removed) = base_object_refs.next_if(|r| r.0 < object_ref.0) {
+                    write_removed(&removed)?;
+                }
+                if let Some(base_ref) = base_object_refs.next_if(|r| r.0 == object_ref.0) {
+                    if base_ref == object_ref {
+                        continue;
+                    }
+                    write_removed(&base_ref)?;
+                }
+            }
             let bucket_num = bucket_func(&object);
             if let Vacant(entry) = object_writers.entry(bucket_num) {
                 entry.insert(LiveObjectSetWriterV1::new(
@@ -405,19 +647,36 @@ impl StateSnapshotWriterV1 {
         for (_, writer) in object_writers.into_iter() {
             files.extend(writer.done()?);
         }
-        self.write_manifest(epoch, files)?;
+        // Whatever is left of the base snapshot's objects is no longer live
+        for removed in base_object_refs.into_iter().flatten() {
+            write_removed(&removed)?;
+        }
+        if let Some(writer) = removed_writer {
+            files.extend(writer.done()?);
+        }
+        let manifest = match base_manifest {
+            None => Manifest::V1(ManifestV1 {
+                snapshot_version: 1,
+                address_length: ObjectID::LENGTH as u64,
+                file_metadata: files,
+                epoch,
+            }),
+            Some((base_epoch, base_manifest_digest)) => Manifest::DeltaV1(DeltaManifestV1 {
+                snapshot_version: 1,
+                address_length: ObjectID::LENGTH as u64,
+                file_metadata: files,
+                epoch,
+                base_epoch,
+                base_manifest_digest,
+            }),
+        };
+        self.write_manifest(epoch, manifest)?;
         Ok(())
     }
 
-    fn write_manifest(&mut self, epoch: u64, file_metadata: Vec<FileMetadata>) -> Result<()> {
+    fn write_manifest(&mut self, epoch: u64, manifest: Manifest) -> Result<()> {
         let (f, manifest_file_path) = self.manifest_file(epoch)?;
         let mut wbuf = BufWriter::new(f);
-        let manifest: Manifest = Manifest::V1(ManifestV1 {
-            snapshot_version: 1,
-            address_length: ObjectID::LENGTH as u64,
-            file_metadata,
-            epoch,
-        });
         let serialized_manifest = bcs::to_bytes(&manifest)?;
         wbuf.write_all(&serialized_manifest)?;
         wbuf.flush()?;
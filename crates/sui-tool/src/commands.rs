@@ -19,7 +19,9 @@ use sui_core::authority_client::AuthorityAPI;
 use sui_protocol_config::Chain;
 use sui_replay::{execute_replay_command, ReplayToolCommand};
 use sui_sdk::{rpc_types::SuiTransactionBlockResponseOptions, SuiClient, SuiClientBuilder};
+use sui_snapshot::filter::LiveObjectFilter;
 use sui_types::messages_consensus::ConsensusTransaction;
+use sui_types::TypeTag;
 use telemetry_subscribers::TracingHandle;
 
 use sui_types::{
@@ -340,6 +342,22 @@ pub enum ToolCommand {
         /// downloaded, and (if --verify is provided) will be verified via committee signature.
         #[clap(long = "all-checkpoints")]
         all_checkpoints: bool,
+
+        /// If any `--filter-*` flags are provided, only the live objects that match at least one
+        /// of them are restored, as BCS files in `<path>/objects`, instead of restoring a
+        /// database. Restores the objects with these IDs.
+        #[clap(long = "filter-object-id")]
+        filter_object_ids: Vec<ObjectID>,
+        /// Restores these packages, and objects of types that they define.
+        #[clap(long = "filter-package")]
+        filter_packages: Vec<ObjectID>,
+        /// Restores objects of these types. A type without type parameters, e.g.
+        /// `0x2::coin::Coin`, matches all of its instantiations.
+        #[clap(long = "filter-type")]
+        filter_types: Vec<TypeTag>,
+        /// Restores objects owned by these addresses or objects.
+        #[clap(long = "filter-owner")]
+        filter_owners: Vec<SuiAddress>,
     },
 
     #[clap(name = "replay")]
@@ -680,6 +698,10 @@ impl ToolCommand {
                 latest,
                 verbose,
                 all_checkpoints,
+                filter_object_ids,
+                filter_packages,
+                filter_types,
+                filter_owners,
             } => {
                 if !verbose {
                     tracing_handle
@@ -801,6 +823,20 @@ impl ToolCommand {
                     );
                 }
 
+                let mut filter = LiveObjectFilter::new();
+                for id in filter_object_ids {
+                    filter = filter.add_object_id(id);
+                }
+                for package in filter_packages {
+                    filter = filter.add_package(package);
+                }
+                for type_ in filter_types {
+                    filter = filter.add_type(type_);
+                }
+                for owner in filter_owners {
+                    filter = filter.add_owner(owner);
+                }
+
                 let verify = verify.unwrap_or_default();
                 download_formal_snapshot(
                     &path,
@@ -812,6 +848,7 @@ impl ToolCommand {
                     network,
                     verify,
                     all_checkpoints,
+                    filter,
                 )
                 .await?;
             }
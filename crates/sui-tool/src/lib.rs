@@ -51,6 +51,7 @@ use sui_core::authority::AuthorityStore;
 use sui_core::checkpoints::CheckpointStore;
 use sui_core::epoch::committee_store::CommitteeStore;
 use sui_core::storage::RocksDbStore;
+use sui_snapshot::filter::{BcsDirectorySink, LiveObjectFilter};
 use sui_snapshot::reader::StateSnapshotReaderV1;
 use sui_snapshot::setup_db_state;
 use sui_storage::object_store::util::{copy_file, exists, get_path};
@@ -825,6 +826,7 @@ pub async fn download_formal_snapshot(
     network: Chain,
     verify: SnapshotVerifyMode,
     all_checkpoints: bool,
+    filter: LiveObjectFilter,
 ) -> Result<(), anyhow::Error> {
     let m = MultiProgress::new();
     m.println(format!(
@@ -864,6 +866,17 @@ pub async fn download_formal_snapshot(
         fs::remove_dir_all(snapshot_dir.clone())?;
     }
     let snapshot_dir_clone = snapshot_dir.clone();
+    // Objects matching the filter, if any, are restored to a directory rather than a database
+    let objects_dir = path.parent().unwrap().join("objects");
+    let objects_sink = if filter.is_empty() {
+        None
+    } else {
+        if objects_dir.exists() {
+            fs::remove_dir_all(objects_dir.clone())?;
+        }
+        Some(BcsDirectorySink::new(objects_dir.clone())?)
+    };
+    let restore_filtered = objects_sink.is_some();
 
     // TODO if verify is false, we should skip generating these and
     // not pass in a channel to the reader
@@ -886,18 +899,31 @@ pub async fn download_formal_snapshot(
         )
         .await
         .unwrap_or_else(|err| panic!("Failed to create reader: {}", err));
-        reader
-            .read(&perpetual_db_clone, abort_registration, Some(sender))
-            .await
-            .unwrap_or_else(|err| panic!("Failed during read: {}", err));
+        match &objects_sink {
+            Some(sink) => {
+                reader
+                    .read_filtered(&filter, sink, abort_registration, Some(sender))
+                    .await
+            }
+            None => {
+                reader
+                    .read(&perpetual_db_clone, abort_registration, Some(sender))
+                    .await
+            }
+        }
+        .unwrap_or_else(|err| panic!("Failed during read: {}", err));
         Ok::<(), anyhow::Error>(())
     });
     let mut root_global_state_hash = GlobalStateHash::default();
-    let mut num_live_objects = 0;
+    // Objects removed by delta snapshots are sent with a negative count.
+    let mut num_live_objects: i64 = 0;
     while let Some((partial_hash, num_objects)) = receiver.recv().await {
         num_live_objects += num_objects;
         root_global_state_hash.union(&partial_hash);
     }
+    let num_live_objects = u64::try_from(num_live_objects).map_err(|_| {
+        anyhow!("Snapshot removes more objects than it contains: {num_live_objects}")
+    })?;
     summaries_handle
         .await
         .expect("Task join failed")
@@ -962,6 +988,17 @@ pub async fn download_formal_snapshot(
         .expect("Task join failed")
         .expect("Snapshot restore task failed");
 
+    if restore_filtered {
+        fs::remove_dir_all(&path)?;
+        fs::remove_dir_all(snapshot_dir.clone())?;
+        println!(
+            "Successfully restored objects matching filter from snapshot at end of epoch {} to {}",
+            epoch,
+            objects_dir.display()
+        );
+        return Ok(());
+    }
+
     // TODO we should ensure this map is being updated for all end of epoch
     // checkpoints during summary sync. This happens in `insert_{verified|certified}_checkpoint`
     // in checkpoint store, but not in the corresponding functions in ObjectStore trait
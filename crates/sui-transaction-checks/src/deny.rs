@@ -2,16 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use fastcrypto_zkp::bn254::zk_login::OIDCProvider;
+use std::collections::HashSet;
+
 use sui_config::{
     dynamic_transaction_signing_checks::DynamicCheckRunnerError,
-    transaction_deny_config::TransactionDenyConfig,
+    transaction_deny_config::{QualifiedName, TransactionDenyConfig},
 };
 use sui_types::{
-    base_types::ObjectRef,
+    base_types::{ObjectID, ObjectRef},
     error::{SuiError, SuiResult, UserInputError},
+    object::Object,
     signature::GenericSignature,
     storage::BackingPackageStore,
-    transaction::{Command, InputObjectKind, TransactionData, TransactionDataAPI},
+    transaction::{
+        Command, InputObjectKind, InputObjects, ReceivingObjects, TransactionData,
+        TransactionDataAPI,
+    },
+    TypeTag,
 };
 use tracing::{error, warn};
 macro_rules! deny_if_true {
@@ -44,6 +51,8 @@ pub fn check_transaction_for_signing(
 
     check_package_dependencies(filter_config, tx_data, package_store)?;
 
+    check_functions(filter_config, tx_data)?;
+
     check_receiving_objects(filter_config, receiving_objects)?;
 
     // NB: Only performed at signing time.
@@ -58,6 +67,70 @@ pub fn check_transaction_for_signing(
     Ok(())
 }
 
+/// Check that none of the objects the transaction reads (as inputs, or receiving objects) are of
+/// a type that is denied by the deny config. This needs the objects, so unlike the other checks,
+/// it runs once they have been loaded.
+pub fn check_input_object_types(
+    filter_config: &TransactionDenyConfig,
+    input_objects: &InputObjects,
+    receiving_objects: &ReceivingObjects,
+) -> SuiResult {
+    let deny_set = filter_config.get_type_deny_set();
+    if deny_set.is_empty() {
+        return Ok(());
+    }
+
+    let inputs = input_objects.iter().filter_map(|input| input.as_object());
+    let receiving = receiving_objects
+        .iter()
+        .filter_map(|receiving| receiving.object.as_object());
+
+    for object in inputs.chain(receiving) {
+        check_object_type(deny_set, object)?;
+    }
+    Ok(())
+}
+
+fn check_object_type(deny_set: &HashSet<QualifiedName>, object: &Object) -> SuiResult {
+    let Some(tag) = object.struct_tag() else {
+        return Ok(());
+    };
+
+    if let Some(denied) = find_denied_type(deny_set, &TypeTag::Struct(Box::new(tag))) {
+        return Err(type_denied(denied));
+    }
+    Ok(())
+}
+
+/// Find a struct type in `tag` (including its type parameters) that is in `deny_set`.
+fn find_denied_type(deny_set: &HashSet<QualifiedName>, tag: &TypeTag) -> Option<QualifiedName> {
+    match tag {
+        TypeTag::Vector(inner) => find_denied_type(deny_set, inner),
+        TypeTag::Struct(tag) => {
+            let name = QualifiedName::new(
+                ObjectID::from(tag.address),
+                tag.module.as_str(),
+                tag.name.as_str(),
+            );
+            if deny_set.contains(&name) {
+                return Some(name);
+            }
+            tag.type_params
+                .iter()
+                .find_map(|param| find_denied_type(deny_set, param))
+        }
+        _ => None,
+    }
+}
+
+fn type_denied(type_: QualifiedName) -> SuiError {
+    SuiError::UserInputError {
+        error: UserInputError::TransactionDenied {
+            error: format!("Usage of type {type_} is temporarily disabled"),
+        },
+    }
+}
+
 fn dynamic_transaction_checks(
     filter_config: &TransactionDenyConfig,
     tx_data: &TransactionData,
@@ -208,6 +281,42 @@ fn check_input_objects(
     Ok(())
 }
 
+fn check_functions(filter_config: &TransactionDenyConfig, tx_data: &TransactionData) -> SuiResult {
+    let function_deny_set = filter_config.get_function_deny_set();
+    let type_deny_set = filter_config.get_type_deny_set();
+    if function_deny_set.is_empty() && type_deny_set.is_empty() {
+        return Ok(());
+    }
+
+    for command in tx_data.kind().iter_commands() {
+        let Command::MoveCall(call) = command else {
+            continue;
+        };
+
+        let function = QualifiedName::new(call.package, &call.module, &call.function);
+        deny_if_true!(
+            function_deny_set.contains(&function),
+            format!("Calls to function {function} are temporarily disabled")
+        );
+
+        if type_deny_set.is_empty() {
+            continue;
+        }
+
+        for type_argument in &call.type_arguments {
+            // Type arguments that don't resolve to a type will fail to execute anyway.
+            let Ok(tag) = type_argument.to_type_tag() else {
+                continue;
+            };
+
+            if let Some(denied) = find_denied_type(type_deny_set, &tag) {
+                return Err(type_denied(denied));
+            }
+        }
+    }
+    Ok(())
+}
+
 fn check_package_dependencies(
     filter_config: &TransactionDenyConfig,
     tx_data: &TransactionData,
@@ -1,18 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::base_types::{AuthorityName, VerifiedExecutionData};
+use crate::base_types::{AuthorityName, ExecutionData, VerifiedExecutionData};
 use crate::committee::Committee;
 use crate::crypto::{AuthoritySignInfo, AuthoritySignature, SuiAuthoritySignature};
 use crate::effects::{TransactionEffects, TransactionEffectsAPI};
 use crate::gas::GasCostSummary;
 use crate::messages_checkpoint::{
     CertifiedCheckpointSummary, CheckpointContents, CheckpointSummary,
-    CheckpointVersionSpecificData, EndOfEpochData, FullCheckpointContents, VerifiedCheckpoint,
-    VerifiedCheckpointContents,
+    CheckpointVersionSpecificData, EndOfEpochData, FullCheckpointContents, TrustedCheckpoint,
+    VerifiedCheckpoint, VerifiedCheckpointContents,
 };
 use crate::transaction::VerifiedTransaction;
 use fastcrypto::traits::Signer;
+use serde::{Deserialize, Serialize};
 use std::mem;
 
 pub trait ValidatorKeypairProvider {
@@ -22,7 +23,7 @@ pub trait ValidatorKeypairProvider {
 
 /// A utility to build consecutive checkpoints by adding transactions to the checkpoint builder.
 /// It's mostly used by simulations, tests and benchmarks.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct MockCheckpointBuilder {
     previous_checkpoint: Option<VerifiedCheckpoint>,
     transactions: Vec<VerifiedExecutionData>,
@@ -30,6 +31,16 @@ pub struct MockCheckpointBuilder {
     epoch: u64,
 }
 
+/// The state of a [`MockCheckpointBuilder`], in a form that can be serialized, so that the builder
+/// can be saved and picked up again later.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MockCheckpointBuilderState {
+    previous_checkpoint: Option<TrustedCheckpoint>,
+    transactions: Vec<ExecutionData>,
+    epoch_rolling_gas_cost_summary: GasCostSummary,
+    epoch: u64,
+}
+
 impl MockCheckpointBuilder {
     pub fn new(previous_checkpoint: VerifiedCheckpoint) -> Self {
         let epoch_rolling_gas_cost_summary =
@@ -44,6 +55,36 @@ impl MockCheckpointBuilder {
         }
     }
 
+    /// Restore a builder from state previously returned by [`MockCheckpointBuilder::state`].
+    pub fn from_state(state: MockCheckpointBuilderState) -> Self {
+        Self {
+            previous_checkpoint: state.previous_checkpoint.map(VerifiedCheckpoint::from),
+            transactions: state
+                .transactions
+                .into_iter()
+                .map(VerifiedExecutionData::new_unchecked)
+                .collect(),
+            epoch_rolling_gas_cost_summary: state.epoch_rolling_gas_cost_summary,
+            epoch: state.epoch,
+        }
+    }
+
+    pub fn state(&self) -> MockCheckpointBuilderState {
+        MockCheckpointBuilderState {
+            previous_checkpoint: self
+                .previous_checkpoint
+                .as_ref()
+                .map(|c| c.serializable_ref().clone()),
+            transactions: self
+                .transactions
+                .iter()
+                .map(|e| e.clone().into_inner())
+                .collect(),
+            epoch_rolling_gas_cost_summary: self.epoch_rolling_gas_cost_summary.clone(),
+            epoch: self.epoch,
+        }
+    }
+
     pub fn size(&self) -> usize {
         self.transactions.len()
     }
@@ -179,7 +220,7 @@ impl MockCheckpointBuilder {
         (checkpoint, contents, full_contents)
     }
 
-    fn create_certified_checkpoint(
+    pub fn create_certified_checkpoint(
         validator_keys: &impl ValidatorKeypairProvider,
         checkpoint: CheckpointSummary,
     ) -> VerifiedCheckpoint {
@@ -3,6 +3,7 @@
 
 use serde::{de::Deserializer, Deserialize, Serialize};
 use serde_with::serde_as;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 // These values set to loosely attempt to limit
@@ -218,6 +219,84 @@ fn default_sketch_tolerance() -> f64 {
     DEFAULT_SKETCH_TOLERANCE
 }
 
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TokenBucketConfig {
+    /// Maximum number of tokens a client can hold, i.e. the largest burst
+    /// of requests (weighted by method cost) that a client can make at once
+    #[serde(default = "default_bucket_capacity")]
+    pub client_capacity: u64,
+    /// Number of tokens per second returned to each client's bucket
+    #[serde(default = "default_bucket_refill_rate")]
+    pub client_refill_rate: f64,
+    #[serde(default = "default_proxied_bucket_capacity")]
+    pub proxied_client_capacity: u64,
+    #[serde(default = "default_proxied_bucket_refill_rate")]
+    pub proxied_client_refill_rate: f64,
+    /// Limits for clients identified by API key (see `PolicyConfig::api_key_header`).
+    /// If unset, the same limits as direct clients are used.
+    #[serde(default)]
+    pub api_key_capacity: Option<u64>,
+    #[serde(default)]
+    pub api_key_refill_rate: Option<f64>,
+    /// Number of tokens taken from the bucket by a request, by method name
+    /// (e.g. `sui_executeTransactionBlock` for JSON-RPC, or `handle_certificate_v2`
+    /// for the validator gRPC service). Methods not listed cost `default_method_cost`.
+    #[serde(default)]
+    pub method_costs: BTreeMap<String, u64>,
+    #[serde(default = "default_method_cost")]
+    pub default_method_cost: u64,
+}
+
+impl Default for TokenBucketConfig {
+    fn default() -> Self {
+        Self {
+            client_capacity: default_bucket_capacity(),
+            client_refill_rate: default_bucket_refill_rate(),
+            proxied_client_capacity: default_proxied_bucket_capacity(),
+            proxied_client_refill_rate: default_proxied_bucket_refill_rate(),
+            api_key_capacity: None,
+            api_key_refill_rate: None,
+            method_costs: BTreeMap::new(),
+            default_method_cost: default_method_cost(),
+        }
+    }
+}
+
+impl TokenBucketConfig {
+    /// Number of tokens taken by a request for `method`.
+    pub fn method_cost(&self, method: Option<&str>) -> u64 {
+        method
+            .and_then(|method| self.method_costs.get(method))
+            .copied()
+            .unwrap_or(self.default_method_cost)
+    }
+}
+
+fn default_bucket_capacity() -> u64 {
+    // as with the frequency threshold, a single client may be a
+    // fullnode proxying traffic from many clients, so by default
+    // only catch unreasonably bursty traffic
+    1_000_000
+}
+
+fn default_bucket_refill_rate() -> f64 {
+    1_000_000.0
+}
+
+fn default_proxied_bucket_capacity() -> u64 {
+    100
+}
+
+fn default_proxied_bucket_refill_rate() -> f64 {
+    10.0
+}
+
+fn default_method_cost() -> u64 {
+    1
+}
+
 // Serializable representation of policy types, used in config
 // in order to easily change in tests or to killswitch
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
@@ -232,6 +311,15 @@ pub enum PolicyType {
     #[serde(rename = "freq-threshold", alias = "FreqThreshold")]
     FreqThreshold(FreqThresholdConfig),
 
+    /// Gives each client a bucket of tokens that refills at a steady rate, and
+    /// blocks clients that make a request costing more tokens than remain in
+    /// their bucket. Unlike `FreqThreshold`, requests can be weighted by method,
+    /// so that clients making expensive requests at a low rate are still caught.
+    /// Note that tallies are still subject to `spam-sample-rate`, which should
+    /// normally be set to 1.0 when using this policy for spam.
+    #[serde(rename = "token-bucket", alias = "TokenBucket")]
+    TokenBucket(TokenBucketConfig),
+
     /* Below this point are test policies, and thus should not be used in production */
     ///
     /// Simple policy that adds connection_ip to blocklist when the same connection_ip
@@ -276,6 +364,13 @@ pub struct PolicyConfig {
     /// and any blocklist related configuration will be ignored.
     #[serde(default)]
     pub allow_list: Option<Vec<String>>,
+    /// Name of a request header carrying an authenticated API key. If set,
+    /// requests carrying this header are also tracked by the key, for policies
+    /// that support it (currently `TokenBucket`), and a key that exceeds its
+    /// limit is blocked for `connection-blocklist-ttl-sec`, regardless of
+    /// the IP address it is used from.
+    #[serde(default)]
+    pub api_key_header: Option<String>,
 }
 
 impl Default for PolicyConfig {
@@ -290,6 +385,7 @@ impl Default for PolicyConfig {
             spam_sample_rate: default_spam_sample_rate(),
             dry_run: default_dry_run(),
             allow_list: None,
+            api_key_header: None,
         }
     }
 }
@@ -467,7 +467,7 @@ impl fmt::Display for ParsedPTBCommand {
     }
 }
 
-struct TyDisplay<'a>(&'a ParsedType);
+pub struct TyDisplay<'a>(pub &'a ParsedType);
 
 impl fmt::Display for TyDisplay<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -0,0 +1,887 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decompiles programmable transactions back into the commands of the `sui client ptb` language,
+//! so that transactions seen on chain can be tweaked and re-run.
+//!
+//! Object inputs are referred to by their ID, the results of commands are bound to names derived
+//! from the command that produced them, and pure inputs are decoded using the types they are used
+//! at (which, for Move calls, involves looking up the signature of the function being called).
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use fastcrypto::encoding::{Base64, Encoding};
+use move_binary_format::{
+    binary_config::BinaryConfig,
+    file_format::{DatatypeHandleIndex, SignatureToken},
+    CompiledModule,
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::IdentStr,
+    parsing::{
+        address::{NumericalAddress, ParsedAddress},
+        parser::NumberFormat,
+        types::{ParsedFqName, ParsedModuleId, ParsedStructType, ParsedType},
+    },
+    u256::U256,
+};
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiTransactionBlockResponseOptions};
+use sui_sdk::apis::ReadApi;
+use sui_types::{
+    base_types::{
+        ObjectID, SuiAddress, TxContext, TxContextKind, RESOLVED_ASCII_STR, RESOLVED_STD_OPTION,
+        RESOLVED_UTF8_STR,
+    },
+    digests::TransactionDigest,
+    id::RESOLVED_SUI_ID,
+    move_package::MovePackage,
+    transaction::{
+        self as Tx, CallArg, ObjectArg, ProgrammableTransaction, SenderSignedData, TransactionData,
+        TransactionDataAPI, TransactionKind,
+    },
+    Identifier, TypeTag,
+};
+
+use crate::sp;
+
+use super::{
+    ast::{
+        self, Argument, ModuleAccess, ParsedPTBCommand, TyDisplay, GAS_BUDGET, GAS_SPONSOR, SENDER,
+    },
+    builder::resolve_package,
+    error::{Span, Spanned},
+};
+
+/// Flag to decompile a transaction that has been executed, given its digest.
+pub const FROM_DIGEST: &str = "--from-digest";
+
+/// Flag to decompile a transaction given its Base64 encoded BCS bytes, as either `TransactionData`,
+/// `SenderSignedData`, or just the `TransactionKind`.
+pub const FROM_BYTES: &str = "--from-bytes";
+
+/// A PTB recovered from a transaction, which is displayed as a `sui client ptb` command line.
+pub struct DecompiledPTB {
+    pub commands: Vec<ParsedPTBCommand>,
+    pub sender: Option<SuiAddress>,
+    pub gas_sponsor: Option<SuiAddress>,
+    pub gas_budget: Option<u64>,
+}
+
+/// The type of a pure input, as far as is needed to decode its value.
+#[derive(Clone, Debug)]
+enum PureType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    Address,
+    String,
+    Vector(Box<PureType>),
+    Option(Box<PureType>),
+}
+
+/// Fetch the transaction that `args` refers to (through `--from-digest` or `--from-bytes`), and
+/// decompile it.
+pub async fn decompile_from_args(
+    reader: &ReadApi,
+    args: &[String],
+) -> anyhow::Result<DecompiledPTB> {
+    let [flag, value] = args else {
+        bail!("{FROM_DIGEST} and {FROM_BYTES} cannot be combined with other commands");
+    };
+
+    let data = if flag == FROM_DIGEST {
+        let digest: TransactionDigest = value
+            .parse()
+            .map_err(|e| anyhow!("Invalid transaction digest '{value}': {e}"))?;
+
+        let response = reader
+            .get_transaction_with_options(
+                digest,
+                SuiTransactionBlockResponseOptions::new().with_raw_input(),
+            )
+            .await?;
+
+        let signed: SenderSignedData = bcs::from_bytes(&response.raw_transaction)
+            .context("Failed to deserialize transaction")?;
+        signed.transaction_data().clone()
+    } else if flag == FROM_BYTES {
+        let bytes = Base64::decode(value).map_err(|_| anyhow!("Invalid Base64 encoding"))?;
+
+        if let Ok(data) = bcs::from_bytes::<TransactionData>(&bytes) {
+            data
+        } else if let Ok(signed) = bcs::from_bytes::<SenderSignedData>(&bytes) {
+            signed.transaction_data().clone()
+        } else if let Ok(kind) = bcs::from_bytes::<TransactionKind>(&bytes) {
+            return decompile_kind(reader, &kind).await;
+        } else {
+            bail!("Failed to parse {FROM_BYTES} as TransactionData or TransactionKind");
+        }
+    } else {
+        bail!("{FROM_DIGEST} and {FROM_BYTES} cannot be combined with other commands");
+    };
+
+    let mut decompiled = decompile_kind(reader, data.kind()).await?;
+    decompiled.sender = Some(data.sender());
+    decompiled.gas_budget = Some(data.gas_budget());
+    if data.gas_owner() != data.sender() {
+        decompiled.gas_sponsor = Some(data.gas_owner());
+    }
+
+    Ok(decompiled)
+}
+
+async fn decompile_kind(reader: &ReadApi, kind: &TransactionKind) -> anyhow::Result<DecompiledPTB> {
+    let TransactionKind::ProgrammableTransaction(ptb) = kind else {
+        bail!(
+            "Only programmable transactions can be decompiled, found {}",
+            kind.name()
+        );
+    };
+
+    Ok(DecompiledPTB {
+        commands: decompile(reader, ptb).await?,
+        sender: None,
+        gas_sponsor: None,
+        gas_budget: None,
+    })
+}
+
+/// Convert `ptb` into a sequence of PTB commands that would build an equivalent transaction.
+pub async fn decompile(
+    reader: &ReadApi,
+    ptb: &ProgrammableTransaction,
+) -> anyhow::Result<Vec<ParsedPTBCommand>> {
+    let pure_types = pure_types(reader, ptb).await?;
+
+    let mut inputs = vec![];
+    for (i, input) in ptb.inputs.iter().enumerate() {
+        inputs.push(match input {
+            CallArg::Pure(bytes) => match pure_types.get(&i) {
+                Some(ty) => Some(
+                    decode_pure(ty, bytes)
+                        .with_context(|| format!("Failed to decode pure input {i}"))?,
+                ),
+                // Inputs that are not used by any command don't need to be represented.
+                None => None,
+            },
+            CallArg::Object(
+                ObjectArg::ImmOrOwnedObject((id, _, _))
+                | ObjectArg::SharedObject { id, .. }
+                | ObjectArg::Receiving((id, _, _)),
+            ) => Some(address(**id)),
+            CallArg::BalanceWithdraw(_) => {
+                bail!("Balance withdrawals cannot be expressed as PTB commands (input {i})")
+            }
+        });
+    }
+
+    // Only the results that are used by later commands need to be named.
+    let mut used = BTreeSet::new();
+    for command in &ptb.commands {
+        for arg in command_arguments(command) {
+            if let Tx::Argument::Result(i) | Tx::Argument::NestedResult(i, _) = arg {
+                used.insert(*i as usize);
+            }
+        }
+    }
+
+    let mut names: BTreeMap<usize, String> = BTreeMap::new();
+    let mut taken = BTreeSet::new();
+    let mut commands = vec![];
+
+    for (i, command) in ptb.commands.iter().enumerate() {
+        let arg = |a: &Tx::Argument| -> anyhow::Result<Spanned<Argument>> {
+            Ok(spanned(match a {
+                Tx::Argument::GasCoin => Argument::Gas,
+                Tx::Argument::Input(j) => inputs
+                    .get(*j as usize)
+                    .cloned()
+                    .flatten()
+                    .ok_or_else(|| anyhow!("Command {i} refers to missing input {j}"))?,
+                Tx::Argument::Result(j) => Argument::Identifier(result_name(&names, i, *j)?),
+                Tx::Argument::NestedResult(j, k) => Argument::VariableAccess(
+                    spanned(result_name(&names, i, *j)?),
+                    vec![spanned(k.to_string())],
+                ),
+            }))
+        };
+
+        let args = |as_: &[Tx::Argument]| -> anyhow::Result<Spanned<Vec<Spanned<Argument>>>> {
+            Ok(spanned(
+                as_.iter().map(&arg).collect::<anyhow::Result<_>>()?,
+            ))
+        };
+
+        let (command, name) = match command {
+            Tx::Command::TransferObjects(objs, to) => (
+                ParsedPTBCommand::TransferObjects(args(objs)?, arg(to)?),
+                "transferred",
+            ),
+
+            Tx::Command::SplitCoins(coin, amounts) => (
+                ParsedPTBCommand::SplitCoins(arg(coin)?, args(amounts)?),
+                "coins",
+            ),
+
+            Tx::Command::MergeCoins(coin, coins) => (
+                ParsedPTBCommand::MergeCoins(arg(coin)?, args(coins)?),
+                "merged",
+            ),
+
+            Tx::Command::MakeMoveVec(ty, elems) => {
+                let ty = match ty {
+                    Some(ty) => ty.to_type_tag()?,
+                    None => object_type(reader, ptb, elems).await?,
+                };
+
+                (
+                    ParsedPTBCommand::MakeMoveVec(spanned(parsed_type(&ty)), args(elems)?),
+                    "vec",
+                )
+            }
+
+            Tx::Command::MoveCall(call) => {
+                let type_args = call
+                    .type_arguments
+                    .iter()
+                    .map(|ty| Ok(parsed_type(&ty.to_type_tag()?)))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+
+                let access = ModuleAccess {
+                    address: spanned(ParsedAddress::Numerical(numerical(*call.package))),
+                    module_name: spanned(Identifier::new(call.module.as_str())?),
+                    function_name: spanned(Identifier::new(call.function.as_str())?),
+                };
+
+                (
+                    ParsedPTBCommand::MoveCall(
+                        spanned(access),
+                        (!type_args.is_empty()).then(|| spanned(type_args)),
+                        call.arguments
+                            .iter()
+                            .map(&arg)
+                            .collect::<anyhow::Result<_>>()?,
+                    ),
+                    call.function.as_str(),
+                )
+            }
+
+            Tx::Command::Publish(..) => bail!(
+                "Command {i} publishes a package, which cannot be decompiled, \
+                 use --publish <PATH> with the package's source instead"
+            ),
+
+            Tx::Command::Upgrade(..) => bail!(
+                "Command {i} upgrades a package, which cannot be decompiled, \
+                 use --upgrade <PATH> with the package's source instead"
+            ),
+        };
+
+        commands.push(command);
+        if used.contains(&i) {
+            let name = fresh_name(&mut taken, name);
+            commands.push(ParsedPTBCommand::Assign(spanned(name.clone()), None));
+            names.insert(i, name);
+        }
+    }
+
+    Ok(commands)
+}
+
+/// Figure out the types that pure inputs should be decoded at, based on their first use.
+async fn pure_types(
+    reader: &ReadApi,
+    ptb: &ProgrammableTransaction,
+) -> anyhow::Result<BTreeMap<usize, PureType>> {
+    let mut types = BTreeMap::new();
+    let mut packages: BTreeMap<ObjectID, MovePackage> = BTreeMap::new();
+
+    let mut use_at = |arg: &Tx::Argument, ty: PureType| {
+        if let Tx::Argument::Input(i) = arg {
+            if matches!(ptb.inputs.get(*i as usize), Some(CallArg::Pure(_))) {
+                types.entry(*i as usize).or_insert(ty);
+            }
+        }
+    };
+
+    for command in &ptb.commands {
+        match command {
+            Tx::Command::TransferObjects(_, to) => use_at(to, PureType::Address),
+
+            Tx::Command::SplitCoins(_, amounts) => {
+                for amount in amounts {
+                    use_at(amount, PureType::U64);
+                }
+            }
+
+            Tx::Command::MakeMoveVec(Some(ty), elems) => {
+                if let Some(ty) = pure_type_of_tag(&ty.to_type_tag()?) {
+                    for elem in elems {
+                        use_at(elem, ty.clone());
+                    }
+                }
+            }
+
+            Tx::Command::MoveCall(call) => {
+                if !packages.contains_key(&call.package) {
+                    let package = resolve_package(reader, call.package, Span::eof_span())
+                        .await
+                        .map_err(|e| anyhow!("{}", e.message))?;
+                    packages.insert(call.package, package);
+                }
+
+                let module = packages[&call.package]
+                    .deserialize_module(
+                        &Identifier::new(call.module.as_str())?,
+                        &BinaryConfig::standard(),
+                    )
+                    .with_context(|| {
+                        format!("Failed to load module {}::{}", call.package, call.module)
+                    })?;
+
+                let type_args = call
+                    .type_arguments
+                    .iter()
+                    .map(|ty| ty.to_type_tag())
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+
+                let parameters = function_parameters(&module, &call.function)?;
+                ensure!(
+                    parameters.len() == call.arguments.len(),
+                    "Expected {} arguments for {}::{}::{}, found {}",
+                    parameters.len(),
+                    call.package,
+                    call.module,
+                    call.function,
+                    call.arguments.len(),
+                );
+
+                for (param, arg) in parameters.iter().zip(&call.arguments) {
+                    if let Some(ty) = pure_type_of_signature(&module, &type_args, param) {
+                        use_at(arg, ty);
+                    }
+                }
+            }
+
+            Tx::Command::MakeMoveVec(None, _)
+            | Tx::Command::MergeCoins(..)
+            | Tx::Command::Publish(..)
+            | Tx::Command::Upgrade(..) => {}
+        }
+    }
+
+    Ok(types)
+}
+
+/// The parameters of `function` in `module`, excluding the `TxContext`, which is supplied by the
+/// runtime.
+fn function_parameters(
+    module: &CompiledModule,
+    function: &str,
+) -> anyhow::Result<Vec<SignatureToken>> {
+    let fdef = module
+        .function_defs
+        .iter()
+        .find(|fdef| {
+            module
+                .identifier_at(module.function_handle_at(fdef.function).name)
+                .as_str()
+                == function
+        })
+        .ok_or_else(|| {
+            anyhow!(
+                "Could not find function '{function}' in module '{}'",
+                module.self_id()
+            )
+        })?;
+
+    let handle = module.function_handle_at(fdef.function);
+    Ok(module
+        .signature_at(handle.parameters)
+        .0
+        .iter()
+        .filter(|tok| matches!(TxContext::kind(module, tok), TxContextKind::None))
+        .cloned()
+        .collect())
+}
+
+fn pure_type_of_signature(
+    module: &CompiledModule,
+    type_args: &[TypeTag],
+    tok: &SignatureToken,
+) -> Option<PureType> {
+    use SignatureToken as S;
+    Some(match tok {
+        S::Bool => PureType::Bool,
+        S::U8 => PureType::U8,
+        S::U16 => PureType::U16,
+        S::U32 => PureType::U32,
+        S::U64 => PureType::U64,
+        S::U128 => PureType::U128,
+        S::U256 => PureType::U256,
+        S::Address => PureType::Address,
+        S::Vector(inner) => {
+            PureType::Vector(Box::new(pure_type_of_signature(module, type_args, inner)?))
+        }
+        S::Datatype(idx) => {
+            let resolved = resolve_datatype(module, *idx);
+            if resolved == RESOLVED_UTF8_STR || resolved == RESOLVED_ASCII_STR {
+                PureType::String
+            } else if resolved == RESOLVED_SUI_ID {
+                PureType::Address
+            } else {
+                return None;
+            }
+        }
+        S::DatatypeInstantiation(inst) => {
+            let (idx, targs) = &**inst;
+            if resolve_datatype(module, *idx) == RESOLVED_STD_OPTION && targs.len() == 1 {
+                PureType::Option(Box::new(pure_type_of_signature(
+                    module, type_args, &targs[0],
+                )?))
+            } else {
+                return None;
+            }
+        }
+        S::TypeParameter(idx) => pure_type_of_tag(type_args.get(*idx as usize)?)?,
+        S::Reference(inner) | S::MutableReference(inner) => {
+            pure_type_of_signature(module, type_args, inner)?
+        }
+        S::Signer => return None,
+    })
+}
+
+fn pure_type_of_tag(tag: &TypeTag) -> Option<PureType> {
+    Some(match tag {
+        TypeTag::Bool => PureType::Bool,
+        TypeTag::U8 => PureType::U8,
+        TypeTag::U16 => PureType::U16,
+        TypeTag::U32 => PureType::U32,
+        TypeTag::U64 => PureType::U64,
+        TypeTag::U128 => PureType::U128,
+        TypeTag::U256 => PureType::U256,
+        TypeTag::Address => PureType::Address,
+        TypeTag::Vector(inner) => PureType::Vector(Box::new(pure_type_of_tag(inner)?)),
+        TypeTag::Struct(stag) => {
+            let resolved = (
+                &stag.address,
+                stag.module.as_ident_str(),
+                stag.name.as_ident_str(),
+            );
+
+            if resolved == RESOLVED_UTF8_STR || resolved == RESOLVED_ASCII_STR {
+                PureType::String
+            } else if resolved == RESOLVED_SUI_ID {
+                PureType::Address
+            } else if resolved == RESOLVED_STD_OPTION && stag.type_params.len() == 1 {
+                PureType::Option(Box::new(pure_type_of_tag(&stag.type_params[0])?))
+            } else {
+                return None;
+            }
+        }
+        TypeTag::Signer => return None,
+    })
+}
+
+fn resolve_datatype(
+    module: &CompiledModule,
+    idx: DatatypeHandleIndex,
+) -> (&AccountAddress, &IdentStr, &IdentStr) {
+    let handle = module.datatype_handle_at(idx);
+    let module_handle = module.module_handle_at(handle.module);
+    (
+        module.address_identifier_at(module_handle.address),
+        module.identifier_at(module_handle.name),
+        module.identifier_at(handle.name),
+    )
+}
+
+/// The type of the objects in `elems`, for a `MakeMoveVec` command that doesn't specify its type.
+async fn object_type(
+    reader: &ReadApi,
+    ptb: &ProgrammableTransaction,
+    elems: &[Tx::Argument],
+) -> anyhow::Result<TypeTag> {
+    let id = elems.iter().find_map(|elem| match elem {
+        Tx::Argument::Input(i) => match ptb.inputs.get(*i as usize)? {
+            CallArg::Object(
+                ObjectArg::ImmOrOwnedObject((id, _, _))
+                | ObjectArg::SharedObject { id, .. }
+                | ObjectArg::Receiving((id, _, _)),
+            ) => Some(*id),
+            _ => None,
+        },
+        _ => None,
+    });
+
+    let Some(id) = id else {
+        bail!(
+            "Cannot infer the element type of a vector that is made from results of other commands"
+        );
+    };
+
+    let object = reader
+        .get_object_with_options(id, SuiObjectDataOptions::new().with_type())
+        .await?
+        .into_object()?;
+
+    Ok(TypeTag::Struct(Box::new(object.object_type()?.try_into()?)))
+}
+
+/// Decode `bytes` as a value of type `ty`, in the form of a PTB argument.
+fn decode_pure(ty: &PureType, mut bytes: &[u8]) -> anyhow::Result<Argument> {
+    let arg = decode_value(ty, &mut bytes)?;
+    ensure!(bytes.is_empty(), "{} trailing bytes", bytes.len());
+    Ok(arg)
+}
+
+fn decode_value(ty: &PureType, bytes: &mut &[u8]) -> anyhow::Result<Argument> {
+    fn take<'b, const N: usize>(bytes: &mut &'b [u8]) -> anyhow::Result<[u8; N]> {
+        ensure!(bytes.len() >= N, "Unexpected end of input");
+        let (head, tail) = bytes.split_at(N);
+        *bytes = tail;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn length(bytes: &mut &[u8]) -> anyhow::Result<usize> {
+        let mut len = 0usize;
+        for shift in (0..32).step_by(7) {
+            let [byte] = take::<1>(bytes)?;
+            len |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(len);
+            }
+        }
+        bail!("Invalid length prefix")
+    }
+
+    let num = |n: U256| Argument::InferredNum(n);
+    Ok(match ty {
+        PureType::Bool => match take::<1>(bytes)? {
+            [0] => Argument::Bool(false),
+            [1] => Argument::Bool(true),
+            [b] => bail!("Invalid bool {b}"),
+        },
+        PureType::U8 => num(u8::from_le_bytes(take(bytes)?).into()),
+        PureType::U16 => num(u16::from_le_bytes(take(bytes)?).into()),
+        PureType::U32 => num(u32::from_le_bytes(take(bytes)?).into()),
+        PureType::U64 => num(u64::from_le_bytes(take(bytes)?).into()),
+        PureType::U128 => num(u128::from_le_bytes(take(bytes)?).into()),
+        PureType::U256 => num(U256::from_le_bytes(&take(bytes)?)),
+        PureType::Address => address(AccountAddress::new(take(bytes)?)),
+
+        PureType::String => {
+            let len = length(bytes)?;
+            ensure!(bytes.len() >= len, "Unexpected end of input");
+            let (head, tail) = bytes.split_at(len);
+            *bytes = tail;
+
+            let s = std::str::from_utf8(head).context("Invalid UTF-8 string")?;
+            ensure!(
+                !(s.contains('"') && s.contains('\'')) && !s.ends_with('\\'),
+                "String {s:?} cannot be expressed as a PTB string literal"
+            );
+            Argument::String(s.to_owned())
+        }
+
+        PureType::Vector(inner) => {
+            let len = length(bytes)?;
+            let mut elems = vec![];
+            for _ in 0..len {
+                elems.push(spanned(decode_value(inner, bytes)?));
+            }
+            Argument::Vector(elems)
+        }
+
+        PureType::Option(inner) => match length(bytes)? {
+            0 => Argument::Option(spanned(None)),
+            1 => Argument::Option(spanned(Some(Box::new(decode_value(inner, bytes)?)))),
+            n => bail!("Invalid option with {n} elements"),
+        },
+    })
+}
+
+/// Pick a name for a result based on `base`, that has not been used before, and is not a keyword.
+fn fresh_name(taken: &mut BTreeSet<String>, base: &str) -> String {
+    let mut name = base.to_owned();
+    let mut suffix = 1;
+    while ast::is_keyword(&name) || taken.contains(&name) {
+        suffix += 1;
+        name = format!("{base}_{suffix}");
+    }
+
+    taken.insert(name.clone());
+    name
+}
+
+fn result_name(names: &BTreeMap<usize, String>, i: usize, j: u16) -> anyhow::Result<String> {
+    names.get(&(j as usize)).cloned().ok_or_else(|| {
+        anyhow!("Command {i} refers to the result of command {j}, which is not before it")
+    })
+}
+
+fn command_arguments(command: &Tx::Command) -> Vec<&Tx::Argument> {
+    match command {
+        Tx::Command::MoveCall(call) => call.arguments.iter().collect(),
+        Tx::Command::TransferObjects(objs, to) => objs.iter().chain([to]).collect(),
+        Tx::Command::SplitCoins(coin, amounts) => [coin].into_iter().chain(amounts).collect(),
+        Tx::Command::MergeCoins(coin, coins) => [coin].into_iter().chain(coins).collect(),
+        Tx::Command::MakeMoveVec(_, elems) => elems.iter().collect(),
+        Tx::Command::Publish(..) => vec![],
+        Tx::Command::Upgrade(_, _, _, ticket) => vec![ticket],
+    }
+}
+
+fn parsed_type(tag: &TypeTag) -> ParsedType {
+    match tag {
+        TypeTag::Bool => ParsedType::Bool,
+        TypeTag::U8 => ParsedType::U8,
+        TypeTag::U16 => ParsedType::U16,
+        TypeTag::U32 => ParsedType::U32,
+        TypeTag::U64 => ParsedType::U64,
+        TypeTag::U128 => ParsedType::U128,
+        TypeTag::U256 => ParsedType::U256,
+        TypeTag::Address => ParsedType::Address,
+        TypeTag::Signer => ParsedType::Signer,
+        TypeTag::Vector(inner) => ParsedType::Vector(Box::new(parsed_type(inner))),
+        TypeTag::Struct(stag) => ParsedType::Struct(ParsedStructType {
+            fq_name: ParsedFqName {
+                module: ParsedModuleId {
+                    address: ParsedAddress::Numerical(numerical(stag.address)),
+                    name: stag.module.to_string(),
+                },
+                name: stag.name.to_string(),
+            },
+            type_args: stag.type_params.iter().map(parsed_type).collect(),
+        }),
+    }
+}
+
+fn numerical(address: AccountAddress) -> NumericalAddress {
+    NumericalAddress::new(address.into_bytes(), NumberFormat::Hex)
+}
+
+fn address(address: AccountAddress) -> Argument {
+    Argument::Address(numerical(address))
+}
+
+fn spanned<T>(value: T) -> Spanned<T> {
+    Span::eof_span().wrap(value)
+}
+
+/// Render `arg` as it would be written on the command line, before shell quoting.
+fn render_argument(arg: &Argument) -> String {
+    match arg {
+        Argument::String(s) if s.contains('"') => format!("'{s}'"),
+        Argument::String(s) => format!("\"{s}\""),
+        Argument::Vector(elems) => format!("vector{}", render_list(elems)),
+        Argument::Option(sp!(_, Some(inner))) => format!("some({})", render_argument(inner)),
+        arg => arg.to_string(),
+    }
+}
+
+fn render_list(args: &[Spanned<Argument>]) -> String {
+    let args: Vec<_> = args.iter().map(|sp!(_, a)| render_argument(a)).collect();
+    format!("[{}]", args.join(", "))
+}
+
+/// The shell tokens for `command`, as they would be passed to `sui client ptb`.
+pub fn command_tokens(command: &ParsedPTBCommand) -> Vec<String> {
+    use ParsedPTBCommand as C;
+
+    let flag = |name: &str| format!("--{name}");
+    match command {
+        C::TransferObjects(sp!(_, objs), sp!(_, to)) => vec![
+            flag(ast::TRANSFER_OBJECTS),
+            render_list(objs),
+            render_argument(to),
+        ],
+
+        C::SplitCoins(sp!(_, coin), sp!(_, amounts)) => vec![
+            flag(ast::SPLIT_COINS),
+            render_argument(coin),
+            render_list(amounts),
+        ],
+
+        C::MergeCoins(sp!(_, coin), sp!(_, coins)) => vec![
+            flag(ast::MERGE_COINS),
+            render_argument(coin),
+            render_list(coins),
+        ],
+
+        C::MakeMoveVec(sp!(_, ty), sp!(_, elems)) => vec![
+            flag(ast::MAKE_MOVE_VEC),
+            format!("<{}>", TyDisplay(ty)),
+            render_list(elems),
+        ],
+
+        C::MoveCall(sp!(_, access), type_args, args) => {
+            let mut tokens = vec![
+                flag(ast::MOVE_CALL),
+                format!(
+                    "{}::{}::{}",
+                    access.address.value, access.module_name.value, access.function_name.value
+                ),
+            ];
+
+            if let Some(sp!(_, type_args)) = type_args {
+                let type_args: Vec<_> =
+                    type_args.iter().map(|t| TyDisplay(t).to_string()).collect();
+                tokens.push(format!("<{}>", type_args.join(", ")));
+            }
+
+            tokens.extend(args.iter().map(|sp!(_, a)| render_argument(a)));
+            tokens
+        }
+
+        C::Assign(sp!(_, name), value) => {
+            let mut tokens = vec![flag(ast::ASSIGN), name.clone()];
+            tokens.extend(value.iter().map(|sp!(_, v)| render_argument(v)));
+            tokens
+        }
+
+        C::Publish(sp!(_, path)) => vec![flag(ast::PUBLISH), path.clone()],
+        C::Upgrade(sp!(_, path), sp!(_, cap)) => {
+            vec![flag(ast::UPGRADE), path.clone(), render_argument(cap)]
+        }
+
+        C::WarnShadows => vec![flag(ast::WARN_SHADOWS)],
+        C::Preview => vec![flag(ast::PREVIEW)],
+    }
+}
+
+impl fmt::Display for DecompiledPTB {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines: Vec<Vec<String>> = self.commands.iter().map(command_tokens).collect();
+
+        if let Some(sender) = self.sender {
+            lines.push(vec![format!("--{SENDER}"), format!("@{sender}")]);
+        }
+
+        if let Some(sponsor) = self.gas_sponsor {
+            lines.push(vec![format!("--{GAS_SPONSOR}"), format!("@{sponsor}")]);
+        }
+
+        if let Some(budget) = self.gas_budget {
+            lines.push(vec![format!("--{GAS_BUDGET}"), budget.to_string()]);
+        }
+
+        write!(f, "sui client ptb")?;
+        for tokens in lines {
+            write!(f, " \\\n ")?;
+            for token in tokens {
+                match shlex::try_quote(&token) {
+                    Ok(quoted) => write!(f, " {quoted}")?,
+                    Err(_) => write!(f, " {token}")?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(ty: PureType, value: impl serde::Serialize) -> String {
+        render_argument(&decode_pure(&ty, &bcs::to_bytes(&value).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn decode_pure_values() {
+        assert_eq!(decode(PureType::Bool, true), "true");
+        assert_eq!(decode(PureType::U8, 42u8), "42");
+        assert_eq!(decode(PureType::U64, 1_000_000u64), "1000000");
+        assert_eq!(decode(PureType::U128, u128::MAX), u128::MAX.to_string());
+        assert_eq!(decode(PureType::Address, AccountAddress::TWO), "@0x2");
+        assert_eq!(decode(PureType::String, "hello world"), "\"hello world\"");
+        assert_eq!(decode(PureType::String, "say \"hi\""), "'say \"hi\"'");
+        assert_eq!(
+            decode(PureType::Vector(Box::new(PureType::U16)), vec![1u16, 2, 3]),
+            "vector[1, 2, 3]"
+        );
+        assert_eq!(
+            decode(PureType::Option(Box::new(PureType::U32)), Some(7u32)),
+            "some(7)"
+        );
+        assert_eq!(
+            decode(PureType::Option(Box::new(PureType::U32)), None::<u32>),
+            "none"
+        );
+    }
+
+    #[test]
+    fn decode_pure_errors() {
+        // Trailing bytes
+        assert!(decode_pure(&PureType::U8, &[1, 2]).is_err());
+        // Not enough bytes
+        assert!(decode_pure(&PureType::U64, &[1, 2]).is_err());
+        // Not representable as a string literal
+        let bytes = bcs::to_bytes("'\"").unwrap();
+        assert!(decode_pure(&PureType::String, &bytes).is_err());
+    }
+
+    #[test]
+    fn fresh_names() {
+        let mut taken = BTreeSet::new();
+        assert_eq!(fresh_name(&mut taken, "coins"), "coins");
+        assert_eq!(fresh_name(&mut taken, "coins"), "coins_2");
+        assert_eq!(fresh_name(&mut taken, "coins"), "coins_3");
+        assert_eq!(fresh_name(&mut taken, "none"), "none_2");
+    }
+
+    #[test]
+    fn render_commands() {
+        let split = ParsedPTBCommand::SplitCoins(
+            spanned(Argument::Gas),
+            spanned(vec![
+                spanned(Argument::InferredNum(1000u64.into())),
+                spanned(Argument::InferredNum(2000u64.into())),
+            ]),
+        );
+
+        let call = ParsedPTBCommand::MoveCall(
+            spanned(ModuleAccess {
+                address: spanned(ParsedAddress::Numerical(numerical(AccountAddress::TWO))),
+                module_name: spanned(Identifier::new("coin").unwrap()),
+                function_name: spanned(Identifier::new("join").unwrap()),
+            }),
+            Some(spanned(vec![parsed_type(&TypeTag::U64)])),
+            vec![
+                spanned(Argument::Identifier("coins".to_string())),
+                spanned(Argument::String("hello world".to_string())),
+            ],
+        );
+
+        let ptb = DecompiledPTB {
+            commands: vec![
+                split,
+                ParsedPTBCommand::Assign(spanned("coins".to_string()), None),
+                call,
+            ],
+            sender: None,
+            gas_sponsor: None,
+            gas_budget: Some(5000000),
+        };
+
+        assert_eq!(
+            ptb.to_string(),
+            "sui client ptb \\\n  \
+             --split-coins gas '[1000, 2000]' \\\n  \
+             --assign coins \\\n  \
+             --move-call 0x2::coin::join '<u64>' coins '\"hello world\"' \\\n  \
+             --gas-budget 5000000"
+        );
+    }
+}
@@ -8,7 +8,9 @@ use crate::{
     client_ptb::{
         ast::{ParsedProgram, Program},
         builder::{resolve_package, PTBBuilder},
+        decompile::{decompile_from_args, FROM_BYTES, FROM_DIGEST},
         error::{build_error_reports, PTBError, Span},
+        script::expand_scripts,
         token::{Lexeme, Token},
     },
     displays::Pretty,
@@ -81,11 +83,10 @@ impl PTB {
             ptb_description().print_help().unwrap();
             return Ok(());
         }
-        let source_string = to_source_string(self.args.clone());
 
         // Tokenize once to detect help flags
         let tokens = self.args.iter().map(|s| s.as_str());
-        for sp!(_, lexeme) in Lexer::new(tokens.clone()).into_iter().flatten() {
+        for sp!(_, lexeme) in Lexer::new(tokens).into_iter().flatten() {
             match lexeme {
                 Lexeme(Token::Command, "help") => return Ok(ptb_description().print_long_help()?),
                 Lexeme(Token::Flag, "h") => return Ok(ptb_description().print_help()?),
@@ -94,6 +95,30 @@ impl PTB {
             }
         }
 
+        // Print an existing transaction as PTB commands, instead of building a new one
+        if self
+            .args
+            .iter()
+            .any(|a| a == FROM_DIGEST || a == FROM_BYTES)
+        {
+            let client = context.get_client().await?;
+            let mut decompiled = decompile_from_args(client.read_api(), &self.args).await?;
+
+            // The active address is the sender by default, so it only needs to be mentioned if
+            // the transaction was sent by someone else.
+            if decompiled.sender == context.active_address().ok() {
+                decompiled.sender = None;
+            }
+
+            println!("{decompiled}");
+            return Ok(());
+        }
+
+        // Splice in any script files, so that the rest of the pipeline sees one long command line
+        let args = expand_scripts(self.args)?;
+        let source_string = to_source_string(args.clone());
+        let tokens = args.iter().map(|s| s.as_str());
+
         // Tokenize and parse to get the program
         let (program, program_metadata) = match ProgramParser::new(tokens)
             .map_err(|e| vec![e])
@@ -357,6 +382,40 @@ pub fn ptb_description() -> clap::Command {
             \n --assign new_coins # bound new_coins to the result of previous transaction"
         )
         .value_names(["NAME", "VALUE"]))
+        .arg(arg!(
+            --"file" <PATH>
+            "Read commands from a PTB script file, in place of this flag."
+        )
+        .long_help(
+            "Read commands from a PTB script file, in place of this flag. Script files contain \
+            the same commands as the command line, quoted the same way, and '#' starts a comment \
+            that runs to the end of the line. Scripts can include other scripts with \
+            '--include <PATH>' (relative to the including script), and refer to parameters as \
+            '${NAME}', or '${NAME:-DEFAULT}', which are supplied with '--arg'.\
+            \n\nExamples:\
+            \n --file release.ptb --arg amount=1000 --gas-budget 50000000\
+            \n\nrelease.ptb:\
+            \n # Split off the amount to send\
+            \n --split-coins gas [${amount}]\
+            \n --assign coins\
+            \n --include transfer.ptb"
+        )
+        .value_hint(ValueHint::FilePath))
+        .arg(arg!(
+            --"arg" <BINDING>
+            "Supply the value for a parameter used by a PTB script file, as NAME=VALUE."
+        ))
+        .arg(arg!(
+            --"from-digest" <DIGEST>
+            "Instead of building a PTB, print the commands that would build the programmable \
+            transaction with this digest, to be tweaked and re-run."
+        ))
+        .arg(arg!(
+            --"from-bytes" <TX_BYTES>
+            "Instead of building a PTB, print the commands that would build this programmable \
+            transaction, given as Base64 encoded BCS bytes of its TransactionData (or \
+            TransactionKind)."
+        ))
         .arg(arg!(
             --"dry-run"
             "Perform a dry run of the PTB instead of executing it."
@@ -0,0 +1,298 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for reading PTBs from script files.
+//!
+//! A script file contains the same commands that would otherwise be passed to `sui client ptb` on
+//! the command line, split into shell tokens the same way a shell would (so quoting works the same
+//! way), and with `#` starting a comment that runs to the end of the line. Scripts are spliced into
+//! the command line wherever `--file <PATH>` appears, and can themselves splice in other scripts
+//! with `--include <PATH>` (relative paths are resolved relative to the including script).
+//!
+//! Scripts can refer to named parameters as `${name}`, or `${name:-default}` to fall back to a
+//! default value, and the values for these parameters are supplied on the command line with
+//! `--arg name=value`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context};
+
+pub const FILE: &str = "--file";
+pub const INCLUDE: &str = "--include";
+pub const ARG: &str = "--arg";
+
+/// Expand the script files referenced by `args` with `--file`, substituting the parameters
+/// supplied with `--arg`. Returns the resulting list of shell tokens, ready to be parsed.
+pub fn expand_scripts(args: Vec<String>) -> anyhow::Result<Vec<String>> {
+    let mut params = BTreeMap::new();
+    let mut rest = vec![];
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg != ARG {
+            rest.push(arg);
+            continue;
+        }
+
+        let binding = args
+            .next()
+            .ok_or_else(|| anyhow!("Expected NAME=VALUE after {ARG}"))?;
+
+        let Some((name, value)) = binding.split_once('=') else {
+            bail!("Expected NAME=VALUE after {ARG}, got '{binding}'");
+        };
+
+        if !is_param_name(name) {
+            bail!("Invalid parameter name '{name}'");
+        }
+
+        if params.insert(name.to_owned(), value.to_owned()).is_some() {
+            bail!("Parameter '{name}' is set more than once");
+        }
+    }
+
+    let mut expander = Expander {
+        params,
+        used: BTreeSet::new(),
+        stack: vec![],
+    };
+
+    let mut tokens = vec![];
+    let mut rest = rest.into_iter();
+    while let Some(token) = rest.next() {
+        if token != FILE {
+            tokens.push(token);
+            continue;
+        }
+
+        let path = rest
+            .next()
+            .ok_or_else(|| anyhow!("Expected a path after {FILE}"))?;
+        expander.expand(Path::new(&path), &mut tokens)?;
+    }
+
+    if let Some(unused) = expander.params.keys().find(|p| !expander.used.contains(*p)) {
+        bail!("Parameter '{unused}' is not used by any script");
+    }
+
+    Ok(tokens)
+}
+
+struct Expander {
+    /// Values for parameters, from the command line.
+    params: BTreeMap<String, String>,
+
+    /// Parameters that have been referred to by some script.
+    used: BTreeSet<String>,
+
+    /// The scripts currently being expanded, to detect cyclic includes.
+    stack: Vec<PathBuf>,
+}
+
+impl Expander {
+    /// Read the script at `path`, and append its shell tokens to `tokens`, after substituting
+    /// parameters and expanding includes.
+    fn expand(&mut self, path: &Path, tokens: &mut Vec<String>) -> anyhow::Result<()> {
+        let canonical = path
+            .canonicalize()
+            .with_context(|| format!("Cannot find PTB script {}", path.display()))?;
+
+        if self.stack.contains(&canonical) {
+            bail!("PTB script {} includes itself", path.display());
+        }
+
+        let contents = std::fs::read_to_string(&canonical)
+            .with_context(|| format!("Cannot read PTB script {}", path.display()))?;
+
+        let Some(split) = shlex::split(&contents) else {
+            bail!("Unbalanced quotes in PTB script {}", path.display());
+        };
+
+        self.stack.push(canonical);
+        let mut split = split.into_iter();
+        while let Some(token) = split.next() {
+            if token != INCLUDE {
+                tokens.push(self.substitute(&token, path)?);
+                continue;
+            }
+
+            let include = split
+                .next()
+                .ok_or_else(|| anyhow!("Expected a path after {INCLUDE} in {}", path.display()))?;
+
+            let include = PathBuf::from(self.substitute(&include, path)?);
+            let include = match path.parent() {
+                Some(parent) if include.is_relative() => parent.join(include),
+                _ => include,
+            };
+
+            self.expand(&include, tokens)?;
+        }
+
+        self.stack.pop();
+        Ok(())
+    }
+
+    /// Replace references to parameters in `token` (which came from the script at `path`) with
+    /// their values.
+    fn substitute(&mut self, token: &str, path: &Path) -> anyhow::Result<String> {
+        let mut substituted = String::new();
+        let mut rest = token;
+
+        while let Some(start) = rest.find("${") {
+            substituted.push_str(&rest[..start]);
+            let reference = &rest[start + 2..];
+
+            let Some(end) = reference.find('}') else {
+                bail!("Unterminated parameter reference in {}", path.display());
+            };
+
+            let (name, default) = match reference[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&reference[..end], None),
+            };
+
+            if !is_param_name(name) {
+                bail!("Invalid parameter name '{name}' in {}", path.display());
+            }
+
+            self.used.insert(name.to_owned());
+            match (self.params.get(name), default) {
+                (Some(value), _) => substituted.push_str(value),
+                (None, Some(default)) => substituted.push_str(default),
+                (None, None) => bail!(
+                    "Missing value for parameter '{name}' used in {}, \
+                     supply one with {ARG} {name}=<VALUE>",
+                    path.display(),
+                ),
+            }
+
+            rest = &reference[end + 1..];
+        }
+
+        substituted.push_str(rest);
+        Ok(substituted)
+    }
+}
+
+fn is_param_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn no_scripts() {
+        let tokens = args(&["--split-coins", "gas", "[1000]", "--assign", "coins"]);
+        assert_eq!(expand_scripts(tokens.clone()).unwrap(), tokens);
+    }
+
+    #[test]
+    fn script_with_params_and_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("lib")).unwrap();
+
+        std::fs::write(
+            dir.path().join("lib/transfer.ptb"),
+            "# Send the coins\n--transfer-objects [coins.0] @${recipient}\n",
+        )
+        .unwrap();
+
+        let main = dir.path().join("main.ptb");
+        std::fs::write(
+            &main,
+            r#"
+            # Split off some coins
+            --split-coins gas [${amount}, ${fee:-10}]
+            --assign coins
+            --include lib/transfer.ptb
+            --move-call std::string::utf8 "'hello world'"
+            "#,
+        )
+        .unwrap();
+
+        let tokens = expand_scripts(args(&[
+            "--file",
+            main.to_str().unwrap(),
+            "--arg",
+            "amount=1000",
+            "--arg",
+            "recipient=0x42",
+            "--gas-budget",
+            "100",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            tokens,
+            args(&[
+                "--split-coins",
+                "gas",
+                "[1000,",
+                "10]",
+                "--assign",
+                "coins",
+                "--transfer-objects",
+                "[coins.0]",
+                "@0x42",
+                "--move-call",
+                "std::string::utf8",
+                "'hello world'",
+                "--gas-budget",
+                "100",
+            ])
+        );
+    }
+
+    #[test]
+    fn missing_param() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("main.ptb");
+        std::fs::write(&main, "--split-coins gas [${amount}]").unwrap();
+
+        let err = expand_scripts(args(&["--file", main.to_str().unwrap()])).unwrap_err();
+        assert!(err.to_string().contains("'amount'"), "{err}");
+    }
+
+    #[test]
+    fn unused_param() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("main.ptb");
+        std::fs::write(&main, "--split-coins gas [${amount}]").unwrap();
+
+        let err = expand_scripts(args(&[
+            "--file",
+            main.to_str().unwrap(),
+            "--arg",
+            "amount=1",
+            "--arg",
+            "amonut=2",
+        ]))
+        .unwrap_err();
+        assert!(err.to_string().contains("'amonut'"), "{err}");
+    }
+
+    #[test]
+    fn cyclic_include() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.ptb"), "--include b.ptb").unwrap();
+        std::fs::write(dir.path().join("b.ptb"), "--include a.ptb").unwrap();
+
+        let main = dir.path().join("a.ptb");
+        let err = expand_scripts(args(&["--file", main.to_str().unwrap()])).unwrap_err();
+        assert!(err.to_string().contains("includes itself"), "{err}");
+    }
+}
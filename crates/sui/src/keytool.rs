@@ -23,6 +23,7 @@ use fastcrypto_zkp::bn254::zk_login::{fetch_jwks, OIDCProvider, ZkLoginInputs};
 use fastcrypto_zkp::bn254::zk_login::{JwkId, JWK};
 use fastcrypto_zkp::bn254::zk_login_api::ZkLoginEnv;
 use im::hashmap::HashMap as ImHashMap;
+use inquire::{Password, PasswordDisplayMode};
 use json_to_table::{json_to_table, Orientation};
 use num_bigint::BigUint;
 use rand::rngs::StdRng;
@@ -35,6 +36,7 @@ use std::fmt::{Debug, Display, Formatter};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use sui_keys::encrypted_keystore::EncryptedFileBasedKeystore;
 use sui_keys::key_derive::generate_new_key;
 use sui_keys::key_identity::KeyIdentity;
 use sui_keys::keypair_file::{
@@ -64,6 +66,14 @@ use tracing::info;
 #[path = "unit_tests/keytool_tests.rs"]
 mod keytool_tests;
 
+/// Environment variable that the passphrase for an encrypted keystore is read from, if it is set,
+/// instead of prompting for it.
+pub const SUI_KEYSTORE_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_PASSPHRASE";
+
+/// Environment variable that a new passphrase for an encrypted keystore is read from (when
+/// encrypting a keystore or changing its passphrase), if it is set, instead of prompting for it.
+pub const SUI_KEYSTORE_NEW_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_NEW_PASSPHRASE";
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 #[clap(rename_all = "kebab-case")]
@@ -76,6 +86,10 @@ pub enum KeyToolCommand {
         /// The alias must start with a letter and can contain only letters, digits, dots, hyphens (-), or underscores (_).
         new_alias: Option<String>,
     },
+    /// Change the passphrase of an encrypted keystore. The current passphrase and the new passphrase
+    /// are read from the SUI_KEYSTORE_PASSPHRASE and SUI_KEYSTORE_NEW_PASSPHRASE environment
+    /// variables if they are set, and are prompted for otherwise.
+    ChangePassphrase,
     /// Convert private key in Hex or Base64 to new format (Bech32
     /// encoded 33 byte flag || private key starting with "suiprivkey").
     /// Hex private key format import and export are both deprecated in
@@ -102,6 +116,11 @@ pub enum KeyToolCommand {
         #[clap(long, default_value = "0")]
         cur_epoch: u64,
     },
+    /// Encrypt a plaintext keystore in place, under a passphrase. The passphrase is read from the
+    /// SUI_KEYSTORE_NEW_PASSPHRASE environment variable if it is set, and is prompted for
+    /// otherwise. The client config is updated to refer to the encrypted keystore, which must be
+    /// unlocked with its passphrase (read from SUI_KEYSTORE_PASSPHRASE, or prompted for) to sign.
+    EncryptKeystore,
     /// Generate a new keypair with key scheme flag {ed25519 | secp256k1 | secp256r1}
     /// with optional derivation path, default to m/44'/784'/0'/0'/0' for ed25519 or
     /// m/54'/784'/0'/0/0 for secp256k1 or m/74'/784'/0'/0/0 for secp256r1. Word
@@ -304,6 +323,13 @@ pub struct AliasUpdate {
     new_alias: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedKeystoreUpdate {
+    keystore_path: PathBuf,
+    num_keys: usize,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DecodedMultiSig {
@@ -458,9 +484,11 @@ pub struct ZkLoginInsecureSignPersonalMessage {
 #[serde(untagged)]
 pub enum CommandOutput {
     Alias(AliasUpdate),
+    ChangePassphrase(EncryptedKeystoreUpdate),
     Convert(ConvertOutput),
     DecodeMultiSig(DecodedMultiSigOutput),
     DecodeOrVerifyTx(DecodeOrVerifyTxOutput),
+    EncryptKeystore(EncryptedKeystoreUpdate),
     Error(String),
     Generate(Key),
     Import(Key),
@@ -492,11 +520,67 @@ impl KeyToolCommand {
                     new_alias,
                 })
             }
+            KeyToolCommand::ChangePassphrase => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!(
+                        "Keystore is not encrypted, use `sui keytool encrypt-keystore` to encrypt it"
+                    ));
+                };
+
+                let old_passphrase = read_passphrase(
+                    SUI_KEYSTORE_PASSPHRASE_ENV,
+                    "Current keystore passphrase:",
+                    false,
+                )?;
+
+                let new_passphrase = read_passphrase(
+                    SUI_KEYSTORE_NEW_PASSPHRASE_ENV,
+                    "New keystore passphrase:",
+                    true,
+                )?;
+
+                encrypted.change_passphrase(&old_passphrase, &new_passphrase)?;
+                CommandOutput::ChangePassphrase(EncryptedKeystoreUpdate {
+                    keystore_path: encrypted.path().to_path_buf(),
+                    num_keys: encrypted.addresses().len(),
+                })
+            }
+
             KeyToolCommand::Convert { value } => {
                 let result = convert_private_key_to_bech32(value)?;
                 CommandOutput::Convert(result)
             }
 
+            KeyToolCommand::EncryptKeystore => {
+                let Keystore::File(file) = keystore else {
+                    return Err(anyhow!("Only plaintext file keystores can be encrypted"));
+                };
+
+                let keystore_path = file
+                    .path()
+                    .ok_or_else(|| anyhow!("Keystore does not have a path"))?
+                    .to_path_buf();
+
+                let passphrase = read_passphrase(
+                    SUI_KEYSTORE_NEW_PASSPHRASE_ENV,
+                    "New keystore passphrase:",
+                    true,
+                )?;
+
+                let encrypted = EncryptedFileBasedKeystore::from_file_keystore(
+                    file,
+                    &keystore_path,
+                    &passphrase,
+                )?;
+                let num_keys = encrypted.addresses().len();
+                *keystore = Keystore::from(encrypted);
+
+                CommandOutput::EncryptKeystore(EncryptedKeystoreUpdate {
+                    keystore_path,
+                    num_keys,
+                })
+            }
+
             KeyToolCommand::DecodeMultiSig {
                 multisig,
                 tx_bytes,
@@ -1402,6 +1486,50 @@ impl Debug for CommandOutput {
     }
 }
 
+/// If `keystore` is an encrypted keystore that is locked, unlock it for the rest of the session,
+/// using the passphrase from the SUI_KEYSTORE_PASSPHRASE environment variable, or by prompting for
+/// it.
+pub fn unlock_keystore(keystore: &mut Keystore) -> Result<(), anyhow::Error> {
+    let Keystore::Encrypted(encrypted) = keystore else {
+        return Ok(());
+    };
+
+    if !encrypted.is_locked() {
+        return Ok(());
+    }
+
+    let passphrase = read_passphrase(
+        SUI_KEYSTORE_PASSPHRASE_ENV,
+        &format!("Passphrase for keystore {}:", encrypted.path().display()),
+        false,
+    )?;
+
+    encrypted.unlock(&passphrase)
+}
+
+/// Read a passphrase from the environment variable `env`, or prompt for it (asking for it twice,
+/// if `confirm` is set) if the variable is not set.
+fn read_passphrase(env: &str, prompt: &str, confirm: bool) -> Result<String, anyhow::Error> {
+    if let Ok(passphrase) = std::env::var(env) {
+        return Ok(passphrase);
+    }
+
+    let mut password = Password::new(prompt).with_display_mode(PasswordDisplayMode::Hidden);
+    if !confirm {
+        password = password.without_confirmation();
+    }
+
+    let passphrase = password
+        .prompt()
+        .map_err(|e| anyhow!("Failed to read passphrase: {e}"))?;
+
+    if passphrase.is_empty() {
+        return Err(anyhow!("Passphrase cannot be empty"));
+    }
+
+    Ok(passphrase)
+}
+
 /// Converts legacy formatted private key to 33 bytes bech32 encoded private key or vice versa.
 /// It can handle:
 /// 1) Hex encoded 32 byte private key (assumes scheme is Ed25519), this is the legacy wallet format
@@ -6,7 +6,7 @@ use crate::client_commands::{
 };
 use crate::fire_drill::{run_fire_drill, FireDrill};
 use crate::genesis_ceremony::{run, Ceremony};
-use crate::keytool::KeyToolCommand;
+use crate::keytool::{unlock_keystore, KeyToolCommand};
 use crate::validator_commands::SuiValidatorCommand;
 use anyhow::{anyhow, bail, ensure, Context};
 use clap::*;
@@ -55,6 +55,7 @@ use sui_graphql_rpc::{
 
 use move_core_types::account_address::AccountAddress;
 use serde_json::json;
+use sui_keys::encrypted_keystore::{write_atomic, EncryptedFileBasedKeystore};
 use sui_keys::keypair_file::read_key;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_move::manage_package::resolve_lock_file_path;
@@ -448,8 +449,26 @@ impl SuiCommand {
             } => {
                 let keystore_path =
                     keystore_path.unwrap_or(sui_config_dir()?.join(SUI_KEYSTORE_FILENAME));
-                let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+                let encrypted = EncryptedFileBasedKeystore::is_encrypted_keystore(&keystore_path);
+                let mut keystore = if encrypted {
+                    Keystore::from(EncryptedFileBasedKeystore::new(&keystore_path)?)
+                } else {
+                    Keystore::from(FileBasedKeystore::new(&keystore_path)?)
+                };
+
+                // Changing the passphrase asks for the current passphrase itself.
+                if !matches!(cmd, KeyToolCommand::ChangePassphrase) {
+                    warn_if_locked(unlock_keystore(&mut keystore));
+                }
+
                 cmd.execute(&mut keystore).await?.print(!json);
+
+                if !encrypted && matches!(keystore, Keystore::Encrypted(_)) {
+                    use_encrypted_keystore_in_config(
+                        &sui_config_dir()?.join(SUI_CLIENT_CONFIG),
+                        &keystore_path,
+                    )?;
+                }
                 Ok(())
             }
             SuiCommand::Client {
@@ -464,6 +483,7 @@ impl SuiCommand {
                 prompt_if_no_config(&config_path, accept_defaults).await?;
                 if let Some(cmd) = cmd {
                     let mut context = WalletContext::new(&config_path)?;
+                    warn_if_locked(unlock_keystore(&mut context.config.keystore));
                     if let Some(env_override) = config.env {
                         context = context.with_env_override(env_override);
                     }
@@ -491,6 +511,7 @@ impl SuiCommand {
                 prompt_if_no_config(&config_path, accept_defaults).await?;
                 let mut context = WalletContext::new(&config_path)?;
                 if let Some(cmd) = cmd {
+                    warn_if_locked(unlock_keystore(&mut context.config.keystore));
                     if let Ok(client) = context.get_client().await {
                         if let Err(e) = client.check_api_version() {
                             eprintln!("{}", format!("[warning] {e}").yellow().bold());
@@ -1335,6 +1356,53 @@ async fn genesis(
     Ok(())
 }
 
+/// Unlocking an encrypted keystore is best-effort: commands that don't need to sign can still run
+/// with the keystore locked, and commands that do will fail with an error saying it is locked.
+fn warn_if_locked(unlocked: Result<(), anyhow::Error>) {
+    if let Err(e) = unlocked {
+        eprintln!(
+            "{}",
+            format!("[warning] Keystore is locked: {e}").yellow().bold()
+        );
+    }
+}
+
+/// Point the client config at `config_path` to the encrypted keystore at `keystore_path`, if it
+/// currently refers to it as a plaintext keystore. The config is edited as YAML, because it can't
+/// be loaded as a `SuiClientConfig` while it refers to an encrypted keystore as a plaintext one.
+fn use_encrypted_keystore_in_config(
+    config_path: &Path,
+    keystore_path: &Path,
+) -> Result<(), anyhow::Error> {
+    if !config_path.exists() {
+        return Ok(());
+    }
+
+    let contents = fs::read_to_string(config_path)?;
+    let mut config: serde_yaml::Value = serde_yaml::from_str(&contents)?;
+    let Some(keystore) = config
+        .get_mut("keystore")
+        .and_then(|keystore| keystore.as_mapping_mut())
+    else {
+        return Ok(());
+    };
+
+    let file = serde_yaml::Value::from("File");
+    let refers_to_keystore = keystore
+        .get(&file)
+        .and_then(|path| path.as_str())
+        .is_some_and(|path| Path::new(path) == keystore_path);
+
+    if refers_to_keystore {
+        if let Some(path) = keystore.remove(&file) {
+            keystore.insert("Encrypted".into(), path);
+        }
+        write_atomic(config_path, serde_yaml::to_string(&config)?.as_bytes())?;
+    }
+
+    Ok(())
+}
+
 async fn prompt_if_no_config(
     wallet_conf_path: &Path,
     accept_defaults: bool,
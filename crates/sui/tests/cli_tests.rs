@@ -630,6 +630,74 @@ async fn test_ptb_publish() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_ptb_decompile_round_trip() -> Result<(), anyhow::Error> {
+    use sui::client_ptb::decompile::{decompile_from_args, FROM_DIGEST};
+    use sui_types::transaction::TransactionData;
+
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+
+    let ptb_string = format!(
+        r#"
+         --split-coins gas "[1000, 2000, 3000]"
+         --assign coins
+         --merge-coins coins.0 "[coins.1]"
+         --make-move-vec "<u64>" "[1, 2, 3]"
+         --move-call std::option::some "<u64>" 42
+         --transfer-objects "[coins.0, coins.2]" @{recipient}
+        "#
+    );
+
+    let (program, _) = PTB::parse_ptb_commands(shlex::split(&ptb_string).unwrap())
+        .map_err(|e| anyhow::anyhow!("Failed to parse PTB: {e:?}"))?;
+    let (built, _) = PTB::build_ptb(program, BTreeMap::new(), client.clone()).await;
+    let original = built.map_err(|e| anyhow::anyhow!("Failed to build PTB: {e:?}"))?;
+
+    let gas = context
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap();
+    let budget = rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC;
+    let data = TransactionData::new_programmable(address, vec![gas], original.clone(), budget, rgp);
+    let response = context
+        .execute_transaction_must_succeed(context.sign_transaction(&data))
+        .await;
+
+    // Decompile the executed transaction, and check that its output parses and builds back into
+    // the same transaction.
+    let decompiled = decompile_from_args(
+        client.read_api(),
+        &[FROM_DIGEST.to_string(), response.digest.to_string()],
+    )
+    .await?
+    .to_string();
+
+    let args = shlex::split(&decompiled).unwrap();
+    assert_eq!(args[..3], ["sui", "client", "ptb"]);
+
+    let (program, metadata) = PTB::parse_ptb_commands(args[3..].to_vec())
+        .map_err(|e| anyhow::anyhow!("Failed to parse decompiled PTB {decompiled}: {e:?}"))?;
+    assert_eq!(
+        metadata
+            .sender
+            .map(|sp| SuiAddress::from(sp.value.into_inner())),
+        Some(address)
+    );
+    assert_eq!(metadata.gas_budget.map(|sp| sp.value), Some(budget));
+    assert!(metadata.gas_sponsor.is_none());
+
+    let (rebuilt, _) = PTB::build_ptb(program, BTreeMap::new(), client).await;
+    let rebuilt = rebuilt.map_err(|e| anyhow::anyhow!("Failed to build {decompiled}: {e:?}"))?;
+    assert_eq!(original, rebuilt, "Decompiled PTB: {decompiled}");
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_custom_genesis() -> Result<(), anyhow::Error> {
     // Create and save genesis config file